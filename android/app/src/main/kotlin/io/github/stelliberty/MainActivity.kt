@@ -10,6 +10,7 @@ import android.graphics.Canvas
 import android.graphics.drawable.BitmapDrawable
 import android.net.VpnService as AndroidVpnService
 import android.util.Log
+import android.widget.Toast
 import androidx.core.content.ContextCompat
 import io.flutter.embedding.android.FlutterActivity
 import io.flutter.embedding.engine.FlutterEngine
@@ -65,6 +66,8 @@ class MainActivity : FlutterActivity() {
     }
 
     // JNI 声明：初始化 Android context 到 Rust 端的 ndk-context
+    // 失败时抛出 IllegalStateException（如 TLS 证书校验器初始化失败）
+    @Throws(IllegalStateException::class)
     private external fun initAndroidContext(activity: Activity)
 
     override fun onDestroy() {
@@ -81,7 +84,9 @@ class MainActivity : FlutterActivity() {
             initAndroidContext(this)
             Log.i(TAG, "ndk-context 初始化成功")
         } catch (e: Exception) {
-            Log.e(TAG, "ndk-context 初始化失败: ${e.message}")
+            // 原生上下文不可用时 HTTPS 请求将无法校验证书，需明确提示
+            Log.e(TAG, "ndk-context 初始化失败，HTTPS 功能可能不可用: ${e.message}", e)
+            Toast.makeText(this, "原生组件初始化失败：${e.message}", Toast.LENGTH_LONG).show()
         }
 
         // 核心日志事件通道：用于将核心日志转发到 Flutter 端
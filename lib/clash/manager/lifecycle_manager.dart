@@ -568,6 +568,8 @@ class LifecycleManager {
       if (version != null) {
         _coreVersion = version;
         _onCoreVersionChanged?.call(version);
+        // 核心已在线，预热 IPC 连接池，减少首次批量测速的建连开销
+        const WarmupIpcPool(connectionCount: 8).sendSignalToRust();
       } else {
         Logger.warning('未能通过 IPC 获取版本号');
         _coreVersion = 'Unknown';
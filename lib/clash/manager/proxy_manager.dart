@@ -5,6 +5,7 @@ import 'package:stelliberty/clash/client/clash_core_client.dart';
 import 'package:stelliberty/clash/config/clash_defaults.dart';
 import 'package:stelliberty/clash/services/delay_test_service.dart';
 import 'package:stelliberty/services/log_print_service.dart';
+import 'package:stelliberty/storage/clash_preferences.dart';
 
 // Clash 代理管理器
 // 负责代理节点的切换、延迟测试
@@ -138,6 +139,7 @@ class ProxyManager {
       proxyName,
       requestId: requestId,
       testUrl: testUrl ?? _getTestUrl(),
+      fallbackTestUrls: ClashPreferences.instance.getFallbackTestUrls(),
     );
   }
 
@@ -168,6 +170,7 @@ class ProxyManager {
       proxyNames,
       requestId: requestId,
       testUrl: testUrl ?? _getTestUrl(),
+      fallbackTestUrls: ClashPreferences.instance.getFallbackTestUrls(),
       onNodeStart: onNodeStart,
       onNodeComplete: onNodeComplete,
     );
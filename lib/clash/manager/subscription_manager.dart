@@ -86,11 +86,13 @@ class SubscriptionManager {
     String url, {
     SubscriptionProxyMode proxyMode = SubscriptionProxyMode.direct,
     String userAgent = ClashDefaults.defaultUserAgent,
+    Map<String, String> customHeaders = const {},
   }) async {
     return await _service.parseRemoteSubscriptionContent(
       url,
       proxyMode: proxyMode,
       userAgent: userAgent,
+      customHeaders: customHeaders,
       mixedPort: _getMixedPort(),
     );
   }
@@ -21,7 +21,8 @@ enum OverrideType {
 // 覆写格式
 enum OverrideFormat {
   yaml('yaml', 'Yaml'),
-  js('js', 'JavaScript');
+  js('js', 'JavaScript'),
+  toml('toml', 'TOML');
 
   const OverrideFormat(this.value, this.displayName);
 
@@ -47,6 +48,8 @@ class OverrideConfig {
   final String? content; // 缓存的内容
   final DateTime? lastUpdate; // 最后更新时间
   final SubscriptionProxyMode proxyMode; // 代理模式（仅远程覆写）
+  final bool isNetworkAllowed; // 允许 JavaScript 覆写使用 httpGet（默认关闭，需用户手动开启）
+  final bool isNondeterminismAllowed; // 允许 JavaScript 覆写使用真实时间与随机数（默认关闭）
 
   const OverrideConfig({
     required this.id,
@@ -58,6 +61,8 @@ class OverrideConfig {
     this.content,
     this.lastUpdate,
     this.proxyMode = SubscriptionProxyMode.direct,
+    this.isNetworkAllowed = false,
+    this.isNondeterminismAllowed = false,
   });
 
   // 创建新覆写
@@ -92,6 +97,8 @@ class OverrideConfig {
     String? content,
     DateTime? lastUpdate,
     SubscriptionProxyMode? proxyMode,
+    bool? isNetworkAllowed,
+    bool? isNondeterminismAllowed,
   }) {
     return OverrideConfig(
       id: id,
@@ -103,6 +110,9 @@ class OverrideConfig {
       content: content ?? this.content,
       lastUpdate: lastUpdate ?? this.lastUpdate,
       proxyMode: proxyMode ?? this.proxyMode,
+      isNetworkAllowed: isNetworkAllowed ?? this.isNetworkAllowed,
+      isNondeterminismAllowed:
+          isNondeterminismAllowed ?? this.isNondeterminismAllowed,
     );
   }
 
@@ -116,6 +126,8 @@ class OverrideConfig {
     // content 不序列化到 JSON，仅用于内存缓存
     'lastUpdate': lastUpdate?.toIso8601String(),
     'proxyMode': proxyMode.value,
+    'isNetworkAllowed': isNetworkAllowed,
+    'isNondeterminismAllowed': isNondeterminismAllowed,
   };
 
   factory OverrideConfig.fromJson(Map<String, dynamic> json) {
@@ -133,6 +145,8 @@ class OverrideConfig {
       proxyMode: SubscriptionProxyMode.fromString(
         json['proxyMode'] ?? 'direct',
       ),
+      isNetworkAllowed: json['isNetworkAllowed'] ?? false,
+      isNondeterminismAllowed: json['isNondeterminismAllowed'] ?? false,
     );
   }
 
@@ -193,6 +193,7 @@ class Subscription {
   final List<String> overrideSortPreferences; // 规则覆写排序偏好（完整顺序，包括未选中的）
   final List<String> failedOverrideIds; // 失败的覆写 ID 列表(启动失败时记录)
   final String userAgent; // User-Agent（仅远程订阅有效，默认为 clash.meta）
+  final Map<String, String> customHeaders; // 下载订阅时附加的请求头（如 API Key）
   final bool hasConfigLoadFailed; // 配置加载失败标记（用于 UI 显示警告）
   final bool autoTestAllDelaysEnabled; // 是否启用自动测试全部延迟
   final int autoTestAllDelaysIntervalMinutes; // 自动测试全部延迟间隔（分钟）
@@ -217,6 +218,7 @@ class Subscription {
     this.overrideSortPreferences = const [],
     this.failedOverrideIds = const [],
     this.userAgent = ClashDefaults.defaultUserAgent,
+    this.customHeaders = const {},
     this.hasConfigLoadFailed = false,
     this.autoTestAllDelaysEnabled = false,
     this.autoTestAllDelaysIntervalMinutes = 10,
@@ -282,6 +284,7 @@ class Subscription {
     List<String>? overrideSortPreferences,
     List<String>? failedOverrideIds,
     String? userAgent,
+    Map<String, String>? customHeaders,
     bool? hasConfigLoadFailed,
     bool? autoTestAllDelaysEnabled,
     int? autoTestAllDelaysIntervalMinutes,
@@ -308,6 +311,7 @@ class Subscription {
           overrideSortPreferences ?? this.overrideSortPreferences,
       failedOverrideIds: failedOverrideIds ?? this.failedOverrideIds,
       userAgent: userAgent ?? this.userAgent,
+      customHeaders: customHeaders ?? this.customHeaders,
       hasConfigLoadFailed: hasConfigLoadFailed ?? this.hasConfigLoadFailed,
       autoTestAllDelaysEnabled:
           autoTestAllDelaysEnabled ?? this.autoTestAllDelaysEnabled,
@@ -338,6 +342,7 @@ class Subscription {
     'overrideSortPreferences': overrideSortPreferences,
     'failedOverrideIds': failedOverrideIds,
     'userAgent': userAgent,
+    'customHeaders': customHeaders,
     'hasConfigLoadFailed': hasConfigLoadFailed,
     'autoTestAllDelaysEnabled': autoTestAllDelaysEnabled,
     'autoTestAllDelaysIntervalMinutes': autoTestAllDelaysIntervalMinutes,
@@ -377,6 +382,9 @@ class Subscription {
           ? List<String>.from(json['failedOverrideIds'] as List)
           : const [],
       userAgent: json['userAgent'] as String? ?? ClashDefaults.defaultUserAgent,
+      customHeaders: json['customHeaders'] != null
+          ? Map<String, String>.from(json['customHeaders'] as Map)
+          : const {},
       hasConfigLoadFailed: json['hasConfigLoadFailed'] as bool? ?? false,
       autoTestAllDelaysEnabled:
           json['autoTestAllDelaysEnabled'] as bool? ?? false,
@@ -1552,6 +1552,10 @@ class ClashProvider extends ChangeNotifier with WidgetsBindingObserver {
 
               final nodeName = message.nodeName;
               final delayMs = message.delayMs;
+              final testedUrl = message.testedUrl;
+              if (testedUrl != null && testedUrl != url) {
+                Logger.debug('节点 $nodeName 使用备用测试地址测得延迟：$testedUrl');
+              }
 
               // 更新节点延迟
               final node = _proxyNodes[nodeName];
@@ -1610,8 +1614,16 @@ class ClashProvider extends ChangeNotifier with WidgetsBindingObserver {
           requestId: requestId,
           nodeNames: proxyNames,
           testUrl: url,
+          fallbackTestUrls: ClashPreferences.instance.getFallbackTestUrls(),
           timeoutMs: timeoutMs,
           concurrency: concurrency,
+          expectedStatus: null,
+          acceptableMs: null,
+          groupName: null,
+          progressBatching: null,
+          nestedGroupPolicy: null,
+          resultLog: null,
+          shouldReportStarted: false,
         ).sendSignalToRust();
 
         // 等待测试完成（最多等待：节点数 × 单个超时 + 10 秒缓冲）
@@ -137,11 +137,13 @@ class SubscriptionProvider extends ChangeNotifier {
     String url, {
     SubscriptionProxyMode proxyMode = SubscriptionProxyMode.direct,
     String userAgent = ClashDefaults.defaultUserAgent,
+    Map<String, String> customHeaders = const {},
   }) async {
     return await _manager.parseRemoteSubscriptionContent(
       url,
       proxyMode: proxyMode,
       userAgent: userAgent,
+      customHeaders: customHeaders,
     );
   }
 
@@ -321,6 +323,7 @@ class SubscriptionProvider extends ChangeNotifier {
     bool downloadNow = true,
     SubscriptionProxyMode proxyMode = SubscriptionProxyMode.direct,
     String? userAgent,
+    Map<String, String> customHeaders = const {},
     bool autoTestAllDelaysEnabled = false,
     int autoTestAllDelaysIntervalMinutes = 10,
     List<String> disabledBuiltinChainProxyNames = const [],
@@ -350,6 +353,7 @@ class SubscriptionProvider extends ChangeNotifier {
         shouldUpdateOnStartup: shouldUpdateOnStartup,
         proxyMode: proxyMode,
         userAgent: effectiveUserAgent,
+        customHeaders: customHeaders,
         autoTestAllDelaysEnabled: autoTestAllDelaysEnabled,
         autoTestAllDelaysIntervalMinutes: autoTestAllDelaysIntervalMinutes,
         disabledBuiltinChainProxyNames: disabledBuiltinChainProxyNames,
@@ -1282,6 +1286,7 @@ class SubscriptionProvider extends ChangeNotifier {
     bool? shouldUpdateOnStartup,
     SubscriptionProxyMode? proxyMode,
     String? userAgent,
+    Map<String, String>? customHeaders,
     bool? autoTestAllDelaysEnabled,
     int? autoTestAllDelaysIntervalMinutes,
     List<String>? disabledBuiltinChainProxyNames,
@@ -1306,6 +1311,7 @@ class SubscriptionProvider extends ChangeNotifier {
             shouldUpdateOnStartup ?? subscription.shouldUpdateOnStartup,
         proxyMode: proxyMode ?? subscription.proxyMode,
         userAgent: userAgent ?? subscription.userAgent,
+        customHeaders: customHeaders ?? subscription.customHeaders,
         autoTestAllDelaysEnabled:
             autoTestAllDelaysEnabled ?? subscription.autoTestAllDelaysEnabled,
         autoTestAllDelaysIntervalMinutes:
@@ -1479,12 +1485,17 @@ class SubscriptionProvider extends ChangeNotifier {
     // 设置切换状态
     _updateState(SubscriptionState.switching());
 
+    final isProfileChanged = _currentSubscriptionId != subscriptionId;
     _currentSubscriptionId = subscriptionId;
     // 保存选择到持久化存储
     await ClashPreferences.instance.setCurrentSubscriptionId(subscriptionId);
     Logger.info('选择订阅：$subscriptionId');
 
     await _cancelDelayTestsForCurrentSubscriptionUpdate();
+    // 旧订阅的节点延迟历史不再适用，避免影响最快节点的选择
+    if (isProfileChanged) {
+      const ClearDelayHistory(nodeName: null).sendSignalToRust();
+    }
 
     try {
       // 重新加载配置文件
@@ -1623,10 +1634,16 @@ class SubscriptionProvider extends ChangeNotifier {
               return OverrideConfig(
                 id: appOverride.id,
                 name: appOverride.name,
-                format: appOverride.format == app_override.OverrideFormat.yaml
-                    ? OverrideFormat.yaml
-                    : OverrideFormat.javascript,
+                format: switch (appOverride.format) {
+                  app_override.OverrideFormat.yaml => OverrideFormat.yaml,
+                  app_override.OverrideFormat.js => OverrideFormat.javascript,
+                  app_override.OverrideFormat.toml => OverrideFormat.toml,
+                },
                 content: appOverride.content!,
+                isNetworkAllowed: appOverride.isNetworkAllowed,
+                isNondeterminismAllowed: appOverride.isNondeterminismAllowed,
+                isRemote:
+                    appOverride.type == app_override.OverrideType.remote,
               );
             })
             .toList();
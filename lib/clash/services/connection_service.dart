@@ -60,7 +60,7 @@ class ConnectionService {
       },
     );
 
-    const StartConnectionStream().sendSignalToRust();
+    const StartConnectionStream(isTyped: null).sendSignalToRust();
     Logger.info('连接监控已启动 (WebSocket 模式)');
   }
 
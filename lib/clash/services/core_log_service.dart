@@ -71,7 +71,7 @@ class ClashLogService {
       });
 
       // 发送启动日志监控信号到 Rust
-      const StartLogStream().sendSignalToRust();
+      const StartLogStream(level: null).sendSignalToRust();
     }
   }
 
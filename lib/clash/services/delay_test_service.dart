@@ -23,6 +23,7 @@ class DelayTestService {
     String proxyName, {
     required int requestId,
     String? testUrl,
+    List<String> fallbackTestUrls = const [],
   }) async {
     final url = testUrl ?? ClashDefaults.defaultTestUrl;
     final timeoutMs = ClashDefaults.proxyDelayTestTimeout;
@@ -49,6 +50,7 @@ class DelayTestService {
           return;
         }
 
+        _logFallbackUrl(proxyName, url, message.testedUrl);
         completer.complete(message.delayMs);
       });
 
@@ -56,7 +58,10 @@ class DelayTestService {
         requestId: requestId,
         nodeName: proxyName,
         testUrl: url,
+        fallbackTestUrls: fallbackTestUrls,
         timeoutMs: timeoutMs,
+        expectedStatus: null,
+        acceptableMs: null,
       ).sendSignalToRust();
 
       final delay = await completer.future.timeout(
@@ -79,6 +84,7 @@ class DelayTestService {
     List<String> proxyNames, {
     required int requestId,
     String? testUrl,
+    List<String> fallbackTestUrls = const [],
     Function(String nodeName)? onNodeStart,
     Function(String nodeName, int delay)? onNodeComplete,
   }) async {
@@ -116,6 +122,7 @@ class DelayTestService {
         final nodeName = message.nodeName;
         final delayMs = message.delayMs;
 
+        _logFallbackUrl(nodeName, url, message.testedUrl);
         onNodeComplete?.call(nodeName, delayMs);
         delayResults[nodeName] = delayMs;
       });
@@ -152,8 +159,16 @@ class DelayTestService {
         requestId: requestId,
         nodeNames: proxyNames,
         testUrl: url,
+        fallbackTestUrls: fallbackTestUrls,
         timeoutMs: timeoutMs,
         concurrency: concurrency,
+        expectedStatus: null,
+        acceptableMs: null,
+        groupName: null,
+        progressBatching: null,
+        nestedGroupPolicy: null,
+        resultLog: null,
+        shouldReportStarted: false,
       ).sendSignalToRust();
 
       final maxWaitTime = Duration(
@@ -172,4 +187,11 @@ class DelayTestService {
       await completeSubscription?.cancel();
     }
   }
+
+  // 主测试地址失败、由备用地址测得延迟时记录实际使用的地址
+  static void _logFallbackUrl(String nodeName, String url, String? testedUrl) {
+    if (testedUrl != null && testedUrl != url) {
+      Logger.debug('节点 $nodeName 使用备用测试地址测得延迟：$testedUrl');
+    }
+  }
 }
@@ -71,7 +71,7 @@ class MemoryService {
       },
     );
 
-    const StartMemoryStream().sendSignalToRust();
+    const StartMemoryStream(coalesceIntervalMs: null).sendSignalToRust();
     Logger.info('内存监控已启动 (WebSocket 模式)');
   }
 
@@ -198,16 +198,21 @@ class OverrideService {
 
   // 获取覆写文件路径
   String _getOverridePath(String id, data.OverrideFormat format) {
-    final ext = format == data.OverrideFormat.yaml ? 'yaml' : 'js';
+    final ext = switch (format) {
+      data.OverrideFormat.yaml => 'yaml',
+      data.OverrideFormat.js => 'js',
+      data.OverrideFormat.toml => 'toml',
+    };
     return PathService.instance.getOverridePath(id, ext);
   }
 
   // 应用覆写列表到订阅配置
-  // 返回应用覆写后的配置内容
+  // 返回应用覆写后的配置内容；subscription 用于向 JavaScript 覆写提供 context
   Future<String> applyOverrides(
     String baseConfigContent,
-    List<data.OverrideConfig> overrides,
-  ) async {
+    List<data.OverrideConfig> overrides, {
+    Subscription? subscription,
+  }) async {
     Logger.debug('applyOverrides');
     Logger.debug('基础配置长度：${baseConfigContent.length} 字符');
     Logger.debug('覆写数量：${overrides.length}');
@@ -241,6 +246,9 @@ class OverrideService {
             name: override.name,
             format: _convertFormat(override.format),
             content: overrideContent,
+            isNetworkAllowed: override.isNetworkAllowed,
+            isNondeterminismAllowed: override.isNondeterminismAllowed,
+            isRemote: override.type == data.OverrideType.remote,
           ),
         );
       } catch (e) {
@@ -272,6 +280,13 @@ class OverrideService {
         requestId: requestId,
         baseConfigContent: baseConfigContent,
         overrides: overrideConfigs,
+        scriptContext: subscription == null
+            ? null
+            : signals.OverrideScriptContext(
+                profileName: subscription.name,
+                profileUrl: subscription.isLocalFile ? null : subscription.url,
+                proxyMode: _convertProxyMode(subscription.proxyMode),
+              ),
       );
 
       request.sendSignalToRust();
@@ -308,6 +323,8 @@ class OverrideService {
         return signals.OverrideFormat.yaml;
       case data.OverrideFormat.js:
         return signals.OverrideFormat.javascript;
+      case data.OverrideFormat.toml:
+        return signals.OverrideFormat.toml;
     }
   }
 
@@ -331,6 +348,9 @@ class OverrideService {
       name: 'Map Override',
       format: signals.OverrideFormat.yaml,
       content: yamlContent,
+      isNetworkAllowed: false,
+      isNondeterminismAllowed: false,
+      isRemote: false,
     );
 
     final requestId =
@@ -349,6 +369,7 @@ class OverrideService {
         requestId: requestId,
         baseConfigContent: baseContent,
         overrides: [tempOverride],
+        scriptContext: null,
       );
 
       request.sendSignalToRust();
@@ -150,6 +150,7 @@ class SubscriptionService {
         url: subscription.url,
         proxyMode: rustProxyMode,
         userAgent: subscription.userAgent,
+        headers: _buildSubscriptionHeaders(subscription.customHeaders),
         timeoutSeconds: Uint64(
           BigInt.from(ClashDefaults.subscriptionDownloadTimeout),
         ),
@@ -172,10 +173,10 @@ class SubscriptionService {
       // 解析订阅信息
       final info = _convertSubscriptionInfo(downloadResult.subscriptionInfo);
 
-      // 获取配置内容并解析
-      final parsedConfigContent = await _parseSubscriptionContent(
-        downloadResult.content,
-      );
+      // 下载时 Rust 已完成解析，无法识别时再单独解析以获取错误信息
+      final parsedConfigContent =
+          downloadResult.parsedConfig ??
+          await _parseSubscriptionContent(downloadResult.content);
 
       // 验证配置文件
       _validateConfig(parsedConfigContent);
@@ -225,6 +226,15 @@ class SubscriptionService {
     }
   }
 
+  // 转换自定义请求头（Dart → Rust），合法性由 Rust 侧校验
+  List<SubscriptionHeader> _buildSubscriptionHeaders(
+    Map<String, String> headers,
+  ) {
+    return headers.entries
+        .map((entry) => SubscriptionHeader(name: entry.key, value: entry.value))
+        .toList();
+  }
+
   // 转换订阅信息（Rust → Dart）
   SubscriptionInfo? _convertSubscriptionInfo(SubscriptionInfoData? rustInfo) {
     if (rustInfo == null) return null;
@@ -322,6 +332,7 @@ class SubscriptionService {
     String url, {
     SubscriptionProxyMode proxyMode = SubscriptionProxyMode.direct,
     String userAgent = ClashDefaults.defaultUserAgent,
+    Map<String, String> customHeaders = const {},
     required int mixedPort,
   }) async {
     final requestId = _buildRequestId('chain-proxy-candidates');
@@ -346,6 +357,7 @@ class SubscriptionService {
         url: url,
         proxyMode: _convertProxyMode(proxyMode),
         userAgent: userAgent,
+        headers: _buildSubscriptionHeaders(customHeaders),
         timeoutSeconds: Uint64(
           BigInt.from(ClashDefaults.subscriptionDownloadTimeout),
         ),
@@ -364,7 +376,8 @@ class SubscriptionService {
         throw Exception(downloadResult.errorMessage ?? '下载失败');
       }
 
-      return await _parseSubscriptionContent(downloadResult.content);
+      return downloadResult.parsedConfig ??
+          await _parseSubscriptionContent(downloadResult.content);
     } finally {
       await downloadSubscription?.cancel();
     }
@@ -643,7 +656,11 @@ class SubscriptionService {
             );
           }
 
-          result = await _overrideService!.applyOverrides(result, overrides);
+          result = await _overrideService!.applyOverrides(
+            result,
+            overrides,
+            subscription: subscription,
+          );
           Logger.info('规则覆写应用成功：${overrides.length} 个覆写');
         } else {
           Logger.warning('overrideIds 非空，但未获取到任何覆写配置');
@@ -34,7 +34,7 @@ class TrafficMonitor {
     });
 
     // 发送启动流量监控信号到 Rust
-    const StartTrafficStream().sendSignalToRust();
+    const StartTrafficStream(coalesceIntervalMs: null).sendSignalToRust();
     Logger.info('流量监控已启动 (IPC 模式)');
   }
 
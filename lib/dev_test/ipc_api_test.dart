@@ -180,7 +180,7 @@ class IpcApiTest {
       });
 
       // 3. 发送启动信号
-      const StartTrafficStream().sendSignalToRust();
+      const StartTrafficStream(coalesceIntervalMs: null).sendSignalToRust();
       Logger.info('  已发送启动流量监控信号...');
 
       // 4. 等待数据接收完成或超时
@@ -230,7 +230,7 @@ class IpcApiTest {
       });
 
       // 3. 发送启动信号
-      const StartLogStream().sendSignalToRust();
+      const StartLogStream(level: null).sendSignalToRust();
       Logger.info('  已发送启动日志监控信号...');
 
       // 4. 等待连接或数据（5 秒超时）
@@ -123,7 +123,7 @@ class OverrideTest {
     await for (final entity in overDir.list()) {
       if (entity is File) {
         final ext = path.extension(entity.path).toLowerCase();
-        if (ext == '.yaml' || ext == '.yml' || ext == '.js') {
+        if (ext == '.yaml' || ext == '.yml' || ext == '.js' || ext == '.toml') {
           files.add(entity);
         }
       }
@@ -160,10 +160,15 @@ class OverrideTest {
         OverrideConfig(
           id: fileName,
           name: fileName,
-          format: ext == '.js'
-              ? OverrideFormat.javascript
-              : OverrideFormat.yaml,
+          format: switch (ext) {
+            '.js' => OverrideFormat.javascript,
+            '.toml' => OverrideFormat.toml,
+            _ => OverrideFormat.yaml,
+          },
           content: content,
+          isNetworkAllowed: false,
+          isNondeterminismAllowed: false,
+          isRemote: false,
         ),
       );
     }
@@ -185,6 +190,7 @@ class OverrideTest {
         requestId: requestId,
         baseConfigContent: baseConfig,
         overrides: overrideConfigs,
+        scriptContext: null,
       );
 
       request.sendSignalToRust();
@@ -281,10 +281,15 @@ class ProviderSetup {
             OverrideConfig(
               id: override.id,
               name: override.name,
-              format: override.format == app_override.OverrideFormat.yaml
-                  ? OverrideFormat.yaml
-                  : OverrideFormat.javascript,
+              format: switch (override.format) {
+                app_override.OverrideFormat.yaml => OverrideFormat.yaml,
+                app_override.OverrideFormat.js => OverrideFormat.javascript,
+                app_override.OverrideFormat.toml => OverrideFormat.toml,
+              },
               content: override.content!,
+              isNetworkAllowed: override.isNetworkAllowed,
+              isNondeterminismAllowed: override.isNondeterminismAllowed,
+              isRemote: override.type == app_override.OverrideType.remote,
             ),
           );
         }
@@ -3,14 +3,16 @@ import 'dart:async';
 import 'package:stelliberty/clash/manager/clash_manager.dart';
 import 'package:stelliberty/src/bindings/signals/signals.dart';
 import 'package:stelliberty/services/log_print_service.dart';
+import 'package:stelliberty/storage/clash_preferences.dart';
 
-// 电源事件服务：监听休眠/唤醒，自动重启核心以恢复状态
+// 电源事件服务：监听休眠/唤醒与 TUN 网卡丢失，自动重启核心以恢复状态
 class PowerEventService {
   static final PowerEventService _instance = PowerEventService._internal();
   factory PowerEventService() => _instance;
   PowerEventService._internal();
 
   StreamSubscription? _subscription;
+  StreamSubscription? _tunMismatchSubscription;
   Future<void> Function()? _onCoreRestoreCompleted;
 
   DateTime? _lastRestartAt;
@@ -43,6 +45,37 @@ class PowerEventService {
         _subscription = null;
       },
     );
+
+    _tunMismatchSubscription = TunStateMismatch.rustSignalStream.listen(
+      (signal) {
+        _handleTunStateMismatch(signal.message);
+      },
+      onError: (Object e, StackTrace stackTrace) {
+        Logger.error('TUN 状态事件流异常：$e\n$stackTrace');
+      },
+      onDone: () {
+        _tunMismatchSubscription = null;
+      },
+    );
+  }
+
+  // TUN 已开启但虚拟网卡不存在（唤醒后常见），用户开启自动恢复时重启核心重建网卡
+  void _handleTunStateMismatch(TunStateMismatch event) {
+    if (!ClashManager.instance.isCoreRunning) {
+      return;
+    }
+
+    if (!ClashPreferences.instance.getTunAutoRecover()) {
+      Logger.warning(
+        'TUN 网卡丢失（设备：${event.configuredDevice ?? "未指定"}），未开启自动恢复，跳过',
+      );
+      return;
+    }
+
+    Logger.warning(
+      'TUN 网卡丢失（设备：${event.configuredDevice ?? "未指定"}），开始重启核心以恢复',
+    );
+    unawaited(_restartCore());
   }
 
   void _handlePowerEvent(SystemPowerEvent event) {
@@ -54,6 +87,9 @@ class PowerEventService {
 
     if (event.eventType == PowerEventType.resumeAutomatic ||
         event.eventType == PowerEventType.resumeSuspend) {
+      // 唤醒后重新应用系统代理，刷新其他应用的代理缓存（未启用时 Rust 侧跳过）
+      const ReapplySystemProxy().sendSignalToRust();
+
       if (!_shouldRestoreAfterResume) {
         Logger.debug('系统唤醒，核心此前未运行，跳过恢复');
         return;
@@ -128,5 +164,7 @@ class PowerEventService {
   void dispose() {
     _subscription?.cancel();
     _subscription = null;
+    _tunMismatchSubscription?.cancel();
+    _tunMismatchSubscription = null;
   }
 }
@@ -135,6 +135,7 @@ class ClashPreferences {
   static const String _kFindProcessMode = 'clash_find_process_mode';
   static const String _kCoreLogLevel = 'clash_core_log_level';
   static const String _kTestUrl = 'clash_test_url';
+  static const String _kFallbackTestUrls = 'clash_fallback_test_urls';
   static const String _kUnifiedDelayEnabled = 'clash_unified_delay_enabled';
   static const String _kMixedPort = 'clash_mixed_port';
   static const String _kSocksPort = 'clash_socks_port';
@@ -163,6 +164,7 @@ class ClashPreferences {
   static const String _kTunDisableIcmpForwarding =
       'clash_tun_disable_icmp_forwarding';
   static const String _kTunMtu = 'clash_tun_mtu';
+  static const String _kTunAutoRecover = 'clash_tun_auto_recover';
 
   // DNS 配置键
   static const String _kDnsOverrideEnabled = 'clash_dns_override_enabled';
@@ -300,6 +302,14 @@ class ClashPreferences {
   // 保存测速链接
   Future<void> setTestUrl(String url) => _setString(_kTestUrl, url);
 
+  // 获取备用测速链接（主链接失败时依次尝试）
+  List<String> getFallbackTestUrls() =>
+      _getStringList(_kFallbackTestUrls, const []);
+
+  // 保存备用测速链接
+  Future<void> setFallbackTestUrls(List<String> urls) =>
+      _setStringList(_kFallbackTestUrls, urls);
+
   // ==================== 统一延迟 ====================
 
   // 获取统一延迟是否启用
@@ -462,6 +472,13 @@ class ClashPreferences {
   // 保存虚拟网卡 MTU 值
   Future<void> setTunMtu(int mtu) => _setInt(_kTunMtu, mtu);
 
+  // 获取虚拟网卡丢失后是否自动重启核心恢复
+  bool getTunAutoRecover() => _getBool(_kTunAutoRecover, false);
+
+  // 保存虚拟网卡自动恢复启用状态
+  Future<void> setTunAutoRecover(bool enabled) =>
+      _setBool(_kTunAutoRecover, enabled);
+
   // ==================== DNS 配置 ====================
 
   // 获取 DNS 覆写是否启用
@@ -540,6 +557,7 @@ class ClashPreferences {
       _kFindProcessMode,
       _kCoreLogLevel,
       _kTestUrl,
+      _kFallbackTestUrls,
       _kUnifiedDelayEnabled,
       _kMixedPort,
       _kSocksPort,
@@ -560,6 +578,7 @@ class ClashPreferences {
       _kTunRouteExcludeAddress,
       _kTunDisableIcmpForwarding,
       _kTunMtu,
+      _kTunAutoRecover,
       _kDnsOverrideEnabled,
       _kOutboundMode,
       _kProxyNodeSortMode,
@@ -588,6 +607,7 @@ class ClashPreferences {
       _kFindProcessMode,
       _kCoreLogLevel,
       _kTestUrl,
+      _kFallbackTestUrls,
       _kUnifiedDelayEnabled,
       _kMixedPort,
       _kSocksPort,
@@ -608,6 +628,7 @@ class ClashPreferences {
       _kTunRouteExcludeAddress,
       _kTunDisableIcmpForwarding,
       _kTunMtu,
+      _kTunAutoRecover,
       _kDnsOverrideEnabled,
       _kOutboundMode,
       _kProxyNodeSortMode,
@@ -372,6 +372,7 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
           shouldUpdateOnStartup: result.shouldUpdateOnStartup,
           proxyMode: result.proxyMode,
           userAgent: result.userAgent,
+          customHeaders: result.customHeaders,
           autoTestAllDelaysEnabled: result.autoTestAllDelaysEnabled,
           autoTestAllDelaysIntervalMinutes:
               result.autoTestAllDelaysIntervalMinutes,
@@ -411,6 +412,7 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
       existingRemoteUrl: latestSubscription.isLocalFile
           ? null
           : latestSubscription.url,
+      existingCustomHeaders: latestSubscription.customHeaders,
       isEditMode: true,
       existingProfileName: latestSubscription.name,
     );
@@ -466,6 +468,7 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
         shouldUpdateOnStartup: result.shouldUpdateOnStartup,
         proxyMode: result.proxyMode,
         userAgent: result.userAgent,
+        customHeaders: result.customHeaders,
         autoTestAllDelaysEnabled: result.autoTestAllDelaysEnabled,
         autoTestAllDelaysIntervalMinutes:
             result.autoTestAllDelaysIntervalMinutes,
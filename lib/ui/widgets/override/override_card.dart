@@ -109,18 +109,16 @@ class OverrideCard extends StatelessWidget {
                             vertical: 2,
                           ),
                           decoration: BoxDecoration(
-                            color: config.format == OverrideFormat.yaml
-                                ? Colors.green.withValues(alpha: 0.1)
-                                : Colors.orange.withValues(alpha: 0.1),
+                            color: _formatColor(
+                              config.format,
+                            ).withValues(alpha: 0.1),
                             borderRadius: BorderRadius.circular(4),
                           ),
                           child: Text(
                             config.format.displayName,
                             style: TextStyle(
                               fontSize: 10,
-                              color: config.format == OverrideFormat.yaml
-                                  ? Colors.green[700]
-                                  : Colors.orange[700],
+                              color: _formatColor(config.format)[700],
                               fontWeight: FontWeight.w500,
                             ),
                           ),
@@ -223,4 +221,13 @@ class OverrideCard extends StatelessWidget {
       ),
     );
   }
+
+  // 格式标签颜色
+  static MaterialColor _formatColor(OverrideFormat format) {
+    return switch (format) {
+      OverrideFormat.yaml => Colors.green,
+      OverrideFormat.js => Colors.orange,
+      OverrideFormat.toml => Colors.blue,
+    };
+  }
 }
@@ -5,6 +5,7 @@ import 'package:stelliberty/services/log_print_service.dart';
 import 'package:stelliberty/i18n/i18n.dart';
 import 'package:stelliberty/ui/widgets/modern_toast.dart';
 import 'package:stelliberty/ui/common/modern_dialog.dart';
+import 'package:stelliberty/ui/common/modern_feature_card.dart';
 import 'package:stelliberty/ui/common/modern_dialog_subs/option_selector.dart';
 import 'package:stelliberty/ui/common/modern_dialog_subs/text_input_field.dart';
 import 'package:stelliberty/ui/common/modern_dialog_subs/file_selector.dart';
@@ -58,6 +59,8 @@ class _OverrideDialogState extends State<OverrideDialog> {
   late final TextEditingController _urlController;
   late OverrideFormat _format;
   late SubscriptionProxyMode _proxyMode;
+  late bool _isNetworkAllowed;
+  late bool _isNondeterminismAllowed;
 
   // 覆写添加方式
   OverrideAddMethod _addMethod = OverrideAddMethod.remote;
@@ -84,6 +87,9 @@ class _OverrideDialogState extends State<OverrideDialog> {
     _format = widget.editingOverride?.format ?? OverrideFormat.yaml;
     _proxyMode =
         widget.editingOverride?.proxyMode ?? SubscriptionProxyMode.direct;
+    _isNetworkAllowed = widget.editingOverride?.isNetworkAllowed ?? false;
+    _isNondeterminismAllowed =
+        widget.editingOverride?.isNondeterminismAllowed ?? false;
 
     if (widget.editingOverride != null) {
       _addMethod = widget.editingOverride!.type == OverrideType.remote
@@ -112,7 +118,18 @@ class _OverrideDialogState extends State<OverrideDialog> {
         _proxyMode !=
             (widget.editingOverride?.proxyMode ?? SubscriptionProxyMode.direct);
 
-    return nameChanged || urlChanged || proxyModeChanged;
+    final networkAccessChanged =
+        _isNetworkAllowed !=
+        (widget.editingOverride?.isNetworkAllowed ?? false);
+    final nondeterminismChanged =
+        _isNondeterminismAllowed !=
+        (widget.editingOverride?.isNondeterminismAllowed ?? false);
+
+    return nameChanged ||
+        urlChanged ||
+        proxyModeChanged ||
+        networkAccessChanged ||
+        nondeterminismChanged;
   }
 
   // 延迟重建，合并同一帧内的多次变更
@@ -237,6 +254,16 @@ class _OverrideDialogState extends State<OverrideDialog> {
               const SizedBox(height: _dialogItemSpacing),
               _buildFileSelector(),
             ],
+
+            // JavaScript 覆写显示真实时间开关，网络访问仅限用户自己编写的覆写
+            if (_format == OverrideFormat.js) ...[
+              if (_addMethod != OverrideAddMethod.remote) ...[
+                const SizedBox(height: _dialogItemSpacing),
+                _buildNetworkAccessToggle(),
+              ],
+              const SizedBox(height: _dialogItemSpacing),
+              _buildNondeterminismToggle(),
+            ],
           ],
         ),
       ),
@@ -290,6 +317,10 @@ class _OverrideDialogState extends State<OverrideDialog> {
           value: OverrideFormat.js,
           title: OverrideFormat.js.displayName,
         ),
+        OptionItem(
+          value: OverrideFormat.toml,
+          title: OverrideFormat.toml.displayName,
+        ),
       ],
       selectedValue: _format,
       onChanged: (value) {
@@ -308,6 +339,34 @@ class _OverrideDialogState extends State<OverrideDialog> {
     );
   }
 
+  // 构建网络访问开关（httpGet），默认关闭
+  Widget _buildNetworkAccessToggle() {
+    final trans = context.translate;
+    return ModernFeatureToggleCard(
+      icon: Icons.public,
+      title: trans.kOverride.network_access_title,
+      subtitle: trans.kOverride.network_access_desc,
+      value: _isNetworkAllowed,
+      onChanged: (value) {
+        setState(() => _isNetworkAllowed = value);
+      },
+    );
+  }
+
+  // 构建真实时间与随机数开关，默认使用固定值
+  Widget _buildNondeterminismToggle() {
+    final trans = context.translate;
+    return ModernFeatureToggleCard(
+      icon: Icons.schedule,
+      title: trans.kOverride.real_time_title,
+      subtitle: trans.kOverride.real_time_desc,
+      value: _isNondeterminismAllowed,
+      onChanged: (value) {
+        setState(() => _isNondeterminismAllowed = value);
+      },
+    );
+  }
+
   // 构建文件选择器
   Widget _buildFileSelector() {
     final trans = context.translate;
@@ -347,7 +406,13 @@ class _OverrideDialogState extends State<OverrideDialog> {
     Logger.info('表单验证通过，继续处理...');
 
     final override = widget.editingOverride != null
-        ? widget.editingOverride!.copyWith(name: _nameController.text.trim())
+        ? widget.editingOverride!.copyWith(
+            name: _nameController.text.trim(),
+            isNetworkAllowed:
+                widget.editingOverride!.type != OverrideType.remote &&
+                _isNetworkAllowed,
+            isNondeterminismAllowed: _isNondeterminismAllowed,
+          )
         : OverrideConfig(
             id: DateTime.now().millisecondsSinceEpoch.toString(),
             name: _nameController.text.trim(),
@@ -365,6 +430,12 @@ class _OverrideDialogState extends State<OverrideDialog> {
             proxyMode: _addMethod == OverrideAddMethod.remote
                 ? _proxyMode
                 : SubscriptionProxyMode.direct,
+            isNetworkAllowed:
+                _format == OverrideFormat.js &&
+                _addMethod != OverrideAddMethod.remote &&
+                _isNetworkAllowed,
+            isNondeterminismAllowed:
+                _format == OverrideFormat.js && _isNondeterminismAllowed,
           );
 
     Logger.info('创建的覆写对象: ${override.name}, ID: ${override.id}');
@@ -20,22 +20,35 @@ class TestUrlCard extends StatefulWidget {
 
 class _TestUrlCardState extends State<TestUrlCard> {
   late final TextEditingController _testUrlController;
+  late final TextEditingController _fallbackTestUrlsController;
   bool _isSaving = false;
 
   @override
   void initState() {
     super.initState();
-    _testUrlController = TextEditingController(
-      text: ClashPreferences.instance.getTestUrl(),
+    final prefs = ClashPreferences.instance;
+    _testUrlController = TextEditingController(text: prefs.getTestUrl());
+    _fallbackTestUrlsController = TextEditingController(
+      text: prefs.getFallbackTestUrls().join('\n'),
     );
   }
 
   @override
   void dispose() {
     _testUrlController.dispose();
+    _fallbackTestUrlsController.dispose();
     super.dispose();
   }
 
+  // 将多行文本解析为非空网址列表
+  List<String> _parseLines(String text) {
+    return text
+        .split('\n')
+        .map((line) => line.trim())
+        .where((line) => line.isNotEmpty)
+        .toList();
+  }
+
   // 保存配置
   Future<void> _saveConfig() async {
     final trans = context.translate;
@@ -46,6 +59,9 @@ class _TestUrlCardState extends State<TestUrlCard> {
     try {
       final clashProvider = Provider.of<ClashProvider>(context, listen: false);
       clashProvider.setTestUrl(_testUrlController.text);
+      await ClashPreferences.instance.setFallbackTestUrls(
+        _parseLines(_fallbackTestUrlsController.text),
+      );
 
       if (mounted) {
         ModernToast.success(trans.clash_features.test_url.save_success);
@@ -124,6 +140,16 @@ class _TestUrlCardState extends State<TestUrlCard> {
               ),
             ),
           ),
+          const SizedBox(height: 12),
+          // 备用网址输入区域：主网址失败时依次尝试
+          ModernTextField(
+            controller: _fallbackTestUrlsController,
+            keyboardType: TextInputType.multiline,
+            labelText: trans.clash_features.test_url.fallback_label,
+            hintText: trans.clash_features.test_url.fallback_hint,
+            maxLines: null,
+            minLines: 2,
+          ),
           const SizedBox(height: 16),
           // 保存按钮
           Row(
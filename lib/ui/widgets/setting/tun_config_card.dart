@@ -17,6 +17,7 @@ import 'package:stelliberty/clash/providers/service_provider.dart';
 import 'package:stelliberty/clash/state/service_states.dart';
 import 'package:stelliberty/i18n/i18n.dart';
 import 'package:stelliberty/services/log_print_service.dart';
+import 'package:stelliberty/storage/clash_preferences.dart';
 import 'package:stelliberty/src/bindings/signals/signals.dart';
 import 'package:rinf/rinf.dart';
 
@@ -72,6 +73,7 @@ class _TunConfigCardState extends State<TunConfigCard> {
   final TextEditingController _tunRouteExcludeAddressesController =
       TextEditingController();
   bool _tunDisableIcmpForwarding = false;
+  bool _tunAutoRecover = false;
 
   // 错误状态
   String? _tunMtuError;
@@ -141,6 +143,7 @@ class _TunConfigCardState extends State<TunConfigCard> {
           .tunRouteExcludeAddresses
           .join('，');
       _tunDisableIcmpForwarding = configState.isTunIcmpForwardingDisabled;
+      _tunAutoRecover = ClashPreferences.instance.getTunAutoRecover();
     });
   }
 
@@ -786,6 +789,41 @@ class _TunConfigCardState extends State<TunConfigCard> {
 
       const SizedBox(height: 16),
 
+      // 网卡丢失后自动恢复（仅影响应用行为，不写入核心配置）
+      Row(
+        mainAxisAlignment: MainAxisAlignment.spaceBetween,
+        children: [
+          Expanded(
+            child: Column(
+              crossAxisAlignment: CrossAxisAlignment.start,
+              children: [
+                Text(
+                  trans.clash_features.tun_mode.auto_recover,
+                  style: Theme.of(context).textTheme.titleSmall,
+                ),
+                const SizedBox(height: 4),
+                Text(
+                  trans.clash_features.tun_mode.auto_recover_desc,
+                  style: Theme.of(
+                    context,
+                  ).textTheme.bodySmall?.copyWith(color: Colors.grey),
+                ),
+              ],
+            ),
+          ),
+          const SizedBox(width: 16),
+          ModernSwitch(
+            value: _tunAutoRecover,
+            onChanged: (value) {
+              setState(() => _tunAutoRecover = value);
+              ClashPreferences.instance.setTunAutoRecover(value);
+            },
+          ),
+        ],
+      ),
+
+      const SizedBox(height: 16),
+
       // 保存按钮
       Row(
         mainAxisAlignment: MainAxisAlignment.end,
@@ -73,6 +73,7 @@ class ChainProxyDialog extends StatefulWidget {
   final bool isLocalImport;
   final String? localFilePath;
   final String? existingRemoteUrl;
+  final Map<String, String> existingCustomHeaders;
   final bool isEditMode;
   final String? existingProfileName;
 
@@ -85,6 +86,7 @@ class ChainProxyDialog extends StatefulWidget {
     required this.isLocalImport,
     this.localFilePath,
     this.existingRemoteUrl,
+    this.existingCustomHeaders = const {},
     required this.isEditMode,
     this.existingProfileName,
   });
@@ -98,6 +100,7 @@ class ChainProxyDialog extends StatefulWidget {
     required bool isLocalImport,
     String? localFilePath,
     String? existingRemoteUrl,
+    Map<String, String> existingCustomHeaders = const {},
     required bool isEditMode,
     String? existingProfileName,
   }) {
@@ -112,6 +115,7 @@ class ChainProxyDialog extends StatefulWidget {
         isLocalImport: isLocalImport,
         localFilePath: localFilePath,
         existingRemoteUrl: existingRemoteUrl,
+        existingCustomHeaders: existingCustomHeaders,
         isEditMode: isEditMode,
         existingProfileName: existingProfileName,
       ),
@@ -251,7 +255,10 @@ class _ChainProxyDialogState extends State<ChainProxyDialog> {
     }
 
     final subscriptionProvider = context.read<SubscriptionProvider>();
-    return await subscriptionProvider.parseRemoteSubscriptionContent(remoteUrl);
+    return await subscriptionProvider.parseRemoteSubscriptionContent(
+      remoteUrl,
+      customHeaders: widget.existingCustomHeaders,
+    );
   }
 
   _ChainProxyDraft _createEmptyDraft() {
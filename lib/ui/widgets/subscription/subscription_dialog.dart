@@ -1,3 +1,4 @@
+import 'package:flutter/foundation.dart' show mapEquals;
 import 'package:flutter/material.dart';
 import 'package:flutter/services.dart';
 import 'package:stelliberty/clash/model/subscription_model.dart';
@@ -37,6 +38,7 @@ class SubscriptionDialog extends StatefulWidget {
   final bool? initialUpdateOnStartup;
   final SubscriptionProxyMode? initialProxyMode;
   final String? initialUserAgent;
+  final Map<String, String> initialCustomHeaders;
   final bool? initialAutoTestAllDelaysEnabled;
   final int? initialAutoTestAllDelaysIntervalMinutes;
   final List<String> initialBuiltinChainProxyNames;
@@ -58,6 +60,7 @@ class SubscriptionDialog extends StatefulWidget {
     this.initialUpdateOnStartup,
     this.initialProxyMode,
     this.initialUserAgent,
+    this.initialCustomHeaders = const {},
     this.initialAutoTestAllDelaysEnabled,
     this.initialAutoTestAllDelaysIntervalMinutes,
     this.initialBuiltinChainProxyNames = const [],
@@ -109,6 +112,7 @@ class SubscriptionDialog extends StatefulWidget {
         initialUpdateOnStartup: subscription.shouldUpdateOnStartup,
         initialProxyMode: subscription.proxyMode,
         initialUserAgent: subscription.userAgent,
+        initialCustomHeaders: subscription.customHeaders,
         initialAutoTestAllDelaysEnabled: subscription.autoTestAllDelaysEnabled,
         initialAutoTestAllDelaysIntervalMinutes:
             subscription.autoTestAllDelaysIntervalMinutes,
@@ -133,6 +137,7 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
   late final TextEditingController _intervalController;
   late final TextEditingController _autoDelayTestIntervalController;
   late final TextEditingController _userAgentController;
+  late final TextEditingController _customHeadersController;
   late final FocusNode _autoDelayTestIntervalFocusNode;
   late int _autoDelayTestIntervalMinutes;
   late AutoUpdateMode _autoUpdateMode;
@@ -182,6 +187,9 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
     _userAgentController = TextEditingController(
       text: widget.initialUserAgent ?? '',
     );
+    _customHeadersController = TextEditingController(
+      text: _formatCustomHeaders(widget.initialCustomHeaders),
+    );
 
     // 初始化自动更新模式和代理模式
     _autoUpdateMode = widget.initialAutoUpdateMode ?? AutoUpdateMode.disabled;
@@ -205,6 +213,7 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
       _handleAutoDelayTestIntervalChanged,
     );
     _userAgentController.addListener(_checkForChanges);
+    _customHeadersController.addListener(_checkForChanges);
     _autoDelayTestIntervalFocusNode.addListener(
       _handleAutoDelayTestFocusChanged,
     );
@@ -252,6 +261,13 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
       if (currentUA != initialUA) {
         return true;
       }
+
+      if (!mapEquals(
+        _parseCustomHeaders(_customHeadersController.text),
+        widget.initialCustomHeaders,
+      )) {
+        return true;
+      }
     }
 
     final initialAutoDelayTestIntervalMinutes =
@@ -375,6 +391,7 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
       _handleAutoDelayTestIntervalChanged,
     );
     _userAgentController.removeListener(_checkForChanges);
+    _customHeadersController.removeListener(_checkForChanges);
     _autoDelayTestIntervalFocusNode.removeListener(
       _handleAutoDelayTestFocusChanged,
     );
@@ -384,6 +401,7 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
     _intervalController.dispose();
     _autoDelayTestIntervalController.dispose();
     _userAgentController.dispose();
+    _customHeadersController.dispose();
     _autoDelayTestIntervalFocusNode.dispose();
     super.dispose();
   }
@@ -521,6 +539,8 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
               ),
               const SizedBox(height: _dialogItemSpacing),
               _buildUserAgentField(),
+              const SizedBox(height: _dialogItemSpacing),
+              _buildCustomHeadersField(),
             ] else if (shouldShowLocalFileSelector) ...[
               const SizedBox(height: _dialogItemSpacing),
               _buildFileSelector(),
@@ -635,6 +655,47 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
     );
   }
 
+  // 构建自定义请求头输入字段，每行一个「名称: 值」
+  Widget _buildCustomHeadersField() {
+    final dialogTrans = context.translate.subscription_dialog;
+    return TextInputField(
+      controller: _customHeadersController,
+      label: dialogTrans.custom_headers_label,
+      hint: dialogTrans.custom_headers_hint,
+      icon: Icons.vpn_key_outlined,
+      minLines: 1,
+      maxLines: null,
+      validator: (value) {
+        final hasInvalidLine = (value ?? '')
+            .split('\n')
+            .map((line) => line.trim())
+            .where((line) => line.isNotEmpty)
+            .any((line) => line.indexOf(':') <= 0);
+        return hasInvalidLine ? dialogTrans.custom_headers_error : null;
+      },
+    );
+  }
+
+  // 请求头以「名称: 值」逐行展示
+  static String _formatCustomHeaders(Map<String, String> headers) {
+    return headers.entries
+        .map((entry) => '${entry.key}: ${entry.value}')
+        .join('\n');
+  }
+
+  // 解析逐行输入的请求头，忽略空行与缺少名称的行
+  static Map<String, String> _parseCustomHeaders(String text) {
+    final headers = <String, String>{};
+    for (final line in text.split('\n')) {
+      final separator = line.indexOf(':');
+      if (separator <= 0) continue;
+      final name = line.substring(0, separator).trim();
+      if (name.isEmpty) continue;
+      headers[name] = line.substring(separator + 1).trim();
+    }
+    return headers;
+  }
+
   // 构建导入方式选择器
   Widget _buildImportModeSelector() {
     final dialogTrans = context.translate.subscription_dialog;
@@ -720,6 +781,7 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
         localFilePath: _selectedFile?.file.path,
         proxyMode: _proxyMode,
         userAgent: userAgent.isEmpty ? _defaultUserAgent : userAgent,
+        customHeaders: _parseCustomHeaders(_customHeadersController.text),
         autoTestAllDelaysIntervalMinutes: _autoDelayTestIntervalMinutes,
         autoTestAllDelaysEnabled: _autoDelayTestIntervalMinutes > 0,
         builtinChainProxyNames: _builtinChainProxyNames,
@@ -795,6 +857,7 @@ class SubscriptionDialogResult {
   final String? localFilePath;
   final SubscriptionProxyMode proxyMode;
   final String userAgent;
+  final Map<String, String> customHeaders;
   final bool autoTestAllDelaysEnabled;
   final int autoTestAllDelaysIntervalMinutes;
   final List<String> builtinChainProxyNames;
@@ -811,6 +874,7 @@ class SubscriptionDialogResult {
     this.localFilePath,
     this.proxyMode = SubscriptionProxyMode.direct,
     String? userAgent,
+    this.customHeaders = const {},
     this.autoTestAllDelaysEnabled = false,
     this.autoTestAllDelaysIntervalMinutes = 10,
     this.builtinChainProxyNames = const [],
@@ -1,5 +1,6 @@
 // L4 原子层模块入口
 
+pub mod host_port;
 pub mod ipc_client;
 #[cfg(target_os = "android")]
 pub mod jni_bridge;
@@ -9,9 +10,13 @@ pub mod override_processor;
 pub mod path_resolver;
 pub mod proxy_parser;
 pub mod shared_types;
+pub mod shutdown;
 pub mod system_proxy;
+pub mod tls_roots;
 
-pub use ipc_client::{IpcClient, IpcHttpResponse};
+pub use ipc_client::{
+    ConnectionStrategy, IpcClient, IpcError, IpcHttpResponse, IpcPingResult, IpcPriority,
+};
 pub use logger::init;
 pub use override_processor::OverrideProcessor;
 pub use path_resolver as path_service;
@@ -0,0 +1,118 @@
+// 主机地址与端口的格式化与拆分：IPv6 地址在 host:port 形式中必须加方括号，
+// 节点解析、系统代理等处统一使用，避免生成 2001:db8::1:443 这类歧义地址
+
+use std::net::Ipv6Addr;
+
+// 判断是否为 IPv6 字面量（允许带 %zone 的链路本地地址）
+fn is_ipv6_literal(host: &str) -> bool {
+    let address = host.split_once('%').map_or(host, |(address, _)| address);
+    address.parse::<Ipv6Addr>().is_ok()
+}
+
+// 规范化主机：去掉方括号，并将 URL 中编码的 zone 分隔符 %25 还原为 %
+pub fn normalize_host(host: &str) -> String {
+    let host = host.trim();
+    let unbracketed = host
+        .strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'))
+        .unwrap_or(host);
+
+    match unbracketed.split_once("%25") {
+        Some((address, zone)) if is_ipv6_literal(address) => format!("{}%{}", address, zone),
+        _ => unbracketed.to_string(),
+    }
+}
+
+// 格式化为 host:port，IPv6 地址自动加方括号
+pub fn format_host_port(host: &str, port: u16) -> String {
+    let host = normalize_host(host);
+    if is_ipv6_literal(&host) {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+// 拆分 host:port，返回的主机不带方括号。
+// 未加方括号的 IPv6 地址按最后一个冒号拆分（部分订阅生成 2001:db8::1:443 这类地址），
+// 拆分后的主机须为合法的 IPv6 地址
+pub fn split_host_port(address: &str) -> Option<(String, u16)> {
+    let address = address.trim();
+
+    let (host, port) = if let Some(rest) = address.strip_prefix('[') {
+        let (host, port) = rest.split_once("]:")?;
+        (host, port)
+    } else {
+        let (host, port) = address.rsplit_once(':')?;
+        if host.contains(':') && !is_ipv6_literal(host) {
+            return None;
+        }
+        (host, port)
+    };
+
+    if host.is_empty() {
+        return None;
+    }
+    let port = port.parse::<u16>().ok()?;
+    Some((normalize_host(host), port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_host_port, normalize_host, split_host_port};
+
+    #[test]
+    fn formats_each_address_family() {
+        assert_eq!(format_host_port("1.2.3.4", 443), "1.2.3.4:443");
+        assert_eq!(format_host_port("example.com", 8080), "example.com:8080");
+        assert_eq!(format_host_port("2001:db8::1", 443), "[2001:db8::1]:443");
+        assert_eq!(format_host_port("[2001:db8::1]", 443), "[2001:db8::1]:443");
+        assert_eq!(format_host_port("fe80::1%eth0", 53), "[fe80::1%eth0]:53");
+        assert_eq!(
+            format_host_port("[fe80::1%25eth0]", 53),
+            "[fe80::1%eth0]:53"
+        );
+    }
+
+    #[test]
+    fn splits_each_address_family() {
+        assert_eq!(
+            split_host_port("1.2.3.4:443"),
+            Some(("1.2.3.4".to_string(), 443))
+        );
+        assert_eq!(
+            split_host_port("example.com:8080"),
+            Some(("example.com".to_string(), 8080))
+        );
+        assert_eq!(
+            split_host_port("[2001:db8::1]:443"),
+            Some(("2001:db8::1".to_string(), 443))
+        );
+        assert_eq!(
+            split_host_port("[fe80::1%25eth0]:53"),
+            Some(("fe80::1%eth0".to_string(), 53))
+        );
+        // 未加方括号的 IPv6 地址以最后一个冒号后的部分为端口
+        assert_eq!(
+            split_host_port("2001:db8::1:443"),
+            Some(("2001:db8::1".to_string(), 443))
+        );
+        assert_eq!(
+            split_host_port("fe80::1%eth0:53"),
+            Some(("fe80::1%eth0".to_string(), 53))
+        );
+        assert_eq!(split_host_port("2001:db8::1"), None);
+        assert_eq!(split_host_port("a:b:443"), None);
+        assert_eq!(split_host_port("example.com"), None);
+        assert_eq!(split_host_port("[::1]:99999"), None);
+    }
+
+    #[test]
+    fn normalizes_url_hosts() {
+        assert_eq!(normalize_host("[2001:db8::1]"), "2001:db8::1");
+        assert_eq!(normalize_host("[fe80::1%25wlan0]"), "fe80::1%wlan0");
+        assert_eq!(normalize_host("example.com"), "example.com");
+        // 非 IPv6 主机中的 %25 保持原样
+        assert_eq!(normalize_host("a%25b"), "a%25b");
+    }
+}
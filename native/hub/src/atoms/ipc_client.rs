@@ -2,5 +2,22 @@
 // 支持轻量连接复用以降低请求开销。
 
 mod client;
+mod credentials;
+mod error;
+mod pool;
+mod scheduler;
+pub mod trace;
 
-pub use client::{IpcClient, IpcHttpResponse};
+#[cfg(test)]
+mod mock_server;
+
+pub use client::{
+    ConnectionStrategy, IpcClient, IpcHttpResponse, IpcPingResult, describe_http_error,
+    encode_name_segment, validate_request_target,
+};
+pub use credentials::{
+    ControllerCredentials, authorization_header, controller_credentials,
+    load_controller_credentials,
+};
+pub use error::{BUDGET_EXCEEDED_ERROR, IpcError};
+pub use scheduler::IpcPriority;
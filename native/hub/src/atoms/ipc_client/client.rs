@@ -1,12 +1,15 @@
 // IPC 客户端原子模块：提供基础 IPC 通信能力。
 // 支持延迟测试场景下的连接复用。
 
+use super::credentials::authorization_header;
+use super::error::IpcError;
+use super::pool::IdlePool;
+use super::scheduler::{IpcPriority, PriorityScheduler};
+use super::trace;
 use once_cell::sync::Lazy;
-use std::collections::VecDeque;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
-use tokio::sync::Mutex;
 use tokio::time::{Duration, timeout};
 
 #[cfg(unix)]
@@ -24,10 +27,186 @@ type IpcStream = UnixStream;
 // HTTP 响应
 pub struct IpcHttpResponse {
     pub status_code: u16,
+    // 响应体不是有效 UTF-8 时为有损解码结果，原始字节见 body_bytes
     pub body: String,
+    // 重定向响应的 Location 头
+    pub location: Option<String>,
+    // 仅在响应体不是有效 UTF-8 时保留原始字节
+    raw_body: Option<Vec<u8>>,
 }
 
-const MAX_POOL_SIZE: usize = 30;
+// 错误信息中响应体的最大展示长度
+const MAX_ERROR_BODY_CHARS: usize = 200;
+
+impl IpcHttpResponse {
+    // 生成包含核心错误信息的描述
+    pub fn error_description(&self) -> String {
+        self.to_error().to_string()
+    }
+
+    // 将非成功响应转换为错误
+    pub fn to_error(&self) -> IpcError {
+        match &self.location {
+            Some(location) if is_redirect_status(self.status_code) => IpcError::Redirect {
+                status: self.status_code,
+                location: location.clone(),
+            },
+            _ => IpcError::Http {
+                status: self.status_code,
+                body: self.body.clone(),
+            },
+        }
+    }
+
+    // 响应体原始字节，供调用方自行处理非 UTF-8 内容
+    pub fn body_bytes(&self) -> &[u8] {
+        self.raw_body.as_deref().unwrap_or(self.body.as_bytes())
+    }
+
+    pub fn is_valid_utf8(&self) -> bool {
+        self.raw_body.is_none()
+    }
+
+    // 响应体不是有效 UTF-8 时返回包含字节数与首个无效字节偏移的描述
+    pub fn utf8_error_description(&self) -> Option<String> {
+        let raw_body = self.raw_body.as_deref()?;
+        let error = std::str::from_utf8(raw_body).err()?;
+        Some(format!(
+            "HTTP {}: 响应体不是有效的 UTF-8：共 {} 字节，首个无效字节位于偏移 {}",
+            self.status_code,
+            raw_body.len(),
+            error.valid_up_to()
+        ))
+    }
+}
+
+// 解码响应体：无效 UTF-8 时不中断请求，返回有损解码结果并保留原始字节
+fn decode_body(bytes: Vec<u8>) -> (String, Option<Vec<u8>>) {
+    match String::from_utf8(bytes) {
+        Ok(body) => (body, None),
+        Err(e) => {
+            let raw_body = e.into_bytes();
+            (
+                String::from_utf8_lossy(&raw_body).into_owned(),
+                Some(raw_body),
+            )
+        }
+    }
+}
+
+// 响应头解析结果
+struct ResponseHead {
+    status_code: u16,
+    content_length: Option<usize>,
+    is_chunked: bool,
+    location: Option<String>,
+    // 服务端是否保持连接
+    is_keep_alive: bool,
+}
+
+// 读取响应体的单段缓冲大小
+const BODY_READ_BUFFER_SIZE: usize = 16 * 1024;
+// 读取到连接关闭时，超过该时长没有新数据视为超时
+const BODY_IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+// 读取到连接关闭时的总时限，避免持续少量写入的连接无限占用
+const BODY_READ_DEADLINE: Duration = Duration::from_secs(30);
+
+fn is_redirect_status(status_code: u16) -> bool {
+    matches!(status_code, 301 | 302 | 303 | 307 | 308)
+}
+
+// 最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 2;
+
+// 是否跟随核心返回的重定向（核心前置反向代理时使用），默认关闭
+static IS_REDIRECT_FOLLOWING_ENABLED: AtomicBool = AtomicBool::new(false);
+
+// 是否复用连接，默认开启
+static IS_POOLING_ENABLED: AtomicBool = AtomicBool::new(true);
+
+// 将 Location 解析为同主机的请求路径，指向其他主机时返回 None
+fn same_host_redirect_path(location: &str) -> Option<String> {
+    if location.starts_with('/') && !location.starts_with("//") {
+        return Some(location.to_string());
+    }
+
+    let url = url::Url::parse(location).ok()?;
+    let is_same_host = matches!(url.scheme(), "http" | "https")
+        && matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    if !is_same_host {
+        return None;
+    }
+
+    let mut path = url.path().to_string();
+    if let Some(query) = url.query() {
+        path.push('?');
+        path.push_str(query);
+    }
+    Some(path)
+}
+
+// 校验请求行：方法与路径中出现空白或控制字符（如 CR/LF）会破坏 HTTP 报文结构
+pub fn validate_request_target(method: &str, path: &str) -> Result<(), String> {
+    let is_unsafe = |text: &str| text.chars().any(|c| c.is_control() || c.is_whitespace());
+    if method.is_empty() || is_unsafe(method) {
+        return Err(format!("请求方法包含非法字符：{:?}", method));
+    }
+    if !path.starts_with('/') || is_unsafe(path) {
+        return Err(format!("请求路径包含非法字符：{:?}", path));
+    }
+    Ok(())
+}
+
+// 将节点、策略组等名称编码为路径段。
+// 含控制字符（换行等）的名称来自异常订阅，直接拒绝而非静默修改
+pub fn encode_name_segment(name: &str) -> Result<String, String> {
+    if name.is_empty() {
+        return Err("名称为空".to_string());
+    }
+    if name.chars().any(char::is_control) {
+        return Err(format!("名称包含控制字符：{:?}", name));
+    }
+    Ok(urlencoding::encode(name).into_owned())
+}
+
+// 生成包含核心错误信息的描述，如 "HTTP 401: unauthorized"。
+// 优先使用 JSON 响应中的 message 字段，否则使用截断后的原始响应体
+pub fn describe_http_error(status_code: u16, body: &str) -> String {
+    let message = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|json| json.get("message")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().chars().take(MAX_ERROR_BODY_CHARS).collect());
+
+    if message.is_empty() {
+        format!("HTTP {}", status_code)
+    } else {
+        format!("HTTP {}: {}", status_code, message)
+    }
+}
+
+// 请求使用的连接策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStrategy {
+    // 每次新建连接，用完即关闭，不影响连接池（一次性操作）
+    Fresh,
+    // 复用连接池；复用的连接失效时自动用新连接重试一次
+    Pooled,
+    // 复用连接池，失败直接返回（调用方自行处理重试）
+    PooledNoRetry,
+}
+
+// 健康检查结果
+pub struct IpcPingResult {
+    pub is_alive: bool,
+    pub round_trip_ms: u64,
+    // /version 返回的核心版本号
+    pub version: Option<String>,
+    pub error_message: Option<String>,
+}
+
+// 连接池默认容量与可设置的上限
+const DEFAULT_POOL_SIZE: usize = 30;
+const MAX_POOL_SIZE: usize = 128;
 const IDLE_TIMEOUT_MS: u64 = 35000;
 
 struct PooledConnection {
@@ -50,15 +229,84 @@ impl PooledConnection {
     }
 }
 
-static IPC_CONNECTION_POOL: Lazy<Arc<Mutex<VecDeque<PooledConnection>>>> =
-    Lazy::new(|| Arc::new(Mutex::new(VecDeque::new())));
+static IPC_CONNECTION_POOL: Lazy<IdlePool<PooledConnection>> =
+    Lazy::new(|| IdlePool::new(DEFAULT_POOL_SIZE));
+
+// 取连接前的优先级调度
+static IPC_SCHEDULER: PriorityScheduler = PriorityScheduler::new();
+
+// 连接重试：总时限与退避区间
+const CONNECT_DEADLINE: Duration = Duration::from_millis(800);
+const CONNECT_INITIAL_BACKOFF_MS: u64 = 2;
+const CONNECT_MAX_BACKOFF_MS: u64 = 50;
+// 写入请求失败后，等待服务端已发出的响应的最长时间
+const EARLY_RESPONSE_WAIT: Duration = Duration::from_millis(500);
+
+// 覆盖 IPC 路径的环境变量
+const IPC_PATH_ENV: &str = "STELLIBERTY_IPC_PATH";
+
+// 运行时设置的 IPC 路径
+static IPC_PATH_OVERRIDE: std::sync::RwLock<Option<String>> = std::sync::RwLock::new(None);
 
 // IPC 客户端（支持可选连接池）
 pub struct IpcClient;
 
 impl IpcClient {
-    // 获取默认 IPC 路径
+    // 获取当前生效的 IPC 路径。
+    // 优先级：环境变量 STELLIBERTY_IPC_PATH > 运行时设置 > 编译期默认值
     pub fn default_ipc_path() -> String {
+        if let Ok(path) = std::env::var(IPC_PATH_ENV)
+            && !path.trim().is_empty()
+        {
+            return path.trim().to_string();
+        }
+
+        let override_path = match IPC_PATH_OVERRIDE.read() {
+            Ok(guard) => guard.clone(),
+            Err(e) => e.into_inner().clone(),
+        };
+
+        override_path.unwrap_or_else(Self::compiled_ipc_path)
+    }
+
+    // 运行时覆盖 IPC 路径，传入 None 恢复默认值
+    pub fn set_ipc_path_override(path: Option<String>) -> Result<(), String> {
+        let path = match path {
+            Some(path) if path.trim().is_empty() => {
+                return Err("IPC 路径不能为空".to_string());
+            }
+            Some(path) => Some(path.trim().to_string()),
+            None => None,
+        };
+
+        log::info!("IPC 路径覆盖已更新：{:?}", path);
+
+        match IPC_PATH_OVERRIDE.write() {
+            Ok(mut guard) => *guard = path,
+            Err(e) => *e.into_inner() = path,
+        }
+        Ok(())
+    }
+
+    // 记录当前生效的 IPC 路径及其来源（启动时调用）
+    pub fn log_effective_ipc_path() {
+        let source = match std::env::var(IPC_PATH_ENV) {
+            Ok(path) if !path.trim().is_empty() => "环境变量",
+            Ok(_) => {
+                log::warn!("环境变量 {} 为空，已忽略", IPC_PATH_ENV);
+                "默认值"
+            }
+            Err(_) => match IPC_PATH_OVERRIDE.read() {
+                Ok(guard) if guard.is_some() => "运行时设置",
+                _ => "默认值",
+            },
+        };
+
+        log::info!("IPC 路径（{}）：{}", source, Self::default_ipc_path());
+    }
+
+    // 编译期默认 IPC 路径（开发与发布版本分离）
+    fn compiled_ipc_path() -> String {
         #[cfg(windows)]
         {
             #[cfg(debug_assertions)]
@@ -84,59 +332,397 @@ impl IpcClient {
         }
     }
 
-    // 发送 GET 请求（每次创建新连接）
-    pub async fn get(path: &str) -> Result<String, String> {
-        let ipc_path = Self::default_ipc_path();
-        let response = Self::request(&ipc_path, "GET", path, None).await?;
+    // 按指定连接策略发送请求，返回完整响应以便调用方区分状态码
+    pub async fn send(
+        method: &str,
+        path: &str,
+        body: Option<&str>,
+        strategy: ConnectionStrategy,
+    ) -> Result<IpcHttpResponse, IpcError> {
+        Self::send_prioritized(method, path, body, strategy, IpcPriority::Interactive, None).await
+    }
 
-        if response.status_code >= 200 && response.status_code < 300 {
-            Ok(response.body)
+    // 在总时限内发送请求：时限覆盖取连接、建连、发送与失效重试的全过程，
+    // 而非每次尝试单独计时
+    pub async fn send_within(
+        method: &str,
+        path: &str,
+        body: Option<&str>,
+        strategy: ConnectionStrategy,
+        budget: Duration,
+    ) -> Result<IpcHttpResponse, IpcError> {
+        Self::send_prioritized(
+            method,
+            path,
+            body,
+            strategy,
+            IpcPriority::Interactive,
+            Some(budget),
+        )
+        .await
+    }
+
+    // 按优先级排队后发送请求。批量请求在名额不足时等待，budget 从放行后开始计时，
+    // 排队时间不计入单次请求的时限
+    pub async fn send_prioritized(
+        method: &str,
+        path: &str,
+        body: Option<&str>,
+        strategy: ConnectionStrategy,
+        priority: IpcPriority,
+        budget: Option<Duration>,
+    ) -> Result<IpcHttpResponse, IpcError> {
+        let _admission = IPC_SCHEDULER
+            .admit(priority, IPC_CONNECTION_POOL.capacity())
+            .await;
+        let deadline = budget.map(|budget| Instant::now() + budget);
+        Self::send_until(method, path, body, strategy, deadline).await
+    }
+
+    // 开启或关闭重定向跟随
+    pub fn set_redirect_following(is_enabled: bool) {
+        IS_REDIRECT_FOLLOWING_ENABLED.store(is_enabled, Ordering::Relaxed);
+        log::info!(
+            "IPC 重定向跟随已{}",
+            if is_enabled { "开启" } else { "关闭" }
+        );
+    }
+
+    // 开启或关闭连接复用。关闭后使用连接池的请求改为每次新建连接、响应后关闭，
+    // 以吞吐换取稳定（不再依赖空闲连接的有效性判断），并清空已有的空闲连接
+    pub async fn set_pooling_enabled(is_enabled: bool) {
+        IS_POOLING_ENABLED.store(is_enabled, Ordering::Relaxed);
+        log::info!("IPC 连接复用已{}", if is_enabled { "开启" } else { "关闭" });
+        if !is_enabled {
+            Self::clear_pool().await;
+        }
+    }
+
+    pub fn is_pooling_enabled() -> bool {
+        IS_POOLING_ENABLED.load(Ordering::Relaxed)
+    }
+
+    // 当前连接池容量
+    pub fn pool_capacity() -> usize {
+        IPC_CONNECTION_POOL.capacity()
+    }
+
+    // 批量请求可同时占用的连接数：连接池容量减去为交互请求预留的份额
+    pub fn bulk_capacity() -> usize {
+        PriorityScheduler::bulk_limit(IPC_CONNECTION_POOL.capacity())
+    }
+
+    // 设置连接池容量（限制在 1..=MAX_POOL_SIZE），返回实际生效的容量。
+    // 批量延迟测试的并发数不会超过该容量，调大并发时需同时调大容量
+    pub fn set_pool_capacity(capacity: usize) -> usize {
+        let capacity = capacity.clamp(1, MAX_POOL_SIZE);
+        IPC_CONNECTION_POOL.set_capacity(capacity);
+        log::info!("IPC 连接池容量已设置为 {}", capacity);
+        capacity
+    }
+
+    // 发送请求；开启重定向跟随时，对同主机重定向最多跟随 MAX_REDIRECTS 次
+    async fn send_until(
+        method: &str,
+        path: &str,
+        body: Option<&str>,
+        strategy: ConnectionStrategy,
+        deadline: Option<Instant>,
+    ) -> Result<IpcHttpResponse, IpcError> {
+        let mut response = Self::send_once(method, path, body, strategy, deadline).await?;
+        if !IS_REDIRECT_FOLLOWING_ENABLED.load(Ordering::Relaxed) {
+            return Ok(response);
+        }
+
+        let mut method = method.to_string();
+        let mut body = body;
+        for _ in 0..MAX_REDIRECTS {
+            let Some(location) = response.location.as_deref() else {
+                break;
+            };
+            if !is_redirect_status(response.status_code) {
+                break;
+            }
+
+            let Some(next_path) = same_host_redirect_path(location) else {
+                log::warn!("拒绝跟随指向其他主机的重定向：{} → {}", path, location);
+                return Err(response.to_error());
+            };
+
+            // 303 一律改用 GET 且不携带请求体
+            if response.status_code == 303 {
+                method = "GET".to_string();
+                body = None;
+            }
+
+            log::debug!("跟随 IPC 重定向：{} → {}", path, next_path);
+            response = Self::send_once(&method, &next_path, body, strategy, deadline).await?;
+        }
+
+        Ok(response)
+    }
+
+    async fn send_once(
+        method: &str,
+        path: &str,
+        body: Option<&str>,
+        strategy: ConnectionStrategy,
+        deadline: Option<Instant>,
+    ) -> Result<IpcHttpResponse, IpcError> {
+        // 关闭连接复用时，使用连接池的请求同样走新建连接
+        let strategy = if Self::is_pooling_enabled() {
+            strategy
         } else {
-            Err(format!("HTTP {}", response.status_code))
+            ConnectionStrategy::Fresh
+        };
+
+        match strategy {
+            ConnectionStrategy::Fresh => {
+                let ipc_path = Self::default_ipc_path();
+                Self::within(deadline, Self::request(&ipc_path, method, path, body)).await?
+            }
+            ConnectionStrategy::Pooled => {
+                Self::request_with_pool(method, path, body, true, deadline).await
+            }
+            ConnectionStrategy::PooledNoRetry => {
+                Self::request_with_pool(method, path, body, false, deadline).await
+            }
         }
     }
 
-    pub async fn get_with_pool(path: &str) -> Result<String, String> {
-        let response = Self::request_with_pool("GET", path, None).await?;
+    // 在截止时间前等待 future 完成；无截止时间时直接等待
+    async fn within<F: std::future::Future>(
+        deadline: Option<Instant>,
+        future: F,
+    ) -> Result<F::Output, IpcError> {
+        match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), future)
+                .await
+                .map_err(|_| IpcError::budget_exceeded()),
+            None => Ok(future.await),
+        }
+    }
+
+    // 发送 GET 请求（每次创建新连接）
+    pub async fn get(path: &str) -> Result<String, IpcError> {
+        let response = Self::send("GET", path, None, ConnectionStrategy::Fresh).await?;
+        Self::into_success_body(response)
+    }
 
+    // 发送 GET 请求（使用连接池）
+    pub async fn get_with_pool(path: &str) -> Result<String, IpcError> {
+        let response = Self::send("GET", path, None, ConnectionStrategy::Pooled).await?;
+        Self::into_success_body(response)
+    }
+
+    // 发送 GET 请求（使用连接池），总耗时（含重试）不超过 budget
+    pub async fn get_with_pool_within(path: &str, budget: Duration) -> Result<String, IpcError> {
+        let response =
+            Self::send_within("GET", path, None, ConnectionStrategy::Pooled, budget).await?;
+        Self::into_success_body(response)
+    }
+
+    // 以批量优先级发送 GET 请求（使用连接池），总耗时（含重试）不超过 budget。
+    // 用于批量延迟测试等大量探测，名额不足时排在交互请求之后
+    pub async fn get_bulk_within(path: &str, budget: Duration) -> Result<String, IpcError> {
+        let response = Self::send_prioritized(
+            "GET",
+            path,
+            None,
+            ConnectionStrategy::Pooled,
+            IpcPriority::Bulk,
+            Some(budget),
+        )
+        .await?;
+        Self::into_success_body(response)
+    }
+
+    // 发送 PUT 请求（使用连接池），返回完整响应以便调用方区分状态码
+    pub async fn put(path: &str, body: &str) -> Result<IpcHttpResponse, IpcError> {
+        Self::send("PUT", path, Some(body), ConnectionStrategy::Pooled).await
+    }
+
+    // 发送 PATCH 请求（使用连接池），返回完整响应以便调用方区分状态码
+    pub async fn patch(path: &str, body: &str) -> Result<IpcHttpResponse, IpcError> {
+        Self::send("PATCH", path, Some(body), ConnectionStrategy::Pooled).await
+    }
+
+    // 发送 DELETE 请求（使用连接池），返回完整响应以便调用方区分状态码
+    pub async fn delete(path: &str) -> Result<IpcHttpResponse, IpcError> {
+        Self::send("DELETE", path, None, ConnectionStrategy::Pooled).await
+    }
+
+    fn into_success_body(response: IpcHttpResponse) -> Result<String, IpcError> {
         if response.status_code >= 200 && response.status_code < 300 {
-            Ok(response.body)
+            match response.utf8_error_description() {
+                Some(description) => Err(IpcError::Decode(description)),
+                None => Ok(response.body),
+            }
         } else {
-            Err(format!("HTTP {}", response.status_code))
+            Err(response.to_error())
         }
     }
 
+    // 健康检查：使用独立连接请求 /version，不经过连接池，
+    // 避免池中失效连接造成误判
+    pub async fn ping(limit: Duration) -> IpcPingResult {
+        let ipc_path = Self::default_ipc_path();
+        let started = Instant::now();
+        let result = timeout(limit, Self::request(&ipc_path, "GET", "/version", None)).await;
+        let round_trip_ms = started.elapsed().as_millis() as u64;
+
+        let (version, error_message) = match result {
+            Ok(Ok(response)) if (200..300).contains(&response.status_code) => {
+                let version = serde_json::from_str::<serde_json::Value>(&response.body)
+                    .ok()
+                    .and_then(|value| value.get("version")?.as_str().map(str::to_string));
+                (version, None)
+            }
+            Ok(Ok(response)) => (None, Some(response.error_description())),
+            Ok(Err(e)) => (None, Some(e.to_string())),
+            Err(_) => (
+                None,
+                Some(format!("健康检查超时（{}ms）", limit.as_millis())),
+            ),
+        };
+
+        IpcPingResult {
+            is_alive: error_message.is_none(),
+            round_trip_ms,
+            version,
+            error_message,
+        }
+    }
+
+    // 清空连接池并逐个关闭连接，返回清理数量。
+    // 核心重启后池中连接全部失效，提前清理可避免重启后首批请求集中失败
+    pub async fn clear_pool() -> usize {
+        Self::drain_pool(&IPC_CONNECTION_POOL).await
+    }
+
+    async fn drain_pool(pool: &IdlePool<PooledConnection>) -> usize {
+        let connections = pool.drain();
+
+        let count = connections.len();
+        for mut pooled in connections {
+            // 对端可能已关闭，关闭失败不影响清理
+            let _ = timeout(Duration::from_millis(100), pooled.conn.shutdown()).await;
+        }
+
+        if count > 0 {
+            log::debug!("已清空 IPC 连接池：{} 个连接", count);
+        }
+        count
+    }
+
+    // 预热连接池：并发建立最多 count 个连接放入池中（已有空闲连接计入其中）。
+    // 尽力而为，单个连接失败仅跳过，返回实际新建的连接数
+    pub async fn warmup(count: usize) -> usize {
+        if !Self::is_pooling_enabled() {
+            return 0;
+        }
+
+        Self::warmup_pool(&IPC_CONNECTION_POOL, &Self::default_ipc_path(), count).await
+    }
+
+    async fn warmup_pool(pool: &IdlePool<PooledConnection>, ipc_path: &str, count: usize) -> usize {
+        let target = count.min(pool.capacity()).saturating_sub(pool.len());
+        if target == 0 {
+            return 0;
+        }
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..target {
+            let ipc_path = ipc_path.to_string();
+            tasks.spawn(async move { Self::connect(&ipc_path).await });
+        }
+
+        let mut opened = 0;
+        let mut last_error = None;
+        while let Some(result) = tasks.join_next().await {
+            match result {
+                Ok(Ok(conn)) => {
+                    // 池已满时直接丢弃连接
+                    let _ = pool.push(PooledConnection {
+                        conn,
+                        last_used: Instant::now(),
+                    });
+                    opened += 1;
+                }
+                Ok(Err(e)) => last_error = Some(e.to_string()),
+                Err(e) => last_error = Some(format!("预热任务异常结束：{}", e)),
+            }
+        }
+
+        match last_error {
+            Some(e) if opened < target => {
+                log::debug!(
+                    "IPC 连接池预热：{}/{} 个连接，最后错误：{}",
+                    opened,
+                    target,
+                    e
+                )
+            }
+            _ => log::debug!("IPC 连接池预热：{}/{} 个连接", opened, target),
+        }
+        opened
+    }
+
+    // 计算第 attempt 次重试前的等待时间：指数退避 + 随机抖动，
+    // 避免多个并发请求在同一时刻重试再次撞上管道繁忙
+    fn connect_backoff(attempt: u32) -> Duration {
+        let base_ms = (CONNECT_INITIAL_BACKOFF_MS << attempt.min(6)).min(CONNECT_MAX_BACKOFF_MS);
+        let jitter_ms = rand::random_range(0..=base_ms / 2);
+        Duration::from_millis(base_ms + jitter_ms)
+    }
+
+    // 管道繁忙（ERROR_PIPE_BUSY）时在时限内重试，其他错误立即返回
     #[cfg(windows)]
-    async fn connect(ipc_path: &str) -> Result<IpcStream, String> {
-        let mut last_err = None;
-        for retry in 0..20 {
+    async fn connect(ipc_path: &str) -> Result<IpcStream, IpcError> {
+        const ERROR_PIPE_BUSY: i32 = 231;
+
+        let deadline = Instant::now() + CONNECT_DEADLINE;
+        let mut attempt = 0;
+
+        loop {
             match ClientOptions::new().open(ipc_path) {
-                Ok(stream) => {
-                    return Ok(stream);
-                }
+                Ok(stream) => return Ok(stream),
                 Err(e) => {
-                    let is_busy = e.raw_os_error() == Some(231);
-                    last_err = Some(e);
-                    if is_busy {
-                        tokio::time::sleep(Duration::from_millis(15 * (retry + 1))).await;
-                        continue;
+                    let is_busy = e.raw_os_error() == Some(ERROR_PIPE_BUSY);
+                    if !is_busy || Instant::now() >= deadline {
+                        return Err(IpcError::Connect(format!("连接 Named Pipe 失败：{}", e)));
                     }
-                    break;
                 }
             }
-        }
 
-        let err = last_err
-            .map(|e| e.to_string())
-            .unwrap_or_else(|| "未知错误".to_string());
-        Err(format!("连接 Named Pipe 失败：{}", err))
+            tokio::time::sleep(Self::connect_backoff(attempt)).await;
+            attempt += 1;
+        }
     }
 
+    // 核心启动阶段 Socket 可能尚未创建或尚未监听，在时限内重试
     #[cfg(unix)]
-    async fn connect(ipc_path: &str) -> Result<IpcStream, String> {
-        UnixStream::connect(ipc_path)
-            .await
-            .map_err(|e| format!("连接 Unix Socket 失败：{}", e))
+    async fn connect(ipc_path: &str) -> Result<IpcStream, IpcError> {
+        use std::io::ErrorKind;
+
+        let deadline = Instant::now() + CONNECT_DEADLINE;
+        let mut attempt = 0;
+
+        loop {
+            match UnixStream::connect(ipc_path).await {
+                Ok(stream) => return Ok(stream),
+                Err(e) => {
+                    let is_not_ready =
+                        matches!(e.kind(), ErrorKind::NotFound | ErrorKind::ConnectionRefused);
+                    if !is_not_ready || Instant::now() >= deadline {
+                        return Err(IpcError::Connect(format!("连接 Unix Socket 失败：{}", e)));
+                    }
+                }
+            }
+
+            tokio::time::sleep(Self::connect_backoff(attempt)).await;
+            attempt += 1;
+        }
     }
 
     async fn request(
@@ -144,77 +730,118 @@ impl IpcClient {
         method: &str,
         path: &str,
         body: Option<&str>,
-    ) -> Result<IpcHttpResponse, String> {
+    ) -> Result<IpcHttpResponse, IpcError> {
         let mut stream = Self::connect(ipc_path).await?;
-        Self::send_request(&mut stream, method, path, body, false).await
+        let (response, _) = Self::send_request(&mut stream, method, path, body, false).await?;
+        Ok(response)
     }
 
     async fn request_with_pool(
         method: &str,
         path: &str,
         body: Option<&str>,
-    ) -> Result<IpcHttpResponse, String> {
-        let mut stream = Self::acquire_connection().await?;
-        let response = Self::send_request(&mut stream, method, path, body, true).await;
-        if response.is_ok() {
+        should_retry_stale: bool,
+        deadline: Option<Instant>,
+    ) -> Result<IpcHttpResponse, IpcError> {
+        let (mut stream, is_reused) = Self::within(deadline, Self::acquire_connection()).await??;
+        let first_attempt = Self::within(
+            deadline,
+            Self::send_request(&mut stream, method, path, body, true),
+        )
+        .await;
+        let (response, is_reusable) = match first_attempt {
+            Ok(Ok(result)) => result,
+            // 复用的连接可能已被核心关闭，换新连接重试一次（仍受同一截止时间约束）
+            Ok(Err(e)) if is_reused && should_retry_stale => {
+                log::debug!("复用连接失效，使用新连接重试：{} {}，{}", method, path, e);
+                let ipc_path = Self::default_ipc_path();
+                stream = Self::within(deadline, Self::connect(&ipc_path)).await??;
+                Self::within(
+                    deadline,
+                    Self::send_request(&mut stream, method, path, body, true),
+                )
+                .await??
+            }
+            Ok(Err(e)) | Err(e) => return Err(e),
+        };
+
+        // 服务端声明将关闭连接时不放回连接池，避免后续请求拿到已关闭的连接
+        if is_reusable {
             Self::release_connection(stream).await;
         }
-        response
+        Ok(response)
     }
 
-    async fn acquire_connection() -> Result<IpcStream, String> {
-        loop {
-            let pooled = {
-                let mut pool = IPC_CONNECTION_POOL.lock().await;
-                pool.pop_front()
-            };
-
-            if let Some(pooled) = pooled {
-                if pooled.last_used.elapsed() < Duration::from_millis(IDLE_TIMEOUT_MS)
-                    && pooled.is_valid()
-                {
-                    return Ok(pooled.conn);
-                }
-                continue;
+    // 获取连接，第二个返回值表示是否来自连接池
+    async fn acquire_connection() -> Result<(IpcStream, bool), IpcError> {
+        // 校验在出队后进行，不占用连接池锁
+        while let Some(pooled) = IPC_CONNECTION_POOL.pop() {
+            if pooled.last_used.elapsed() < Duration::from_millis(IDLE_TIMEOUT_MS)
+                && pooled.is_valid()
+            {
+                return Ok((pooled.conn, true));
             }
-
-            break;
         }
 
-        Self::connect(&Self::default_ipc_path()).await
+        let stream = Self::connect(&Self::default_ipc_path()).await?;
+        Ok((stream, false))
     }
 
-    async fn release_connection(conn: IpcStream) {
-        let mut pool = IPC_CONNECTION_POOL.lock().await;
-        if pool.len() < MAX_POOL_SIZE {
-            pool.push_back(PooledConnection {
-                conn,
-                last_used: Instant::now(),
-            });
+    async fn release_connection(mut conn: IpcStream) {
+        // 请求期间关闭了连接复用时直接关闭，不再放回池中
+        if !Self::is_pooling_enabled() {
+            let _ = timeout(Duration::from_millis(100), conn.shutdown()).await;
+            return;
         }
+
+        // 池已满时直接丢弃连接
+        let _ = IPC_CONNECTION_POOL.push(PooledConnection {
+            conn,
+            last_used: Instant::now(),
+        });
     }
 
+    // 发送请求并读取响应，同时返回连接是否可复用
     async fn send_request<S>(
         stream: &mut S,
         method: &str,
         path: &str,
         body: Option<&str>,
         keep_alive: bool,
-    ) -> Result<IpcHttpResponse, String>
+    ) -> Result<(IpcHttpResponse, bool), IpcError>
     where
         S: AsyncReadExt + AsyncWriteExt + Unpin,
     {
         // 构建 HTTP 请求
+        validate_request_target(method, path).map_err(IpcError::InvalidRequest)?;
         let request = Self::build_http_request(method, path, body, keep_alive);
+        trace::trace_request(method, path, &request);
 
         // 发送请求
-        stream
-            .write_all(request.as_bytes())
-            .await
-            .map_err(|e| format!("发送请求失败：{}", e))?;
+        if let Err(write_error) = stream.write_all(request.as_bytes()).await {
+            // 核心可能在读完请求体之前就返回错误（如 413）并关闭连接，
+            // 此时写入会因管道断开失败，优先返回核心已发出的响应
+            return match timeout(EARLY_RESPONSE_WAIT, Self::read_http_response(stream)).await {
+                Ok(Ok((response, _))) => {
+                    log::debug!(
+                        "写入请求失败，但核心已提前响应：{} {}，HTTP {}，{}",
+                        method,
+                        path,
+                        response.status_code,
+                        write_error
+                    );
+                    trace::trace_response(method, path, response.status_code, &response.body);
+                    // 请求未完整写出，连接状态不确定，不再复用
+                    Ok((response, false))
+                }
+                _ => Err(IpcError::Io(format!("发送请求失败：{}", write_error))),
+            };
+        }
 
         // 读取响应
-        Self::read_http_response(stream).await
+        let (response, is_keep_alive) = Self::read_http_response(stream).await?;
+        trace::trace_response(method, path, response.status_code, &response.body);
+        Ok((response, is_keep_alive))
     }
 
     fn build_http_request(
@@ -225,7 +852,12 @@ impl IpcClient {
     ) -> String {
         let mut request = format!("{} {} HTTP/1.1\r\n", method, path);
         request.push_str("Host: localhost\r\n");
-        if !keep_alive {
+        if let Some(authorization) = authorization_header() {
+            request.push_str(&authorization);
+        }
+        if keep_alive {
+            request.push_str("Connection: keep-alive\r\n");
+        } else {
             request.push_str("Connection: close\r\n");
         }
 
@@ -241,12 +873,37 @@ impl IpcClient {
         request
     }
 
-    async fn read_http_response<S>(stream: &mut S) -> Result<IpcHttpResponse, String>
+    // 读取响应；第二个返回值表示服务端是否保持连接
+    async fn read_http_response<S>(stream: &mut S) -> Result<(IpcHttpResponse, bool), IpcError>
     where
         S: AsyncReadExt + Unpin,
     {
         let mut reader = BufReader::new(stream);
+        let mut head = Self::read_response_head(&mut reader).await?;
+
+        let mut body_bytes = Vec::new();
+        Self::read_body(&mut reader, &mut head, |chunk| {
+            body_bytes.extend_from_slice(chunk);
+            Ok(())
+        })
+        .await?;
+        let (body, raw_body) = decode_body(body_bytes);
+
+        Ok((
+            IpcHttpResponse {
+                status_code: head.status_code,
+                body,
+                location: head.location,
+                raw_body,
+            },
+            head.is_keep_alive,
+        ))
+    }
 
+    async fn read_response_head<R>(reader: &mut BufReader<R>) -> Result<ResponseHead, IpcError>
+    where
+        R: AsyncReadExt + Unpin,
+    {
         // 读取 header
         let mut header_lines = Vec::new();
         loop {
@@ -254,10 +911,10 @@ impl IpcClient {
             let size = reader
                 .read_line(&mut line)
                 .await
-                .map_err(|e| format!("读取响应行失败：{}", e))?;
+                .map_err(|e| IpcError::Io(format!("读取响应行失败：{}", e)))?;
 
             if size == 0 {
-                return Err("连接意外关闭".to_string());
+                return Err(IpcError::ClosedUnexpectedly);
             }
 
             if line == "\r\n" {
@@ -268,12 +925,19 @@ impl IpcClient {
         }
 
         // 解析 status line
-        let status_line = header_lines.first().ok_or_else(|| "响应为空".to_string())?;
+        let status_line = header_lines
+            .first()
+            .ok_or_else(|| IpcError::Decode("响应为空".to_string()))?;
         let status_code = Self::parse_status_code(status_line)?;
 
-        // 解析 headers
-        let mut content_length: Option<usize> = None;
-        let mut is_chunked = false;
+        // 解析 headers：HTTP/1.1 默认保持连接，HTTP/1.0 默认关闭
+        let mut head = ResponseHead {
+            status_code,
+            content_length: None,
+            is_chunked: false,
+            location: None,
+            is_keep_alive: !status_line.starts_with("HTTP/1.0"),
+        };
 
         for line in &header_lines[1..] {
             if let Some((key, value)) = line.split_once(':') {
@@ -281,61 +945,123 @@ impl IpcClient {
                 let value = value.trim();
 
                 if key.eq_ignore_ascii_case("content-length") {
-                    content_length = value.parse().ok();
+                    head.content_length = value.parse().ok();
                 }
                 if key.eq_ignore_ascii_case("transfer-encoding") && value.contains("chunked") {
-                    is_chunked = true;
+                    head.is_chunked = true;
+                }
+                if key.eq_ignore_ascii_case("location") {
+                    head.location = Some(value.to_string());
+                }
+                if key.eq_ignore_ascii_case("connection") {
+                    let value = value.to_ascii_lowercase();
+                    if value.contains("close") {
+                        head.is_keep_alive = false;
+                    } else if value.contains("keep-alive") {
+                        head.is_keep_alive = true;
+                    }
                 }
             }
         }
 
-        // 读取 body
-        let body = if is_chunked {
-            Self::read_chunked_body(&mut reader).await?
-        } else if let Some(length) = content_length {
-            let mut body_bytes = vec![0u8; length];
-            reader
-                .read_exact(&mut body_bytes)
-                .await
-                .map_err(|e| format!("读取响应体失败：{}", e))?;
-            String::from_utf8(body_bytes).map_err(|e| format!("解码响应体失败：{}", e))?
-        } else {
-            let mut body_bytes = Vec::new();
-            match timeout(Duration::from_secs(5), reader.read_to_end(&mut body_bytes)).await {
-                Ok(Ok(_)) => {
-                    String::from_utf8(body_bytes).map_err(|e| format!("解码响应体失败：{}", e))?
-                }
-                Ok(Err(e)) => return Err(format!("读取响应体失败：{}", e)),
-                Err(_) => return Err("读取响应体超时".to_string()),
+        Ok(head)
+    }
+
+    // 读取 body，每读到一段数据即交给 on_chunk，单段不超过 BODY_READ_BUFFER_SIZE
+    async fn read_body<R, F>(
+        reader: &mut BufReader<R>,
+        head: &mut ResponseHead,
+        mut on_chunk: F,
+    ) -> Result<(), IpcError>
+    where
+        R: AsyncReadExt + Unpin,
+        F: FnMut(&[u8]) -> Result<(), IpcError>,
+    {
+        // 1xx、204、304 按协议没有响应体，不能等待连接关闭
+        let has_no_body =
+            (100..200).contains(&head.status_code) || matches!(head.status_code, 204 | 304);
+        if has_no_body {
+            return Ok(());
+        }
+
+        if head.is_chunked {
+            return Self::read_chunked_body(reader, on_chunk).await;
+        }
+
+        let mut buffer = vec![0u8; BODY_READ_BUFFER_SIZE];
+        if let Some(length) = head.content_length {
+            let mut remaining = length;
+            while remaining > 0 {
+                let piece = &mut buffer[..remaining.min(BODY_READ_BUFFER_SIZE)];
+                reader
+                    .read_exact(piece)
+                    .await
+                    .map_err(|e| IpcError::Io(format!("读取响应体失败：{}", e)))?;
+                on_chunk(piece)?;
+                remaining -= piece.len();
             }
-        };
+            return Ok(());
+        }
 
-        Ok(IpcHttpResponse { status_code, body })
+        // 无长度信息时读取到连接关闭为止，连接不可再复用
+        head.is_keep_alive = false;
+        Self::read_until_close(reader, on_chunk, BODY_IDLE_TIMEOUT, BODY_READ_DEADLINE).await
+    }
+
+    // 读取到连接关闭为止：单次等待不超过 idle_timeout，总耗时不超过 deadline
+    async fn read_until_close<R, F>(
+        reader: &mut BufReader<R>,
+        mut on_chunk: F,
+        idle_timeout: Duration,
+        deadline: Duration,
+    ) -> Result<(), IpcError>
+    where
+        R: AsyncReadExt + Unpin,
+        F: FnMut(&[u8]) -> Result<(), IpcError>,
+    {
+        let mut buffer = vec![0u8; BODY_READ_BUFFER_SIZE];
+        let started = Instant::now();
+        loop {
+            let remaining = deadline.saturating_sub(started.elapsed());
+            if remaining.is_zero() {
+                return Err(IpcError::Timeout("读取响应体超过总时限".to_string()));
+            }
+            match timeout(idle_timeout.min(remaining), reader.read(&mut buffer)).await {
+                Ok(Ok(0)) => return Ok(()),
+                Ok(Ok(size)) => on_chunk(&buffer[..size])?,
+                Ok(Err(e)) => return Err(IpcError::Io(format!("读取响应体失败：{}", e))),
+                Err(_) => return Err(IpcError::Timeout("读取响应体超时".to_string())),
+            }
+        }
     }
 
-    fn parse_status_code(status_line: &str) -> Result<u16, String> {
+    fn parse_status_code(status_line: &str) -> Result<u16, IpcError> {
         let parts: Vec<&str> = status_line.split_whitespace().collect();
         if parts.len() < 2 {
-            return Err(format!("无效的状态行：{}", status_line));
+            return Err(IpcError::Decode(format!("无效的状态行：{}", status_line)));
         }
 
         parts[1]
             .parse::<u16>()
-            .map_err(|_| format!("无效的状态码：{}", parts[1]))
+            .map_err(|_| IpcError::Decode(format!("无效的状态码：{}", parts[1])))
     }
 
-    async fn read_chunked_body<R>(reader: &mut BufReader<R>) -> Result<String, String>
+    async fn read_chunked_body<R, F>(
+        reader: &mut BufReader<R>,
+        mut on_chunk: F,
+    ) -> Result<(), IpcError>
     where
         R: AsyncReadExt + Unpin,
+        F: FnMut(&[u8]) -> Result<(), IpcError>,
     {
-        let mut body = Vec::new();
+        let mut buffer = vec![0u8; BODY_READ_BUFFER_SIZE];
 
         loop {
             let mut size_line = String::new();
             reader
                 .read_line(&mut size_line)
                 .await
-                .map_err(|e| format!("读取 chunk 大小失败：{}", e))?;
+                .map_err(|e| IpcError::Io(format!("读取 chunk 大小失败：{}", e)))?;
 
             let size_line = size_line.trim();
             if size_line.is_empty() {
@@ -343,7 +1069,7 @@ impl IpcClient {
             }
 
             let chunk_size = usize::from_str_radix(size_line, 16)
-                .map_err(|e| format!("解析 chunk 大小失败：{}", e))?;
+                .map_err(|e| IpcError::Decode(format!("解析 chunk 大小失败：{}", e)))?;
 
             if chunk_size == 0 {
                 let mut end = String::new();
@@ -351,17 +1077,261 @@ impl IpcClient {
                 break;
             }
 
-            let mut chunk_data = vec![0u8; chunk_size];
-            reader
-                .read_exact(&mut chunk_data)
-                .await
-                .map_err(|e| format!("读取 chunk 数据失败：{}", e))?;
-            body.extend_from_slice(&chunk_data);
+            // 大 chunk 分段交付，避免按核心给出的 chunk 大小分配内存
+            let mut remaining = chunk_size;
+            while remaining > 0 {
+                let piece = &mut buffer[..remaining.min(BODY_READ_BUFFER_SIZE)];
+                reader
+                    .read_exact(piece)
+                    .await
+                    .map_err(|e| IpcError::Io(format!("读取 chunk 数据失败：{}", e)))?;
+                on_chunk(piece)?;
+                remaining -= piece.len();
+            }
 
             let mut crlf = String::new();
             reader.read_line(&mut crlf).await.ok();
         }
 
-        String::from_utf8(body).map_err(|e| format!("解码 chunked body 失败：{}", e))
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock_server::{MockIpcServer, MockResponse};
+    use super::super::pool::IdlePool;
+    use super::{
+        IpcClient, IpcError, encode_name_segment, same_host_redirect_path, validate_request_target,
+    };
+    use tokio::io::{AsyncWriteExt, BufReader};
+    use tokio::time::Duration;
+
+    #[tokio::test]
+    async fn reads_content_length_and_chunked_bodies() -> Result<(), String> {
+        let server = MockIpcServer::start(vec![
+            MockResponse::text(200, r#"{"version":"v1"}"#),
+            MockResponse::chunked(200, &[r#"{"proxies":"#, r#"{}}"#]),
+        ])
+        .await?;
+
+        let response = IpcClient::request(server.path(), "GET", "/version", None).await?;
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.body, r#"{"version":"v1"}"#);
+
+        let response = IpcClient::request(server.path(), "GET", "/proxies", None).await?;
+        assert_eq!(response.body, r#"{"proxies":{}}"#);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reads_body_until_close_without_content_length() -> Result<(), String> {
+        let server = MockIpcServer::start(vec![MockResponse::until_close(200, "done")]).await?;
+
+        let mut stream = IpcClient::connect(server.path()).await?;
+        let (response, is_keep_alive) =
+            IpcClient::send_request(&mut stream, "GET", "/logs", None, true).await?;
+        assert_eq!(response.body, "done");
+        assert!(!is_keep_alive);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn surfaces_error_status_with_core_message() -> Result<(), String> {
+        let server = MockIpcServer::start(vec![MockResponse::text(
+            503,
+            r#"{"message":"core is restarting"}"#,
+        )])
+        .await?;
+
+        let response = IpcClient::request(server.path(), "GET", "/version", None).await?;
+        assert_eq!(response.status_code, 503);
+        assert_eq!(response.error_description(), "HTTP 503: core is restarting");
+        assert!(matches!(
+            IpcClient::into_success_body(response),
+            Err(IpcError::Http { status: 503, .. })
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn returns_early_response_when_body_write_fails() -> Result<(), String> {
+        let server = MockIpcServer::start(vec![
+            MockResponse::text(413, r#"{"message":"payload too large"}"#).early(),
+        ])
+        .await?;
+
+        let body = "x".repeat(8 * 1024 * 1024);
+        let response = IpcClient::request(server.path(), "PUT", "/configs", Some(&body)).await?;
+        assert_eq!(response.status_code, 413);
+        assert_eq!(response.error_description(), "HTTP 413: payload too large");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn names_location_of_unfollowed_redirect() -> Result<(), String> {
+        let server = MockIpcServer::start(vec![MockResponse::raw(
+            "HTTP/1.1 302 Found\r\nLocation: /clash/version\r\nContent-Length: 0\r\n\r\n",
+        )])
+        .await?;
+
+        let mut stream = IpcClient::connect(server.path()).await?;
+        let (response, is_keep_alive) =
+            IpcClient::send_request(&mut stream, "GET", "/version", None, true).await?;
+        assert_eq!(response.status_code, 302);
+        assert!(is_keep_alive);
+        assert!(response.error_description().contains("/clash/version"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keeps_raw_bytes_of_invalid_utf8_body() -> Result<(), String> {
+        let mut raw = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nok".to_vec();
+        raw.extend_from_slice(&[0xff, 0xfe, b'!']);
+        let server = MockIpcServer::start(vec![MockResponse::bytes(raw)]).await?;
+
+        let mut stream = IpcClient::connect(server.path()).await?;
+        let (response, _) =
+            IpcClient::send_request(&mut stream, "GET", "/version", None, true).await?;
+        assert!(!response.is_valid_utf8());
+        assert_eq!(response.body_bytes(), &[b'o', b'k', 0xff, 0xfe, b'!']);
+
+        let error = IpcClient::into_success_body(response)
+            .err()
+            .ok_or("无效 UTF-8 不应视为成功")?;
+        assert!(matches!(error, IpcError::Decode(_)));
+        assert!(error.to_string().contains("共 5 字节"));
+        assert!(error.to_string().contains("偏移 2"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn assembles_large_chunked_body() -> Result<(), String> {
+        let large_chunk = "x".repeat(40 * 1024);
+        let chunks = [r#"{"proxies":["#, large_chunk.as_str(), "]}"];
+        let server = MockIpcServer::start(vec![MockResponse::chunked(200, &chunks)]).await?;
+
+        let response = IpcClient::request(server.path(), "GET", "/proxies", None).await?;
+        assert_eq!(response.body, chunks.concat());
+        Ok(())
+    }
+
+    // 持续有数据但总耗时超限时同样超时
+    #[tokio::test]
+    async fn stops_reading_until_close_at_deadline() -> Result<(), String> {
+        let server = MockIpcServer::start(vec![
+            MockResponse::until_close(200, &"x".repeat(40)).slowly(1, Duration::from_millis(20)),
+        ])
+        .await?;
+
+        let mut stream = IpcClient::connect(server.path()).await?;
+        stream
+            .write_all(b"GET /logs HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut reader = BufReader::new(&mut stream);
+        IpcClient::read_response_head(&mut reader).await?;
+
+        let result = IpcClient::read_until_close(
+            &mut reader,
+            |_| Ok(()),
+            Duration::from_secs(1),
+            Duration::from_millis(200),
+        )
+        .await;
+        assert!(matches!(result, Err(IpcError::Timeout(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_control_characters_in_request_line() {
+        assert!(validate_request_target("GET", "/proxies/HK%0A01/delay").is_ok());
+        assert!(validate_request_target("GET", "/proxies/HK\r\nX: 1").is_err());
+        assert!(validate_request_target("GET", "/a b").is_err());
+        assert!(validate_request_target("GET\n", "/version").is_err());
+
+        assert_eq!(
+            encode_name_segment("香港 01").as_deref(),
+            Ok("%E9%A6%99%E6%B8%AF%2001")
+        );
+        assert!(encode_name_segment("HK\n01").is_err());
+        assert!(encode_name_segment("").is_err());
+    }
+
+    #[test]
+    fn accepts_only_same_host_redirects() {
+        assert_eq!(
+            same_host_redirect_path("/clash/version").as_deref(),
+            Some("/clash/version")
+        );
+        assert_eq!(
+            same_host_redirect_path("http://localhost/clash/logs?level=info").as_deref(),
+            Some("/clash/logs?level=info")
+        );
+        assert_eq!(same_host_redirect_path("//evil.example/version"), None);
+        assert_eq!(
+            same_host_redirect_path("https://evil.example/version"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn assembles_slowly_written_response() -> Result<(), String> {
+        let server = MockIpcServer::start(vec![
+            MockResponse::text(200, r#"{"delay":42}"#).slowly(7, Duration::from_millis(10)),
+        ])
+        .await?;
+
+        let response = IpcClient::request(server.path(), "GET", "/delay", None).await?;
+        assert_eq!(response.body, r#"{"delay":42}"#);
+        Ok(())
+    }
+
+    // 使用独立的连接池与路径，不影响全局 IPC 状态
+    #[tokio::test]
+    async fn warmup_seeds_pool_with_fresh_connections() -> Result<(), String> {
+        let server = MockIpcServer::start(Vec::new()).await?;
+        let pool = IdlePool::new(4);
+
+        let opened = IpcClient::warmup_pool(&pool, server.path(), 3).await;
+        assert_eq!(pool.len(), 3);
+        // 已有空闲连接计入目标数量
+        assert_eq!(IpcClient::warmup_pool(&pool, server.path(), 3).await, 0);
+        let cleared = IpcClient::drain_pool(&pool).await;
+
+        assert_eq!(opened, 3);
+        assert_eq!(cleared, 3);
+        assert_eq!(server.connections(), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reuses_keep_alive_connection_until_server_closes() -> Result<(), String> {
+        let server = MockIpcServer::start(vec![
+            MockResponse::text(200, "first"),
+            MockResponse::text(200, "second"),
+            MockResponse::raw(
+                "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 4\r\n\r\nlast",
+            )
+            .closing(),
+        ])
+        .await?;
+
+        let mut stream = IpcClient::connect(server.path()).await?;
+        for expected in ["first", "second"] {
+            let (response, is_keep_alive) =
+                IpcClient::send_request(&mut stream, "GET", "/version", None, true).await?;
+            assert_eq!(response.body, expected);
+            assert!(is_keep_alive);
+        }
+
+        let (response, is_keep_alive) =
+            IpcClient::send_request(&mut stream, "PATCH", "/configs", Some("{}"), true).await?;
+        assert_eq!(response.body, "last");
+        assert!(!is_keep_alive);
+
+        assert_eq!(server.connections(), 1);
+        assert_eq!(server.requests(), 3);
+        Ok(())
     }
 }
@@ -0,0 +1,144 @@
+// 外部控制器凭据：从核心配置文件读取 secret 与 external-controller。
+// 请求携带 secret 可避免用户在配置中设置密钥后出现 401。
+// IPC 客户端目前只有命名管道与 Unix 套接字传输，没有 TCP 回退，
+// 解析出的控制器地址仅通过 controller_credentials() 提供给调用方。
+
+use crate::atoms::host_port::split_host_port;
+use once_cell::sync::Lazy;
+use serde_yaml_ng::Value;
+use std::path::Path;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ControllerCredentials {
+    pub secret: Option<String>,
+    // external-controller 的（主机，端口），未配置时为 None
+    pub external_controller: Option<(String, u16)>,
+}
+
+static CONTROLLER_CREDENTIALS: Lazy<RwLock<ControllerCredentials>> =
+    Lazy::new(|| RwLock::new(ControllerCredentials::default()));
+
+// 当前生效的控制器凭据
+pub fn controller_credentials() -> ControllerCredentials {
+    match CONTROLLER_CREDENTIALS.read() {
+        Ok(guard) => guard.clone(),
+        Err(e) => e.into_inner().clone(),
+    }
+}
+
+fn set_controller_credentials(credentials: ControllerCredentials) {
+    match CONTROLLER_CREDENTIALS.write() {
+        Ok(mut guard) => *guard = credentials,
+        Err(e) => *e.into_inner() = credentials,
+    }
+}
+
+// 从核心配置文件加载凭据（核心启动与重载配置时调用）。
+// 文件不存在时清空凭据，缺少字段时对应项为 None
+pub fn load_controller_credentials(config_path: &Path) -> Result<(), String> {
+    let content = match std::fs::read_to_string(config_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            log::debug!(
+                "核心配置文件不存在，清空控制器凭据：{}",
+                config_path.display()
+            );
+            set_controller_credentials(ControllerCredentials::default());
+            return Ok(());
+        }
+        Err(e) => return Err(format!("读取核心配置文件失败：{}", e)),
+    };
+
+    let credentials = parse_controller_credentials(&content)?;
+    log::info!(
+        "已从核心配置读取控制器凭据：secret={}，external-controller={}",
+        if credentials.secret.is_some() {
+            "已设置"
+        } else {
+            "未设置"
+        },
+        credentials.external_controller.as_ref().map_or_else(
+            || "未设置".to_string(),
+            |(host, port)| format!("{}:{}", host, port)
+        )
+    );
+    set_controller_credentials(credentials);
+    Ok(())
+}
+
+fn parse_controller_credentials(content: &str) -> Result<ControllerCredentials, String> {
+    let config: Value =
+        serde_yaml_ng::from_str(content).map_err(|e| format!("解析核心配置文件失败：{}", e))?;
+
+    let secret = config
+        .get("secret")
+        .and_then(|v| v.as_str())
+        .filter(|secret| !secret.is_empty())
+        .and_then(|secret| {
+            // 密钥写入请求头，含控制字符时无法安全发送
+            if secret.chars().any(char::is_control) {
+                log::warn!("核心配置中的 secret 含控制字符，已忽略");
+                None
+            } else {
+                Some(secret.to_string())
+            }
+        });
+
+    // ":9090" 形式省略主机，核心监听全部地址，本机通过回环地址访问
+    let external_controller = config
+        .get("external-controller")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .and_then(|address| match address.strip_prefix(':') {
+            Some(port) => port
+                .parse::<u16>()
+                .ok()
+                .map(|port| ("127.0.0.1".to_string(), port)),
+            None => split_host_port(address),
+        });
+
+    Ok(ControllerCredentials {
+        secret,
+        external_controller,
+    })
+}
+
+// 请求头：配置了 secret 时返回 Authorization 行（含换行）
+pub fn authorization_header() -> Option<String> {
+    controller_credentials()
+        .secret
+        .map(|secret| format!("Authorization: Bearer {}\r\n", secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ControllerCredentials, parse_controller_credentials};
+
+    #[test]
+    fn reads_secret_and_controller_address() -> Result<(), String> {
+        let credentials = parse_controller_credentials(
+            "mixed-port: 7890\nsecret: 'abc123'\nexternal-controller: 127.0.0.1:9090\n",
+        )?;
+        assert_eq!(credentials.secret.as_deref(), Some("abc123"));
+        assert_eq!(
+            credentials.external_controller,
+            Some(("127.0.0.1".to_string(), 9090))
+        );
+
+        let credentials = parse_controller_credentials("external-controller: ':9097'\n")?;
+        assert_eq!(credentials.secret, None);
+        assert_eq!(
+            credentials.external_controller,
+            Some(("127.0.0.1".to_string(), 9097))
+        );
+
+        assert_eq!(
+            parse_controller_credentials("secret: ''\nmode: rule\n")?,
+            ControllerCredentials::default()
+        );
+        assert!(parse_controller_credentials("secret: [").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+// IPC 请求错误：按失败原因区分，调用方可据此匹配而非比较错误文本。
+// Display 输出与此前的字符串错误保持一致，便于日志与 Dart 侧展示。
+
+use std::fmt;
+
+use super::client::describe_http_error;
+
+// 请求超出调用方给定的总时限
+pub const BUDGET_EXCEEDED_ERROR: &str = "IPC 请求超出总时限";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpcError {
+    // 请求方法或路径非法，未发送
+    InvalidRequest(String),
+    // 连接 IPC 失败（Socket 不存在、管道繁忙等）
+    Connect(String),
+    // 超出总时限或读取响应超时
+    Timeout(String),
+    // 核心返回非 2xx 状态码
+    Http { status: u16, body: String },
+    // 核心返回未跟随的重定向
+    Redirect { status: u16, location: String },
+    // 响应无法解析（状态行、chunk 大小、非 UTF-8 响应体等）
+    Decode(String),
+    // 响应完成前连接被关闭
+    ClosedUnexpectedly,
+    // 读写连接失败
+    Io(String),
+}
+
+impl IpcError {
+    // 超出总时限
+    pub fn budget_exceeded() -> Self {
+        IpcError::Timeout(BUDGET_EXCEEDED_ERROR.to_string())
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, IpcError::Timeout(_))
+    }
+
+    // 核心返回的状态码；请求未到达核心时为 None
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            IpcError::Http { status, .. } | IpcError::Redirect { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for IpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpcError::InvalidRequest(message)
+            | IpcError::Connect(message)
+            | IpcError::Timeout(message)
+            | IpcError::Decode(message)
+            | IpcError::Io(message) => f.write_str(message),
+            IpcError::Http { status, body } => f.write_str(&describe_http_error(*status, body)),
+            IpcError::Redirect { status, location } => {
+                write!(f, "HTTP {}: 核心返回重定向，Location: {}", status, location)
+            }
+            IpcError::ClosedUnexpectedly => f.write_str("连接意外关闭"),
+        }
+    }
+}
+
+impl std::error::Error for IpcError {}
+
+// 便于返回 Result<_, String> 的调用方直接使用 ?
+impl From<IpcError> for String {
+    fn from(error: IpcError) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,280 @@
+// 测试用 IPC 模拟服务端：在临时 Unix Socket / Named Pipe 上按脚本返回 HTTP 响应，
+// 用于测试分块传输、缺少 Content-Length、错误状态码、慢响应与连接复用等场景
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+
+static NEXT_SERVER_ID: AtomicU32 = AtomicU32::new(0);
+
+// 单个脚本响应：按片段依次写出，片段之间可插入延迟
+#[derive(Clone)]
+pub struct MockResponse {
+    pub parts: Vec<Vec<u8>>,
+    pub delay_between_parts: Duration,
+    // 写完后主动关闭连接
+    pub should_close: bool,
+    // 不读取请求体，读完请求头即响应（模拟核心提前拒绝大请求体）
+    pub is_early: bool,
+}
+
+impl MockResponse {
+    // 带 Content-Length 的完整响应
+    pub fn text(status_code: u16, body: &str) -> Self {
+        Self::raw(format!(
+            "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            status_code,
+            body.len(),
+            body
+        ))
+    }
+
+    // 分块传输响应
+    pub fn chunked(status_code: u16, chunks: &[&str]) -> Self {
+        let mut raw = format!(
+            "HTTP/1.1 {} Mock\r\nTransfer-Encoding: chunked\r\n\r\n",
+            status_code
+        );
+        for chunk in chunks {
+            raw.push_str(&format!("{:x}\r\n{}\r\n", chunk.len(), chunk));
+        }
+        raw.push_str("0\r\n\r\n");
+        Self::raw(raw)
+    }
+
+    // 无 Content-Length，以关闭连接标记响应结束
+    pub fn until_close(status_code: u16, body: &str) -> Self {
+        let mut response = Self::raw(format!("HTTP/1.1 {} Mock\r\n\r\n{}", status_code, body));
+        response.should_close = true;
+        response
+    }
+
+    pub fn raw(raw: impl Into<String>) -> Self {
+        Self::bytes(raw.into().into_bytes())
+    }
+
+    // 原样写出的字节报文，可包含非 UTF-8 内容
+    pub fn bytes(raw: Vec<u8>) -> Self {
+        Self {
+            parts: vec![raw],
+            delay_between_parts: Duration::ZERO,
+            should_close: false,
+            is_early: false,
+        }
+    }
+
+    // 将响应拆成多个片段慢速写出
+    pub fn slowly(mut self, part_size: usize, delay: Duration) -> Self {
+        let whole = self.parts.concat();
+        self.parts = whole.chunks(part_size.max(1)).map(<[u8]>::to_vec).collect();
+        self.delay_between_parts = delay;
+        self
+    }
+
+    pub fn closing(mut self) -> Self {
+        self.should_close = true;
+        self
+    }
+
+    // 读完请求头即响应并关闭连接，不读取请求体
+    pub fn early(mut self) -> Self {
+        self.is_early = true;
+        self.should_close = true;
+        self
+    }
+}
+
+// 服务端统计：用于断言连接是否被复用
+#[derive(Default)]
+pub struct MockStats {
+    pub connections: AtomicUsize,
+    pub requests: AtomicUsize,
+}
+
+pub struct MockIpcServer {
+    path: String,
+    stats: Arc<MockStats>,
+    task: JoinHandle<()>,
+}
+
+impl MockIpcServer {
+    // 启动服务端，响应按请求到达顺序依次取用（跨连接共享）
+    pub async fn start(responses: Vec<MockResponse>) -> Result<Self, String> {
+        let path = Self::unique_path();
+        let stats = Arc::new(MockStats::default());
+        let script = Arc::new(Mutex::new(responses.into_iter()));
+        let task = Self::spawn_listener(&path, script, stats.clone())?;
+
+        Ok(Self { path, stats, task })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn connections(&self) -> usize {
+        self.stats.connections.load(Ordering::SeqCst)
+    }
+
+    pub fn requests(&self) -> usize {
+        self.stats.requests.load(Ordering::SeqCst)
+    }
+
+    fn unique_path() -> String {
+        let id = NEXT_SERVER_ID.fetch_add(1, Ordering::SeqCst);
+
+        #[cfg(windows)]
+        {
+            format!(r"\\.\pipe\stelliberty_test_{}_{}", std::process::id(), id)
+        }
+
+        #[cfg(unix)]
+        {
+            std::env::temp_dir()
+                .join(format!(
+                    "stelliberty_test_{}_{}.sock",
+                    std::process::id(),
+                    id
+                ))
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    #[cfg(unix)]
+    fn spawn_listener(
+        path: &str,
+        script: Arc<Mutex<std::vec::IntoIter<MockResponse>>>,
+        stats: Arc<MockStats>,
+    ) -> Result<JoinHandle<()>, String> {
+        let _ = std::fs::remove_file(path);
+        let listener = tokio::net::UnixListener::bind(path)
+            .map_err(|e| format!("绑定测试 Socket 失败：{}", e))?;
+
+        Ok(tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                stats.connections.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(serve_connection(stream, script.clone(), stats.clone()));
+            }
+        }))
+    }
+
+    #[cfg(windows)]
+    fn spawn_listener(
+        path: &str,
+        script: Arc<Mutex<std::vec::IntoIter<MockResponse>>>,
+        stats: Arc<MockStats>,
+    ) -> Result<JoinHandle<()>, String> {
+        use tokio::net::windows::named_pipe::ServerOptions;
+
+        let path = path.to_string();
+        let mut server = ServerOptions::new()
+            .first_pipe_instance(true)
+            .create(&path)
+            .map_err(|e| format!("创建测试 Named Pipe 失败：{}", e))?;
+
+        Ok(tokio::spawn(async move {
+            loop {
+                if server.connect().await.is_err() {
+                    break;
+                }
+                // 先创建下一个实例再交出当前连接，保证客户端随时可连
+                let next = match ServerOptions::new().create(&path) {
+                    Ok(next) => next,
+                    Err(_) => break,
+                };
+                let connected = std::mem::replace(&mut server, next);
+                stats.connections.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(serve_connection(connected, script.clone(), stats.clone()));
+            }
+        }))
+    }
+}
+
+impl Drop for MockIpcServer {
+    fn drop(&mut self) {
+        self.task.abort();
+        #[cfg(unix)]
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+// 逐个读取请求并写出脚本响应，直到脚本耗尽或需要关闭连接
+async fn serve_connection<S>(
+    stream: S,
+    script: Arc<Mutex<std::vec::IntoIter<MockResponse>>>,
+    stats: Arc<MockStats>,
+) where
+    S: AsyncReadExt + AsyncWriteExt + Unpin,
+{
+    let mut reader = BufReader::new(stream);
+
+    loop {
+        let Some(content_length) = read_request_head(&mut reader).await else {
+            return;
+        };
+        stats.requests.fetch_add(1, Ordering::SeqCst);
+
+        let Some(response) = script.lock().await.next() else {
+            return;
+        };
+
+        if !response.is_early {
+            let mut body = vec![0u8; content_length];
+            if reader.read_exact(&mut body).await.is_err() {
+                return;
+            }
+        }
+
+        let stream = reader.get_mut();
+        for (index, part) in response.parts.iter().enumerate() {
+            if index > 0 && !response.delay_between_parts.is_zero() {
+                tokio::time::sleep(response.delay_between_parts).await;
+            }
+            if stream.write_all(part).await.is_err() {
+                return;
+            }
+            let _ = stream.flush().await;
+        }
+
+        if response.should_close {
+            let _ = stream.shutdown().await;
+            return;
+        }
+    }
+}
+
+// 读取请求头并返回 Content-Length，连接关闭时返回 None
+async fn read_request_head<R>(reader: &mut BufReader<R>) -> Option<usize>
+where
+    R: AsyncReadExt + Unpin,
+{
+    let mut content_length = 0usize;
+    let mut is_first_line = true;
+
+    loop {
+        let mut line = String::new();
+        match reader.read_line(&mut line).await {
+            Ok(0) | Err(_) => return None,
+            Ok(_) => {}
+        }
+
+        if line == "\r\n" {
+            break;
+        }
+        if is_first_line {
+            is_first_line = false;
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':')
+            && key.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+
+    Some(content_length)
+}
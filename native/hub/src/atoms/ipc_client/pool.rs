@@ -0,0 +1,78 @@
+// IPC 空闲连接池：单个同步锁保护的队列。
+// hub 运行在单线程运行时上，分片无法降低竞争；临界区只做出入队，连接有效性校验在锁外进行。
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+pub(super) struct IdlePool<T> {
+    items: Mutex<VecDeque<T>>,
+    capacity: AtomicUsize,
+}
+
+impl<T> IdlePool<T> {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            items: Mutex::new(VecDeque::new()),
+            capacity: AtomicUsize::new(capacity),
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub(super) fn capacity(&self) -> usize {
+        self.capacity.load(Ordering::Acquire)
+    }
+
+    // 调整容量；缩小时已有的空闲项保留，之后归还的项在池满时被丢弃
+    pub(super) fn set_capacity(&self, capacity: usize) {
+        self.capacity.store(capacity, Ordering::Release);
+    }
+
+    // 取出最早放入的空闲项
+    pub(super) fn pop(&self) -> Option<T> {
+        self.lock().pop_front()
+    }
+
+    // 放回一项，池已满时原样返回由调用方丢弃
+    pub(super) fn push(&self, item: T) -> Result<(), T> {
+        let mut items = self.lock();
+        if items.len() >= self.capacity() {
+            return Err(item);
+        }
+        items.push_back(item);
+        Ok(())
+    }
+
+    // 取出全部空闲项
+    pub(super) fn drain(&self) -> Vec<T> {
+        self.lock().drain(..).collect()
+    }
+
+    // 锁中毒时继续使用：临界区只做出入队，不会留下不一致状态
+    fn lock(&self) -> MutexGuard<'_, VecDeque<T>> {
+        self.items.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IdlePool;
+
+    #[test]
+    fn keeps_fifo_order_and_capacity() -> Result<(), String> {
+        let pool = IdlePool::new(2);
+        pool.push(1).map_err(|_| "连接池不应已满".to_string())?;
+        pool.push(2).map_err(|_| "连接池不应已满".to_string())?;
+        assert_eq!(pool.push(3), Err(3));
+
+        assert_eq!(pool.pop(), Some(1));
+        pool.set_capacity(1);
+        assert_eq!(pool.push(4), Err(4));
+        assert_eq!(pool.drain(), vec![2]);
+        assert_eq!(pool.len(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+// IPC 请求调度：在取连接前按优先级放行，避免批量测速占满连接后界面请求排在探测之后。
+// 交互请求（切换节点、读取配置等）始终立即放行；批量请求的并发上限为
+// 连接池容量减去为交互请求预留的份额，超出时按到达顺序等待。
+
+use std::sync::{Mutex, MutexGuard};
+use tokio::sync::Notify;
+
+// 为交互请求预留的连接数
+pub(super) const RESERVED_INTERACTIVE_SLOTS: usize = 2;
+
+// 请求优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpcPriority {
+    // 用户操作触发的请求，不排队
+    Interactive,
+    // 批量延迟测试等大量探测请求，受并发上限约束
+    Bulk,
+}
+
+pub(super) struct PriorityScheduler {
+    in_flight_bulk: Mutex<usize>,
+    released: Notify,
+}
+
+// 放行凭证，批量请求的凭证释放时归还名额
+pub(super) struct Admission<'a> {
+    bulk_scheduler: Option<&'a PriorityScheduler>,
+}
+
+impl PriorityScheduler {
+    pub(super) const fn new() -> Self {
+        Self {
+            in_flight_bulk: Mutex::new(0),
+            released: Notify::const_new(),
+        }
+    }
+
+    // 给定连接池容量下批量请求可同时占用的连接数（至少为 1）
+    pub(super) fn bulk_limit(pool_capacity: usize) -> usize {
+        pool_capacity
+            .saturating_sub(RESERVED_INTERACTIVE_SLOTS)
+            .max(1)
+    }
+
+    // 按优先级等待放行；等待期间被取消不会占用名额
+    pub(super) async fn admit(&self, priority: IpcPriority, pool_capacity: usize) -> Admission<'_> {
+        if priority == IpcPriority::Interactive {
+            return Admission {
+                bulk_scheduler: None,
+            };
+        }
+
+        let limit = Self::bulk_limit(pool_capacity);
+        let mut has_waited = false;
+        loop {
+            // 先注册唤醒再检查名额，避免检查后、等待前的释放被错过
+            let released = self.released.notified();
+            tokio::pin!(released);
+            released.as_mut().enable();
+
+            {
+                let mut in_flight = self.lock_bulk();
+                if *in_flight < limit {
+                    *in_flight += 1;
+                    return Admission {
+                        bulk_scheduler: Some(self),
+                    };
+                }
+            }
+            if !has_waited {
+                has_waited = true;
+                log::trace!("批量 IPC 请求已达并发上限 {}，排队等待", limit);
+            }
+            released.await;
+        }
+    }
+
+    // 临界区只做计数，锁中毒时继续使用
+    fn lock_bulk(&self) -> MutexGuard<'_, usize> {
+        self.in_flight_bulk
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for Admission<'_> {
+    fn drop(&mut self) {
+        let Some(scheduler) = self.bulk_scheduler else {
+            return;
+        };
+        {
+            let mut in_flight = scheduler.lock_bulk();
+            *in_flight = in_flight.saturating_sub(1);
+        }
+        scheduler.released.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IpcPriority, PriorityScheduler};
+    use std::time::Duration;
+
+    // 批量请求占满名额时，新的批量请求排队，交互请求仍立即放行
+    #[tokio::test]
+    async fn interactive_requests_bypass_saturated_bulk_queue() -> Result<(), String> {
+        let scheduler = PriorityScheduler::new();
+        let capacity = 4;
+        let limit = PriorityScheduler::bulk_limit(capacity);
+
+        let mut bulk = Vec::new();
+        for _ in 0..limit {
+            bulk.push(scheduler.admit(IpcPriority::Bulk, capacity).await);
+        }
+
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(50),
+            scheduler.admit(IpcPriority::Bulk, capacity),
+        )
+        .await;
+        assert!(blocked.is_err());
+
+        tokio::time::timeout(
+            Duration::from_millis(50),
+            scheduler.admit(IpcPriority::Interactive, capacity),
+        )
+        .await
+        .map_err(|_| "交互请求不应排队".to_string())?;
+
+        // 释放一个批量名额后，排队的批量请求得以放行
+        let waiting = scheduler.admit(IpcPriority::Bulk, capacity);
+        drop(bulk.pop());
+        let _admitted = tokio::time::timeout(Duration::from_millis(50), waiting)
+            .await
+            .map_err(|_| "释放名额后批量请求仍在排队".to_string())?;
+        assert_eq!(*scheduler.lock_bulk(), limit);
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+// IPC 报文跟踪：在 trace 级别输出请求与响应，便于排查与核心的交互问题。
+// 默认关闭，需运行时显式开启；输出前脱敏并截断，避免泄露凭据或刷屏。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// 单条报文最多输出的字符数
+const MAX_TRACE_CHARS: usize = 2048;
+
+static IS_IPC_TRACE_ENABLED: AtomicBool = AtomicBool::new(false);
+
+// 需要隐藏的凭据字段名关键字，字段名包含任一关键字即隐藏（如 obfs-password、up-psk）
+const SECRET_KEY_WORDS: &str =
+    "password|passwd|secret|token|auth-str|private-key|pre-shared-key|psk|uuid";
+
+// 脱敏规则：匹配的第一个分组保留，其后的值替换为占位内容
+struct Redaction {
+    regex: Regex,
+    replacement: &'static str,
+}
+
+// 任一规则编译失败时为 Err，此时不输出报文内容
+static REDACTIONS: Lazy<Result<Vec<Redaction>, regex::Error>> = Lazy::new(|| {
+    let secret_key = format!(r"[\w-]*(?:{})[\w-]*", SECRET_KEY_WORDS);
+    let rules = [
+        // Authorization 请求头
+        (r"(?im)^(authorization:\s*).*$".to_string(), "${1}<已隐藏>"),
+        // JSON 中的密码类字段
+        (
+            format!(r#"(?i)("{}"\s*:\s*)"(?:[^"\\]|\\.)*""#, secret_key),
+            "${1}\"<已隐藏>\"",
+        ),
+        // YAML 中的密码类字段，包括 PUT /configs 的 payload 字符串里转义后的换行
+        (
+            format!(
+                r#"(?i)((?:^|\n|\\n|[{{,"])[ \t-]*{}[ \t]*:[ \t]*)(?:[^\n\\"]|\\[^n])*"#,
+                secret_key
+            ),
+            "${1}<已隐藏>",
+        ),
+        // URL 查询参数中的 secret、token
+        (
+            r"(?i)([?&](?:secret|token)=)[^&\s#]*".to_string(),
+            "${1}<已隐藏>",
+        ),
+    ];
+    rules
+        .into_iter()
+        .map(|(pattern, replacement)| {
+            Ok(Redaction {
+                regex: Regex::new(&pattern)?,
+                replacement,
+            })
+        })
+        .collect()
+});
+
+// 开启或关闭 IPC 报文跟踪
+pub fn set_trace_enabled(is_enabled: bool) {
+    IS_IPC_TRACE_ENABLED.store(is_enabled, Ordering::Relaxed);
+    log::info!("IPC 报文跟踪已{}", if is_enabled { "开启" } else { "关闭" });
+}
+
+pub fn is_trace_enabled() -> bool {
+    IS_IPC_TRACE_ENABLED.load(Ordering::Relaxed) && log::log_enabled!(log::Level::Trace)
+}
+
+// 输出请求报文
+pub fn trace_request(method: &str, path: &str, raw_request: &str) {
+    if !is_trace_enabled() {
+        return;
+    }
+
+    log::trace!("IPC → {} {}\n{}", method, path, sanitize(raw_request));
+}
+
+// 输出响应摘要与响应体
+pub fn trace_response(method: &str, path: &str, status_code: u16, body: &str) {
+    if !is_trace_enabled() {
+        return;
+    }
+
+    log::trace!(
+        "IPC ← {} {}：HTTP {}（{} 字节）\n{}",
+        method,
+        path,
+        status_code,
+        body.len(),
+        sanitize(body)
+    );
+}
+
+// 截断并脱敏报文：先截断再脱敏，大型配置只处理实际输出的部分；
+// 脱敏规则不可用时整段省略，避免输出未脱敏的凭据
+fn sanitize(text: &str) -> String {
+    let redactions = match REDACTIONS.as_ref() {
+        Ok(redactions) => redactions,
+        Err(e) => {
+            log::warn!("IPC 报文脱敏规则不可用：{}", e);
+            return format!("[无法脱敏，已省略 {} 字节]", text.len());
+        }
+    };
+
+    let truncated_at = text
+        .char_indices()
+        .nth(MAX_TRACE_CHARS)
+        .map(|(index, _)| index);
+    let mut sanitized = text[..truncated_at.unwrap_or(text.len())].to_string();
+    for redaction in redactions {
+        sanitized = redaction
+            .regex
+            .replace_all(&sanitized, redaction.replacement)
+            .into_owned();
+    }
+
+    if truncated_at.is_some() {
+        format!("{}…[已截断，总长度：{}字节]", sanitized, text.len())
+    } else {
+        sanitized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_TRACE_CHARS, sanitize};
+
+    #[test]
+    fn hides_headers_and_json_fields() {
+        let request = "PUT /configs HTTP/1.1\r\nAuthorization: Bearer abc\r\n\r\n{\"secret\":\"s3\",\"name\":\"a\"}";
+        let sanitized = sanitize(request);
+        assert!(!sanitized.contains("abc"));
+        assert!(!sanitized.contains("s3"));
+        assert!(sanitized.contains("Authorization: <已隐藏>"));
+        assert!(sanitized.contains(r#""secret":"<已隐藏>""#));
+        assert!(sanitized.contains(r#""name":"a""#));
+    }
+
+    // payload 中的 YAML 以转义换行出现在 JSON 字符串里
+    #[test]
+    fn hides_yaml_fields_inside_payload() {
+        let body = r#"{"path":"","payload":"secret: top\nproxies:\n  - name: a\n    password: \"p,w\"\n    port: 443\n  - {name: b, password: inline}"}"#;
+        let sanitized = sanitize(body);
+        for leaked in ["top", "p,w", "inline"] {
+            assert!(!sanitized.contains(leaked), "{}", sanitized);
+        }
+        assert!(sanitized.contains(r"\n    port: 443"));
+        assert!(sanitized.contains("name: a"));
+    }
+
+    #[test]
+    fn hides_keys_containing_secret_words() {
+        let config = "proxies:\n  - name: hy2\n    obfs-password: obfs1\n    up: 100\n  - name: vmess\n    uuid: 7f5c-uuid\n    pre-shared-key: psk1\n  - {name: snell, psk: psk2}\n";
+        let sanitized = sanitize(config);
+        for leaked in ["obfs1", "7f5c-uuid", "psk1", "psk2"] {
+            assert!(!sanitized.contains(leaked), "{}", sanitized);
+        }
+        assert!(sanitized.contains("obfs-password: <已隐藏>"));
+        assert!(sanitized.contains("up: 100"));
+
+        let json = sanitize(r#"{"obfs-password":"obfs1","uuid":"7f5c-uuid","name":"a"}"#);
+        assert_eq!(
+            json,
+            r#"{"obfs-password":"<已隐藏>","uuid":"<已隐藏>","name":"a"}"#
+        );
+    }
+
+    #[test]
+    fn redacts_after_truncating() {
+        let text = format!("{}password: abc", "x".repeat(MAX_TRACE_CHARS - 12));
+        let sanitized = sanitize(&text);
+        assert!(!sanitized.contains("ab"), "{}", sanitized);
+        assert!(sanitized.contains("[已截断"));
+    }
+
+    #[test]
+    fn hides_secret_query_values() {
+        let sanitized = sanitize("GET /logs?level=info&secret=abc&token=xyz HTTP/1.1");
+        assert_eq!(
+            sanitized,
+            "GET /logs?level=info&secret=<已隐藏>&token=<已隐藏> HTTP/1.1"
+        );
+    }
+
+    #[test]
+    fn truncates_long_bodies() {
+        let sanitized = sanitize(&"x".repeat(MAX_TRACE_CHARS + 10));
+        assert!(sanitized.starts_with(&"x".repeat(MAX_TRACE_CHARS)));
+        assert!(sanitized.ends_with(&format!("[已截断，总长度：{}字节]", MAX_TRACE_CHARS + 10)));
+    }
+}
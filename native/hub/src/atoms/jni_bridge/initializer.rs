@@ -1,11 +1,74 @@
 // Android JNI 初始化器
 
 use jni::JNIEnv;
-use jni::objects::{GlobalRef, JObject};
-use std::sync::OnceLock;
+use jni::objects::{GlobalRef, JObject, JValue};
+use std::sync::Mutex;
 
-static ACTIVITY_REF: OnceLock<GlobalRef> = OnceLock::new();
+// 初始化失败时抛给 Kotlin 的异常类型
+const INIT_EXCEPTION_CLASS: &str = "java/lang/IllegalStateException";
+// logcat 标签（Rust 日志器未就绪时使用）
+const LOGCAT_TAG: &str = "StellibertyNative";
 
+// 当前 Activity 的全局引用。Activity 会在旋转、切换主题等配置变更时重建，
+// 因此每次初始化都替换为新的引用，并释放旧引用
+static ACTIVITY_REF: Mutex<Option<GlobalRef>> = Mutex::new(None);
+
+// 获取 Application Context：生命周期与进程一致，不随 Activity 重建失效
+fn application_context<'a>(env: &mut JNIEnv<'a>, activity: &JObject<'a>) -> Option<JObject<'a>> {
+    let result = env
+        .call_method(
+            activity,
+            "getApplicationContext",
+            "()Landroid/content/Context;",
+            &[],
+        )
+        .and_then(|value| value.l());
+
+    match result {
+        Ok(context) if !context.is_null() => Some(context),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("获取 Application Context 失败: {:?}", e);
+            let _ = env.exception_clear();
+            None
+        }
+    }
+}
+
+// 输出错误日志：Rust 日志器尚未初始化时，经当前线程的 JNIEnv 调用 android.util.Log，
+// 保证错误在 logcat 中可见。JNI 回调线程必然已附加到 VM，无需另行 attach
+fn log_error(env: &mut JNIEnv, message: &str) {
+    if log::max_level() != log::LevelFilter::Off {
+        log::error!("{}", message);
+        return;
+    }
+
+    let (Ok(tag), Ok(text)) = (env.new_string(LOGCAT_TAG), env.new_string(message)) else {
+        let _ = env.exception_clear();
+        return;
+    };
+    let result = env.call_static_method(
+        "android/util/Log",
+        "e",
+        "(Ljava/lang/String;Ljava/lang/String;)I",
+        &[JValue::Object(&tag), JValue::Object(&text)],
+    );
+    if result.is_err() {
+        let _ = env.exception_clear();
+    }
+}
+
+// 记录错误并向 Kotlin 抛出异常，使调用方感知初始化失败
+fn fail(env: &mut JNIEnv, message: String) {
+    // 清除此前 JNI 调用遗留的异常，否则无法抛出新异常
+    let _ = env.exception_clear();
+    log_error(env, &message);
+    if let Err(e) = env.throw_new(INIT_EXCEPTION_CLASS, &message) {
+        log_error(env, &format!("抛出 Java 异常失败: {:?}", e));
+    }
+}
+
+// 初始化 Android 上下文；失败时抛出 IllegalStateException
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_io_github_stelliberty_MainActivity_initAndroidContext<'a>(
     mut env: JNIEnv<'a>,
@@ -15,33 +78,58 @@ pub extern "system" fn Java_io_github_stelliberty_MainActivity_initAndroidContex
     let vm = match env.get_java_vm() {
         Ok(vm) => vm,
         Err(e) => {
-            log::error!("获取 JavaVM 失败: {:?}", e);
+            fail(&mut env, format!("获取 JavaVM 失败: {:?}", e));
             return;
         }
     };
 
-    // init_hosted 会消费 activity，先复制一份
-    let activity_for_ndk = unsafe { JObject::from_raw(activity.as_raw()) };
-    if let Err(e) = rustls_platform_verifier::android::init_hosted(&mut env, activity) {
-        log::error!("rustls-platform-verifier 初始化失败: {:?}", e);
+    // rustls-platform-verifier 只会初始化一次并永久持有传入的 Context，
+    // 优先传入 Application Context，避免持有已销毁的 Activity。
+    // init_hosted 会消费传入对象，回退时先复制一份 activity
+    let verifier_context = application_context(&mut env, &activity)
+        .unwrap_or_else(|| unsafe { JObject::from_raw(activity.as_raw()) });
+    if let Err(e) = rustls_platform_verifier::android::init_hosted(&mut env, verifier_context) {
+        fail(
+            &mut env,
+            format!("rustls-platform-verifier 初始化失败: {:?}", e),
+        );
         return;
     }
     log::info!("rustls-platform-verifier 初始化成功");
 
-    let global_activity = match env.new_global_ref(&activity_for_ndk) {
+    let global_activity = match env.new_global_ref(&activity) {
         Ok(global) => global,
         Err(e) => {
-            log::error!("创建全局引用失败: {:?}", e);
+            fail(&mut env, format!("创建全局引用失败: {:?}", e));
             return;
         }
     };
 
     let activity_ptr = global_activity.as_raw();
-    let _ = ACTIVITY_REF.set(global_activity);
-
     let vm_ptr = vm.get_java_vm_pointer();
+
+    let mut activity_ref = ACTIVITY_REF.lock().unwrap_or_else(|e| {
+        log::error!("获取 Activity 引用锁失败：{}", e);
+        e.into_inner()
+    });
+
+    // Activity 重建：ndk-context 不允许重复初始化，需先释放旧上下文
+    let is_reinitializing = activity_ref.is_some();
     unsafe {
+        if is_reinitializing {
+            ndk_context::release_android_context();
+        }
         ndk_context::initialize_android_context(vm_ptr.cast(), activity_ptr.cast());
     }
-    log::info!("ndk-context 初始化成功");
+
+    // 新上下文生效后再替换并释放旧的全局引用（GlobalRef 析构时删除 JNI 引用）
+    let previous = activity_ref.replace(global_activity);
+    drop(activity_ref);
+    drop(previous);
+
+    if is_reinitializing {
+        log::info!("ndk-context 已随 Activity 重建重新初始化");
+    } else {
+        log::info!("ndk-context 初始化成功");
+    }
 }
@@ -1,6 +1,8 @@
 // 日志初始化原子模块
 
 pub mod initializer;
+pub mod runtime_filter;
 
 // 导出公共接口
 pub use initializer::init;
+pub use runtime_filter::set_log_filter;
@@ -1,3 +1,4 @@
+use crate::atoms::shutdown;
 use chrono::Local;
 use log;
 use once_cell::sync::Lazy;
@@ -7,13 +8,14 @@ use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::PathBuf;
 use std::sync::Mutex;
-use tokio::spawn;
 
 #[cfg(not(target_os = "android"))]
 use env_logger;
 
 #[cfg(target_os = "android")]
-use android_logger::{Config, FilterBuilder};
+use android_logger::Config;
+
+use super::runtime_filter::{self, ReloadableLogger};
 
 // Dart → Rust：设置应用日志开关请求
 #[derive(Deserialize, DartSignal)]
@@ -38,6 +40,34 @@ impl SetAppLogEnabled {
     }
 }
 
+// Dart → Rust：设置模块级日志过滤规则（如 ipc_client=info,delay_testing=trace，空串恢复默认）
+#[derive(Deserialize, DartSignal)]
+pub struct SetLogFilter {
+    pub filter: String,
+}
+
+// Rust → Dart：设置日志过滤规则响应
+#[derive(Serialize, RustSignal)]
+pub struct SetLogFilterResult {
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+impl SetLogFilter {
+    // 处理设置日志过滤规则请求
+    pub fn handle(&self) {
+        let result = runtime_filter::set_log_filter(&self.filter);
+        if let Err(e) = &result {
+            log::warn!("{}", e);
+        }
+        SetLogFilterResult {
+            is_successful: result.is_ok(),
+            error_message: result.err(),
+        }
+        .send_signal_to_dart();
+    }
+}
+
 const MAX_LOG_FILE_SIZE: u64 = 10 * 1024 * 1024; // 10MB 轮转阈值
 
 static LOG_FILE_PATH: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
@@ -47,20 +77,11 @@ static LOGGER: Lazy<()> = Lazy::new(|| {
     #[cfg(target_os = "android")]
     {
         // Android 平台：使用 android_logger 输出到 logcat，自定义格式
-        android_logger::init_once(
+        let logger = android_logger::AndroidLogger::new(
             Config::default()
-                .with_max_level(if cfg!(debug_assertions) {
-                    log::LevelFilter::Debug
-                } else {
-                    log::LevelFilter::Info
-                })
+                // 过滤交由 ReloadableLogger 处理，这里放行所有等级
+                .with_max_level(log::LevelFilter::Trace)
                 .with_tag("hub")
-                // 使用 FilterBuilder 过滤第三方库日志
-                .with_filter(
-                    FilterBuilder::new()
-                        .parse("debug,tungstenite=warn,tokio_tungstenite=warn,reqwest=warn,hyper=warn,h2=warn")
-                        .build()
-                )
                 // 自定义格式：添加时间戳和等级标签
                 .format(|f, record| {
                     // 时间戳
@@ -79,22 +100,24 @@ static LOGGER: Lazy<()> = Lazy::new(|| {
                         log::Level::Trace => "[RsTrace]",
                     };
 
-                    write!(f, "{} {} {} >> {}", level_str, timestamp, path_with_dots, record.args())
-                })
+                    write!(
+                        f,
+                        "{} {} {} >> {}",
+                        level_str,
+                        timestamp,
+                        path_with_dots,
+                        record.args()
+                    )
+                }),
         );
+        install_logger(logger);
     }
 
     #[cfg(not(target_os = "android"))]
     {
-        // 桌面平台：使用 env_logger
-        let default_level = if cfg!(debug_assertions) {
-            "debug,tungstenite=warn,tokio_tungstenite=warn,reqwest=warn,hyper=warn,h2=warn"
-        } else {
-            "info,tungstenite=warn,tokio_tungstenite=warn,reqwest=warn,hyper=warn,h2=warn"
-        };
-        let env = env_logger::Env::default().default_filter_or(default_level);
-
-        env_logger::Builder::from_env(env)
+        // 桌面平台：使用 env_logger 格式化输出，过滤交由 ReloadableLogger 处理
+        let logger = env_logger::Builder::new()
+            .filter_level(log::LevelFilter::Trace)
             .format(|buf, record| {
                 let timestamp = Local::now().format("%Y/%m/%d %H:%M:%S");
                 let file = record.file().unwrap_or("unknown");
@@ -147,10 +170,18 @@ static LOGGER: Lazy<()> = Lazy::new(|| {
 
                 Ok(())
             })
-            .init();
+            .build();
+        install_logger(logger);
     }
 });
 
+// 安装带运行时过滤的日志器（默认规则即单一全局等级）
+fn install_logger<L: log::Log + 'static>(logger: L) {
+    if log::set_boxed_logger(Box::new(ReloadableLogger::new(logger))).is_ok() {
+        log::set_max_level(runtime_filter::max_level());
+    }
+}
+
 // 写入日志到文件（受 Dart 端开关控制，多进程安全，失败静默）
 fn write_to_file(log_line: &str) -> std::io::Result<()> {
     // 检查开关状态
@@ -230,7 +261,7 @@ pub fn setup_logger() {
 
 // 初始化消息监听器
 pub fn init_message_listener() {
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = SetAppLogEnabled::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -238,6 +269,14 @@ pub fn init_message_listener() {
         }
         log::info!("应用日志开关消息通道已关闭，退出监听器");
     });
+
+    shutdown::spawn_listener(async {
+        let receiver = SetLogFilter::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+        log::info!("日志过滤规则消息通道已关闭，退出监听器");
+    });
 }
 
 // 统一初始化函数：设置日志路径、初始化日志系统和消息监听器
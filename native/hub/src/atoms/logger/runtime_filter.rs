@@ -0,0 +1,204 @@
+// 运行时日志过滤：在底层日志器之外包一层可替换的过滤器，
+// 支持按模块设置等级（如 ipc_client=info,delay_testing=trace），无需重启即可生效
+
+use log::{LevelFilter, Log, Metadata, Record};
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+// 本 crate 的模块前缀，用于展开简写的模块名
+const CRATE_NAME: &str = env!("CARGO_CRATE_NAME");
+
+// 简写模块名展开时尝试的分层
+const MODULE_LAYERS: [&str; 2] = ["atoms", "molecules"];
+
+// 第三方库日志默认降噪
+const NOISY_CRATES: &str =
+    "tungstenite=warn,tokio_tungstenite=warn,reqwest=warn,hyper=warn,h2=warn";
+
+// 当前生效的过滤器
+static ACTIVE_FILTER: Lazy<RwLock<env_filter::Filter>> =
+    Lazy::new(|| RwLock::new(build_filter(&base_spec(), None).unwrap_or_else(|_| fallback())));
+
+// 默认过滤规则：单一全局等级 + 第三方库降噪；桌面平台优先读取 RUST_LOG
+pub fn base_spec() -> String {
+    #[cfg(not(target_os = "android"))]
+    if let Ok(spec) = std::env::var("RUST_LOG")
+        && !spec.trim().is_empty()
+    {
+        return spec;
+    }
+
+    let level = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "info"
+    };
+    format!("{},{}", level, NOISY_CRATES)
+}
+
+fn fallback() -> env_filter::Filter {
+    env_filter::Builder::new()
+        .filter_level(LevelFilter::Info)
+        .build()
+}
+
+// 在默认规则之上叠加模块规则，同名规则以后者为准
+fn build_filter(base: &str, overrides: Option<&str>) -> Result<env_filter::Filter, String> {
+    let mut builder = env_filter::Builder::new();
+    builder
+        .try_parse(base)
+        .map_err(|e| format!("解析默认日志过滤规则失败：{}", e))?;
+
+    if let Some(overrides) = overrides {
+        builder
+            .try_parse(&expand_module_names(overrides))
+            .map_err(|e| format!("解析日志过滤规则失败：{}", e))?;
+    }
+
+    Ok(builder.build())
+}
+
+// 将简写模块名（如 ipc_client）展开为本 crate 下的完整路径，原名保留以匹配第三方库
+fn expand_module_names(spec: &str) -> String {
+    let (directives, message_filter) = match spec.split_once('/') {
+        Some((directives, regex)) => (directives, Some(regex)),
+        None => (spec, None),
+    };
+
+    let mut expanded = Vec::new();
+    for directive in directives.split(',').map(str::trim) {
+        if directive.is_empty() {
+            continue;
+        }
+        expanded.push(directive.to_string());
+
+        let Some((name, level)) = directive.split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        if name.contains("::") || name == CRATE_NAME {
+            continue;
+        }
+        for layer in MODULE_LAYERS {
+            expanded.push(format!(
+                "{}::{}::{}={}",
+                CRATE_NAME,
+                layer,
+                name,
+                level.trim()
+            ));
+        }
+    }
+
+    let mut result = expanded.join(",");
+    if let Some(regex) = message_filter {
+        result.push('/');
+        result.push_str(regex);
+    }
+    result
+}
+
+// 设置模块级过滤规则；传入空串恢复默认规则
+pub fn set_log_filter(spec: &str) -> Result<(), String> {
+    let spec = spec.trim();
+    let overrides = (!spec.is_empty()).then_some(spec);
+    let filter = build_filter(&base_spec(), overrides)?;
+    let max_level = filter.filter();
+
+    match ACTIVE_FILTER.write() {
+        Ok(mut guard) => *guard = filter,
+        Err(e) => return Err(format!("获取日志过滤器锁失败：{}", e)),
+    }
+    log::set_max_level(max_level);
+
+    match overrides {
+        Some(spec) => log::info!("日志过滤规则已更新：{}", spec),
+        None => log::info!("日志过滤规则已恢复默认"),
+    }
+    Ok(())
+}
+
+// 当前过滤规则允许的最高等级
+pub fn max_level() -> LevelFilter {
+    ACTIVE_FILTER
+        .read()
+        .map(|filter| filter.filter())
+        .unwrap_or(LevelFilter::Info)
+}
+
+// 可替换过滤器的日志器包装：底层日志器只负责格式化与输出
+pub struct ReloadableLogger<L: Log> {
+    inner: L,
+}
+
+impl<L: Log> ReloadableLogger<L> {
+    pub fn new(inner: L) -> Self {
+        Self { inner }
+    }
+}
+
+impl<L: Log> Log for ReloadableLogger<L> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        ACTIVE_FILTER
+            .read()
+            .map(|filter| filter.enabled(metadata))
+            .unwrap_or(false)
+    }
+
+    fn log(&self, record: &Record) {
+        let is_matched = ACTIVE_FILTER
+            .read()
+            .map(|filter| filter.matches(record))
+            .unwrap_or(false);
+        if is_matched {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CRATE_NAME, build_filter, expand_module_names};
+    use log::{Level, Metadata};
+
+    #[test]
+    fn expands_short_module_names() {
+        assert_eq!(
+            expand_module_names("ipc_client=trace, info"),
+            format!(
+                "ipc_client=trace,{0}::atoms::ipc_client=trace,{0}::molecules::ipc_client=trace,info",
+                CRATE_NAME
+            )
+        );
+        // 完整路径与本 crate 名不再展开，消息过滤保留在末尾
+        let spec = format!("{}=warn,reqwest::connect=debug/timeout", CRATE_NAME);
+        assert_eq!(expand_module_names(&spec), spec);
+        assert_eq!(expand_module_names(" , "), "");
+    }
+
+    #[test]
+    fn overrides_apply_on_top_of_base_spec() -> Result<(), String> {
+        let filter = build_filter(
+            "info,reqwest=warn",
+            Some("delay_testing=trace,reqwest=debug"),
+        )?;
+        let is_enabled = |target: &str, level: Level| {
+            filter.enabled(&Metadata::builder().target(target).level(level).build())
+        };
+
+        let tester = format!("{}::molecules::delay_testing::tester", CRATE_NAME);
+        assert!(is_enabled(&tester, Level::Trace));
+        assert!(!is_enabled(
+            &format!("{}::atoms::ipc_client", CRATE_NAME),
+            Level::Debug
+        ));
+        assert!(is_enabled("reqwest::connect", Level::Debug));
+
+        assert!(build_filter("info", Some("ipc_client=loud")).is_err());
+        Ok(())
+    }
+}
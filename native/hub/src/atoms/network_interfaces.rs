@@ -4,7 +4,8 @@ pub mod detector;
 
 // 导出公共接口
 pub use detector::{
-    GetNetworkInterfaces, NetworkInterfacesInfo, get_hostname, get_network_addresses,
+    GetNetworkInterfaces, InterfaceAddress, InterfaceKind, NetworkInterfaceDetail,
+    NetworkInterfacesInfo, get_hostname, get_interface_details, get_network_addresses,
 };
 
 pub use detector::init;
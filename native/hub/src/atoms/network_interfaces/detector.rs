@@ -1,10 +1,11 @@
 // 网络接口信息查询：提供跨平台的网络信息获取能力。
-// 输出可用地址列表与主机名。
+// 输出可用地址列表、按接口分组的地址详情与主机名。
 
-use rinf::{DartSignal, RustSignal};
+use crate::atoms::shutdown;
+use rinf::{DartSignal, RustSignal, SignalPiece};
 use serde::{Deserialize, Serialize};
-use std::net::IpAddr;
-use tokio::spawn;
+#[cfg(not(target_os = "android"))]
+use std::net::Ipv6Addr;
 
 // Dart → Rust：获取网络接口信息
 #[derive(Deserialize, DartSignal)]
@@ -15,55 +16,129 @@ pub struct GetNetworkInterfaces;
 pub struct NetworkInterfacesInfo {
     pub addresses: Vec<String>,
     pub hostname: Option<String>,
+    pub interfaces: Vec<NetworkInterfaceDetail>,
+    pub is_tun_adapter_present: bool,
+}
+
+// 网络接口分类
+#[derive(Serialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InterfaceKind {
+    Physical = 0,
+    Loopback = 1,
+    VirtualTunnel = 2,
+}
+
+// 虚拟隧道网卡的名称前缀（小写匹配），新的驱动命名直接追加即可
+#[cfg(not(target_os = "android"))]
+const TUNNEL_NAME_PREFIXES: &[&str] = &["wintun", "mihomo", "clash", "wireguard"];
+
+// 后接数字序号时才算虚拟隧道网卡的前缀，避免误判 tunl0 等系统网卡
+#[cfg(not(target_os = "android"))]
+const NUMBERED_TUNNEL_NAME_PREFIXES: &[&str] = &["tun", "tap", "wg"];
+
+// 需完整匹配的虚拟隧道网卡名称（mihomo 默认设备名）
+#[cfg(not(target_os = "android"))]
+const TUNNEL_NAMES: &[&str] = &["meta"];
+
+// Windows 网卡描述中的虚拟隧道驱动关键字（小写匹配）。
+// 不使用泛化的 "tunnel"，否则会误判 Teredo 等系统隧道
+#[cfg(not(target_os = "android"))]
+const TUNNEL_DESCRIPTION_KEYWORDS: &[&str] = &["wintun", "tap-windows", "wireguard", "mihomo"];
+
+// 单个网络接口及其地址
+#[derive(Serialize, SignalPiece, Clone, Debug)]
+pub struct NetworkInterfaceDetail {
+    pub name: String,
+    pub index: u32,
+    pub kind: InterfaceKind,
+    // 网卡描述，仅 Windows 提供
+    pub description: Option<String>,
+    pub addresses: Vec<InterfaceAddress>,
+}
+
+// 接口上的单个地址
+#[derive(Serialize, SignalPiece, Clone, Debug)]
+pub struct InterfaceAddress {
+    pub address: String,
+    pub is_ipv6: bool,
+    pub prefix_length: Option<u8>,
+    // 仅链路本地 IPv6 地址携带作用域 ID（即接口索引）
+    pub scope_id: Option<u32>,
+    pub is_link_local: bool,
+    // 临时/隐私地址标记，系统未提供该信息时为 None
+    pub is_temporary: Option<bool>,
 }
 
 impl GetNetworkInterfaces {
     // 收集系统网络接口信息并输出可用地址列表。
-    pub fn handle(&self) {
+    // 主机名与 Windows 网卡描述需要启动外部进程，放到阻塞线程池执行，避免卡住其他信号处理
+    pub async fn handle(&self) {
         log::info!("收到获取网络接口请求");
 
-        let mut addresses = vec!["127.0.0.1".to_string(), "localhost".to_string()];
+        let response = match tokio::task::spawn_blocking(collect_network_interfaces).await {
+            Ok(response) => response,
+            Err(e) => {
+                log::error!("收集网络接口信息的任务异常退出：{}", e);
+                return;
+            }
+        };
 
-        let hostname = get_hostname();
+        response.send_signal_to_dart();
+    }
+}
 
-        if let Some(ref host) = hostname
-            && host != "localhost"
-            && host != "127.0.0.1"
-        {
-            addresses.push(format!("{}.local", host));
-        }
+// 同步收集网络接口信息，会阻塞当前线程
+fn collect_network_interfaces() -> NetworkInterfacesInfo {
+    let mut addresses = vec!["127.0.0.1".to_string(), "localhost".to_string()];
 
-        match get_network_addresses() {
-            Ok(mut addrs) => {
-                addresses.append(&mut addrs);
-            }
-            Err(e) => {
-                log::warn!("获取网络接口失败：{}", e);
-            }
+    let hostname = get_hostname();
+
+    if let Some(ref host) = hostname
+        && host != "localhost"
+        && host != "127.0.0.1"
+    {
+        addresses.push(format!("{}.local", host));
+    }
+
+    match get_network_addresses() {
+        Ok(mut addrs) => {
+            addresses.append(&mut addrs);
+        }
+        Err(e) => {
+            log::warn!("获取网络接口失败：{}", e);
         }
+    }
 
-        addresses.sort();
-        addresses.dedup();
+    addresses.sort();
+    addresses.dedup();
 
-        let clean_addresses = addresses
-            .iter()
-            .map(|addr| {
-                if let Some(percent_pos) = addr.find('%') {
-                    addr[..percent_pos].to_string()
-                } else {
-                    addr.clone()
-                }
-            })
-            .collect();
+    let clean_addresses = addresses
+        .iter()
+        .map(|addr| {
+            if let Some(percent_pos) = addr.find('%') {
+                addr[..percent_pos].to_string()
+            } else {
+                addr.clone()
+            }
+        })
+        .collect();
 
-        log::debug!("最终地址列表：{:?}", clean_addresses);
+    log::debug!("最终地址列表：{:?}", clean_addresses);
 
-        let response = NetworkInterfacesInfo {
-            addresses: clean_addresses,
-            hostname,
-        };
+    let interfaces = get_interface_details().unwrap_or_else(|e| {
+        log::warn!("获取网络接口详情失败：{}", e);
+        Vec::new()
+    });
 
-        response.send_signal_to_dart();
+    let is_tun_adapter_present = interfaces
+        .iter()
+        .any(|iface| iface.kind == InterfaceKind::VirtualTunnel);
+
+    NetworkInterfacesInfo {
+        addresses: clean_addresses,
+        hostname,
+        interfaces,
+        is_tun_adapter_present,
     }
 }
 
@@ -111,46 +186,102 @@ fn is_apipa_address(ip: &std::net::Ipv4Addr) -> bool {
 }
 
 // 获取所有活动网络接口的 IP 地址，并过滤无效与内部地址。
+// IPv6 地址不依赖接口是否同时拥有 IPv4，纯 IPv6 网络同样可见。
 pub fn get_network_addresses() -> Result<Vec<String>, String> {
+    let interfaces = get_interface_details()?;
+
+    let addresses: Vec<String> = interfaces
+        .into_iter()
+        .flat_map(|iface| iface.addresses)
+        .map(|addr| addr.address)
+        .collect();
+
+    log::info!("获取到{}个网络地址", addresses.len());
+    Ok(addresses)
+}
+
+// 获取按接口分组的地址详情（分类、前缀长度、作用域 ID、临时地址标记）。
+// 回环地址与 APIPA 地址会被过滤，但接口本身仍会保留以便判断 TUN 网卡是否存在。
+// Windows 下可能启动 PowerShell 读取网卡描述，异步上下文中需通过 spawn_blocking 调用。
+pub fn get_interface_details() -> Result<Vec<NetworkInterfaceDetail>, String> {
     #[cfg(not(target_os = "android"))]
     {
-        use network_interface::NetworkInterface;
-        use network_interface::NetworkInterfaceConfig;
+        use network_interface::{Addr, NetworkInterface, NetworkInterfaceConfig};
 
         let interfaces =
             NetworkInterface::show().map_err(|e| format!("无法获取网络接口：{}", e))?;
 
         log::debug!("network-interface 返回了{}个接口", interfaces.len());
 
-        let mut addresses = Vec::new();
+        let ipv6_flags = read_ipv6_address_flags();
+        let indices: Vec<u32> = interfaces.iter().map(|iface| iface.index).collect();
+        let descriptions = read_adapter_descriptions(&indices);
+        let mut details = Vec::new();
 
         for iface in interfaces {
-            let has_valid_ipv4 = iface.addr.iter().any(|addr| {
-                if let IpAddr::V4(ipv4) = addr.ip() {
-                    !ipv4.is_loopback() && !is_apipa_address(&ipv4)
-                } else {
-                    false
-                }
-            });
+            let mut addresses = Vec::new();
 
             for addr in &iface.addr {
-                match addr.ip() {
-                    IpAddr::V4(ipv4) => {
-                        if !ipv4.is_loopback() && !is_apipa_address(&ipv4) {
-                            addresses.push(ipv4.to_string());
+                match addr {
+                    Addr::V4(v4) => {
+                        if v4.ip.is_loopback() || is_apipa_address(&v4.ip) {
+                            continue;
                         }
+                        addresses.push(InterfaceAddress {
+                            address: v4.ip.to_string(),
+                            is_ipv6: false,
+                            prefix_length: v4
+                                .netmask
+                                .map(|mask| u32::from(mask).count_ones() as u8),
+                            scope_id: None,
+                            is_link_local: false,
+                            is_temporary: None,
+                        });
                     }
-                    IpAddr::V6(ipv6) => {
-                        if !ipv6.is_loopback() && has_valid_ipv4 {
-                            addresses.push(ipv6.to_string());
+                    Addr::V6(v6) => {
+                        if v6.ip.is_loopback() || v6.ip.is_unspecified() {
+                            continue;
                         }
+                        let is_link_local = v6.ip.is_unicast_link_local();
+                        let flags = ipv6_flags
+                            .iter()
+                            .find(|flag| flag.ip == v6.ip && flag.interface == iface.name);
+                        addresses.push(InterfaceAddress {
+                            address: v6.ip.to_string(),
+                            is_ipv6: true,
+                            prefix_length: flags.map(|flag| flag.prefix_length).or_else(|| {
+                                v6.netmask.map(|mask| u128::from(mask).count_ones() as u8)
+                            }),
+                            scope_id: is_link_local.then_some(iface.index),
+                            is_link_local,
+                            is_temporary: flags.map(|flag| flag.is_temporary),
+                        });
                     }
                 }
             }
+
+            let description = descriptions
+                .iter()
+                .find(|(index, _)| *index == iface.index)
+                .map(|(_, description)| description.clone());
+            let has_ipv4 = addresses.iter().any(|addr| !addr.is_ipv6);
+            let kind = classify_interface(
+                &iface.name,
+                description.as_deref(),
+                iface.internal,
+                has_ipv4,
+            );
+
+            details.push(NetworkInterfaceDetail {
+                name: iface.name,
+                index: iface.index,
+                kind,
+                description,
+                addresses,
+            });
         }
 
-        log::info!("获取到{}个网络地址", addresses.len());
-        Ok(addresses)
+        Ok(details)
     }
 
     #[cfg(target_os = "android")]
@@ -159,12 +290,210 @@ pub fn get_network_addresses() -> Result<Vec<String>, String> {
     }
 }
 
+// 根据平台启发式规则判断接口类型
+#[cfg(not(target_os = "android"))]
+fn classify_interface(
+    name: &str,
+    description: Option<&str>,
+    is_internal: bool,
+    has_ipv4: bool,
+) -> InterfaceKind {
+    let name = name.to_lowercase();
+    if is_internal || name == "lo" || name.starts_with("loopback") {
+        return InterfaceKind::Loopback;
+    }
+
+    let has_numbered_suffix = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .is_some_and(|suffix| !suffix.is_empty() && suffix.bytes().all(|b| b.is_ascii_digit()))
+    };
+    // macOS 自带的 utun 网卡（iCloud 专用代理等）只有 IPv6 链路本地地址，带 IPv4 地址的才是代理建立的
+    let is_proxy_utun = has_numbered_suffix("utun") && has_ipv4;
+    let is_tunnel_name = is_proxy_utun
+        || TUNNEL_NAMES.contains(&name.as_str())
+        || TUNNEL_NAME_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+        || NUMBERED_TUNNEL_NAME_PREFIXES
+            .iter()
+            .any(|prefix| has_numbered_suffix(prefix));
+    let is_tunnel_description = description.is_some_and(|description| {
+        let description = description.to_lowercase();
+        TUNNEL_DESCRIPTION_KEYWORDS
+            .iter()
+            .any(|keyword| description.contains(keyword))
+    });
+
+    if is_tunnel_name || is_tunnel_description {
+        InterfaceKind::VirtualTunnel
+    } else {
+        InterfaceKind::Physical
+    }
+}
+
+// 网卡描述缓存：记录读取时的接口索引集合，集合不变时复用
+#[cfg(target_os = "windows")]
+struct AdapterDescriptionCache {
+    indices: Vec<u32>,
+    descriptions: Vec<(u32, String)>,
+}
+
+#[cfg(target_os = "windows")]
+static ADAPTER_DESCRIPTION_CACHE: std::sync::Mutex<Option<AdapterDescriptionCache>> =
+    std::sync::Mutex::new(None);
+
+// Windows 读取网卡描述（接口索引 → 描述）。启动 PowerShell 较慢，
+// 仅在接口增减（如 TUN 网卡重建）时重新读取
+#[cfg(target_os = "windows")]
+fn read_adapter_descriptions(indices: &[u32]) -> Vec<(u32, String)> {
+    let mut indices = indices.to_vec();
+    indices.sort_unstable();
+    indices.dedup();
+
+    let mut cache = ADAPTER_DESCRIPTION_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(cached) = cache.as_ref().filter(|cached| cached.indices == indices) {
+        return cached.descriptions.clone();
+    }
+
+    let Some(descriptions) = query_adapter_descriptions() else {
+        return Vec::new();
+    };
+    *cache = Some(AdapterDescriptionCache {
+        indices,
+        descriptions: descriptions.clone(),
+    });
+    descriptions
+}
+
+// 通过 Get-NetAdapter 查询网卡描述，失败时返回 None 且不写入缓存
+#[cfg(target_os = "windows")]
+fn query_adapter_descriptions() -> Option<Vec<(u32, String)>> {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Get-NetAdapter -IncludeHidden | ForEach-Object { \"$($_.ifIndex)`t$($_.InterfaceDescription)\" }",
+        ])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        Ok(_) | Err(_) => {
+            log::debug!("读取网卡描述失败，仅按名称分类");
+            return None;
+        }
+    };
+
+    let descriptions = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (index, description) = line.split_once('\t')?;
+            Some((index.trim().parse().ok()?, description.trim().to_string()))
+        })
+        .collect();
+    Some(descriptions)
+}
+
+// 其他平台没有独立的网卡描述，仅按名称分类
+#[cfg(all(not(target_os = "windows"), not(target_os = "android")))]
+fn read_adapter_descriptions(_indices: &[u32]) -> Vec<(u32, String)> {
+    Vec::new()
+}
+
+// 系统额外提供的 IPv6 地址属性
+#[cfg(not(target_os = "android"))]
+struct Ipv6AddressFlags {
+    interface: String,
+    ip: Ipv6Addr,
+    prefix_length: u8,
+    is_temporary: bool,
+}
+
+// Linux 通过 /proc/net/if_inet6 读取前缀长度与 IFA_F_TEMPORARY 标记。
+// 每行格式：地址 接口索引 前缀长度 作用域 标志 接口名（均为十六进制）
+#[cfg(target_os = "linux")]
+fn read_ipv6_address_flags() -> Vec<Ipv6AddressFlags> {
+    const IFA_F_TEMPORARY: u32 = 0x01;
+
+    let Ok(content) = std::fs::read_to_string("/proc/net/if_inet6") else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 6 {
+                return None;
+            }
+            let ip = u128::from_str_radix(fields[0], 16)
+                .ok()
+                .map(Ipv6Addr::from)?;
+            let prefix_length = u8::from_str_radix(fields[2], 16).ok()?;
+            let flags = u32::from_str_radix(fields[4], 16).ok()?;
+            Some(Ipv6AddressFlags {
+                interface: fields[5].to_string(),
+                ip,
+                prefix_length,
+                is_temporary: flags & IFA_F_TEMPORARY != 0,
+            })
+        })
+        .collect()
+}
+
+// 其他平台暂不提供额外的 IPv6 地址属性
+#[cfg(all(not(target_os = "linux"), not(target_os = "android")))]
+fn read_ipv6_address_flags() -> Vec<Ipv6AddressFlags> {
+    Vec::new()
+}
+
 pub fn init() {
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = GetNetworkInterfaces::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
-            dart_signal.message.handle();
+            dart_signal.message.handle().await;
         }
         log::info!("获取网络接口消息通道已关闭，退出监听器");
     });
 }
+
+#[cfg(all(test, not(target_os = "android")))]
+mod tests {
+    use super::{InterfaceKind, classify_interface};
+
+    #[test]
+    fn classifies_tunnel_names_without_system_false_positives() {
+        let kind = |name: &str, has_ipv4: bool| classify_interface(name, None, false, has_ipv4);
+
+        for name in ["Meta", "Mihomo", "tun0", "tap1", "wg0", "wintun"] {
+            assert_eq!(kind(name, false), InterfaceKind::VirtualTunnel, "{}", name);
+        }
+        // macOS 自带 utun、Linux IPIP 隧道 tunl0 等不应视为代理网卡
+        for name in ["utun0", "tunl0", "metalink", "wlan0", "tun"] {
+            assert_eq!(kind(name, false), InterfaceKind::Physical, "{}", name);
+        }
+        assert_eq!(kind("utun4", true), InterfaceKind::VirtualTunnel);
+        assert_eq!(kind("lo", false), InterfaceKind::Loopback);
+        assert_eq!(
+            classify_interface(
+                "Teredo",
+                Some("Teredo Tunneling Pseudo-Interface"),
+                false,
+                false
+            ),
+            InterfaceKind::Physical
+        );
+        assert_eq!(
+            classify_interface("以太网 2", Some("Wintun Userspace Tunnel"), false, true),
+            InterfaceKind::VirtualTunnel
+        );
+    }
+}
@@ -1,10 +1,13 @@
 // 覆写处理器原子模块：提供 YAML 合并与 JavaScript 执行能力。
 // 面向上层提供稳定的覆写处理接口。
 
+mod config_diff;
 mod js_executor;
 mod processor;
+mod result_cache;
 mod yaml_merger;
 
-pub use js_executor::JsExecutor;
-pub use processor::OverrideProcessor;
+pub use config_diff::{ConfigChange, ConfigChangeKind, ConfigDiff};
+pub use js_executor::{ConsoleLevel, ConsoleMessage, JsExecutor};
+pub use processor::{OverrideProcessor, ScriptContext};
 pub use yaml_merger::YamlMerger;
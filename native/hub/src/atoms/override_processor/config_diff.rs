@@ -0,0 +1,266 @@
+// 配置差异：比较覆写前后的配置，输出结构化的变更列表。
+// 数组只汇总增删数量，避免大型配置产生过长的差异结果。
+
+use rinf::SignalPiece;
+use serde::Serialize;
+use serde_yaml_ng::Value as YamlValue;
+use std::collections::HashMap;
+
+// 变更条目上限，超出后截断
+const MAX_CHANGES: usize = 500;
+// 单个值的展示长度上限
+const MAX_VALUE_PREVIEW_CHARS: usize = 200;
+
+// 变更类型
+#[derive(Serialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigChangeKind {
+    Added = 0,
+    Removed = 1,
+    Changed = 2,
+    ArrayChanged = 3,
+}
+
+// 单条变更：路径使用 JSON Path 形式，如 $.dns.enable
+#[derive(Serialize, SignalPiece, Clone, Debug)]
+pub struct ConfigChange {
+    pub path: String,
+    pub kind: ConfigChangeKind,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    // 数组变更汇总，如 "+12 项，-0 项"
+    pub summary: Option<String>,
+}
+
+// 差异结果
+#[derive(Debug, Default)]
+pub struct ConfigDiff {
+    pub changes: Vec<ConfigChange>,
+    pub is_truncated: bool,
+}
+
+impl ConfigDiff {
+    // 比较两份已解析的配置
+    pub fn between(old: &YamlValue, new: &YamlValue) -> Self {
+        let mut diff = Self::default();
+        diff.compare("$".to_string(), old, new);
+        diff
+    }
+
+    fn push(&mut self, change: ConfigChange) {
+        if self.changes.len() >= MAX_CHANGES {
+            self.is_truncated = true;
+            return;
+        }
+        self.changes.push(change);
+    }
+
+    fn compare(&mut self, path: String, old: &YamlValue, new: &YamlValue) {
+        if self.is_truncated || old == new {
+            return;
+        }
+
+        match (old, new) {
+            (YamlValue::Mapping(old_map), YamlValue::Mapping(new_map)) => {
+                for (key, old_value) in old_map {
+                    let child_path = join_path(&path, key);
+                    match new_map.get(key) {
+                        Some(new_value) => self.compare(child_path, old_value, new_value),
+                        None => self.push(ConfigChange {
+                            path: child_path,
+                            kind: ConfigChangeKind::Removed,
+                            old_value: Some(preview_value(old_value)),
+                            new_value: None,
+                            summary: None,
+                        }),
+                    }
+                }
+
+                for (key, new_value) in new_map {
+                    if !old_map.contains_key(key) {
+                        self.push(ConfigChange {
+                            path: join_path(&path, key),
+                            kind: ConfigChangeKind::Added,
+                            old_value: None,
+                            new_value: Some(preview_value(new_value)),
+                            summary: None,
+                        });
+                    }
+                }
+            }
+            (YamlValue::Sequence(old_items), YamlValue::Sequence(new_items)) => {
+                let (added, removed) = count_sequence_changes(old_items, new_items);
+                // 仅顺序变化时增删均为 0，仍记录一条汇总
+                let summary = if added == 0 && removed == 0 {
+                    "顺序调整".to_string()
+                } else {
+                    format!("+{} 项，-{} 项", added, removed)
+                };
+                self.push(ConfigChange {
+                    path,
+                    kind: ConfigChangeKind::ArrayChanged,
+                    old_value: Some(preview_value(old)),
+                    new_value: Some(preview_value(new)),
+                    summary: Some(summary),
+                });
+            }
+            _ => self.push(ConfigChange {
+                path,
+                kind: ConfigChangeKind::Changed,
+                old_value: Some(preview_value(old)),
+                new_value: Some(preview_value(new)),
+                summary: None,
+            }),
+        }
+    }
+}
+
+// 按内容统计数组元素的增删数量（视为多重集合）
+fn count_sequence_changes(old_items: &[YamlValue], new_items: &[YamlValue]) -> (usize, usize) {
+    let mut counts: HashMap<String, i64> = HashMap::new();
+    for item in old_items {
+        *counts.entry(fingerprint(item)).or_default() -= 1;
+    }
+    for item in new_items {
+        *counts.entry(fingerprint(item)).or_default() += 1;
+    }
+
+    counts.values().fold((0, 0), |(added, removed), count| {
+        if *count > 0 {
+            (added + *count as usize, removed)
+        } else {
+            (added, removed + count.unsigned_abs() as usize)
+        }
+    })
+}
+
+fn fingerprint(value: &YamlValue) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn join_path(parent: &str, key: &YamlValue) -> String {
+    let key = match key {
+        YamlValue::String(s) => s.clone(),
+        other => fingerprint(other),
+    };
+
+    let is_plain = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    if is_plain {
+        format!("{}.{}", parent, key)
+    } else {
+        format!("{}[{}]", parent, serde_json::Value::String(key))
+    }
+}
+
+// 生成值的简短预览：标量完整展示，映射与数组只展示规模
+fn preview_value(value: &YamlValue) -> String {
+    match value {
+        YamlValue::Mapping(map) => format!("{{…}}（{} 个键）", map.len()),
+        YamlValue::Sequence(items) => format!("[…]（{} 项）", items.len()),
+        other => {
+            let text = fingerprint(other);
+            if text.chars().count() > MAX_VALUE_PREVIEW_CHARS {
+                let truncated: String = text.chars().take(MAX_VALUE_PREVIEW_CHARS).collect();
+                format!("{}…", truncated)
+            } else {
+                text
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigChangeKind, ConfigDiff, MAX_CHANGES};
+    use serde_yaml_ng::Value as YamlValue;
+
+    fn parse(yaml: &str) -> Result<YamlValue, String> {
+        serde_yaml_ng::from_str(yaml).map_err(|e| format!("解析 YAML 失败：{}", e))
+    }
+
+    #[test]
+    fn reports_added_removed_and_changed_keys() -> Result<(), String> {
+        let old = parse("mode: rule\nipv6: false\ndns:\n  enable: false\n")?;
+        let new = parse("mode: global\ndns:\n  enable: true\n\"allow lan\": true\n")?;
+        let diff = ConfigDiff::between(&old, &new);
+
+        let changes: Vec<_> = diff
+            .changes
+            .iter()
+            .map(|change| {
+                (
+                    change.path.as_str(),
+                    change.kind,
+                    change.old_value.as_deref(),
+                    change.new_value.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    "$.mode",
+                    ConfigChangeKind::Changed,
+                    Some("\"rule\""),
+                    Some("\"global\"")
+                ),
+                ("$.ipv6", ConfigChangeKind::Removed, Some("false"), None),
+                (
+                    "$.dns.enable",
+                    ConfigChangeKind::Changed,
+                    Some("false"),
+                    Some("true")
+                ),
+                (
+                    "$[\"allow lan\"]",
+                    ConfigChangeKind::Added,
+                    None,
+                    Some("true")
+                ),
+            ]
+        );
+        assert!(!diff.is_truncated);
+        assert!(ConfigDiff::between(&old, &old).changes.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn summarizes_array_changes() -> Result<(), String> {
+        let old = parse("rules: [a, b, c]\nproxies: [x, y]\n")?;
+        let new = parse("rules: [a, c, d, e]\nproxies: [y, x]\n")?;
+        let diff = ConfigDiff::between(&old, &new);
+
+        assert_eq!(diff.changes.len(), 2);
+        let rules = &diff.changes[0];
+        assert_eq!(rules.path, "$.rules");
+        assert_eq!(rules.kind, ConfigChangeKind::ArrayChanged);
+        assert_eq!(rules.summary.as_deref(), Some("+2 项，-1 项"));
+        assert_eq!(rules.old_value.as_deref(), Some("[…]（3 项）"));
+
+        let proxies = &diff.changes[1];
+        assert_eq!(proxies.path, "$.proxies");
+        assert_eq!(proxies.summary.as_deref(), Some("顺序调整"));
+        Ok(())
+    }
+
+    #[test]
+    fn truncates_after_max_changes() -> Result<(), String> {
+        let old = parse("{}")?;
+        let new_yaml: String = (0..MAX_CHANGES + 10)
+            .map(|i| format!("key{}: {}\n", i, i))
+            .collect();
+        let diff = ConfigDiff::between(&old, &parse(&new_yaml)?);
+
+        assert_eq!(diff.changes.len(), MAX_CHANGES);
+        assert!(diff.is_truncated);
+        assert!(
+            diff.changes
+                .iter()
+                .all(|change| change.kind == ConfigChangeKind::Added)
+        );
+        Ok(())
+    }
+}
@@ -1,18 +1,234 @@
 // JavaScript 覆写执行器：负责在 QuickJS 中执行覆写脚本并返回结果。
 // 入口约定： main(config) 返回可 JSON 序列化的配置对象。
+//
+// 可选能力 httpGet(url)：由 Rust 侧同步发起 GET 请求并返回响应文本。
+// 覆写脚本可能来自不受信任的订阅或远程链接，开启后脚本可借此外传配置中的敏感信息。
+// 因此该能力默认关闭，由用户在单个覆写上手动开启。目标地址（含重定向与 DNS 解析结果）
+// 为本机、局域网等非公网地址时拒绝请求。
+//
+// 只读全局对象 context：由 Rust 调用方注入，结构固定为
+//   {
+//     override: { id: string, name: string },
+//     mode: "Direct" | "System" | "Core" | null,   // 订阅更新使用的代理模式
+//     profile: { name: string | null, url: string | null }  // 本地订阅 url 为 null
+//   }
+// 该对象只读，脚本对其（含嵌套字段）的修改会被静默忽略。
+//
+// 确定性执行：默认将 Date 固定为 1970-01-01T00:00:00Z 并按 UTC 计算本地时间，
+// Math.random 使用固定种子，保证同一覆写作用于同一配置时输出逐字节一致
+// （差异预览与缓存依赖这一点）。需要真实时间与随机数时由调用方显式开启。
+// 这只保证结果可复现，并非安全隔离。
+//
+// console.log/info/debug/warn/error 的输出会被收集（脚本抛出异常时同样保留），
+// 执行后通过 take_console_messages 取出，供覆写编辑器展示。
 
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+use crate::atoms::tls_roots;
+use rinf::SignalPiece;
+use serde::Serialize;
 use serde_json::Value as JsonValue;
 use serde_yaml_ng::Value as YamlValue;
 
 #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
-use rquickjs::{Context, Runtime};
+use rquickjs::{Context, Ctx, Exception, Function, Persistent, Runtime};
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+use std::net::{IpAddr, SocketAddr};
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+use std::sync::{Arc, Mutex};
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+use std::time::Duration;
+
+// httpGet 单次请求超时与响应体大小上限
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+const HTTP_GET_TIMEOUT: Duration = Duration::from_secs(10);
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+const HTTP_GET_MAX_BYTES: usize = 5 * 1024 * 1024;
+// httpGet 最多跟随的重定向次数
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+const HTTP_GET_MAX_REDIRECTS: usize = 5;
+
+// 单次执行最多收集的 console 输出条数与单条输出的最大字符数
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+const MAX_CONSOLE_MESSAGES: usize = 200;
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+const MAX_CONSOLE_MESSAGE_CHARS: usize = 2000;
+
+// console 输出级别
+#[derive(Serialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsoleLevel {
+    Log = 0,
+    Warn = 1,
+    Error = 2,
+}
+
+// 脚本的一条 console 输出
+#[derive(Serialize, SignalPiece, Clone, Debug, PartialEq, Eq)]
+pub struct ConsoleMessage {
+    pub level: ConsoleLevel,
+    pub message: String,
+}
+
+// 本次执行收集到的输出，超出条数上限的只计数
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+#[derive(Default)]
+struct ConsoleBuffer {
+    messages: Vec<ConsoleMessage>,
+    dropped_count: usize,
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+impl ConsoleBuffer {
+    fn push(&mut self, level: ConsoleLevel, message: String) {
+        if self.messages.len() >= MAX_CONSOLE_MESSAGES {
+            self.dropped_count += 1;
+            return;
+        }
+
+        let message = match message.char_indices().nth(MAX_CONSOLE_MESSAGE_CHARS) {
+            Some((end, _)) => format!("{}…（已截断）", &message[..end]),
+            None => message,
+        };
+        self.messages.push(ConsoleMessage { level, message });
+    }
+
+    fn take(&mut self) -> Vec<ConsoleMessage> {
+        let mut messages = std::mem::take(&mut self.messages);
+        let dropped_count = std::mem::take(&mut self.dropped_count);
+        if dropped_count > 0 {
+            messages.push(ConsoleMessage {
+                level: ConsoleLevel::Warn,
+                message: format!("输出过多，已省略 {} 条", dropped_count),
+            });
+        }
+        messages
+    }
+}
+
+// 接收输出函数并安装全局 console：参数按空格拼接，对象以 JSON 展示
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+const CONSOLE_INSTALLER: &str = r#"
+(function(sink) {
+    function format(args) {
+        return Array.prototype.map.call(args, function(arg) {
+            if (typeof arg === 'string') {
+                return arg;
+            }
+            try {
+                var text = JSON.stringify(arg);
+                return text === undefined ? String(arg) : text;
+            } catch (e) {
+                return String(arg);
+            }
+        }).join(' ');
+    }
+    function writer(level) {
+        return function() { sink(level, format(arguments)); };
+    }
+    globalThis.console = {
+        log: writer(0),
+        info: writer(0),
+        debug: writer(0),
+        warn: writer(1),
+        error: writer(2)
+    };
+})
+"#;
+
+// 返回 configure(isDeterministic) 函数：真实的 Date 与 Math.random 保存在闭包中，
+// 每次调用都会重置随机数种子
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+const DETERMINISM_INSTALLER: &str = r#"
+(function() {
+    var RealDate = Date;
+    var realRandom = Math.random;
+    var FIXED_TIME = 0;
+    var RANDOM_SEED = 0x2545F491;
+    // 本次执行是否读取过固定时间或随机数
+    var hasReadFixedValue = false;
+
+    function FixedDate() {
+        if (!new.target) {
+            return new FixedDate().toString();
+        }
+        var args = Array.prototype.slice.call(arguments);
+        if (args.length === 0) {
+            hasReadFixedValue = true;
+        }
+        var date = args.length === 0
+            ? new RealDate(FIXED_TIME)
+            : new (Function.prototype.bind.apply(RealDate, [null].concat(args)))();
+        Object.setPrototypeOf(date, new.target.prototype);
+        return date;
+    }
+    FixedDate.prototype = Object.create(RealDate.prototype, {
+        constructor: { value: FixedDate, writable: true, configurable: true }
+    });
+    FixedDate.now = function() {
+        hasReadFixedValue = true;
+        return FIXED_TIME;
+    };
+    FixedDate.parse = RealDate.parse;
+    FixedDate.UTC = RealDate.UTC;
+
+    // 本地时间按 UTC 计算，结果与所在时区无关
+    var proto = FixedDate.prototype;
+    var utcProto = RealDate.prototype;
+    ['FullYear', 'Month', 'Date', 'Day', 'Hours', 'Minutes', 'Seconds', 'Milliseconds']
+        .forEach(function(name) {
+            proto['get' + name] = utcProto['getUTC' + name];
+            if (name !== 'Day') {
+                proto['set' + name] = utcProto['setUTC' + name];
+            }
+        });
+    proto.getTimezoneOffset = function() { return 0; };
+    proto.toString = utcProto.toUTCString;
+    proto.toLocaleString = utcProto.toISOString;
+    proto.toDateString = function() { return this.toISOString().slice(0, 10); };
+    proto.toLocaleDateString = proto.toDateString;
+    proto.toTimeString = function() { return this.toISOString().slice(11, 19); };
+    proto.toLocaleTimeString = proto.toTimeString;
+
+    function configure(isDeterministic) {
+        hasReadFixedValue = false;
+        if (!isDeterministic) {
+            globalThis.Date = RealDate;
+            Math.random = realRandom;
+            return;
+        }
+
+        // mulberry32
+        var state = RANDOM_SEED;
+        globalThis.Date = FixedDate;
+        Math.random = function() {
+            hasReadFixedValue = true;
+            state = (state + 0x6D2B79F5) | 0;
+            var t = Math.imul(state ^ (state >>> 15), 1 | state);
+            t = (t + Math.imul(t ^ (t >>> 7), 61 | t)) ^ t;
+            return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+        };
+    }
+    configure.hasReadFixedValue = function() { return hasReadFixedValue; };
+    return configure;
+})()
+"#;
 
 // JavaScript 执行器
 pub struct JsExecutor {
+    // 须先于运行时释放，字段顺序不可调整
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    configure_determinism: Persistent<Function<'static>>,
     #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
     runtime: Runtime,
     #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
     context: Context,
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    is_http_get_enabled: bool,
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    is_nondeterminism_allowed: bool,
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    script_context: JsonValue,
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    console_buffer: Arc<Mutex<ConsoleBuffer>>,
 }
 
 impl JsExecutor {
@@ -22,8 +238,22 @@ impl JsExecutor {
         let runtime = Runtime::new().map_err(|e| format!("初始化 JavaScript 运行时失败：{}", e))?;
         let context =
             Context::full(&runtime).map_err(|e| format!("初始化 JavaScript 上下文失败：{}", e))?;
+        let configure_determinism = context
+            .with(|ctx| {
+                let configure: Function = ctx.eval(DETERMINISM_INSTALLER)?;
+                Ok::<_, rquickjs::Error>(Persistent::save(&ctx, configure))
+            })
+            .map_err(|e| format!("初始化确定性执行环境失败：{}", e))?;
 
-        Ok(Self { runtime, context })
+        Ok(Self {
+            configure_determinism,
+            runtime,
+            context,
+            is_http_get_enabled: false,
+            is_nondeterminism_allowed: false,
+            script_context: JsonValue::Null,
+            console_buffer: Arc::new(Mutex::new(ConsoleBuffer::default())),
+        })
     }
 
     #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
@@ -31,6 +261,47 @@ impl JsExecutor {
         Ok(Self {})
     }
 
+    // 设置是否向脚本注入 httpGet(url)，默认关闭。
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub fn set_http_get_enabled(&mut self, is_enabled: bool) {
+        self.is_http_get_enabled = is_enabled;
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    pub fn set_http_get_enabled(&mut self, _is_enabled: bool) {}
+
+    // 设置是否向脚本提供真实的时间与随机数，默认关闭（结果可复现）
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub fn set_nondeterminism_allowed(&mut self, is_allowed: bool) {
+        self.is_nondeterminism_allowed = is_allowed;
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    pub fn set_nondeterminism_allowed(&mut self, _is_allowed: bool) {}
+
+    // 设置下一次执行注入的 context 对象（结构见文件头部说明）
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub fn set_script_context(&mut self, script_context: JsonValue) {
+        self.script_context = script_context;
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    pub fn set_script_context(&mut self, _script_context: JsonValue) {}
+
+    // 取出最近一次执行收集到的 console 输出（执行失败时同样可用）
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub fn take_console_messages(&self) -> Vec<ConsoleMessage> {
+        match self.console_buffer.lock() {
+            Ok(mut buffer) => buffer.take(),
+            Err(e) => e.into_inner().take(),
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    pub fn take_console_messages(&self) -> Vec<ConsoleMessage> {
+        Vec::new()
+    }
+
     // 应用 JavaScript 覆写：YAML 转 JSON，执行 main(config)，再转换为 YAML。
     // 返回覆写后的配置内容。
     #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
@@ -40,11 +311,17 @@ impl JsExecutor {
         log::info!("JS 脚本长度：{}字节", js_code.len());
 
         // 1. 解析 YAML 转 JSON
-        let yaml_val: YamlValue = serde_yaml_ng::from_str(base_content).map_err(|e| {
+        let mut yaml_val: YamlValue = serde_yaml_ng::from_str(base_content).map_err(|e| {
             log::error!("解析 YAML 配置失败：{}", e);
             format!("解析配置失败：{}", e)
         })?;
 
+        // 展开 `<<` 合并键，避免脚本看到字面量 "<<" 键
+        yaml_val.apply_merge().map_err(|e| {
+            log::error!("展开 YAML 合并键失败：{}", e);
+            format!("解析配置失败：{}", e)
+        })?;
+
         let json_val: JsonValue = serde_json::to_value(&yaml_val).map_err(|e| {
             log::error!("转换为 JSON 失败：{}", e);
             format!("转换为 JSON 失败：{}", e)
@@ -106,6 +383,10 @@ impl JsExecutor {
         );
 
         // 3. 执行 JavaScript
+        self.install_console()?;
+        self.install_http_get()?;
+        self.install_script_context()?;
+        self.install_determinism()?;
         log::info!("开始执行 JavaScript");
         let result_str = self.execute_js(&full_js_code).map_err(|e| {
             log::error!("JavaScript 执行失败：{}", e);
@@ -113,6 +394,7 @@ impl JsExecutor {
         })?;
 
         log::info!("JavaScript 执行成功");
+        self.warn_if_fixed_value_read();
         log::info!("JavaScript 结果长度：{}字节", result_str.len());
 
         // 4. JSON 转 YAML
@@ -160,6 +442,123 @@ impl JsExecutor {
         Err("当前平台不支持 JavaScript 覆写".to_string())
     }
 
+    // 清空上一次的输出并重新安装 console，避免脚本替换后的 console 残留
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    fn install_console(&self) -> Result<(), String> {
+        self.take_console_messages();
+        let buffer = self.console_buffer.clone();
+        self.context
+            .with(|ctx| {
+                let sink = Function::new(ctx.clone(), move |level: i32, message: String| {
+                    let level = match level {
+                        1 => ConsoleLevel::Warn,
+                        2 => ConsoleLevel::Error,
+                        _ => ConsoleLevel::Log,
+                    };
+                    match buffer.lock() {
+                        Ok(mut buffer) => buffer.push(level, message),
+                        Err(e) => e.into_inner().push(level, message),
+                    }
+                })?;
+                let installer: Function = ctx.eval(CONSOLE_INSTALLER)?;
+                installer.call::<_, ()>((sink,))
+            })
+            .map_err(|e| format!("配置 console 失败：{}", e))
+    }
+
+    // 按能力开关注入或移除全局 httpGet，避免上一次执行的状态残留
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    fn install_http_get(&self) -> Result<(), String> {
+        let is_enabled = self.is_http_get_enabled;
+        self.context
+            .with(|ctx| {
+                let globals = ctx.globals();
+                if is_enabled {
+                    log::warn!("已为当前覆写脚本开启 httpGet 网络能力");
+                    let http_get = Function::new(ctx.clone(), js_http_get)?;
+                    globals.set("httpGet", http_get)
+                } else {
+                    globals.remove("httpGet")
+                }
+            })
+            .map_err(|e| format!("配置 httpGet 能力失败：{}", e))
+    }
+
+    // 每次执行前切换确定性环境，同时重置随机数种子
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    fn install_determinism(&self) -> Result<(), String> {
+        let is_deterministic = !self.is_nondeterminism_allowed;
+        if !is_deterministic {
+            log::warn!("已为当前覆写脚本开放真实时间与随机数");
+        }
+        let configure_determinism = self.configure_determinism.clone();
+        self.context
+            .with(|ctx| {
+                configure_determinism
+                    .restore(&ctx)?
+                    .call::<_, ()>((is_deterministic,))
+            })
+            .map_err(|e| format!("配置确定性执行环境失败：{}", e))
+    }
+
+    // 脚本读取了固定时间或随机数时，结果与真实环境下不同，提示用户按需开放
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    fn warn_if_fixed_value_read(&self) {
+        let configure_determinism = self.configure_determinism.clone();
+        let has_read = self.context.with(|ctx| {
+            configure_determinism
+                .restore(&ctx)?
+                .get::<_, Function>("hasReadFixedValue")?
+                .call::<_, bool>(())
+        });
+        match has_read {
+            Ok(true) => log::warn!(
+                "覆写脚本读取了固定的时间或随机数，结果可能与预期不同；如需真实值，请在该覆写中开启真实时间与随机数"
+            ),
+            Ok(false) => {}
+            Err(e) => log::warn!("检查确定性执行环境失败：{}", e),
+        }
+    }
+
+    // 注入只读 context，每次执行前重新定义，避免上一次的值残留
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    fn install_script_context(&self) -> Result<(), String> {
+        let context_json = serde_json::to_string(&self.script_context)
+            .map_err(|e| format!("序列化 context 失败：{}", e))?;
+        let install_code = format!(
+            r#"
+            (function() {{
+                // 只读代理：读取时逐层包装，写入、删除、重定义均返回成功但不生效，
+                // 严格模式下也不会抛出异常
+                function readOnly(value) {{
+                    if (value === null || typeof value !== 'object') {{
+                        return value;
+                    }}
+                    return new Proxy(value, {{
+                        get: function(target, key) {{ return readOnly(target[key]); }},
+                        set: function() {{ return true; }},
+                        deleteProperty: function() {{ return true; }},
+                        defineProperty: function() {{ return true; }}
+                    }});
+                }}
+                var frozen = readOnly(JSON.parse({}));
+                Object.defineProperty(globalThis, 'context', {{
+                    get: function() {{ return frozen; }},
+                    set: function() {{}},
+                    enumerable: false,
+                    configurable: true
+                }});
+            }})()
+            "#,
+            // 以 JSON 字符串字面量嵌入，无需额外转义
+            serde_json::Value::String(context_json)
+        );
+
+        self.context
+            .with(|ctx| ctx.eval::<(), _>(install_code.as_str()))
+            .map_err(|e| format!("注入 context 失败：{}", e))
+    }
+
     #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
     fn execute_js(&self, full_js_code: &str) -> Result<String, String> {
         // 保持运行时生命周期，避免上下文提前释放
@@ -169,3 +568,318 @@ impl JsExecutor {
             .map_err(|e| format!("JavaScript 执行失败：{}", e))
     }
 }
+
+// 脚本中 httpGet(url) 的实现，失败时抛出 JavaScript 异常
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+fn js_http_get<'js>(ctx: Ctx<'js>, url: String) -> rquickjs::Result<String> {
+    http_get_blocking(&url).map_err(|e| Exception::throw_message(&ctx, &e))
+}
+
+// 同步执行 GET 请求。脚本执行可能位于 Tokio 运行时内，
+// 因此在独立线程中创建临时运行时，避免阻塞或嵌套运行时。
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+fn http_get_blocking(url: &str) -> Result<String, String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("httpGet 地址无效：{}", e))?;
+    check_public_target(&parsed)?;
+
+    log::info!("覆写脚本发起 httpGet：{}", parsed);
+
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| format!("创建 httpGet 运行时失败：{}", e))?;
+        runtime.block_on(fetch_text(parsed))
+    })
+    .join()
+    .map_err(|_| "httpGet 线程异常退出".to_string())?
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+async fn fetch_text(url: url::Url) -> Result<String, String> {
+    // 不经过代理，保证连接的正是解析校验过的地址
+    let builder = reqwest::Client::builder()
+        .timeout(HTTP_GET_TIMEOUT)
+        .no_proxy()
+        .dns_resolver(std::sync::Arc::new(PublicOnlyResolver))
+        .redirect(reqwest::redirect::Policy::custom(|attempt| {
+            if attempt.previous().len() >= HTTP_GET_MAX_REDIRECTS {
+                return attempt.error("httpGet 重定向次数过多");
+            }
+            match check_public_target(attempt.url()) {
+                Ok(()) => attempt.follow(),
+                Err(e) => attempt.error(e),
+            }
+        }));
+    // 与订阅、覆写下载一致，信任用户配置的额外根证书
+    let client = tls_roots::apply(builder)
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败：{}", e))?;
+
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("httpGet 请求失败：{}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("httpGet 请求失败：HTTP {}", status.as_u16()));
+    }
+
+    if response
+        .content_length()
+        .is_some_and(|length| length as usize > HTTP_GET_MAX_BYTES)
+    {
+        return Err(format!("httpGet 响应超过 {} 字节上限", HTTP_GET_MAX_BYTES));
+    }
+
+    // 逐块读取，防止未声明长度的响应突破上限
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("httpGet 读取响应失败：{}", e))?
+    {
+        if body.len() + chunk.len() > HTTP_GET_MAX_BYTES {
+            return Err(format!("httpGet 响应超过 {} 字节上限", HTTP_GET_MAX_BYTES));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    String::from_utf8(body).map_err(|e| format!("httpGet 响应不是有效的 UTF-8：{}", e))
+}
+
+// 校验 httpGet 目标：仅允许 http/https，主机为 IP 时须为公网地址
+// （域名在连接前由 PublicOnlyResolver 校验解析结果）
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+fn check_public_target(url: &url::Url) -> Result<(), String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("httpGet 仅支持 http/https：{}", url));
+    }
+
+    let ip = match url.host() {
+        Some(url::Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(url::Host::Ipv6(ip)) => IpAddr::V6(ip),
+        Some(url::Host::Domain(_)) => return Ok(()),
+        None => return Err(format!("httpGet 地址缺少主机：{}", url)),
+    };
+    if is_public_ip(ip) {
+        Ok(())
+    } else {
+        Err(format!("httpGet 不允许访问本机或局域网地址：{}", ip))
+    }
+}
+
+// 是否为允许 httpGet 访问的地址。198.18.0.0/15 为核心 fake-ip 默认网段，
+// TUN 模式下公网域名会解析到该网段，因此不拦截
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            let is_shared = a == 100 && (64..128).contains(&b);
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || is_shared
+                || a == 0)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ipv4) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(ipv4));
+            }
+            let first = ip.segments()[0];
+            let is_unique_local = first & 0xfe00 == 0xfc00;
+            let is_link_local = first & 0xffc0 == 0xfe80;
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || is_unique_local
+                || is_link_local)
+        }
+    }
+}
+
+// 只返回公网地址的 DNS 解析器，防止域名指向本机或局域网（含 DNS 重绑定）
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+struct PublicOnlyResolver;
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+impl reqwest::dns::Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("httpGet 不允许访问本机或局域网地址：{}", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+#[cfg(all(
+    test,
+    any(target_os = "windows", target_os = "linux", target_os = "macos")
+))]
+mod tests {
+    use super::*;
+
+    const PROBE_SCRIPT: &str = r#"
+        function main(config) {
+            config['has-http-get'] = typeof httpGet === 'function';
+            return config;
+        }
+    "#;
+
+    #[test]
+    fn test_http_get_disabled_by_default() -> Result<(), String> {
+        let mut executor = JsExecutor::new()?;
+        let result = executor.apply("mode: rule\n", PROBE_SCRIPT)?;
+        assert!(result.contains("has-http-get: false"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_script_context_is_read_only() -> Result<(), String> {
+        let mut executor = JsExecutor::new()?;
+        executor.set_script_context(serde_json::json!({
+            "override": { "id": "o1", "name": "Shared" },
+            "mode": "System",
+            "profile": { "name": "Home", "url": null },
+        }));
+        let script = r#"
+            function main(config) {
+                context.mode = 'Core';
+                context.profile.name = 'Changed';
+                delete context.override;
+                context = null;
+                config['seen-mode'] = context.mode;
+                config['seen-profile'] = context.profile.name;
+                config['seen-override'] = context.override.name;
+                return config;
+            }
+        "#;
+        let result = executor.apply("mode: rule\n", script)?;
+        assert!(result.contains("seen-mode: System"));
+        assert!(result.contains("seen-profile: Home"));
+        assert!(result.contains("seen-override: Shared"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_http_get_enabled_explicitly() -> Result<(), String> {
+        let mut executor = JsExecutor::new()?;
+        executor.set_http_get_enabled(true);
+        let result = executor.apply("mode: rule\n", PROBE_SCRIPT)?;
+        assert!(result.contains("has-http-get: true"));
+
+        // 关闭后不应残留上一次注入的函数
+        executor.set_http_get_enabled(false);
+        let result = executor.apply("mode: rule\n", PROBE_SCRIPT)?;
+        assert!(result.contains("has-http-get: false"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_http_get_rejects_local_targets() -> Result<(), String> {
+        for url in [
+            "http://127.0.0.1:9090/configs",
+            "http://10.0.0.1/",
+            "http://192.168.1.1/",
+            "http://100.100.1.1/",
+            "http://[::1]/",
+            "http://[fd00::1]/",
+            "http://[::ffff:192.168.1.1]/",
+            "file:///etc/passwd",
+        ] {
+            let parsed = url::Url::parse(url).map_err(|e| e.to_string())?;
+            assert!(check_public_target(&parsed).is_err(), "{}", url);
+        }
+
+        for url in [
+            "https://example.com/",
+            "http://1.1.1.1/",
+            "http://198.18.0.5/",
+        ] {
+            let parsed = url::Url::parse(url).map_err(|e| e.to_string())?;
+            check_public_target(&parsed)?;
+        }
+
+        Ok(())
+    }
+
+    // 域名解析到本机同样拒绝
+    #[tokio::test]
+    async fn test_resolver_drops_local_addresses() -> Result<(), String> {
+        use reqwest::dns::Resolve;
+        use std::str::FromStr;
+
+        let name = reqwest::dns::Name::from_str("localhost").map_err(|e| e.to_string())?;
+        assert!(PublicOnlyResolver.resolve(name).await.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_runs_are_deterministic_by_default() -> Result<(), String> {
+        let script = r#"
+            function main(config) {
+                config['now'] = Date.now();
+                config['created'] = new Date().toString();
+                config['hour'] = new Date(86400000 * 365).getHours();
+                config['random'] = [Math.random(), Math.random()];
+                return config;
+            }
+        "#;
+
+        let mut executor = JsExecutor::new()?;
+        let first = executor.apply("mode: rule\n", script)?;
+        let second = executor.apply("mode: rule\n", script)?;
+        let third = JsExecutor::new()?.apply("mode: rule\n", script)?;
+        assert_eq!(first, second);
+        assert_eq!(first, third);
+        assert!(first.contains("now: 0"));
+        assert!(first.contains("hour: 0"));
+
+        executor.set_nondeterminism_allowed(true);
+        let real = executor.apply("mode: rule\n", script)?;
+        assert!(!real.contains("now: 0"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_console_output_is_captured_even_on_error() -> Result<(), String> {
+        let mut executor = JsExecutor::new()?;
+        let script = r#"
+            function main(config) {
+                console.log('mode', config.mode, { port: 7890 });
+                console.warn('careful');
+                for (let i = 0; i < 300; i++) {
+                    console.error('x'.repeat(5000));
+                }
+                throw new Error('boom');
+            }
+        "#;
+        assert!(executor.apply("mode: rule\n", script).is_err());
+
+        let messages = executor.take_console_messages();
+        assert_eq!(messages.len(), MAX_CONSOLE_MESSAGES + 1);
+        assert_eq!(messages[0].level, ConsoleLevel::Log);
+        assert_eq!(messages[0].message, r#"mode rule {"port":7890}"#);
+        assert_eq!(messages[1].level, ConsoleLevel::Warn);
+        assert!(messages[2].message.chars().count() < MAX_CONSOLE_MESSAGE_CHARS + 16);
+        assert!(messages[MAX_CONSOLE_MESSAGES].message.contains("102"));
+
+        // 下一次执行前清空
+        executor.apply("mode: rule\n", PROBE_SCRIPT)?;
+        assert!(executor.take_console_messages().is_empty());
+        Ok(())
+    }
+}
@@ -1,14 +1,30 @@
 // 覆写处理器：组合 YAML 合并与 JavaScript 执行能力。
 // 提供统一的覆写应用流程。
 
-use super::js_executor::JsExecutor;
+use super::config_diff::ConfigDiff;
+use super::js_executor::{ConsoleMessage, JsExecutor};
+use super::result_cache;
 use super::yaml_merger::YamlMerger;
 use crate::atoms::shared_types::{OverrideConfig, OverrideFormat};
+use serde_json::json;
+
+// 覆写所应用的订阅信息，注入 JavaScript 覆写的 context 对象
+#[derive(Debug, Clone, Default)]
+pub struct ScriptContext {
+    pub profile_name: Option<String>,
+    pub profile_url: Option<String>,
+    // 代理模式名称：Direct、System 或 Core
+    pub proxy_mode: Option<String>,
+}
 
 // 覆写处理器
 pub struct OverrideProcessor {
     yaml_merger: YamlMerger,
     js_executor: JsExecutor,
+    script_context: ScriptContext,
+    is_cache_enabled: bool,
+    // 最近一次应用中 JavaScript 覆写的 console 输出
+    console_messages: Vec<ConsoleMessage>,
 }
 
 impl OverrideProcessor {
@@ -21,9 +37,27 @@ impl OverrideProcessor {
         Ok(Self {
             yaml_merger,
             js_executor,
+            script_context: ScriptContext::default(),
+            is_cache_enabled: true,
+            console_messages: Vec::new(),
         })
     }
 
+    // 设置 JavaScript 覆写可读取的订阅信息
+    pub fn set_script_context(&mut self, script_context: ScriptContext) {
+        self.script_context = script_context;
+    }
+
+    // 设置是否使用覆写结果缓存，默认开启
+    pub fn set_cache_enabled(&mut self, is_enabled: bool) {
+        self.is_cache_enabled = is_enabled;
+    }
+
+    // 取出最近一次应用覆写时收集的 console 输出；命中缓存的脚本不会产生输出
+    pub fn take_console_messages(&mut self) -> Vec<ConsoleMessage> {
+        std::mem::take(&mut self.console_messages)
+    }
+
     // 按顺序应用覆写并返回最终配置。
     pub fn apply_overrides(
         &mut self,
@@ -31,8 +65,18 @@ impl OverrideProcessor {
         overrides: Vec<OverrideConfig>,
     ) -> Result<String, String> {
         let mut current_config = base_config.to_string();
+        self.console_messages.clear();
 
         for (i, override_cfg) in overrides.iter().enumerate() {
+            if override_cfg.format == OverrideFormat::Unknown {
+                log::warn!(
+                    "[{}] 覆写 {} 的格式无法识别（可能由新版本写入），已跳过",
+                    i,
+                    override_cfg.name
+                );
+                continue;
+            }
+
             log::info!(
                 "[{}] 应用覆写：{}（{:?}）",
                 i,
@@ -40,20 +84,132 @@ impl OverrideProcessor {
                 override_cfg.format
             );
 
+            let script_context = match override_cfg.format {
+                OverrideFormat::Javascript => json!({
+                    "override": { "id": override_cfg.id, "name": override_cfg.name },
+                    "mode": self.script_context.proxy_mode,
+                    "profile": {
+                        "name": self.script_context.profile_name,
+                        "url": self.script_context.profile_url,
+                    },
+                }),
+                OverrideFormat::Yaml | OverrideFormat::Toml | OverrideFormat::Unknown => {
+                    serde_json::Value::Null
+                }
+            };
+
+            // httpGet 仅开放给用户自己编写的覆写，远程覆写即使带有开关也不生效
+            let is_http_get_enabled = override_cfg.is_network_allowed && !override_cfg.is_remote;
+            if override_cfg.is_network_allowed && override_cfg.is_remote {
+                log::warn!(
+                    "[{}] 远程覆写 {} 不允许使用 httpGet，已忽略",
+                    i,
+                    override_cfg.name
+                );
+            }
+
+            // 允许 httpGet 或使用真实时间的脚本结果不可复现，不缓存
+            let is_cacheable = self.is_cache_enabled
+                && !(matches!(override_cfg.format, OverrideFormat::Javascript)
+                    && (is_http_get_enabled || override_cfg.is_nondeterminism_allowed));
+            let cache_key = is_cacheable.then(|| {
+                result_cache::cache_key(&current_config, override_cfg, &script_context.to_string())
+            });
+            if let Some(cached) = cache_key.and_then(result_cache::get_cached) {
+                log::info!("[{}] 使用缓存的覆写结果", i);
+                current_config = cached;
+                continue;
+            }
+
             current_config = match override_cfg.format {
                 OverrideFormat::Yaml => self
                     .yaml_merger
                     .apply(&current_config, &override_cfg.content)
                     .map_err(|e| format!("YAML 覆写失败：{}", e))?,
-                OverrideFormat::Javascript => self
-                    .js_executor
-                    .apply(&current_config, &override_cfg.content)
-                    .map_err(|e| format!("JavaScript 覆写失败：{}", e))?,
+                OverrideFormat::Toml => self
+                    .yaml_merger
+                    .apply_toml(&current_config, &override_cfg.content)
+                    .map_err(|e| format!("TOML 覆写失败：{}", e))?,
+                OverrideFormat::Javascript => {
+                    self.js_executor.set_http_get_enabled(is_http_get_enabled);
+                    self.js_executor
+                        .set_nondeterminism_allowed(override_cfg.is_nondeterminism_allowed);
+                    self.js_executor.set_script_context(script_context);
+                    let result = self
+                        .js_executor
+                        .apply(&current_config, &override_cfg.content);
+                    // 脚本抛出异常时也保留已产生的输出
+                    self.console_messages
+                        .extend(self.js_executor.take_console_messages());
+                    result.map_err(|e| format!("JavaScript 覆写失败：{}", e))?
+                }
+                OverrideFormat::Unknown => current_config,
             };
 
+            if let Some(cache_key) = cache_key {
+                result_cache::store(cache_key, &current_config);
+            }
+
             log::info!("[{}] 覆写应用成功", i);
         }
 
         Ok(current_config)
     }
+
+    // 预览覆写效果：应用覆写后与基础配置比较，返回结构化差异。
+    pub fn preview_overrides(
+        &mut self,
+        base_config: &str,
+        overrides: Vec<OverrideConfig>,
+    ) -> Result<ConfigDiff, String> {
+        let merged_config = self.apply_overrides(base_config, overrides)?;
+
+        let base_value = YamlMerger::parse_resolved(base_config)
+            .map_err(|e| format!("解析基础配置失败：{}", e))?;
+        let merged_value = YamlMerger::parse_resolved(&merged_config)
+            .map_err(|e| format!("解析覆写结果失败：{}", e))?;
+
+        Ok(ConfigDiff::between(&base_value, &merged_value))
+    }
+}
+
+#[cfg(all(
+    test,
+    any(target_os = "windows", target_os = "linux", target_os = "macos")
+))]
+mod tests {
+    use super::OverrideProcessor;
+    use crate::atoms::shared_types::{OverrideConfig, OverrideFormat};
+
+    const PROBE_SCRIPT: &str = r#"
+        function main(config) {
+            config['has-http-get'] = typeof httpGet === 'function';
+            return config;
+        }
+    "#;
+
+    fn js_override(is_remote: bool) -> OverrideConfig {
+        OverrideConfig {
+            id: "probe".to_string(),
+            name: "probe".to_string(),
+            format: OverrideFormat::Javascript,
+            content: PROBE_SCRIPT.to_string(),
+            is_network_allowed: true,
+            is_nondeterminism_allowed: false,
+            is_remote,
+        }
+    }
+
+    #[test]
+    fn test_remote_override_cannot_use_http_get() -> Result<(), String> {
+        let mut processor = OverrideProcessor::new()?;
+        processor.set_cache_enabled(false);
+
+        let result = processor.apply_overrides("mode: rule\n", vec![js_override(true)])?;
+        assert!(result.contains("has-http-get: false"));
+
+        let result = processor.apply_overrides("mode: rule\n", vec![js_override(false)])?;
+        assert!(result.contains("has-http-get: true"));
+        Ok(())
+    }
 }
@@ -0,0 +1,105 @@
+// 覆写结果缓存：以（输入配置，覆写内容与格式，脚本 context）的哈希为键缓存单步覆写输出。
+// 覆写执行是确定性的，相同输入必然得到相同输出；任一输入变化都会得到新的键。
+// 按条目数与总字节数双重限制，超出时淘汰最久未使用的条目。
+
+use crate::atoms::shared_types::OverrideConfig;
+use once_cell::sync::Lazy;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, MutexGuard};
+
+const MAX_ENTRIES: usize = 32;
+const MAX_TOTAL_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Default)]
+struct ResultCache {
+    entries: HashMap<u64, String>,
+    // 使用顺序，队尾为最近使用
+    order: VecDeque<u64>,
+    total_bytes: usize,
+}
+
+impl ResultCache {
+    fn get(&mut self, key: u64) -> Option<String> {
+        let value = self.entries.get(&key)?.clone();
+        self.touch(key);
+        Some(value)
+    }
+
+    fn insert(&mut self, key: u64, value: String) {
+        // 单个结果超过总上限时不缓存
+        if value.len() > MAX_TOTAL_BYTES {
+            return;
+        }
+
+        if let Some(previous) = self.entries.insert(key, value) {
+            self.total_bytes -= previous.len();
+        }
+        self.total_bytes += self.entries.get(&key).map_or(0, String::len);
+        self.touch(key);
+
+        while self.entries.len() > MAX_ENTRIES || self.total_bytes > MAX_TOTAL_BYTES {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            if let Some(evicted) = self.entries.remove(&oldest) {
+                self.total_bytes -= evicted.len();
+            }
+        }
+    }
+
+    fn touch(&mut self, key: u64) {
+        self.order.retain(|existing| *existing != key);
+        self.order.push_back(key);
+    }
+}
+
+static RESULT_CACHE: Lazy<Mutex<ResultCache>> = Lazy::new(|| Mutex::new(ResultCache::default()));
+
+fn lock_cache() -> MutexGuard<'static, ResultCache> {
+    RESULT_CACHE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// 计算单步覆写的缓存键；script_context 为注入 JavaScript 的 context（JSON 文本）
+pub(super) fn cache_key(input: &str, override_cfg: &OverrideConfig, script_context: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    input.hash(&mut hasher);
+    override_cfg.content.hash(&mut hasher);
+    (override_cfg.format as u8).hash(&mut hasher);
+    script_context.hash(&mut hasher);
+    hasher.finish()
+}
+
+pub(super) fn get_cached(key: u64) -> Option<String> {
+    lock_cache().get(key)
+}
+
+pub(super) fn store(key: u64, output: &str) {
+    lock_cache().insert(key, output.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_ENTRIES, ResultCache};
+
+    #[test]
+    fn evicts_least_recently_used_entries() {
+        let mut cache = ResultCache::default();
+        for key in 0..MAX_ENTRIES as u64 {
+            cache.insert(key, format!("output-{}", key));
+        }
+
+        // 访问最早的条目后，再插入新条目应淘汰第二早的条目
+        assert_eq!(cache.get(0).as_deref(), Some("output-0"));
+        cache.insert(100, "output-100".to_string());
+
+        assert!(cache.get(0).is_some());
+        assert!(cache.get(1).is_none());
+        assert_eq!(cache.entries.len(), MAX_ENTRIES);
+        assert_eq!(
+            cache.total_bytes,
+            cache.entries.values().map(String::len).sum::<usize>()
+        );
+    }
+}
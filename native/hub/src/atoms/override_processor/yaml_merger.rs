@@ -1,7 +1,16 @@
 // YAML 配置深度合并：支持特殊语法的覆写合并策略。
 // 用于将覆写配置稳定合并到基础配置。
+// 合并保持键的原有顺序，并尽量保留顶层键前的整行注释。
 
 use serde_yaml_ng::Value as YamlValue;
+use std::collections::HashMap;
+
+// 顶层注释：文件头注释与各顶层键前的注释块
+#[derive(Default)]
+struct TopLevelComments {
+    header: Vec<String>,
+    by_key: HashMap<String, Vec<String>>,
+}
 
 // YAML 合并器
 pub struct YamlMerger;
@@ -21,18 +30,180 @@ impl YamlMerger {
     // 应用 YAML 覆写：解析两份 YAML 并深度合并后返回结果。
     pub fn apply(&self, base_content: &str, override_content: &str) -> Result<String, String> {
         // 解析基础配置
-        let base_value: YamlValue = serde_yaml_ng::from_str(base_content)
-            .map_err(|e| format!("解析基础配置失败：{}", e))?;
+        let base_value =
+            Self::parse_resolved(base_content).map_err(|e| format!("解析基础配置失败：{}", e))?;
 
         // 解析覆写配置
-        let override_value: YamlValue = serde_yaml_ng::from_str(override_content)
+        let override_value = Self::parse_resolved(override_content)
             .map_err(|e| format!("解析覆写配置失败：{}", e))?;
 
+        let override_comments =
+            Self::collect_top_level_comments(override_content, Self::strip_merge_syntax);
+        Self::merge_and_render(base_content, base_value, override_value, override_comments)
+    }
+
+    // 应用 TOML 覆写：TOML 表转换为同构的 YAML 映射后走相同的深度合并流程。
+    // 嵌套表对应嵌套映射，特殊键语法（`"key!"`、`"+key"` 等）同样适用。
+    pub fn apply_toml(&self, base_content: &str, override_content: &str) -> Result<String, String> {
+        let base_value =
+            Self::parse_resolved(base_content).map_err(|e| format!("解析基础配置失败：{}", e))?;
+
+        let override_table: toml::Table = toml::from_str(override_content)
+            .map_err(|e| format!("解析 TOML 覆写配置失败：{}", e))?;
+        let override_value = Self::toml_to_yaml(toml::Value::Table(override_table));
+
+        Self::merge_and_render(
+            base_content,
+            base_value,
+            override_value,
+            TopLevelComments::default(),
+        )
+    }
+
+    // TOML 值转换为 YAML 值。直接反序列化会把日期时间变成内部标记映射，
+    // YAML 中没有对应类型，按 TOML 原文保存为字符串
+    fn toml_to_yaml(value: toml::Value) -> YamlValue {
+        match value {
+            toml::Value::String(text) => YamlValue::String(text),
+            toml::Value::Integer(number) => YamlValue::Number(number.into()),
+            toml::Value::Float(number) => YamlValue::Number(number.into()),
+            toml::Value::Boolean(flag) => YamlValue::Bool(flag),
+            toml::Value::Datetime(datetime) => YamlValue::String(datetime.to_string()),
+            toml::Value::Array(items) => {
+                YamlValue::Sequence(items.into_iter().map(Self::toml_to_yaml).collect())
+            }
+            toml::Value::Table(table) => YamlValue::Mapping(
+                table
+                    .into_iter()
+                    .map(|(key, value)| (YamlValue::String(key), Self::toml_to_yaml(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    // 深度合并并序列化，随后恢复顶层注释
+    fn merge_and_render(
+        base_content: &str,
+        base_value: YamlValue,
+        override_value: YamlValue,
+        override_comments: TopLevelComments,
+    ) -> Result<String, String> {
         // 深度合并
         let merged = Self::deep_merge(base_value, override_value)?;
 
         // 序列化回 YAML
-        serde_yaml_ng::to_string(&merged).map_err(|e| format!("序列化配置失败：{}", e))
+        let output =
+            serde_yaml_ng::to_string(&merged).map_err(|e| format!("序列化配置失败：{}", e))?;
+
+        // 标准反序列化会丢弃注释，这里将顶层注释重新插回。
+        // 基础配置的注释优先，覆写新增的键使用覆写中的注释
+        let mut comments = Self::collect_top_level_comments(base_content, |key| key.to_string());
+        for (key, lines) in override_comments.by_key {
+            comments.by_key.entry(key).or_insert(lines);
+        }
+
+        Ok(Self::restore_top_level_comments(&output, &comments))
+    }
+
+    // 解析 YAML 并展开锚点：别名在解析时已替换为实际内容，
+    // 这里再将 `<<` 合并键展开为普通键，避免合并后出现悬空或重复的合并键
+    pub(super) fn parse_resolved(content: &str) -> Result<YamlValue, String> {
+        let mut value: YamlValue = serde_yaml_ng::from_str(content).map_err(|e| e.to_string())?;
+        value.apply_merge().map_err(|e| e.to_string())?;
+        Ok(value)
+    }
+
+    // 收集顶层键之前紧邻的整行注释。
+    // 首个顶层键之前、以空行分隔的注释视为文件头注释；嵌套块中的注释不处理。
+    fn collect_top_level_comments(
+        content: &str,
+        normalize_key: impl Fn(&str) -> String,
+    ) -> TopLevelComments {
+        let mut comments = TopLevelComments::default();
+        let mut pending: Vec<String> = Vec::new();
+        let mut has_seen_key = false;
+
+        for line in content.lines() {
+            if line.starts_with('#') {
+                pending.push(line.to_string());
+            } else if line.trim().is_empty() {
+                if !has_seen_key && !pending.is_empty() {
+                    comments.header.append(&mut pending);
+                }
+            } else if let Some(key) = Self::top_level_key(line) {
+                has_seen_key = true;
+                if !pending.is_empty() {
+                    comments
+                        .by_key
+                        .insert(normalize_key(&key), std::mem::take(&mut pending));
+                }
+            } else {
+                pending.clear();
+            }
+        }
+
+        comments
+    }
+
+    // 将注释插回序列化结果中对应顶层键之前
+    fn restore_top_level_comments(output: &str, comments: &TopLevelComments) -> String {
+        if comments.header.is_empty() && comments.by_key.is_empty() {
+            return output.to_string();
+        }
+
+        let mut result = String::with_capacity(output.len());
+        for line in &comments.header {
+            result.push_str(line);
+            result.push('\n');
+        }
+        if !comments.header.is_empty() {
+            result.push('\n');
+        }
+
+        for line in output.lines() {
+            if let Some(key) = Self::top_level_key(line)
+                && let Some(lines) = comments.by_key.get(&key)
+            {
+                for comment in lines {
+                    result.push_str(comment);
+                    result.push('\n');
+                }
+            }
+            result.push_str(line);
+            result.push('\n');
+        }
+
+        result
+    }
+
+    // 解析顶层映射键（行首无缩进的 `key:`），返回去除引号后的键名
+    fn top_level_key(line: &str) -> Option<String> {
+        let first = line.chars().next()?;
+        if first.is_whitespace() || matches!(first, '#' | '-' | '{' | '[') {
+            return None;
+        }
+
+        let (key, rest) = line.split_once(':')?;
+        if !(rest.is_empty() || rest.starts_with(' ')) {
+            return None;
+        }
+
+        Some(key.trim().trim_matches(['\'', '"']).to_string())
+    }
+
+    // 去除覆写键上的特殊语法标记，得到实际键名
+    fn strip_merge_syntax(key: &str) -> String {
+        let key = key
+            .strip_suffix('!')
+            .or_else(|| key.strip_prefix('+'))
+            .or_else(|| key.strip_suffix('+'))
+            .unwrap_or(key);
+
+        if key.starts_with('<') && key.ends_with('>') && key.len() > 2 {
+            key[1..key.len() - 1].to_string()
+        } else {
+            key.to_string()
+        }
     }
 
     // 深度合并两个 YAML 值，支持 `key!`、`+key`、`key+`、`<key>` 特殊语法。
@@ -101,10 +272,10 @@ impl YamlMerger {
                     let yaml_key = YamlValue::String(clean_key.to_string());
 
                     // 5. 默认行为：递归合并或替换
-                    if let Some(base_value) = base_map.remove(&yaml_key) {
-                        // 使用 remove 避免克隆，然后递归合并
-                        let merged_value = Self::deep_merge(base_value, override_value)?;
-                        base_map.insert(yaml_key, merged_value);
+                    if let Some(base_value) = base_map.get_mut(&yaml_key) {
+                        // 原地取出再写回，避免克隆并保持键的原有位置
+                        let old_value = std::mem::take(base_value);
+                        *base_value = Self::deep_merge(old_value, override_value)?;
                     } else {
                         // 基础配置中不存在，直接添加
                         base_map.insert(yaml_key, override_value);
@@ -125,3 +296,201 @@ impl YamlMerger {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANCHORED_BASE: &str = r#"
+proxy-defaults: &proxy-defaults
+  udp: true
+  skip-cert-verify: false
+proxies:
+  - name: node-a
+    <<: *proxy-defaults
+    type: ss
+    server: a.example.com
+    port: 443
+  - name: node-b
+    <<: *proxy-defaults
+    type: trojan
+    server: b.example.com
+    port: 443
+proxy-groups:
+  - &auto-group
+    name: auto
+    type: url-test
+    proxies: [node-a, node-b]
+  - *auto-group
+"#;
+
+    fn parse(content: &str) -> Result<YamlValue, String> {
+        serde_yaml_ng::from_str(content).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_merge_resolves_anchors_and_aliases() -> Result<(), String> {
+        let override_content = r#"
+proxy-defaults:
+  udp: false
+proxy-groups!:
+  - name: auto
+    type: url-test
+    proxies: [node-a]
+"#;
+        let result = YamlMerger::new().apply(ANCHORED_BASE, override_content)?;
+        let merged = parse(&result)?;
+
+        let expected = parse(
+            r#"
+proxy-defaults:
+  udp: false
+  skip-cert-verify: false
+proxies:
+  - name: node-a
+    udp: true
+    skip-cert-verify: false
+    type: ss
+    server: a.example.com
+    port: 443
+  - name: node-b
+    udp: true
+    skip-cert-verify: false
+    type: trojan
+    server: b.example.com
+    port: 443
+proxy-groups:
+  - name: auto
+    type: url-test
+    proxies: [node-a]
+"#,
+        )?;
+
+        assert_eq!(merged, expected);
+        assert!(!result.contains("<<"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_untouched_aliases_keep_content() -> Result<(), String> {
+        let result = YamlMerger::new().apply(ANCHORED_BASE, "mode: rule\n")?;
+        let merged = parse(&result)?;
+
+        let groups = merged["proxy-groups"]
+            .as_sequence()
+            .ok_or_else(|| "缺少 proxy-groups".to_string())?;
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], groups[1]);
+        assert_eq!(merged["proxies"][1]["udp"], YamlValue::Bool(true));
+        assert_eq!(merged["mode"], YamlValue::String("rule".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_keeps_key_order_and_top_level_comments() -> Result<(), String> {
+        let base = "# 我的配置\n\
+                    \n\
+                    # 端口\n\
+                    mixed-port: 7890\n\
+                    # 运行模式\n\
+                    mode: rule\n\
+                    dns:\n\
+                    \x20 # 嵌套注释不保留\n\
+                    \x20 enable: false\n\
+                    log-level: info\n";
+        let override_content = "mode: global\n\
+                                 dns:\n\
+                                 \x20 enable: true\n\
+                                 # 新增规则\n\
+                                 rules!:\n\
+                                 \x20 - MATCH,DIRECT\n";
+
+        let result = YamlMerger::new().apply(base, override_content)?;
+        assert_eq!(
+            result,
+            "# 我的配置\n\
+             \n\
+             # 端口\n\
+             mixed-port: 7890\n\
+             # 运行模式\n\
+             mode: global\n\
+             dns:\n\
+             \x20 enable: true\n\
+             log-level: info\n\
+             # 新增规则\n\
+             rules:\n\
+             - MATCH,DIRECT\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_toml_override_merges_onto_yaml_base() -> Result<(), String> {
+        let base = r#"
+mixed-port: 7890
+dns:
+  enable: false
+  nameserver:
+    - 223.5.5.5
+  fallback-filter:
+    geoip: true
+"#;
+        let override_content = r#"
+[dns]
+enable = true
+enhanced-mode = "fake-ip"
+"nameserver+" = ["8.8.8.8"]
+
+[dns.fallback-filter]
+geoip-code = "CN"
+"#;
+        let result = YamlMerger::new().apply_toml(base, override_content)?;
+        let merged = parse(&result)?;
+
+        let expected = parse(
+            r#"
+mixed-port: 7890
+dns:
+  enable: true
+  enhanced-mode: fake-ip
+  nameserver:
+    - 223.5.5.5
+    - 8.8.8.8
+  fallback-filter:
+    geoip: true
+    geoip-code: CN
+"#,
+        )?;
+
+        assert_eq!(merged, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_toml_datetimes_become_strings() -> Result<(), String> {
+        let override_content = r#"
+[profile]
+updated-at = 2024-05-01T08:30:00Z
+local-date = 2024-05-01
+"#;
+        let result = YamlMerger::new().apply_toml("mode: rule\n", override_content)?;
+        let merged = parse(&result)?;
+
+        let expected = parse(
+            r#"
+mode: rule
+profile:
+  updated-at: "2024-05-01T08:30:00Z"
+  local-date: "2024-05-01"
+"#,
+        )?;
+        assert_eq!(merged, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_toml_override_is_rejected() {
+        let result = YamlMerger::new().apply_toml("mode: rule\n", "[dns\nenable = true");
+        assert!(result.is_err());
+    }
+}
@@ -2,4 +2,4 @@
 
 mod parser;
 
-pub use parser::ProxyParser;
+pub use parser::{MixedParseResult, ProxyImportResult, ProxyParser, SkippedSegment};
@@ -6,10 +6,226 @@ use serde_json::{Value as JsonValue, json};
 use std::collections::HashMap;
 use url::Url;
 
+use crate::atoms::host_port::{normalize_host, split_host_port};
+
 // 代理链接解析器
 pub struct ProxyParser;
 
+// YAML 代理导入结果：成功的节点与逐条错误（错误不影响整批导入）
+#[derive(Debug, Default)]
+pub struct ProxyImportResult {
+    pub proxies: Vec<JsonValue>,
+    pub errors: Vec<String>,
+}
+
+// 混合内容解析结果：恢复出的节点与被跳过片段的明细
+#[derive(Debug, Default)]
+pub struct MixedParseResult {
+    pub proxies: Vec<JsonValue>,
+    pub skipped: Vec<SkippedSegment>,
+}
+
+// 被跳过的片段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedSegment {
+    // 片段起始行号（从 1 开始，整体 Base64 解码后为解码内容中的行号）
+    pub line_number: usize,
+    // 片段开头的预览文本
+    pub preview: String,
+    pub reason: String,
+}
+
+// 参与节点稳定标识计算的凭据字段
+const NODE_CREDENTIAL_KEYS: [&str; 7] = [
+    "uuid",
+    "password",
+    "auth-str",
+    "psk",
+    "username",
+    "private-key",
+    "token",
+];
+
+// 参与节点稳定标识计算的传输字段（JSON Pointer）。
+// 经 CDN 中转的节点常共用服务器、端口与凭据，仅靠 SNI、传输方式与路径区分
+const NODE_TRANSPORT_POINTERS: [&str; 9] = [
+    "/cipher",
+    "/network",
+    "/servername",
+    "/sni",
+    "/ws-opts/path",
+    "/ws-opts/host",
+    "/ws-opts/headers/Host",
+    "/grpc-opts/grpc-service-name",
+    "/h2-opts/path",
+];
+
+// FNV-1a 64 位哈希：标识需跨版本与重启保持一致，不能使用 DefaultHasher
+struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl StableHasher {
+    // 每段之后写入分隔符，避免 "ab"+"c" 与 "a"+"bc" 冲突
+    fn write(&mut self, text: &str) {
+        for byte in text.bytes().chain(std::iter::once(0)) {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+// 混合内容中尚未结束的 YAML 片段（起始行号，内容）
+type PendingYamlBlock = Option<(usize, String)>;
+
 impl ProxyParser {
+    // 解析用户粘贴的任意内容，尽可能恢复全部节点。按固定顺序识别：
+    // 1. 整体为 Base64 且解码为 UTF-8 文本时，使用解码后的内容；
+    // 2. 逐行处理：`scheme://` 开头的行按协议解析为分享链接；
+    // 3. 单行 Base64 且解码后包含分享链接时，逐行解析解码内容；
+    // 4. 其余连续行合并为 YAML 片段，按代理列表或完整配置提取节点；
+    // 5. YAML 片段之外的注释行（# 或 //）与无法识别的内容记入跳过明细。
+    pub fn parse_any(text: &str) -> MixedParseResult {
+        let text = text.trim();
+        let decoded = if Self::is_base64(text) {
+            Self::decode_base64_text(text)
+        } else {
+            None
+        };
+        let content = decoded.as_deref().unwrap_or(text);
+
+        let mut result = MixedParseResult::default();
+        let mut yaml_block: PendingYamlBlock = None;
+
+        for (index, raw_line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.trim();
+
+            if Self::is_share_link(line) {
+                Self::flush_yaml_block(&mut yaml_block, &mut result);
+                Self::collect_share_link(line, line_number, &mut result);
+                continue;
+            }
+
+            if let Some(decoded_links) = Self::decode_base64_links(line) {
+                Self::flush_yaml_block(&mut yaml_block, &mut result);
+                for link in decoded_links
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                {
+                    Self::collect_share_link(link, line_number, &mut result);
+                }
+                continue;
+            }
+
+            match yaml_block {
+                Some((_, ref mut block)) => {
+                    block.push_str(raw_line);
+                    block.push('\n');
+                }
+                None if line.is_empty() => {}
+                None if line.starts_with('#') || line.starts_with("//") => {
+                    result.skipped.push(SkippedSegment {
+                        line_number,
+                        preview: Self::preview(line),
+                        reason: "注释行".to_string(),
+                    });
+                }
+                None => yaml_block = Some((line_number, format!("{}\n", raw_line))),
+            }
+        }
+        Self::flush_yaml_block(&mut yaml_block, &mut result);
+
+        log::info!(
+            "混合内容解析完成：恢复 {} 个节点，跳过 {} 个片段",
+            result.proxies.len(),
+            result.skipped.len()
+        );
+        result
+    }
+
+    // 是否为 `scheme://` 形式的分享链接
+    fn is_share_link(line: &str) -> bool {
+        line.split_once("://").is_some_and(|(scheme, _)| {
+            !scheme.is_empty()
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        })
+    }
+
+    fn collect_share_link(link: &str, line_number: usize, result: &mut MixedParseResult) {
+        match Self::parse_single_proxy(link) {
+            Ok(proxy) => result.proxies.push(proxy),
+            Err(reason) => result.skipped.push(SkippedSegment {
+                line_number,
+                preview: Self::preview(link),
+                reason,
+            }),
+        }
+    }
+
+    // 单行 Base64：解码后包含分享链接时返回解码内容
+    fn decode_base64_links(line: &str) -> Option<String> {
+        if line.len() < 16 || line.contains("://") {
+            return None;
+        }
+        let decoded = Self::decode_base64_text(line)?;
+        decoded
+            .lines()
+            .any(|decoded_line| Self::is_share_link(decoded_line.trim()))
+            .then_some(decoded)
+    }
+
+    fn decode_base64_text(content: &str) -> Option<String> {
+        let clean = content.replace(|c: char| c.is_whitespace(), "");
+        let bytes = BASE64.decode(clean.as_bytes()).ok()?;
+        String::from_utf8(bytes).ok()
+    }
+
+    fn flush_yaml_block(yaml_block: &mut PendingYamlBlock, result: &mut MixedParseResult) {
+        let Some((line_number, block)) = yaml_block.take() else {
+            return;
+        };
+
+        match Self::parse_yaml_proxies(&block) {
+            Ok(imported) => {
+                result.proxies.extend(imported.proxies);
+                result
+                    .skipped
+                    .extend(imported.errors.into_iter().map(|reason| SkippedSegment {
+                        line_number,
+                        preview: Self::preview(block.trim()),
+                        reason,
+                    }));
+            }
+            Err(reason) => result.skipped.push(SkippedSegment {
+                line_number,
+                preview: Self::preview(block.trim()),
+                reason,
+            }),
+        }
+    }
+
+    // 取前 50 个字符作为预览（按字符截断，避免 UTF-8 边界问题）
+    fn preview(text: &str) -> String {
+        text.lines()
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .take(50)
+            .collect()
+    }
+
     // 解析订阅内容并输出标准 Clash 配置。
     pub fn parse_subscription(content: &str) -> Result<String, String> {
         let content = content.trim();
@@ -116,6 +332,509 @@ impl ProxyParser {
         Ok(proxies_array.clone())
     }
 
+    // 解析用户粘贴的 Clash/mihomo YAML 代理片段。
+    // 支持完整配置（仅提取 proxies）或纯代理列表，单个节点出错只记录错误。
+    pub fn parse_yaml_proxies(content: &str) -> Result<ProxyImportResult, String> {
+        let yaml_value: serde_yaml_ng::Value =
+            serde_yaml_ng::from_str(content.trim()).map_err(|e| format!("YAML 解析失败：{}", e))?;
+
+        let entries = match yaml_value {
+            serde_yaml_ng::Value::Sequence(entries) => entries,
+            serde_yaml_ng::Value::Mapping(mut map) => match map.remove("proxies") {
+                Some(serde_yaml_ng::Value::Sequence(entries)) => entries,
+                Some(_) => return Err("proxies 不是数组".to_string()),
+                None => return Err("未找到 proxies 字段".to_string()),
+            },
+            _ => return Err("YAML 内容既不是配置也不是代理列表".to_string()),
+        };
+
+        let mut result = ProxyImportResult::default();
+
+        for (index, entry) in entries.into_iter().enumerate() {
+            let entry = match serde_json::to_value(&entry) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    result
+                        .errors
+                        .push(format!("第 {} 个节点：转换为 JSON 失败：{}", index + 1, e));
+                    continue;
+                }
+            };
+
+            // 错误信息优先使用节点名称，缺失时退回序号
+            let label = entry
+                .get("name")
+                .and_then(Self::scalar_to_string)
+                .unwrap_or_else(|| format!("第 {} 个节点", index + 1));
+
+            match Self::normalize_imported_proxy(entry) {
+                Ok(proxy) => result.proxies.push(proxy),
+                Err(e) => {
+                    log::warn!("跳过无效 YAML 代理：{} - {}", label, e);
+                    result.errors.push(format!("{}：{}", label, e));
+                }
+            }
+        }
+
+        log::info!(
+            "YAML 代理导入完成：成功 {} 个，失败 {} 个",
+            result.proxies.len(),
+            result.errors.len()
+        );
+
+        Ok(result)
+    }
+
+    // 校验并规范化单个导入节点的必需字段（name、type、server、port）
+    fn normalize_imported_proxy(mut proxy: JsonValue) -> Result<JsonValue, String> {
+        let map = proxy.as_object_mut().ok_or("节点不是对象")?;
+
+        let name = map
+            .get("name")
+            .and_then(Self::scalar_to_string)
+            .filter(|name| !name.trim().is_empty())
+            .ok_or("缺少 name 字段")?;
+
+        let proxy_type = map
+            .get("type")
+            .and_then(|value| value.as_str())
+            .map(|value| value.trim().to_lowercase())
+            .filter(|value| !value.is_empty())
+            .ok_or("缺少 type 字段")?;
+
+        let server = map
+            .get("server")
+            .and_then(Self::scalar_to_string)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .ok_or("缺少 server 字段")?;
+
+        let port = match map.get("port") {
+            Some(JsonValue::Number(number)) => number.as_u64(),
+            Some(JsonValue::String(text)) => text.trim().parse::<u64>().ok(),
+            _ => return Err("缺少 port 字段".to_string()),
+        }
+        .filter(|port| (1..=65535).contains(port))
+        .ok_or("port 不是有效端口")?;
+
+        map.insert("name".to_string(), json!(name));
+        map.insert("type".to_string(), json!(proxy_type));
+        map.insert("server".to_string(), json!(server));
+        map.insert("port".to_string(), json!(port));
+
+        Ok(proxy)
+    }
+
+    // 计算节点的稳定标识：由类型、服务器、端口、凭据与传输字段计算，与显示名称无关。
+    // 订阅更新只改名称时标识不变，延迟历史与去重据此关联；缺少必需字段时返回 None
+    pub fn stable_node_id(proxy: &JsonValue) -> Option<String> {
+        let proxy_type = proxy.get("type")?.as_str()?.trim().to_lowercase();
+        let server = normalize_host(&Self::scalar_to_string(proxy.get("server")?)?).to_lowercase();
+        let port = Self::scalar_to_string(proxy.get("port")?)?;
+        if proxy_type.is_empty() || server.is_empty() {
+            return None;
+        }
+
+        let mut hasher = StableHasher::default();
+        for part in [proxy_type.as_str(), server.as_str(), port.trim()] {
+            hasher.write(part);
+        }
+        // 凭据只参与哈希，不出现在标识中
+        for key in NODE_CREDENTIAL_KEYS {
+            if let Some(value) = proxy.get(key).and_then(Self::scalar_to_string) {
+                hasher.write(key);
+                hasher.write(&value);
+            }
+        }
+        for pointer in NODE_TRANSPORT_POINTERS {
+            if let Some(value) = proxy.pointer(pointer).and_then(Self::scalar_to_string) {
+                hasher.write(pointer);
+                hasher.write(&value);
+            }
+        }
+
+        Some(format!("{}-{:016x}", proxy_type, hasher.finish()))
+    }
+
+    // 将字符串或数字标量转换为字符串（YAML 中纯数字名称会被解析为数字）
+    fn scalar_to_string(value: &JsonValue) -> Option<String> {
+        match value {
+            JsonValue::String(text) => Some(text.clone()),
+            JsonValue::Number(number) => Some(number.to_string()),
+            _ => None,
+        }
+    }
+
+    // 将 sing-box 配置中的 outbounds 转换为 mihomo 代理节点。
+    // 支持完整配置或纯出站数组，不支持的出站类型会被跳过并记录其标签。
+    pub fn convert_sing_box_outbounds(content: &str) -> Result<ProxyImportResult, String> {
+        let value: JsonValue = serde_json::from_str(content.trim())
+            .map_err(|e| format!("sing-box 配置解析失败：{}", e))?;
+
+        let outbounds = match value {
+            JsonValue::Array(outbounds) => outbounds,
+            JsonValue::Object(mut map) => match map.remove("outbounds") {
+                Some(JsonValue::Array(outbounds)) => outbounds,
+                Some(_) => return Err("outbounds 不是数组".to_string()),
+                None => return Err("未找到 outbounds 字段".to_string()),
+            },
+            _ => return Err("内容既不是 sing-box 配置也不是出站列表".to_string()),
+        };
+
+        let mut result = ProxyImportResult::default();
+        let mut skipped_tags = Vec::new();
+
+        for (index, outbound) in outbounds.iter().enumerate() {
+            let tag = outbound
+                .get("tag")
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("第 {} 个出站", index + 1));
+            let outbound_type = outbound
+                .get("type")
+                .and_then(|value| value.as_str())
+                .unwrap_or("");
+
+            let converted = match outbound_type {
+                "shadowsocks" => Self::convert_sing_box_shadowsocks(outbound),
+                "vmess" => Self::convert_sing_box_vmess(outbound),
+                "vless" => Self::convert_sing_box_vless(outbound),
+                "trojan" => Self::convert_sing_box_trojan(outbound),
+                "hysteria2" => Self::convert_sing_box_hysteria2(outbound),
+                "tuic" => Self::convert_sing_box_tuic(outbound),
+                _ => {
+                    skipped_tags.push(format!("{}（{}）", tag, outbound_type));
+                    continue;
+                }
+            };
+
+            match converted.and_then(Self::normalize_imported_proxy) {
+                Ok(proxy) => result.proxies.push(proxy),
+                Err(e) => {
+                    log::warn!("sing-box 出站转换失败：{} - {}", tag, e);
+                    result.errors.push(format!("{}：{}", tag, e));
+                }
+            }
+        }
+
+        if !skipped_tags.is_empty() {
+            let skipped = skipped_tags.join("、");
+            log::warn!("跳过不支持的 sing-box 出站：{}", skipped);
+            result
+                .errors
+                .push(format!("跳过不支持的出站类型：{}", skipped));
+        }
+
+        log::info!(
+            "sing-box 出站转换完成：成功 {} 个，失败或跳过 {} 个",
+            result.proxies.len(),
+            outbounds.len() - result.proxies.len()
+        );
+
+        Ok(result)
+    }
+
+    fn convert_sing_box_shadowsocks(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::sing_box_base(outbound, "ss");
+        proxy["cipher"] = json!(Self::sing_box_str(outbound, "method")?);
+        proxy["password"] = json!(Self::sing_box_str(outbound, "password")?);
+
+        if let Some(plugin) = outbound.get("plugin").and_then(|value| value.as_str()) {
+            let opts: HashMap<&str, &str> = outbound
+                .get("plugin_opts")
+                .and_then(|value| value.as_str())
+                .unwrap_or("")
+                .split(';')
+                .filter(|item| !item.is_empty())
+                .map(|item| item.split_once('=').unwrap_or((item, "")))
+                .collect();
+
+            match plugin {
+                "obfs-local" => {
+                    proxy["plugin"] = json!("obfs");
+                    proxy["plugin-opts"] = json!({
+                        "mode": opts.get("obfs").copied().unwrap_or("http"),
+                        "host": opts.get("obfs-host").copied().unwrap_or(""),
+                    });
+                }
+                "v2ray-plugin" => {
+                    proxy["plugin"] = json!("v2ray-plugin");
+                    proxy["plugin-opts"] = json!({
+                        "mode": opts.get("mode").copied().unwrap_or("websocket"),
+                        "host": opts.get("host").copied().unwrap_or(""),
+                        "path": opts.get("path").copied().unwrap_or("/"),
+                        "tls": opts.contains_key("tls"),
+                    });
+                }
+                _ => return Err(format!("不支持的 Shadowsocks 插件：{}", plugin)),
+            }
+        }
+
+        Ok(proxy)
+    }
+
+    fn convert_sing_box_vmess(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::sing_box_base(outbound, "vmess");
+        proxy["uuid"] = json!(Self::sing_box_str(outbound, "uuid")?);
+        proxy["alterId"] = json!(
+            outbound
+                .get("alter_id")
+                .and_then(|value| value.as_i64())
+                .unwrap_or(0)
+        );
+        proxy["cipher"] = json!(
+            outbound
+                .get("security")
+                .and_then(|value| value.as_str())
+                .unwrap_or("auto")
+        );
+
+        Self::apply_sing_box_tls(outbound, &mut proxy, "servername", true);
+        Self::apply_sing_box_transport(outbound, &mut proxy)?;
+
+        Ok(proxy)
+    }
+
+    fn convert_sing_box_vless(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::sing_box_base(outbound, "vless");
+        proxy["uuid"] = json!(Self::sing_box_str(outbound, "uuid")?);
+
+        if let Some(flow) = outbound.get("flow").and_then(|value| value.as_str())
+            && !flow.is_empty()
+        {
+            proxy["flow"] = json!(flow);
+        }
+
+        Self::apply_sing_box_tls(outbound, &mut proxy, "servername", true);
+        Self::apply_sing_box_transport(outbound, &mut proxy)?;
+
+        Ok(proxy)
+    }
+
+    fn convert_sing_box_trojan(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::sing_box_base(outbound, "trojan");
+        proxy["password"] = json!(Self::sing_box_str(outbound, "password")?);
+
+        Self::apply_sing_box_tls(outbound, &mut proxy, "sni", false);
+        Self::apply_sing_box_transport(outbound, &mut proxy)?;
+
+        Ok(proxy)
+    }
+
+    fn convert_sing_box_hysteria2(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::sing_box_base(outbound, "hysteria2");
+        proxy["password"] = json!(Self::sing_box_str(outbound, "password")?);
+
+        if let Some(up) = outbound.get("up_mbps").and_then(|value| value.as_i64()) {
+            proxy["up"] = json!(up);
+        }
+        if let Some(down) = outbound.get("down_mbps").and_then(|value| value.as_i64()) {
+            proxy["down"] = json!(down);
+        }
+
+        // 端口跳跃：sing-box 使用 "起:止" 列表，mihomo 使用逗号分隔的 "起-止"
+        if let Some(ports) = outbound.get("server_ports") {
+            let ports = Self::json_string_list(ports)
+                .iter()
+                .map(|range| range.replace(':', "-"))
+                .collect::<Vec<_>>()
+                .join(",");
+            if !ports.is_empty() {
+                proxy["ports"] = json!(ports);
+            }
+        }
+
+        if let Some(obfs) = outbound.get("obfs") {
+            if let Some(obfs_type) = obfs.get("type").and_then(|value| value.as_str()) {
+                proxy["obfs"] = json!(obfs_type);
+            }
+            if let Some(password) = obfs.get("password").and_then(|value| value.as_str()) {
+                proxy["obfs-password"] = json!(password);
+            }
+        }
+
+        Self::apply_sing_box_tls(outbound, &mut proxy, "sni", false);
+
+        Ok(proxy)
+    }
+
+    fn convert_sing_box_tuic(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::sing_box_base(outbound, "tuic");
+        proxy["uuid"] = json!(Self::sing_box_str(outbound, "uuid")?);
+        proxy["password"] = json!(Self::sing_box_str(outbound, "password")?);
+
+        if let Some(congestion) = outbound
+            .get("congestion_control")
+            .and_then(|value| value.as_str())
+        {
+            proxy["congestion-controller"] = json!(congestion);
+        }
+        if let Some(mode) = outbound
+            .get("udp_relay_mode")
+            .and_then(|value| value.as_str())
+        {
+            proxy["udp-relay-mode"] = json!(mode);
+        }
+        if let Some(zero_rtt) = outbound
+            .get("zero_rtt_handshake")
+            .and_then(|value| value.as_bool())
+        {
+            proxy["reduce-rtt"] = json!(zero_rtt);
+        }
+
+        Self::apply_sing_box_tls(outbound, &mut proxy, "sni", false);
+
+        Ok(proxy)
+    }
+
+    // 生成 mihomo 节点的公共字段，缺失字段交由 normalize_imported_proxy 报错
+    fn sing_box_base(outbound: &JsonValue, proxy_type: &str) -> JsonValue {
+        json!({
+            "name": outbound.get("tag").cloned().unwrap_or(JsonValue::Null),
+            "type": proxy_type,
+            "server": outbound.get("server").cloned().unwrap_or(JsonValue::Null),
+            "port": outbound.get("server_port").cloned().unwrap_or(JsonValue::Null),
+            "udp": true,
+        })
+    }
+
+    fn sing_box_str(outbound: &JsonValue, key: &str) -> Result<String, String> {
+        outbound
+            .get(key)
+            .and_then(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| format!("缺少 {} 字段", key))
+    }
+
+    // 映射 sing-box 的 tls 配置块，sni_key 为目标协议在 mihomo 中的 SNI 字段名
+    fn apply_sing_box_tls(
+        outbound: &JsonValue,
+        proxy: &mut JsonValue,
+        sni_key: &str,
+        set_tls_flag: bool,
+    ) {
+        let Some(tls) = outbound.get("tls") else {
+            return;
+        };
+        if !tls
+            .get("enabled")
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false)
+        {
+            return;
+        }
+
+        if set_tls_flag {
+            proxy["tls"] = json!(true);
+        }
+        if let Some(sni) = tls.get("server_name").and_then(|value| value.as_str()) {
+            proxy[sni_key] = json!(sni);
+        }
+        if let Some(insecure) = tls.get("insecure").and_then(|value| value.as_bool()) {
+            proxy["skip-cert-verify"] = json!(insecure);
+        }
+        if let Some(alpn) = tls.get("alpn") {
+            proxy["alpn"] = json!(Self::json_string_list(alpn));
+        }
+        if let Some(fingerprint) = tls
+            .pointer("/utls/fingerprint")
+            .and_then(|value| value.as_str())
+        {
+            proxy["client-fingerprint"] = json!(fingerprint);
+        }
+
+        // Reality 配置
+        if let Some(reality) = tls.get("reality")
+            && reality
+                .get("enabled")
+                .and_then(|value| value.as_bool())
+                .unwrap_or(false)
+        {
+            proxy["reality-opts"] = json!({
+                "public-key": reality.get("public_key").and_then(|v| v.as_str()).unwrap_or(""),
+                "short-id": reality.get("short_id").and_then(|v| v.as_str()).unwrap_or(""),
+            });
+        }
+    }
+
+    // 映射 sing-box 的 transport 配置块
+    fn apply_sing_box_transport(outbound: &JsonValue, proxy: &mut JsonValue) -> Result<(), String> {
+        let Some(transport) = outbound.get("transport") else {
+            return Ok(());
+        };
+
+        let path = transport.get("path").and_then(|value| value.as_str());
+        let headers = transport.get("headers").cloned();
+
+        match transport.get("type").and_then(|value| value.as_str()) {
+            Some("ws") | Some("httpupgrade") => {
+                proxy["network"] = json!("ws");
+                let mut ws_opts = json!({ "path": path.unwrap_or("/") });
+                if let Some(headers) = headers {
+                    ws_opts["headers"] = headers;
+                }
+                if let Some(max_early_data) = transport.get("max_early_data") {
+                    ws_opts["max-early-data"] = max_early_data.clone();
+                }
+                if let Some(header_name) = transport.get("early_data_header_name") {
+                    ws_opts["early-data-header-name"] = header_name.clone();
+                }
+                if transport.get("type").and_then(|value| value.as_str()) == Some("httpupgrade") {
+                    ws_opts["v2ray-http-upgrade"] = json!(true);
+                }
+                proxy["ws-opts"] = ws_opts;
+            }
+            Some("grpc") => {
+                proxy["network"] = json!("grpc");
+                proxy["grpc-opts"] = json!({
+                    "grpc-service-name": transport
+                        .get("service_name")
+                        .and_then(|value| value.as_str())
+                        .unwrap_or(""),
+                });
+            }
+            Some("http") => {
+                let hosts = transport
+                    .get("host")
+                    .map(Self::json_string_list)
+                    .unwrap_or_default();
+                // sing-box 的 http 传输在启用 TLS 时为 HTTP/2，否则为 HTTP/1.1
+                if proxy.get("tls").and_then(|value| value.as_bool()) == Some(true) {
+                    proxy["network"] = json!("h2");
+                    proxy["h2-opts"] = json!({ "host": hosts, "path": path.unwrap_or("/") });
+                } else {
+                    proxy["network"] = json!("http");
+                    let mut http_opts = json!({ "path": [path.unwrap_or("/")] });
+                    if !hosts.is_empty() {
+                        http_opts["headers"] = json!({ "Host": hosts });
+                    }
+                    proxy["http-opts"] = http_opts;
+                }
+            }
+            Some(other) => return Err(format!("不支持的传输类型：{}", other)),
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    // 将字符串（逗号分隔）或字符串数组统一转换为列表
+    fn json_string_list(value: &JsonValue) -> Vec<String> {
+        match value {
+            JsonValue::String(text) => text
+                .split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect(),
+            JsonValue::Array(items) => items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
     // 解析代理链接列表
     fn parse_proxy_links(content: &str) -> Result<Vec<JsonValue>, String> {
         let mut proxies = Vec::new();
@@ -175,7 +894,7 @@ impl ProxyParser {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
         let uuid = url.username();
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url.port().ok_or("缺少端口")? as i64;
 
         let params = Self::parse_query_params(url.query().unwrap_or(""));
@@ -247,7 +966,7 @@ impl ProxyParser {
         let mut proxy = json!({
             "name": data["ps"].as_str().unwrap_or("VMess"),
             "type": "vmess",
-            "server": data["add"].as_str().unwrap_or(""),
+            "server": normalize_host(data["add"].as_str().unwrap_or("")),
             "port": data["port"].as_str().unwrap_or("443").parse::<i64>().unwrap_or(443),
             "uuid": data["id"].as_str().unwrap_or(""),
             "alterId": data["aid"].as_str().unwrap_or("0").parse::<i64>().unwrap_or(0),
@@ -294,7 +1013,7 @@ impl ProxyParser {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
         let password = url.username();
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url.port().unwrap_or(443) as i64;
 
         let params = Self::parse_query_params(url.query().unwrap_or(""));
@@ -327,7 +1046,7 @@ impl ProxyParser {
     fn parse_hysteria(link: &str) -> Result<JsonValue, String> {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url.port().unwrap_or(443) as i64;
         let auth = url.username();
 
@@ -381,11 +1100,8 @@ impl ProxyParser {
         // 解析服务器和端口
         let (server_port, name_part) = rest.split_once('#').unwrap_or((rest, "Shadowsocks"));
 
-        let (server, port_str) = server_port
-            .rsplit_once(':')
-            .ok_or("SS 链接格式错误：缺少端口")?;
-
-        let port = port_str.parse::<i64>().map_err(|_| "端口解析失败")?;
+        let (server, port) =
+            split_host_port(server_port).ok_or("SS 链接格式错误：服务器地址或端口无效")?;
 
         let name = Self::url_decode(name_part);
 
@@ -411,12 +1127,14 @@ impl ProxyParser {
 
         let (main_part, params_part) = decoded_str.split_once("/?").unwrap_or((&decoded_str, ""));
 
-        let parts: Vec<&str> = main_part.split(':').collect();
+        // 服务器可能是 IPv6 地址，从右侧拆分出其余 5 个字段
+        let mut parts: Vec<&str> = main_part.rsplitn(6, ':').collect();
         if parts.len() < 6 {
             return Err("SSR 链接格式错误".to_string());
         }
+        parts.reverse();
 
-        let server = parts[0];
+        let server = normalize_host(parts[0]);
         let port = parts[1].parse::<i64>().map_err(|_| "端口解析失败")?;
         let protocol = parts[2];
         let method = parts[3];
@@ -480,7 +1198,7 @@ impl ProxyParser {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
         let password = url.username();
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url.port().unwrap_or(443) as i64;
 
         let params = Self::parse_query_params(url.query().unwrap_or(""));
@@ -527,13 +1245,27 @@ impl ProxyParser {
     fn parse_tuic(link: &str) -> Result<JsonValue, String> {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
-        let uuid = url.username();
-        let password = url.password().unwrap_or("");
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        // TUIC v5 的用户信息必须是 uuid:password
+        let uuid = Self::url_decode(url.username());
+        let password = url.password().map(Self::url_decode).unwrap_or_default();
+        if uuid.is_empty() || password.is_empty() {
+            return Err("TUIC 链接缺少 uuid:password 用户信息".to_string());
+        }
+
+        // IPv6 地址去掉方括号，mihomo 的 server 字段不接受带括号的地址
+        let server = match url.host().ok_or("缺少服务器地址")? {
+            url::Host::Ipv6(addr) => addr.to_string(),
+            host => host.to_string(),
+        };
         let port = url.port().unwrap_or(443) as i64;
 
         let params = Self::parse_query_params(url.query().unwrap_or(""));
         let name = Self::url_decode(url.fragment().unwrap_or("TUIC"));
+        let is_flag_set = |keys: &[&str]| {
+            keys.iter()
+                .filter_map(|key| params.get(*key))
+                .any(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+        };
 
         let mut proxy = json!({
             "name": name,
@@ -542,19 +1274,49 @@ impl ProxyParser {
             "port": port,
             "uuid": uuid,
             "password": password,
-            "skip-cert-verify": params.get("insecure").map(|s| s == "1").unwrap_or(false),
+            "udp": true,
+            "skip-cert-verify": is_flag_set(&["insecure", "allow_insecure", "allowInsecure"]),
         });
 
-        if let Some(sni) = params.get("sni") {
+        if let Some(sni) = params.get("sni").filter(|sni| !sni.is_empty()) {
             proxy["sni"] = json!(sni);
         }
 
         if let Some(alpn) = params.get("alpn") {
-            proxy["alpn"] = json!(alpn.split(',').collect::<Vec<_>>());
+            let alpn = alpn
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .collect::<Vec<_>>();
+            if !alpn.is_empty() {
+                proxy["alpn"] = json!(alpn);
+            }
+        }
+
+        if let Some(congestion) = [
+            "congestion_control",
+            "congestion-control",
+            "congestion_controller",
+        ]
+        .iter()
+        .find_map(|key| params.get(*key))
+        {
+            proxy["congestion-controller"] = json!(congestion);
+        }
+
+        if let Some(mode) = ["udp_relay_mode", "udp-relay-mode"]
+            .iter()
+            .find_map(|key| params.get(*key))
+        {
+            proxy["udp-relay-mode"] = json!(mode);
+        }
+
+        if is_flag_set(&["disable_sni", "disable-sni"]) {
+            proxy["disable-sni"] = json!(true);
         }
 
-        if let Some(congestion) = params.get("congestion_control") {
-            proxy["congestion-control"] = json!(congestion);
+        if is_flag_set(&["reduce_rtt", "reduce-rtt", "zero_rtt_handshake"]) {
+            proxy["reduce-rtt"] = json!(true);
         }
 
         Ok(proxy)
@@ -564,7 +1326,7 @@ impl ProxyParser {
     fn parse_http(link: &str) -> Result<JsonValue, String> {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url
             .port()
             .unwrap_or(if link.starts_with("https") { 443 } else { 80 }) as i64;
@@ -603,7 +1365,7 @@ impl ProxyParser {
     fn parse_socks(link: &str) -> Result<JsonValue, String> {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url.port().unwrap_or(1080) as i64;
         let username = if url.username().is_empty() {
             None
@@ -698,3 +1460,205 @@ impl ProxyParser {
         Ok(yaml_string)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ProxyParser;
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD as BASE64;
+
+    #[test]
+    fn recovers_proxies_from_mixed_paste() {
+        let encoded_links = BASE64.encode("trojan://pass@b.example.com:443#B\n");
+        let text = format!(
+            "# 我的节点\n\
+             socks5://c.example.com:1080#C\n\
+             unknown://whatever\n\
+             {}\n\
+             proxies:\n\
+             \x20 - {{name: A, type: ss, server: a.example.com, port: 8388, cipher: aes-128-gcm, password: x}}\n\
+             \x20 - {{name: Broken, type: ss}}\n",
+            encoded_links
+        );
+
+        let result = ProxyParser::parse_any(&text);
+        let names: Vec<&str> = result
+            .proxies
+            .iter()
+            .filter_map(|proxy| proxy["name"].as_str())
+            .collect();
+        assert_eq!(names, ["C", "B", "A"]);
+
+        let skipped: Vec<(usize, &str)> = result
+            .skipped
+            .iter()
+            .map(|segment| (segment.line_number, segment.reason.as_str()))
+            .collect();
+        assert_eq!(skipped.len(), 3);
+        assert_eq!(skipped[0], (1, "注释行"));
+        assert_eq!(skipped[1].0, 3);
+        assert!(skipped[1].1.contains("不支持的协议"));
+        assert_eq!(skipped[2].0, 5);
+        assert!(skipped[2].1.contains("Broken"));
+    }
+
+    #[test]
+    fn parses_shadowsocks_with_unbracketed_ipv6() -> Result<(), String> {
+        let auth = BASE64.encode("aes-256-gcm:pass");
+        for server_port in ["2001:db8::1:8388", "[2001:db8::1]:8388"] {
+            let link = format!("ss://{}@{}#v6", auth, server_port);
+            let proxy = ProxyParser::parse_shadowsocks(&link)?;
+            assert_eq!(proxy["server"], "2001:db8::1");
+            assert_eq!(proxy["port"], 8388);
+            assert_eq!(proxy["cipher"], "aes-256-gcm");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn imports_yaml_proxies_and_reports_bad_entries() -> Result<(), String> {
+        let config = "mixed-port: 7890\n\
+                      proxies:\n\
+                      \x20 - {name: 123, type: SS, server: ' a.example.com ', port: '8388', cipher: aes-128-gcm, password: x}\n\
+                      \x20 - {name: NoServer, type: trojan, port: 443}\n\
+                      \x20 - {name: BadPort, type: trojan, server: b.example.com, port: 70000}\n\
+                      \x20 - {type: socks5, server: c.example.com, port: 1080}\n";
+
+        let result = ProxyParser::parse_yaml_proxies(config)?;
+        assert_eq!(result.proxies.len(), 1);
+        let proxy = &result.proxies[0];
+        assert_eq!(proxy["name"], "123");
+        assert_eq!(proxy["type"], "ss");
+        assert_eq!(proxy["server"], "a.example.com");
+        assert_eq!(proxy["port"], 8388);
+        assert_eq!(proxy["cipher"], "aes-128-gcm");
+        assert_eq!(
+            result.errors,
+            [
+                "NoServer：缺少 server 字段",
+                "BadPort：port 不是有效端口",
+                "第 4 个节点：缺少 name 字段",
+            ]
+        );
+
+        // 纯代理列表同样可导入，缺少 proxies 的配置报错
+        let list = "- {name: A, type: ss, server: a.example.com, port: 8388}\n";
+        assert_eq!(ProxyParser::parse_yaml_proxies(list)?.proxies.len(), 1);
+        assert!(ProxyParser::parse_yaml_proxies("mixed-port: 7890\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn converts_sing_box_outbounds() -> Result<(), String> {
+        let config = r#"{
+            "outbounds": [
+                {
+                    "type": "vless", "tag": "VL", "server": "v.example.com", "server_port": 443,
+                    "uuid": "b831381d-6324-4d53-ad4f-8cda48b30811", "flow": "xtls-rprx-vision",
+                    "tls": {
+                        "enabled": true, "server_name": "sni.example.com",
+                        "utls": { "enabled": true, "fingerprint": "chrome" },
+                        "reality": { "enabled": true, "public_key": "pk", "short_id": "ab" }
+                    },
+                    "transport": { "type": "ws", "path": "/ws", "headers": { "Host": "h.example.com" } }
+                },
+                {
+                    "type": "hysteria2", "tag": "HY2", "server": "h.example.com", "server_port": 443,
+                    "server_ports": ["20000:30000"], "password": "p",
+                    "tls": { "enabled": true, "server_name": "h.example.com", "insecure": true }
+                },
+                { "type": "trojan", "tag": "NoPassword", "server": "t.example.com", "server_port": 443 },
+                { "type": "direct", "tag": "direct" },
+                { "type": "selector", "tag": "proxy", "outbounds": ["VL"] }
+            ]
+        }"#;
+
+        let result = ProxyParser::convert_sing_box_outbounds(config)?;
+        let names: Vec<&str> = result
+            .proxies
+            .iter()
+            .filter_map(|proxy| proxy["name"].as_str())
+            .collect();
+        assert_eq!(names, ["VL", "HY2"]);
+
+        let vless = &result.proxies[0];
+        assert_eq!(vless["type"], "vless");
+        assert_eq!(vless["flow"], "xtls-rprx-vision");
+        assert_eq!(vless["tls"], true);
+        assert_eq!(vless["servername"], "sni.example.com");
+        assert_eq!(vless["client-fingerprint"], "chrome");
+        assert_eq!(vless["reality-opts"]["public-key"], "pk");
+        assert_eq!(vless["network"], "ws");
+        assert_eq!(vless["ws-opts"]["headers"]["Host"], "h.example.com");
+
+        let hysteria2 = &result.proxies[1];
+        assert_eq!(hysteria2["ports"], "20000-30000");
+        assert_eq!(hysteria2["sni"], "h.example.com");
+        assert_eq!(hysteria2["skip-cert-verify"], true);
+
+        assert_eq!(
+            result.errors,
+            [
+                "NoPassword：缺少 password 字段",
+                "跳过不支持的出站类型：direct（direct）、proxy（selector）",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stable_node_id_ignores_display_name() {
+        let original = serde_json::json!({
+            "name": "🇭🇰 香港 01", "type": "ss", "server": "hk.example.com",
+            "port": 8388, "cipher": "aes-128-gcm", "password": "secret",
+        });
+        let mut renamed = original.clone();
+        renamed["name"] = serde_json::json!("HK-01 | 1.5x");
+        renamed["port"] = serde_json::json!("8388");
+
+        let id = ProxyParser::stable_node_id(&original);
+        assert!(id.as_deref().is_some_and(|id| id.starts_with("ss-")));
+        assert_eq!(id, ProxyParser::stable_node_id(&renamed));
+
+        let mut rotated = original.clone();
+        rotated["password"] = serde_json::json!("rotated");
+        assert_ne!(id, ProxyParser::stable_node_id(&rotated));
+        assert_eq!(
+            ProxyParser::stable_node_id(&serde_json::json!({"name": "A", "type": "ss"})),
+            None
+        );
+    }
+
+    #[test]
+    fn stable_node_id_separates_cdn_fronted_nodes() {
+        let base = serde_json::json!({
+            "name": "CDN 01", "type": "vless", "server": "cdn.example.com", "port": 443,
+            "uuid": "b831381d-6324-4d53-ad4f-8cda48b30811", "network": "ws",
+            "servername": "a.example.com",
+            "ws-opts": { "path": "/a", "headers": { "Host": "a.example.com" } },
+        });
+        let id = ProxyParser::stable_node_id(&base);
+        assert!(id.is_some());
+
+        let mut other_sni = base.clone();
+        other_sni["servername"] = serde_json::json!("b.example.com");
+        let mut other_host = base.clone();
+        other_host["ws-opts"]["headers"]["Host"] = serde_json::json!("b.example.com");
+        let mut other_path = base.clone();
+        other_path["ws-opts"]["path"] = serde_json::json!("/b");
+        let mut other_network = base.clone();
+        other_network["network"] = serde_json::json!("grpc");
+
+        for variant in [&other_sni, &other_host, &other_path, &other_network] {
+            assert_ne!(id, ProxyParser::stable_node_id(variant), "{}", variant);
+        }
+
+        let mut other_cipher = serde_json::json!({
+            "type": "ss", "server": "hk.example.com", "port": 8388,
+            "cipher": "aes-128-gcm", "password": "secret",
+        });
+        let ss_id = ProxyParser::stable_node_id(&other_cipher);
+        other_cipher["cipher"] = serde_json::json!("chacha20-ietf-poly1305");
+        assert_ne!(ss_id, ProxyParser::stable_node_id(&other_cipher));
+    }
+}
@@ -5,10 +5,14 @@ use rinf::SignalPiece;
 use serde::{Deserialize, Serialize};
 
 // 覆写格式
-#[derive(Deserialize, Serialize, SignalPiece, Clone, Copy, Debug)]
+#[derive(Deserialize, Serialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum OverrideFormat {
     Yaml = 0,
     Javascript = 1,
+    Toml = 3,
+    // 新版本写入、当前版本不认识的格式，应用覆写时原样跳过，避免整个配置加载失败
+    #[serde(other)]
+    Unknown = 255,
 }
 
 // 覆写配置
@@ -18,4 +22,37 @@ pub struct OverrideConfig {
     pub name: String,
     pub format: OverrideFormat,
     pub content: String,
+    // 是否允许 JavaScript 覆写使用 httpGet，由用户在单个覆写上手动开启
+    #[serde(default)]
+    pub is_network_allowed: bool,
+    // 是否让 JavaScript 覆写使用真实时间与随机数，默认使用固定值以保证结果可复现
+    #[serde(default)]
+    pub is_nondeterminism_allowed: bool,
+    // 是否为远程覆写；远程脚本不由用户编写，始终不开放 httpGet
+    #[serde(default)]
+    pub is_remote: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OverrideFormat;
+    use serde::Deserialize;
+    use serde::de::value::{Error, U32Deserializer};
+
+    #[test]
+    fn unknown_format_falls_back_instead_of_failing() -> Result<(), String> {
+        // 信号按变体序号编码
+        let decode = |index| {
+            OverrideFormat::deserialize(U32Deserializer::<Error>::new(index))
+                .map_err(|e| e.to_string())
+        };
+        assert_eq!(decode(0)?, OverrideFormat::Yaml);
+        assert_eq!(decode(2)?, OverrideFormat::Toml);
+        assert_eq!(decode(42)?, OverrideFormat::Unknown);
+
+        let format: OverrideFormat =
+            serde_json::from_str(r#""Json""#).map_err(|e| e.to_string())?;
+        assert_eq!(format, OverrideFormat::Unknown);
+        Ok(())
+    }
 }
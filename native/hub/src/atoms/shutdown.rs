@@ -0,0 +1,45 @@
+// 应用关闭信号：Dart 端关闭后由入口层触发。
+// 此时 Dart 已无法接收信号，长时间运行的任务收到信号后只需停止工作、释放资源；
+// 通过 spawn_listener 启动的监听循环在收到信号后立即退出。
+// Dart 热重启时 Rust 入口会在同一进程内再次运行，启动时须先复位信号。
+
+use once_cell::sync::Lazy;
+use std::future::Future;
+use tokio::sync::watch;
+
+static SHUTDOWN_TX: Lazy<watch::Sender<bool>> = Lazy::new(|| watch::channel(false).0);
+
+pub fn is_shutting_down() -> bool {
+    *SHUTDOWN_TX.borrow()
+}
+
+// 等待应用关闭信号
+pub async fn wait_for_shutdown() {
+    let mut receiver = SHUTDOWN_TX.subscribe();
+    let _ = receiver
+        .wait_for(|is_shutting_down| *is_shutting_down)
+        .await;
+}
+
+// 启动随应用关闭而退出的监听任务（如 Dart 消息监听循环）
+pub fn spawn_listener<F>(listener: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = listener => {}
+            _ = wait_for_shutdown() => {}
+        }
+    });
+}
+
+// Dart 启动时复位关闭信号，避免上一次运行留下的信号让监听任务立即退出
+pub fn reset() {
+    SHUTDOWN_TX.send_replace(false);
+}
+
+// 触发关闭信号
+pub fn begin_shutdown() {
+    SHUTDOWN_TX.send_replace(true);
+}
@@ -3,6 +3,9 @@
 pub mod manager;
 
 // 导出公共接口
-pub use manager::{disable_proxy, enable_proxy, get_proxy_info};
+pub use manager::{
+    ProxyResult, SystemProxySettings, apply_system_proxy, clear_system_proxy, current_state,
+    disable_proxy, enable_proxy, get_proxy_info, last_applied_proxy, reapply_system_proxy,
+};
 
 pub use manager::init;
@@ -1,9 +1,12 @@
 // 系统代理配置管理：提供跨平台的系统级代理设置能力。
 // 对外暴露启用、禁用与状态查询接口。
 
-use rinf::{DartSignal, RustSignal};
+use crate::atoms::shutdown;
+use rinf::{DartSignal, RustSignal, SignalPiece};
 use serde::{Deserialize, Serialize};
-use tokio::spawn;
+use std::sync::Mutex;
+
+use crate::atoms::host_port::split_host_port;
 
 // Dart → Rust：启用系统代理
 #[derive(Deserialize, DartSignal)]
@@ -24,6 +27,38 @@ pub struct DisableSystemProxy;
 #[derive(Deserialize, DartSignal)]
 pub struct GetSystemProxy;
 
+// Dart → Rust：重新应用当前系统代理（系统唤醒后调用）
+#[derive(Deserialize, DartSignal)]
+pub struct ReapplySystemProxy;
+
+// Rust → Dart：重新应用系统代理结果
+#[derive(Serialize, RustSignal)]
+pub struct ReapplySystemProxyResult {
+    pub is_successful: bool,
+    // 未启用系统代理时为 false
+    pub is_reapplied: bool,
+    pub error_message: Option<String>,
+}
+
+// Dart → Rust：获取系统代理状态并判断是否指向本应用
+#[derive(Deserialize, DartSignal)]
+pub struct GetSystemProxyState {
+    pub expected_port: u16,
+}
+
+// Rust → Dart：系统代理状态（含是否由本应用设置）
+#[derive(Serialize, RustSignal, Debug, Clone)]
+pub struct SystemProxyState {
+    pub is_enabled: bool,
+    pub server: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    // PAC 模式下的自动配置脚本地址
+    pub pac_url: Option<String>,
+    // 代理指向本机且端口与预期一致，或 PAC 地址为本应用写入的 PAC 文件
+    pub is_ours: bool,
+}
+
 // Rust → Dart：代理操作结果
 #[derive(Serialize, RustSignal)]
 pub struct SystemProxyResult {
@@ -50,6 +85,162 @@ pub enum ProxyResult {
 pub struct ProxyInfo {
     pub is_enabled: bool,
     pub server: Option<String>,
+    // PAC 模式下的自动配置脚本地址，目前仅 Windows 读取
+    pub pac_url: Option<String>,
+}
+
+// 读取系统代理并判断是否指向本应用的本地端口。
+pub async fn current_state(expected_port: u16) -> SystemProxyState {
+    let info = get_proxy_info().await;
+    let endpoints: Vec<(String, u16)> = info
+        .server
+        .as_deref()
+        .map(parse_proxy_endpoints)
+        .unwrap_or_default();
+
+    let is_own_pac = info.pac_url.as_deref().is_some_and(|pac_url| {
+        last_applied_proxy().is_some_and(|settings| {
+            settings.should_use_pac_mode && is_pac_url_for_file(pac_url, &settings.pac_file_path)
+        })
+    });
+    let is_ours = info.is_enabled
+        && (is_own_pac
+            || endpoints
+                .iter()
+                .any(|(host, port)| *port == expected_port && is_loopback_host(host)));
+    let (host, port) = endpoints
+        .into_iter()
+        .next()
+        .map_or((None, None), |(host, port)| (Some(host), Some(port)));
+
+    SystemProxyState {
+        is_enabled: info.is_enabled,
+        server: info.server,
+        host,
+        port,
+        pac_url: info.pac_url,
+        is_ours,
+    }
+}
+
+// 解析代理服务器字符串，兼容以下格式：
+// "127.0.0.1:7890"、"http=127.0.0.1:7890;https=127.0.0.1:7890"、"http://127.0.0.1:7890/"、
+// "127.0.0.1:7890/path"（地址后的路径被忽略）
+fn parse_proxy_endpoints(server: &str) -> Vec<(String, u16)> {
+    server
+        .split([';', ' '])
+        .filter_map(|entry| {
+            let entry = entry.trim();
+            let entry = entry.split_once('=').map_or(entry, |(_, value)| value);
+            let entry = entry.split_once("://").map_or(entry, |(_, value)| value);
+            let authority = entry
+                .split_once('/')
+                .map_or(entry, |(authority, _)| authority);
+            split_host_port(authority)
+        })
+        .collect()
+}
+
+// PAC 地址是否指向给定的本地 PAC 文件（忽略 file:// 前缀、斜杠方向与大小写）
+fn is_pac_url_for_file(pac_url: &str, pac_file_path: &str) -> bool {
+    let normalize = |path: &str| {
+        let path = path.trim();
+        let path = path
+            .strip_prefix("file:///")
+            .or_else(|| path.strip_prefix("file://"))
+            .unwrap_or(path);
+        path.replace('\\', "/")
+            .trim_start_matches('/')
+            .to_lowercase()
+    };
+    !pac_file_path.is_empty() && normalize(pac_url) == normalize(pac_file_path)
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+// 系统代理参数
+#[derive(Deserialize, SignalPiece, Debug, Clone)]
+pub struct SystemProxySettings {
+    pub host: String,
+    pub port: u16,
+    pub bypass_domains: Vec<String>,
+    pub should_use_pac_mode: bool,
+    pub pac_script: String,
+    pub pac_file_path: String,
+}
+
+// 最近一次成功应用的系统代理参数
+static LAST_APPLIED_PROXY: Mutex<Option<SystemProxySettings>> = Mutex::new(None);
+
+fn set_last_applied_proxy(settings: Option<SystemProxySettings>) {
+    match LAST_APPLIED_PROXY.lock() {
+        Ok(mut guard) => *guard = settings,
+        Err(e) => log::error!("记录系统代理参数失败：{}", e),
+    }
+}
+
+// 最近一次成功应用的系统代理参数，未启用时为 None
+pub fn last_applied_proxy() -> Option<SystemProxySettings> {
+    match LAST_APPLIED_PROXY.lock() {
+        Ok(guard) => guard.clone(),
+        Err(e) => {
+            log::error!("读取系统代理参数失败：{}", e);
+            None
+        }
+    }
+}
+
+// 应用系统代理，成功后记录参数供唤醒后重新应用
+pub async fn apply_system_proxy(settings: SystemProxySettings) -> ProxyResult {
+    let result = enable_proxy(
+        &settings.host,
+        settings.port,
+        settings.bypass_domains.clone(),
+        settings.should_use_pac_mode,
+        &settings.pac_script,
+        &settings.pac_file_path,
+    )
+    .await;
+
+    if matches!(result, ProxyResult::Success) {
+        set_last_applied_proxy(Some(settings));
+    }
+    result
+}
+
+// 禁用系统代理，成功后清除记录的参数
+pub async fn clear_system_proxy() -> ProxyResult {
+    let result = disable_proxy().await;
+    if matches!(result, ProxyResult::Success) {
+        set_last_applied_proxy(None);
+    }
+    result
+}
+
+// 重新写入最近一次应用的系统代理，写入后由各平台实现通知系统刷新。
+// 唤醒后注册表仍指向本应用端口，但 WinHTTP 等进程内缓存可能已失效，
+// 重新应用可让其他应用重新读取代理设置。未启用系统代理时返回 None。
+pub async fn reapply_system_proxy() -> Option<ProxyResult> {
+    let settings = last_applied_proxy()?;
+
+    log::info!("重新应用系统代理：{}:{}", settings.host, settings.port);
+
+    let result = enable_proxy(
+        &settings.host,
+        settings.port,
+        settings.bypass_domains,
+        settings.should_use_pac_mode,
+        &settings.pac_script,
+        &settings.pac_file_path,
+    )
+    .await;
+
+    Some(result)
 }
 
 impl EnableSystemProxy {
@@ -61,17 +252,16 @@ impl EnableSystemProxy {
             log::info!("收到启用代理请求：{}:{}", self.host, self.port);
         }
 
-        let result = enable_proxy(
-            &self.host,
-            self.port,
-            self.bypass_domains,
-            self.should_use_pac_mode,
-            &self.pac_script,
-            &self.pac_file_path,
-        )
-        .await;
+        let settings = SystemProxySettings {
+            host: self.host,
+            port: self.port,
+            bypass_domains: self.bypass_domains,
+            should_use_pac_mode: self.should_use_pac_mode,
+            pac_script: self.pac_script,
+            pac_file_path: self.pac_file_path,
+        };
 
-        let response = match result {
+        let response = match apply_system_proxy(settings).await {
             ProxyResult::Success => SystemProxyResult {
                 is_successful: true,
                 error_message: None,
@@ -94,9 +284,7 @@ impl DisableSystemProxy {
     pub async fn handle(&self) {
         log::info!("收到禁用代理请求");
 
-        let result = disable_proxy().await;
-
-        let response = match result {
+        let response = match clear_system_proxy().await {
             ProxyResult::Success => SystemProxyResult {
                 is_successful: true,
                 error_message: None,
@@ -130,13 +318,61 @@ impl GetSystemProxy {
     }
 }
 
+impl GetSystemProxyState {
+    // 查询系统代理状态并判断是否由本应用设置。
+    pub async fn handle(&self) {
+        log::info!("收到获取系统代理归属请求，预期端口：{}", self.expected_port);
+
+        let state = current_state(self.expected_port).await;
+        if state.is_enabled && !state.is_ours {
+            log::warn!("系统代理指向其他程序：{:?}", state.server);
+        }
+
+        state.send_signal_to_dart();
+    }
+}
+
+impl ReapplySystemProxy {
+    // 重新应用系统代理并返回结果。
+    pub async fn handle(&self) {
+        log::info!("收到重新应用系统代理请求");
+
+        let response = match reapply_system_proxy().await {
+            None => {
+                log::debug!("系统代理未启用，跳过重新应用");
+                ReapplySystemProxyResult {
+                    is_successful: true,
+                    is_reapplied: false,
+                    error_message: None,
+                }
+            }
+            Some(ProxyResult::Success) => ReapplySystemProxyResult {
+                is_successful: true,
+                is_reapplied: true,
+                error_message: None,
+            },
+            Some(ProxyResult::Error(msg)) => {
+                log::error!("重新应用系统代理失败：{}", msg);
+                ReapplySystemProxyResult {
+                    is_successful: false,
+                    is_reapplied: false,
+                    error_message: Some(msg),
+                }
+            }
+        };
+
+        response.send_signal_to_dart();
+    }
+}
+
 #[cfg(target_os = "windows")]
 mod windows_impl {
     use super::{ProxyInfo, ProxyResult};
+    use crate::atoms::host_port::format_host_port;
     use std::ffi::OsStr;
     use std::fs;
     use std::os::windows::ffi::OsStrExt;
-    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::Foundation::{ERROR_SUCCESS, LPARAM, WPARAM};
     use windows::Win32::NetworkManagement::Rras::{RASENTRYNAMEW, RasEnumEntriesW};
     use windows::Win32::Networking::WinInet::{
         INTERNET_OPTION_PER_CONNECTION_OPTION, INTERNET_OPTION_REFRESH,
@@ -145,8 +381,48 @@ mod windows_impl {
         INTERNET_PER_CONN_PROXY_BYPASS, INTERNET_PER_CONN_PROXY_SERVER, InternetQueryOptionW,
         InternetSetOptionW, PROXY_TYPE_AUTO_PROXY_URL, PROXY_TYPE_DIRECT, PROXY_TYPE_PROXY,
     };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        HWND_BROADCAST, SMTO_ABORTIFHUNG, SendMessageTimeoutW, WM_SETTINGCHANGE,
+    };
     use windows::core::PWSTR;
 
+    // 通知 WinINET 设置已变更并刷新，再广播 WM_SETTINGCHANGE（"Internet Settings"），
+    // 让正在运行的浏览器等应用立即重新读取代理设置，而非等到重启。
+    // 广播会逐个等待顶层窗口响应，放到阻塞线程池执行，不占用异步工作线程
+    fn notify_settings_changed() {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn_blocking(broadcast_settings_changed);
+            }
+            Err(_) => broadcast_settings_changed(),
+        }
+    }
+
+    fn broadcast_settings_changed() {
+        let area: Vec<u16> = OsStr::new("Internet Settings")
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+
+        unsafe {
+            let _ = InternetSetOptionW(None, INTERNET_OPTION_SETTINGS_CHANGED, None, 0);
+            let _ = InternetSetOptionW(None, INTERNET_OPTION_REFRESH, None, 0);
+
+            let result = SendMessageTimeoutW(
+                HWND_BROADCAST,
+                WM_SETTINGCHANGE,
+                WPARAM(0),
+                LPARAM(area.as_ptr() as isize),
+                SMTO_ABORTIFHUNG,
+                1000,
+                None,
+            );
+            if result.0 == 0 {
+                log::warn!("广播 WM_SETTINGCHANGE 超时或失败");
+            }
+        }
+    }
+
     // 配置并启用系统代理，可选使用 PAC 脚本。
     pub async fn enable_proxy(
         host: &str,
@@ -161,7 +437,7 @@ mod windows_impl {
             return enable_proxy_pac(host, port, pac_script, pac_file_path);
         }
 
-        let proxy_server = format!("{}:{}", host, port);
+        let proxy_server = format_host_port(host, port);
         log::info!("正在设置系统代理：{}", proxy_server);
 
         unsafe {
@@ -225,8 +501,7 @@ mod windows_impl {
             set_ras_proxy(&mut list);
 
             // 通知系统刷新
-            let _ = InternetSetOptionW(None, INTERNET_OPTION_SETTINGS_CHANGED, None, 0);
-            let _ = InternetSetOptionW(None, INTERNET_OPTION_REFRESH, None, 0);
+            notify_settings_changed();
 
             log::info!("系统代理设置成功：{}", proxy_server);
             ProxyResult::Success
@@ -311,8 +586,7 @@ mod windows_impl {
             set_ras_proxy(&mut list);
 
             // 通知系统刷新
-            let _ = InternetSetOptionW(None, INTERNET_OPTION_SETTINGS_CHANGED, None, 0);
-            let _ = InternetSetOptionW(None, INTERNET_OPTION_REFRESH, None, 0);
+            notify_settings_changed();
 
             log::info!("系统代理设置成功(PAC 模式)：{}", pac_url);
             ProxyResult::Success
@@ -356,8 +630,7 @@ mod windows_impl {
             set_ras_proxy(&mut list);
 
             // 通知系统刷新
-            let _ = InternetSetOptionW(None, INTERNET_OPTION_SETTINGS_CHANGED, None, 0);
-            let _ = InternetSetOptionW(None, INTERNET_OPTION_REFRESH, None, 0);
+            notify_settings_changed();
 
             log::info!("系统代理已禁用");
             ProxyResult::Success
@@ -411,7 +684,26 @@ mod windows_impl {
         }
     }
 
-    // 查询当前系统代理状态与服务器地址。
+    // 读取查询结果中的字符串选项，未设置时为 None
+    unsafe fn read_string_option(option: &INTERNET_PER_CONN_OPTIONW) -> Option<String> {
+        unsafe {
+            let value_ptr = *(&option.Value as *const _ as *const PWSTR);
+            if value_ptr.is_null() {
+                return None;
+            }
+
+            let mut len = 0;
+            let mut ptr = value_ptr.0;
+            while *ptr != 0 {
+                len += 1;
+                ptr = ptr.add(1);
+            }
+            let value = String::from_utf16_lossy(std::slice::from_raw_parts(value_ptr.0, len));
+            (!value.is_empty()).then_some(value)
+        }
+    }
+
+    // 查询当前系统代理状态与服务器地址；PAC 模式同样视为已启用，并返回 PAC 地址。
     pub async fn get_proxy_info() -> ProxyInfo {
         unsafe {
             // 准备查询选项
@@ -425,7 +717,12 @@ mod windows_impl {
                 Value: std::mem::zeroed(),
             };
 
-            let mut options = [option_flags, option_server];
+            let option_pac_url = INTERNET_PER_CONN_OPTIONW {
+                dwOption: INTERNET_PER_CONN_AUTOCONFIG_URL,
+                Value: std::mem::zeroed(),
+            };
+
+            let mut options = [option_flags, option_server, option_pac_url];
 
             let mut list = INTERNET_PER_CONN_OPTION_LISTW {
                 dwSize: std::mem::size_of::<INTERNET_PER_CONN_OPTION_LISTW>() as u32,
@@ -452,6 +749,7 @@ mod windows_impl {
                     return ProxyInfo {
                         is_enabled: false,
                         server: None,
+                        pac_url: None,
                     };
                 }
             }
@@ -459,41 +757,30 @@ mod windows_impl {
             // 读取代理标志
             let flags = *(&options[0].Value as *const _ as *const u32);
             let is_proxy_enabled = (flags & PROXY_TYPE_PROXY) != 0;
+            let is_pac_enabled = (flags & PROXY_TYPE_AUTO_PROXY_URL) != 0;
 
-            if !is_proxy_enabled {
-                return ProxyInfo {
-                    is_enabled: false,
-                    server: None,
-                };
-            }
-
-            // 读取代理服务器地址
-            let server_ptr = *(&options[1].Value as *const _ as *const PWSTR);
-            if server_ptr.is_null() {
-                return ProxyInfo {
-                    is_enabled: true,
-                    server: None,
-                };
-            }
-
-            // 转换为 Rust String
-            let server_wide = {
-                let mut len = 0;
-                let mut ptr = server_ptr.0;
-                while *ptr != 0 {
-                    len += 1;
-                    ptr = ptr.add(1);
-                }
-                std::slice::from_raw_parts(server_ptr.0, len)
+            let server = if is_proxy_enabled {
+                read_string_option(&options[1])
+            } else {
+                None
+            };
+            let pac_url = if is_pac_enabled {
+                read_string_option(&options[2])
+            } else {
+                None
             };
 
-            let server_string = String::from_utf16_lossy(server_wide);
-
-            log::info!("当前系统代理：{}", server_string);
+            if let Some(ref server) = server {
+                log::info!("当前系统代理：{}", server);
+            }
+            if let Some(ref pac_url) = pac_url {
+                log::info!("当前系统 PAC 地址：{}", pac_url);
+            }
 
             ProxyInfo {
-                is_enabled: true,
-                server: Some(server_string),
+                is_enabled: is_proxy_enabled || is_pac_enabled,
+                server,
+                pac_url,
             }
         }
     }
@@ -637,6 +924,7 @@ mod macos_impl {
                 return ProxyInfo {
                     is_enabled: false,
                     server: None,
+                    pac_url: None,
                 };
             }
         };
@@ -677,6 +965,7 @@ mod macos_impl {
                 return ProxyInfo {
                     is_enabled: true,
                     server: Some(server_str),
+                    pac_url: None,
                 };
             }
         }
@@ -684,6 +973,7 @@ mod macos_impl {
         ProxyInfo {
             is_enabled: false,
             server: None,
+            pac_url: None,
         }
     }
 }
@@ -695,12 +985,17 @@ mod macos_impl {
 mod linux_impl {
     use super::{ProxyInfo, ProxyResult};
     use std::ffi::OsStr;
-    use std::process::{Command, Output};
+    use std::io::Read;
+    use std::process::{Command, Output, Stdio};
+    use std::time::{Duration, Instant};
 
     const GNOME_PROXY_SCHEMA: &str = "org.gnome.system.proxy";
     const PROXY_TYPES: [&str; 3] = ["http", "https", "socks"];
     const KWRITECONFIG_COMMANDS: [&str; 2] = ["kwriteconfig6", "kwriteconfig5"];
     const KREADCONFIG_COMMANDS: [&str; 2] = ["kreadconfig6", "kreadconfig5"];
+    // 外部命令执行时限：桌面环境的 dbus 卡死时 gsettings 等命令可能永久阻塞
+    const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+    const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(20);
 
     // 检测桌面环境类型
     fn detect_desktop_environment() -> String {
@@ -717,17 +1012,16 @@ mod linux_impl {
         ProxyInfo {
             is_enabled: false,
             server: None,
+            pac_url: None,
         }
     }
 
     // 按顺序选择第一个可执行成功的命令
     fn find_working_command(candidates: &[&'static str], probe_arg: &str) -> Option<&'static str> {
-        candidates.iter().copied().find(|candidate| {
-            Command::new(candidate)
-                .arg(probe_arg)
-                .output()
-                .is_ok_and(|output| output.status.success())
-        })
+        candidates
+            .iter()
+            .copied()
+            .find(|candidate| run_command(candidate, [probe_arg]).is_ok())
     }
 
     // 选择可用的 KDE 写配置命令
@@ -766,10 +1060,7 @@ mod linux_impl {
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
     {
-        let output = Command::new(command)
-            .args(args)
-            .output()
-            .map_err(|e| format!("执行 {command} 失败：{e}"))?;
+        let output = output_with_timeout(command, args, COMMAND_TIMEOUT)?;
 
         if output.status.success() {
             return Ok(output);
@@ -786,6 +1077,74 @@ mod linux_impl {
         Err(format!("{command} 执行失败：{stderr}"))
     }
 
+    // 执行命令并收集输出，超过时限时终止子进程。
+    // 轮询等待会阻塞当前线程，只能在阻塞线程池中调用
+    fn output_with_timeout<I, S>(
+        command: &str,
+        args: I,
+        timeout: Duration,
+    ) -> Result<Output, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut child = Command::new(command)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("执行 {command} 失败：{e}"))?;
+
+        // 输出在独立线程中读取，避免管道写满导致子进程阻塞
+        let stdout_reader = child.stdout.take().map(spawn_pipe_reader);
+        let stderr_reader = child.stderr.take().map(spawn_pipe_reader);
+
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!(
+                        "{command} 执行超时（{}s），已终止",
+                        timeout.as_secs()
+                    ));
+                }
+                Ok(None) => std::thread::sleep(COMMAND_POLL_INTERVAL),
+                Err(e) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!("等待 {command} 退出失败：{e}"));
+                }
+            }
+        };
+
+        Ok(Output {
+            status,
+            stdout: join_pipe_reader(stdout_reader),
+            stderr: join_pipe_reader(stderr_reader),
+        })
+    }
+
+    fn spawn_pipe_reader<R>(mut pipe: R) -> std::thread::JoinHandle<Vec<u8>>
+    where
+        R: Read + Send + 'static,
+    {
+        std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = pipe.read_to_end(&mut buffer);
+            buffer
+        })
+    }
+
+    fn join_pipe_reader(reader: Option<std::thread::JoinHandle<Vec<u8>>>) -> Vec<u8> {
+        reader
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    }
+
     // 执行命令并确保成功退出
     fn run_command<I, S>(command: &str, args: I) -> Result<(), String>
     where
@@ -993,7 +1352,7 @@ mod linux_impl {
     }
 
     // 获取 GNOME 系统代理状态
-    async fn get_proxy_info_gnome() -> ProxyInfo {
+    fn get_proxy_info_gnome() -> ProxyInfo {
         let mode = match read_command_output("gsettings", ["get", GNOME_PROXY_SCHEMA, "mode"]) {
             Ok(mode) => mode,
             Err(_) => return disabled_proxy_info(),
@@ -1028,11 +1387,12 @@ mod linux_impl {
         ProxyInfo {
             is_enabled: true,
             server: Some(server_str),
+            pac_url: None,
         }
     }
 
     // 获取 dconf 系统代理状态
-    async fn get_proxy_info_dconf() -> ProxyInfo {
+    fn get_proxy_info_dconf() -> ProxyInfo {
         let mode = match read_command_output("dconf", ["read", "/system/proxy/mode"]) {
             Ok(mode) => mode,
             Err(_) => return disabled_proxy_info(),
@@ -1061,11 +1421,12 @@ mod linux_impl {
         ProxyInfo {
             is_enabled: true,
             server: Some(server_str),
+            pac_url: None,
         }
     }
 
     // 获取 KDE 系统代理状态
-    async fn get_proxy_info_kde() -> ProxyInfo {
+    fn get_proxy_info_kde() -> ProxyInfo {
         let Some(command) = kreadconfig_command() else {
             return disabled_proxy_info();
         };
@@ -1117,6 +1478,7 @@ mod linux_impl {
         ProxyInfo {
             is_enabled: true,
             server: Some(server_str),
+            pac_url: None,
         }
     }
 
@@ -1163,7 +1525,7 @@ mod linux_impl {
         ProxyResult::Success
     }
 
-    // 启用 Linux 系统代理
+    // 启用 Linux 系统代理：外部命令同步执行，放到阻塞线程池，避免占住运行时线程
     pub async fn enable_proxy(
         host: &str,
         port: u16,
@@ -1174,13 +1536,20 @@ mod linux_impl {
     ) -> ProxyResult {
         log::info!("正在设置 Linux 系统代理：{}:{}", host, port);
 
+        let host = host.to_string();
+        tokio::task::spawn_blocking(move || enable_proxy_blocking(&host, port, &bypass_domains))
+            .await
+            .unwrap_or_else(|e| ProxyResult::Error(format!("设置 Linux 系统代理失败：{}", e)))
+    }
+
+    fn enable_proxy_blocking(host: &str, port: u16, bypass_domains: &[String]) -> ProxyResult {
         let mut applied_backends = Vec::new();
         let mut errors = Vec::new();
 
         if let Some(command) = kwriteconfig_command() {
             collect_backend_result(
                 "KDE",
-                enable_proxy_kde(command, host, port, &bypass_domains),
+                enable_proxy_kde(command, host, port, bypass_domains),
                 &mut applied_backends,
                 &mut errors,
             );
@@ -1188,14 +1557,14 @@ mod linux_impl {
 
         collect_backend_result(
             "gsettings",
-            enable_proxy_gsettings(host, port, &bypass_domains),
+            enable_proxy_gsettings(host, port, bypass_domains),
             &mut applied_backends,
             &mut errors,
         );
 
         collect_backend_result(
             "dconf",
-            enable_proxy_dconf(host, port, &bypass_domains),
+            enable_proxy_dconf(host, port, bypass_domains),
             &mut applied_backends,
             &mut errors,
         );
@@ -1207,6 +1576,12 @@ mod linux_impl {
     pub async fn disable_proxy() -> ProxyResult {
         log::info!("正在禁用 Linux 系统代理");
 
+        tokio::task::spawn_blocking(disable_proxy_blocking)
+            .await
+            .unwrap_or_else(|e| ProxyResult::Error(format!("禁用 Linux 系统代理失败：{}", e)))
+    }
+
+    fn disable_proxy_blocking() -> ProxyResult {
         let mut applied_backends = Vec::new();
         let mut errors = Vec::new();
 
@@ -1240,26 +1615,35 @@ mod linux_impl {
     pub async fn get_proxy_info() -> ProxyInfo {
         log::info!("正在查询 Linux 系统代理状态");
 
+        tokio::task::spawn_blocking(get_proxy_info_blocking)
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("查询 Linux 系统代理状态失败：{}", e);
+                disabled_proxy_info()
+            })
+    }
+
+    fn get_proxy_info_blocking() -> ProxyInfo {
         let should_prefer_kde = is_kde();
         if should_prefer_kde {
-            let kde_proxy_info = get_proxy_info_kde().await;
+            let kde_proxy_info = get_proxy_info_kde();
             if kde_proxy_info.is_enabled {
                 return kde_proxy_info;
             }
         }
 
-        let gnome_proxy_info = get_proxy_info_gnome().await;
+        let gnome_proxy_info = get_proxy_info_gnome();
         if gnome_proxy_info.is_enabled {
             return gnome_proxy_info;
         }
 
-        let dconf_proxy_info = get_proxy_info_dconf().await;
+        let dconf_proxy_info = get_proxy_info_dconf();
         if dconf_proxy_info.is_enabled {
             return dconf_proxy_info;
         }
 
         if !should_prefer_kde {
-            let kde_proxy_info = get_proxy_info_kde().await;
+            let kde_proxy_info = get_proxy_info_kde();
             if kde_proxy_info.is_enabled {
                 return kde_proxy_info;
             }
@@ -1267,6 +1651,26 @@ mod linux_impl {
 
         disabled_proxy_info()
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::output_with_timeout;
+        use std::time::{Duration, Instant};
+
+        #[test]
+        fn kills_command_exceeding_timeout() -> Result<(), String> {
+            let started = Instant::now();
+            let result = output_with_timeout("sleep", ["10"], Duration::from_millis(200));
+
+            let error = result.err().ok_or("超时命令应返回错误")?;
+            assert!(error.contains("sleep") && error.contains("超时"));
+            assert!(started.elapsed() < Duration::from_secs(5));
+
+            let output = output_with_timeout("echo", ["ok"], Duration::from_secs(5))?;
+            assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "ok");
+            Ok(())
+        }
+    }
 }
 
 // ==================== 平台导出 ====================
@@ -1306,11 +1710,12 @@ pub async fn get_proxy_info() -> ProxyInfo {
     ProxyInfo {
         is_enabled: false,
         server: None,
+        pac_url: None,
     }
 }
 
 pub fn init() {
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = EnableSystemProxy::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle().await;
@@ -1318,7 +1723,7 @@ pub fn init() {
         log::info!("启用代理消息通道已关闭，退出监听器");
     });
 
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = DisableSystemProxy::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle().await;
@@ -1326,11 +1731,87 @@ pub fn init() {
         log::info!("禁用代理消息通道已关闭，退出监听器");
     });
 
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = GetSystemProxy::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle().await;
         }
         log::info!("获取系统代理状态消息通道已关闭，退出监听器");
     });
+
+    shutdown::spawn_listener(async {
+        let receiver = GetSystemProxyState::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle().await;
+        }
+        log::info!("获取系统代理归属消息通道已关闭，退出监听器");
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = ReapplySystemProxy::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle().await;
+        }
+        log::info!("重新应用系统代理消息通道已关闭，退出监听器");
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_loopback_host, is_pac_url_for_file, parse_proxy_endpoints};
+
+    fn endpoint(host: &str, port: u16) -> (String, u16) {
+        (host.to_string(), port)
+    }
+
+    #[test]
+    fn parses_proxy_server_formats() {
+        assert_eq!(
+            parse_proxy_endpoints("127.0.0.1:7890"),
+            vec![endpoint("127.0.0.1", 7890)]
+        );
+        assert_eq!(
+            parse_proxy_endpoints("http=127.0.0.1:7890;https=localhost:7891"),
+            vec![endpoint("127.0.0.1", 7890), endpoint("localhost", 7891)]
+        );
+        assert_eq!(
+            parse_proxy_endpoints("http://127.0.0.1:7890/"),
+            vec![endpoint("127.0.0.1", 7890)]
+        );
+        assert_eq!(
+            parse_proxy_endpoints("127.0.0.1:7890/proxy"),
+            vec![endpoint("127.0.0.1", 7890)]
+        );
+        assert_eq!(
+            parse_proxy_endpoints("socks=[::1]:7891/"),
+            vec![endpoint("::1", 7891)]
+        );
+        assert!(parse_proxy_endpoints("").is_empty());
+        assert!(parse_proxy_endpoints("127.0.0.1").is_empty());
+    }
+
+    #[test]
+    fn detects_loopback_hosts() {
+        for host in ["localhost", "LOCALHOST", "127.0.0.1", "127.1.2.3", "::1"] {
+            assert!(is_loopback_host(host), "{}", host);
+        }
+        for host in ["192.168.1.1", "example.com", "::", "localhost.example.com"] {
+            assert!(!is_loopback_host(host), "{}", host);
+        }
+    }
+
+    #[test]
+    fn matches_pac_url_to_file_path() {
+        let path = r"C:\Users\me\AppData\Stelliberty\proxy.pac";
+        assert!(is_pac_url_for_file(
+            "file:///C:/Users/me/AppData/Stelliberty/proxy.pac",
+            path
+        ));
+        assert!(is_pac_url_for_file(
+            "file:///c:/users/me/appdata/stelliberty/proxy.pac",
+            path
+        ));
+        assert!(!is_pac_url_for_file("http://example.com/proxy.pac", path));
+        assert!(!is_pac_url_for_file("file:///C:/proxy.pac", ""));
+    }
 }
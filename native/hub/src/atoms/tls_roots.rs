@@ -0,0 +1,169 @@
+// 额外信任的根证书：用于企业网络中间人代理等自签根证书环境。
+// 只在系统根证书之外追加信任，不替换系统校验。
+// 安全提示：追加的根证书可为任意域名签发证书，持有其私钥者能解密并篡改
+// 订阅、覆写与更新下载等出站 HTTPS 流量，只应添加确认可信的根证书。
+// Android 已由平台校验器使用系统证书（含用户安装的证书），此处不生效。
+
+use crate::atoms::shutdown;
+use once_cell::sync::Lazy;
+use reqwest::{Certificate, ClientBuilder};
+use rinf::{DartSignal, RustSignal};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// 指定额外根证书 PEM 文件的环境变量
+const EXTRA_CA_FILE_ENV: &str = "STELLIBERTY_EXTRA_CA_FILE";
+
+// Dart → Rust：设置额外信任的根证书文件，path 为 None 时清除
+#[derive(Deserialize, DartSignal)]
+pub struct SetExtraCaFile {
+    pub path: Option<String>,
+}
+
+// Rust → Dart：额外根证书设置结果
+#[derive(Serialize, RustSignal)]
+pub struct ExtraCaFileResult {
+    pub is_successful: bool,
+    // 当前生效的额外根证书数量（含环境变量指定的）
+    pub certificate_count: u32,
+    pub error_message: Option<String>,
+}
+
+// 环境变量指定的根证书，首次使用时加载
+static ENV_ROOTS: Lazy<Vec<Certificate>> = Lazy::new(|| {
+    let path = match std::env::var(EXTRA_CA_FILE_ENV) {
+        Ok(path) if !path.trim().is_empty() => path.trim().to_string(),
+        _ => return Vec::new(),
+    };
+
+    match load_pem_file(&path) {
+        Ok(certs) => {
+            log::info!(
+                "已从环境变量 {} 加载 {} 个额外根证书",
+                EXTRA_CA_FILE_ENV,
+                certs.len()
+            );
+            certs
+        }
+        Err(e) => {
+            log::warn!("加载环境变量 {} 指定的根证书失败：{}", EXTRA_CA_FILE_ENV, e);
+            Vec::new()
+        }
+    }
+});
+
+// 运行时设置的根证书
+static RUNTIME_ROOTS: RwLock<Vec<Certificate>> = RwLock::new(Vec::new());
+
+// 运行时根证书每次变更递增，长期复用的客户端据此判断是否需要重建
+static ROOTS_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+impl SetExtraCaFile {
+    pub fn handle(self) {
+        let result = set_extra_ca_file(self.path.as_deref());
+        let response = match result {
+            Ok(()) => ExtraCaFileResult {
+                is_successful: true,
+                certificate_count: extra_roots().len() as u32,
+                error_message: None,
+            },
+            Err(e) => {
+                log::error!("设置额外根证书失败：{}", e);
+                ExtraCaFileResult {
+                    is_successful: false,
+                    certificate_count: extra_roots().len() as u32,
+                    error_message: Some(e),
+                }
+            }
+        };
+        response.send_signal_to_dart();
+    }
+}
+
+// 设置运行时额外根证书文件，传入 None 清除；加载失败时保留原有设置
+pub fn set_extra_ca_file(path: Option<&str>) -> Result<(), String> {
+    let certs = match path {
+        Some(path) if path.trim().is_empty() => {
+            return Err("证书文件路径不能为空".to_string());
+        }
+        Some(path) => load_pem_file(path.trim())?,
+        None => Vec::new(),
+    };
+
+    log::info!("额外根证书已更新：{} 个", certs.len());
+    match RUNTIME_ROOTS.write() {
+        Ok(mut guard) => *guard = certs,
+        Err(e) => *e.into_inner() = certs,
+    }
+    ROOTS_GENERATION.fetch_add(1, Ordering::Relaxed);
+    Ok(())
+}
+
+// 当前根证书设置的版本号
+pub fn roots_generation() -> u64 {
+    ROOTS_GENERATION.load(Ordering::Relaxed)
+}
+
+// 当前生效的全部额外根证书
+pub fn extra_roots() -> Vec<Certificate> {
+    let mut certs = ENV_ROOTS.clone();
+    match RUNTIME_ROOTS.read() {
+        Ok(guard) => certs.extend(guard.iter().cloned()),
+        Err(e) => certs.extend(e.into_inner().iter().cloned()),
+    }
+    certs
+}
+
+// 在系统根证书之外追加信任额外根证书
+#[cfg(not(target_os = "android"))]
+pub fn apply(builder: ClientBuilder) -> ClientBuilder {
+    let certs = extra_roots();
+    if certs.is_empty() {
+        return builder;
+    }
+    builder.tls_certs_merge(certs)
+}
+
+#[cfg(target_os = "android")]
+pub fn apply(builder: ClientBuilder) -> ClientBuilder {
+    builder
+}
+
+fn load_pem_file(path: &str) -> Result<Vec<Certificate>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("读取证书文件失败：{}", e))?;
+    parse_pem_bundle(&bytes)
+}
+
+fn parse_pem_bundle(bytes: &[u8]) -> Result<Vec<Certificate>, String> {
+    let certs =
+        Certificate::from_pem_bundle(bytes).map_err(|e| format!("解析证书文件失败：{}", e))?;
+    if certs.is_empty() {
+        return Err("证书文件中没有 PEM 格式的证书".to_string());
+    }
+    Ok(certs)
+}
+
+pub fn init() {
+    shutdown::spawn_listener(async {
+        let receiver = SetExtraCaFile::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_pem_bundle;
+
+    #[test]
+    fn rejects_bundle_without_certificates() {
+        assert!(parse_pem_bundle(b"").is_err());
+        assert!(parse_pem_bundle(b"not a certificate").is_err());
+        assert!(
+            parse_pem_bundle(b"-----BEGIN CERTIFICATE-----\n!!!\n-----END CERTIFICATE-----\n")
+                .is_err()
+        );
+    }
+}
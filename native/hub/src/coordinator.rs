@@ -16,3 +16,10 @@ pub fn cleanup() {
     log::info!("清理协调层资源");
     clash_coordinator::cleanup();
 }
+
+// 应用退出时释放全部资源：停止核心、清空 IPC 连接池并停止系统监听
+pub async fn shutdown() {
+    cleanup();
+    cleanup_network_resources().await;
+    system_coordinator::shutdown().await;
+}
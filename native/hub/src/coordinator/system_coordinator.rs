@@ -1,6 +1,10 @@
 // 系统协调器：编排所有系统相关操作
 
 use crate::molecules::system_operations;
+use std::time::Duration;
+
+// 应用退出时等待电源监听线程退出的最长时间
+const POWER_LISTENER_JOIN_TIMEOUT: Duration = Duration::from_secs(1);
 
 pub struct SystemCoordinator;
 
@@ -21,3 +25,8 @@ pub fn init() {
     // 初始化分子层监听器（内部会完成必要的原子层初始化）
     system_operations::init_listeners();
 }
+
+// 应用退出时停止系统监听
+pub async fn shutdown() {
+    system_operations::shutdown_power_event_listener(POWER_LISTENER_JOIN_TIMEOUT).await;
+}
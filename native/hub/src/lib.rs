@@ -17,12 +17,22 @@ async fn main() {
     // 初始化日志系统（注入路径，解除原子间依赖）
     atoms::logger::init(log_path);
 
+    // 复位上一次运行（如 Dart 热重启前）留下的关闭信号
+    atoms::shutdown::reset();
+
     // 初始化协调层（内部会初始化所有分子层）
     coordinator::init_all();
 
     // 等待 Dart 关闭信号
     dart_shutdown().await;
 
-    // 清理资源
-    coordinator::cleanup();
+    shutdown().await;
+}
+
+// 关闭全部子系统：停止长任务（如批量延迟测试）、退出消息监听循环，
+// 再释放核心进程、IPC 连接池与电源监听线程
+pub async fn shutdown() {
+    atoms::shutdown::begin_shutdown();
+    coordinator::shutdown().await;
+    log::info!("应用资源已全部释放");
 }
@@ -1,3 +1,4 @@
+use crate::atoms::shutdown;
 use rinf::{DartSignal, RustSignal, SignalPiece};
 use serde::{Deserialize, Serialize};
 use serde_yaml_ng::{Mapping, Value as YamlValue};
@@ -244,9 +245,7 @@ fn yaml_key(key: &str) -> YamlValue {
 }
 
 pub fn init() {
-    use tokio::spawn;
-
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = BuildChainProxyConfigRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -1,6 +1,7 @@
 // Clash 配置生成器：生成运行时配置（应用覆写并注入运行时参数）。
 // 输出内容用于驱动核心运行时状态。
 
+use crate::atoms::shutdown;
 use rinf::{DartSignal, RustSignal};
 use serde::{Deserialize, Serialize};
 
@@ -152,9 +153,7 @@ fn log_config_summary(config_yaml: &str) {
 }
 
 pub fn init() {
-    use tokio::spawn;
-
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = GenerateRuntimeConfigRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
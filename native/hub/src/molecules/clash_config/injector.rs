@@ -3,6 +3,7 @@
 use serde_yaml_ng::{Mapping, Value as YamlValue};
 
 use super::runtime_params::RuntimeConfigParams;
+use crate::atoms::IpcClient;
 
 // 注入运行时参数到 Clash 配置
 pub fn inject_runtime_params(
@@ -23,10 +24,7 @@ pub fn inject_runtime_params(
     // 注入 IPC 端点
     #[cfg(windows)]
     {
-        #[cfg(debug_assertions)]
-        let pipe_path = r"\\.\pipe\stelliberty_dev".to_string();
-        #[cfg(not(debug_assertions))]
-        let pipe_path = r"\\.\pipe\stelliberty".to_string();
+        let pipe_path = IpcClient::default_ipc_path();
 
         config_map.insert(
             YamlValue::String("external-controller-pipe".to_string()),
@@ -37,10 +35,7 @@ pub fn inject_runtime_params(
 
     #[cfg(unix)]
     {
-        #[cfg(debug_assertions)]
-        let socket_path = "/tmp/stelliberty_dev.sock".to_string();
-        #[cfg(not(debug_assertions))]
-        let socket_path = "/tmp/stelliberty.sock".to_string();
+        let socket_path = IpcClient::default_ipc_path();
 
         config_map.insert(
             YamlValue::String("external-controller-unix".to_string()),
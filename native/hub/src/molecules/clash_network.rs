@@ -1,24 +1,64 @@
 // Clash 网络管理分子模块
 
 pub mod connection;
+pub mod connection_model;
+pub mod core_lifecycle;
+pub mod core_status;
+pub mod core_version;
 pub mod handlers;
 pub mod ipc_client;
+pub mod node_ids;
+pub mod outbound_ip;
+pub mod providers;
+pub mod proxies_model;
+pub mod proxy_mode;
+pub mod running_config;
 pub mod ws_client;
 
 #[cfg(windows)]
 pub use connection::connect_named_pipe;
 #[cfg(unix)]
 pub use connection::connect_unix_socket;
+pub use connection_model::{ConnectionInfo, ConnectionSnapshot};
+pub use core_lifecycle::{CoreLifecycleEvent, CoreLifecycleState, subscribe_core_lifecycle};
+pub use core_status::{CoreStatus, GetCoreStatus, record_core_started};
+pub use core_version::{CoreVersionInfo, get_core_version};
 pub use handlers::{
+    CheckCoreAlive, CloseAllConnections, CloseConnection, CloseConnectionResult, CoreAliveResult,
+    CoreMemory, CoreMemoryUnavailable, CoreVersionResult, GetCoreVersion, GetProxiesSnapshot,
     IpcConnectionData, IpcDeleteRequest, IpcGetRequest, IpcLogData, IpcMemoryData, IpcPatchRequest,
-    IpcPostRequest, IpcPutRequest, IpcResponse, IpcTrafficData, StartConnectionStream,
+    IpcPostRequest, IpcPutRequest, IpcResponse, IpcTrafficData, PatchRuntimeConfig,
+    PatchRuntimeConfigResult, ProxiesSnapshot, ReloadConfig, ReloadConfigResult, SelectProxy,
+    SelectProxyResult, SetIpcPoolSize, SetIpcPoolingEnabled, SetIpcRedirectFollowing,
+    SetIpcTraceLogging, SetLogStreamLevel, SetWsReconnectPolicy, StartConnectionStream,
     StartLogStream, StartMemoryStream, StartTrafficStream, StopConnectionStream, StopLogStream,
-    StopMemoryStream, StopTrafficStream, StreamResult, cleanup_all_network_resources,
-    init_rest_api_listeners, internal_ipc_get, start_connection_pool_health_check,
+    StopMemoryStream, StopTrafficStream, StreamResult, WarmupIpcPool,
+    cleanup_all_network_resources, clear_ipc_connection_pools, get_group_members,
+    get_proxies_snapshot, init_rest_api_listeners, internal_ipc_get, refresh_core_config_state,
+    select_proxy, start_connection_pool_health_check,
 };
 pub use ipc_client::{HttpResponse, IpcClient};
-pub use ws_client::WebSocketClient;
+pub use node_ids::node_id;
+pub use outbound_ip::{GetOutboundIp, OutboundIpGeo, OutboundIpResult};
+pub use providers::{
+    GetProviders, HealthCheckProvider, HealthCheckProviderResult, ProviderInfo, ProviderKind,
+    ProvidersSnapshot, UpdateProvider, UpdateProviderResult,
+};
+pub use proxies_model::{DelayHistoryEntry, ProxyGroupInfo, ProxyMemberInfo};
+pub use proxy_mode::{SetProxyMode, SetProxyModeResult};
+pub use running_config::{
+    GetRunningConfig, ListenPort, ListenerProtocol, RunningConfigFields, RunningConfigResult,
+    RunningTunSettings, get_listen_ports, get_running_config,
+};
+pub use ws_client::{ReconnectPolicy, WebSocketClient};
 
 pub fn init_listeners() {
+    crate::atoms::IpcClient::log_effective_ipc_path();
     init_rest_api_listeners();
+    providers::init();
+    proxy_mode::init();
+    core_status::init();
+    outbound_ip::init();
+    running_config::init();
+    core_lifecycle::start_core_lifecycle_monitor();
 }
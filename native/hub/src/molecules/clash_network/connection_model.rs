@@ -0,0 +1,124 @@
+// 连接数据模型：将核心 /connections 返回的 JSON 解析为强类型结构。
+// 兼容不同核心版本的字段缺失与命名差异，缺失字段使用默认值。
+
+use rinf::{RustSignal, SignalPiece};
+use serde::Serialize;
+use serde_json::Value;
+
+// 单个连接
+#[derive(Serialize, SignalPiece, Clone, Debug, Default)]
+pub struct ConnectionInfo {
+    pub id: String,
+    pub host: String,
+    pub source_ip: String,
+    pub source_port: u16,
+    pub destination_ip: String,
+    pub destination_port: u16,
+    // tcp 或 udp
+    pub network: String,
+    pub rule: String,
+    pub rule_payload: String,
+    // 代理链（从出站节点到策略组）
+    pub chains: Vec<String>,
+    pub upload: u64,
+    pub download: u64,
+    // 连接开始时间（RFC 3339 字符串）
+    pub start: String,
+}
+
+// Rust → Dart：连接快照
+#[derive(Serialize, RustSignal, Clone, Debug, Default)]
+pub struct ConnectionSnapshot {
+    pub upload_total: u64,
+    pub download_total: u64,
+    pub connections: Vec<ConnectionInfo>,
+}
+
+impl ConnectionSnapshot {
+    // 解析 /connections 返回的 JSON
+    pub fn from_json(value: &Value) -> Self {
+        let connections = value
+            .get("connections")
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter().map(ConnectionInfo::from_json).collect())
+            .unwrap_or_default();
+
+        Self {
+            upload_total: read_u64(value, &["uploadTotal", "upload_total"]),
+            download_total: read_u64(value, &["downloadTotal", "download_total"]),
+            connections,
+        }
+    }
+}
+
+impl ConnectionInfo {
+    // 解析单个连接，字段位于 metadata 下
+    pub fn from_json(value: &Value) -> Self {
+        let metadata = value.get("metadata").unwrap_or(&Value::Null);
+
+        // 旧版本核心没有 destinationIP 时退回 remoteDestination
+        let destination_ip = read_string(metadata, &["destinationIP", "destinationIp"]);
+        let destination_ip = if destination_ip.is_empty() {
+            read_string(metadata, &["remoteDestination"])
+        } else {
+            destination_ip
+        };
+
+        // 域名为空时退回嗅探得到的域名
+        let host = read_string(metadata, &["host"]);
+        let host = if host.is_empty() {
+            read_string(metadata, &["sniffHost"])
+        } else {
+            host
+        };
+
+        let chains = value
+            .get("chains")
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            id: read_string(value, &["id"]),
+            host,
+            source_ip: read_string(metadata, &["sourceIP", "sourceIp"]),
+            source_port: read_u64(metadata, &["sourcePort"]) as u16,
+            destination_ip,
+            destination_port: read_u64(metadata, &["destinationPort"]) as u16,
+            network: read_string(metadata, &["network"]).to_lowercase(),
+            rule: read_string(value, &["rule"]),
+            rule_payload: read_string(value, &["rulePayload", "rule_payload"]),
+            chains,
+            upload: read_u64(value, &["upload"]),
+            download: read_u64(value, &["download"]),
+            start: read_string(value, &["start"]),
+        }
+    }
+}
+
+// 按候选字段名读取字符串，数字会被转换为字符串
+fn read_string(value: &Value, keys: &[&str]) -> String {
+    keys.iter()
+        .find_map(|key| match value.get(*key) {
+            Some(Value::String(text)) => Some(text.clone()),
+            Some(Value::Number(number)) => Some(number.to_string()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+// 按候选字段名读取无符号整数，兼容数字字符串（如旧版本的端口字段）
+fn read_u64(value: &Value, keys: &[&str]) -> u64 {
+    keys.iter()
+        .find_map(|key| match value.get(*key) {
+            Some(Value::Number(number)) => number.as_u64(),
+            Some(Value::String(text)) => text.trim().parse().ok(),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
@@ -0,0 +1,180 @@
+// 核心生命周期监视：定期探测 /version，在核心上线、下线、重启时发出事件。
+// 状态需连续多次观测一致才确认，避免短暂抖动造成误报。
+
+use super::core_status::{record_core_down, record_core_up};
+use super::core_version::clear_core_version_cache;
+use super::handlers::clear_ipc_connection_pools;
+use crate::atoms::IpcClient;
+use once_cell::sync::Lazy;
+use rinf::{RustSignal, SignalPiece};
+use serde::Serialize;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+// 探测周期
+const PROBE_INTERVAL: Duration = Duration::from_secs(2);
+// 单次探测时限
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+// 确认状态变化所需的连续观测次数
+const DEBOUNCE_COUNT: u32 = 2;
+
+#[derive(Serialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoreLifecycleState {
+    Up = 0,
+    Down = 1,
+    // 下线后重新上线，或运行中版本号发生变化
+    Restarted = 2,
+}
+
+// Rust → Dart：核心生命周期状态变化
+#[derive(Serialize, RustSignal)]
+pub struct CoreLifecycleEvent {
+    pub state: CoreLifecycleState,
+    pub version: Option<String>,
+}
+
+// Rust 内部订阅者使用的事件通道
+static LIFECYCLE_SENDER: Lazy<broadcast::Sender<CoreLifecycleState>> =
+    Lazy::new(|| broadcast::channel(16).0);
+
+// 订阅核心生命周期事件
+pub fn subscribe_core_lifecycle() -> broadcast::Receiver<CoreLifecycleState> {
+    LIFECYCLE_SENDER.subscribe()
+}
+
+// 由探测结果推导生命周期状态
+#[derive(Default)]
+struct LifecycleTracker {
+    // 已确认的在线状态，尚未确认时为 None
+    is_up: Option<bool>,
+    has_been_up: bool,
+    version: Option<String>,
+    // 待确认的在线状态及其连续观测次数
+    pending: Option<(bool, u32)>,
+}
+
+impl LifecycleTracker {
+    // 记录一次探测：在线时传入版本号（可能无法解析），离线时传入 None
+    fn observe(&mut self, probe: Option<Option<String>>) -> Option<CoreLifecycleState> {
+        let is_up = probe.is_some();
+
+        if self.is_up == Some(is_up) {
+            self.pending = None;
+            let version = probe.flatten()?;
+            let is_version_changed = self
+                .version
+                .as_ref()
+                .is_some_and(|previous| *previous != version);
+            self.version = Some(version);
+            return is_version_changed.then_some(CoreLifecycleState::Restarted);
+        }
+
+        let count = match self.pending {
+            Some((pending_is_up, count)) if pending_is_up == is_up => count + 1,
+            _ => 1,
+        };
+        if count < DEBOUNCE_COUNT {
+            self.pending = Some((is_up, count));
+            return None;
+        }
+
+        self.pending = None;
+        self.is_up = Some(is_up);
+        if !is_up {
+            return Some(CoreLifecycleState::Down);
+        }
+
+        self.version = probe.flatten();
+        let state = if self.has_been_up {
+            CoreLifecycleState::Restarted
+        } else {
+            CoreLifecycleState::Up
+        };
+        self.has_been_up = true;
+        Some(state)
+    }
+}
+
+// 启动核心生命周期监视（进程内只需启动一次）
+pub fn start_core_lifecycle_monitor() {
+    tokio::spawn(async {
+        let mut tracker = LifecycleTracker::default();
+        let mut interval = tokio::time::interval(PROBE_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let ping = IpcClient::ping(PROBE_TIMEOUT).await;
+            let probe = ping.is_alive.then_some(ping.version);
+            let Some(state) = tracker.observe(probe) else {
+                continue;
+            };
+
+            log::info!(
+                "核心生命周期：{:?}（版本：{}）",
+                state,
+                tracker.version.as_deref().unwrap_or("未知")
+            );
+
+            match state {
+                CoreLifecycleState::Up => record_core_up(),
+                CoreLifecycleState::Down => record_core_down(),
+                // 重启后旧连接与版本缓存均已失效
+                CoreLifecycleState::Restarted => {
+                    record_core_up();
+                    clear_core_version_cache().await;
+                    clear_ipc_connection_pools().await;
+                }
+            }
+
+            let _ = LIFECYCLE_SENDER.send(state);
+            CoreLifecycleEvent {
+                state,
+                version: tracker.version.clone(),
+            }
+            .send_signal_to_dart();
+        }
+    });
+
+    log::info!(
+        "核心生命周期监视已启动（{}s 间隔）",
+        PROBE_INTERVAL.as_secs()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CoreLifecycleState, LifecycleTracker};
+
+    fn up(version: &str) -> Option<Option<String>> {
+        Some(Some(version.to_string()))
+    }
+
+    #[test]
+    fn debounces_transitions_and_infers_restart() {
+        let mut tracker = LifecycleTracker::default();
+
+        assert_eq!(tracker.observe(up("v1.18.0")), None);
+        assert_eq!(tracker.observe(up("v1.18.0")), Some(CoreLifecycleState::Up));
+
+        // 单次探测失败视为抖动
+        assert_eq!(tracker.observe(None), None);
+        assert_eq!(tracker.observe(up("v1.18.0")), None);
+
+        assert_eq!(tracker.observe(None), None);
+        assert_eq!(tracker.observe(None), Some(CoreLifecycleState::Down));
+        assert_eq!(tracker.observe(up("v1.18.0")), None);
+        assert_eq!(
+            tracker.observe(up("v1.18.0")),
+            Some(CoreLifecycleState::Restarted)
+        );
+
+        // 未观测到下线但版本变化（核心被替换）
+        assert_eq!(
+            tracker.observe(up("v1.19.0")),
+            Some(CoreLifecycleState::Restarted)
+        );
+        assert_eq!(tracker.observe(up("v1.19.0")), None);
+    }
+}
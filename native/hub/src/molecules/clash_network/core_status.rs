@@ -0,0 +1,123 @@
+// 核心运行状态记录：核心没有提供运行时长，由生命周期监视与配置重载事件记录。
+
+use super::proxy_mode::read_core_mode;
+use crate::atoms::shutdown;
+use crate::molecules::CoreMode;
+use rinf::{DartSignal, RustSignal};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Instant;
+
+// Dart → Rust：获取核心运行状态
+#[derive(Deserialize, DartSignal)]
+pub struct GetCoreStatus;
+
+// Rust → Dart：核心运行状态
+#[derive(Serialize, RustSignal)]
+pub struct CoreStatus {
+    pub is_running: bool,
+    // 自生命周期监视确认核心上线起的秒数，未运行时为 None
+    pub uptime_secs: Option<u64>,
+    // 最近一次成功重载配置的时间（Unix 毫秒），本次运行内未重载时为 None
+    pub last_reload_ms: Option<i64>,
+    // 核心当前出站模式，查询失败时为 None
+    pub mode: Option<CoreMode>,
+}
+
+#[derive(Default)]
+struct CoreStatusRecord {
+    up_since: Option<Instant>,
+    last_reload_ms: Option<i64>,
+}
+
+static CORE_STATUS_RECORD: Mutex<CoreStatusRecord> = Mutex::new(CoreStatusRecord {
+    up_since: None,
+    last_reload_ms: None,
+});
+
+fn with_record<R>(f: impl FnOnce(&mut CoreStatusRecord) -> R) -> R {
+    match CORE_STATUS_RECORD.lock() {
+        Ok(mut guard) => f(&mut guard),
+        Err(e) => f(&mut e.into_inner()),
+    }
+}
+
+// 核心上线或重启：重新开始计时。上线确认晚于核心启动，期间的重载记录仍然有效，
+// 重载记录只在核心启动或下线时清除
+pub(super) fn record_core_up() {
+    with_record(|record| record.up_since = Some(Instant::now()));
+}
+
+// 由进程管理在启动新核心时调用，上一个核心的重载记录不再有效
+pub fn record_core_started() {
+    with_record(|record| record.last_reload_ms = None);
+}
+
+pub(super) fn record_core_down() {
+    with_record(|record| *record = CoreStatusRecord::default());
+}
+
+pub(super) fn record_config_reload() {
+    with_record(|record| record.last_reload_ms = Some(chrono::Utc::now().timestamp_millis()));
+}
+
+impl GetCoreStatus {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let (uptime_secs, last_reload_ms) = with_record(|record| {
+                (
+                    record.up_since.map(|since| since.elapsed().as_secs()),
+                    record.last_reload_ms,
+                )
+            });
+
+            CoreStatus {
+                is_running: uptime_secs.is_some(),
+                uptime_secs,
+                last_reload_ms,
+                mode: match uptime_secs {
+                    Some(_) => read_core_mode().await.ok(),
+                    None => None,
+                },
+            }
+            .send_signal_to_dart();
+        });
+    }
+}
+
+pub fn init() {
+    shutdown::spawn_listener(async {
+        let receiver = GetCoreStatus::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        record_config_reload, record_core_down, record_core_started, record_core_up, with_record,
+    };
+
+    #[test]
+    fn keeps_reload_time_until_core_start_or_down() {
+        let last_reload_ms = || with_record(|record| record.last_reload_ms);
+
+        record_core_started();
+        record_config_reload();
+        let reloaded_at = last_reload_ms();
+        assert!(reloaded_at.is_some());
+
+        // 生命周期监视随后确认上线或重启，不应清除刚才的重载记录
+        record_core_up();
+        assert_eq!(last_reload_ms(), reloaded_at);
+
+        record_core_down();
+        assert_eq!(last_reload_ms(), None);
+
+        record_config_reload();
+        record_core_started();
+        assert_eq!(last_reload_ms(), None);
+    }
+}
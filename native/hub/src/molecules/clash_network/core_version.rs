@@ -0,0 +1,92 @@
+// 核心版本信息：解析 GET /version 并推导功能支持情况。
+// 版本在核心进程生命周期内不变，首次获取后缓存，核心停止时清除。
+
+use crate::atoms::IpcClient;
+use once_cell::sync::Lazy;
+use rinf::SignalPiece;
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+// 核心版本与功能标记
+#[derive(Serialize, SignalPiece, Clone, Debug, Default)]
+pub struct CoreVersionInfo {
+    pub version: String,
+    // mihomo（Clash.Meta）
+    pub is_meta: bool,
+    // Clash Premium
+    pub is_premium: bool,
+    pub supports_tun: bool,
+    pub supports_sniffing: bool,
+    // 是否提供 /group/{name}/delay 接口
+    pub supports_group_delay: bool,
+    // 是否提供 /memory 内存统计接口
+    pub supports_memory: bool,
+}
+
+static CORE_VERSION_CACHE: Lazy<RwLock<Option<CoreVersionInfo>>> = Lazy::new(|| RwLock::new(None));
+
+impl CoreVersionInfo {
+    pub fn from_json(value: &serde_json::Value) -> Self {
+        let version = value
+            .get("version")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let is_meta = value.get("meta").and_then(|v| v.as_bool()).unwrap_or(false);
+        let is_premium = value
+            .get("premium")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        // /group 延迟接口自 Clash.Meta 1.14 起提供；无法解析的版本号（如 alpha 构建）视为支持
+        let supports_group_delay =
+            is_meta && parse_semver(&version).is_none_or(|ver| ver >= (1, 14, 0));
+
+        Self {
+            supports_tun: is_meta || is_premium,
+            supports_sniffing: is_meta,
+            supports_group_delay,
+            // /memory 为 mihomo 扩展接口，Clash Premium 与原版核心没有
+            supports_memory: is_meta,
+            version,
+            is_meta,
+            is_premium,
+        }
+    }
+}
+
+// 解析形如 v1.18.1 的版本号
+fn parse_semver(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.trim().trim_start_matches('v').split(['.', '-']);
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+// 获取核心版本（优先使用缓存）
+pub async fn get_core_version() -> Result<CoreVersionInfo, String> {
+    if let Some(info) = CORE_VERSION_CACHE.read().await.as_ref() {
+        return Ok(info.clone());
+    }
+
+    let body = IpcClient::get_with_pool("/version").await?;
+    let value: serde_json::Value =
+        serde_json::from_str(&body).map_err(|e| format!("解析核心版本失败：{}", e))?;
+    let info = CoreVersionInfo::from_json(&value);
+
+    log::info!(
+        "核心版本：{}（meta={}, premium={}）",
+        info.version,
+        info.is_meta,
+        info.is_premium
+    );
+
+    *CORE_VERSION_CACHE.write().await = Some(info.clone());
+    Ok(info)
+}
+
+// 清除版本缓存（核心停止或重启时调用）
+pub async fn clear_core_version_cache() {
+    CORE_VERSION_CACHE.write().await.take();
+}
@@ -1,14 +1,27 @@
 // IPC 请求处理器：接收 Dart 请求并转发到核心接口。
 // 内置重试、连接池与必要的降噪日志策略。
 
+use super::connection_model::ConnectionSnapshot;
+use super::core_status::record_config_reload;
+use super::core_version::{CoreVersionInfo, clear_core_version_cache, get_core_version};
 use super::ipc_client::IpcClient;
-use super::ws_client::WebSocketClient;
+use super::node_ids::{assign_node_ids, load_node_ids};
+use super::proxies_model::{ProxyGroupInfo, parse_proxy_groups};
+use super::ws_client::{ReconnectPolicy, WebSocketClient, set_reconnect_policy};
+use crate::atoms::ipc_client::{
+    describe_http_error, encode_name_segment, load_controller_credentials,
+};
+use crate::atoms::shutdown;
+use crate::atoms::{ConnectionStrategy, IpcClient as PooledIpcClient, IpcHttpResponse};
+use crate::molecules::CoreMode;
 use once_cell::sync::Lazy;
 use rinf::{DartSignal, RustSignal};
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
 use tokio::sync::{RwLock, Semaphore};
 
 #[cfg(unix)]
@@ -74,7 +87,16 @@ pub struct IpcResponse {
 
 // Dart → Rust：开始监听 Clash 日志
 #[derive(Deserialize, DartSignal)]
-pub struct StartLogStream;
+pub struct StartLogStream {
+    // 最低日志级别（debug/info/warning/error），为空时使用 info
+    pub level: Option<String>,
+}
+
+// Dart → Rust：调整日志流的最低级别（无需重启日志流）
+#[derive(Deserialize, DartSignal)]
+pub struct SetLogStreamLevel {
+    pub level: String,
+}
 
 // Dart → Rust：停止监听 Clash 日志
 #[derive(Deserialize, DartSignal)]
@@ -89,7 +111,10 @@ pub struct IpcLogData {
 
 // Dart → Rust：开始监听流量数据
 #[derive(Deserialize, DartSignal)]
-pub struct StartTrafficStream;
+pub struct StartTrafficStream {
+    // 合并窗口（毫秒），窗口内只发送最新样本；为空时使用 500ms，0 表示不合并
+    pub coalesce_interval_ms: Option<u64>,
+}
 
 // Dart → Rust：停止监听流量数据
 #[derive(Deserialize, DartSignal)]
@@ -104,7 +129,10 @@ pub struct IpcTrafficData {
 
 // Dart → Rust：开始监听内存数据
 #[derive(Deserialize, DartSignal)]
-pub struct StartMemoryStream;
+pub struct StartMemoryStream {
+    // 合并窗口（毫秒），窗口内只发送最新样本；为空时使用 1000ms，0 表示不合并
+    pub coalesce_interval_ms: Option<u64>,
+}
 
 // Dart → Rust：停止监听内存数据
 #[derive(Deserialize, DartSignal)]
@@ -117,9 +145,45 @@ pub struct IpcMemoryData {
     pub oslimit: u64,
 }
 
+// Rust → Dart：核心不提供内存统计，内存监控不会启动
+#[derive(Serialize, RustSignal)]
+pub struct CoreMemoryUnavailable {
+    pub reason: String,
+}
+
+// 核心内存占用（字节），oslimit 为 0 表示未设置上限
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CoreMemory {
+    pub inuse: u64,
+    pub oslimit: u64,
+}
+
+impl CoreMemory {
+    // 缺少 inuse 字段的消息不是内存统计，返回 None
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let obj = value.as_object()?;
+        Some(Self {
+            inuse: obj.get("inuse")?.as_u64()?,
+            oslimit: obj.get("oslimit").and_then(|v| v.as_u64()).unwrap_or(0),
+        })
+    }
+
+    // 以 IpcMemoryData 发送给 Dart
+    fn emit_sample(self) {
+        IpcMemoryData {
+            inuse: self.inuse,
+            oslimit: self.oslimit,
+        }
+        .send_signal_to_dart();
+    }
+}
+
 // Dart → Rust：开始监听连接数据
 #[derive(Deserialize, DartSignal)]
-pub struct StartConnectionStream;
+pub struct StartConnectionStream {
+    // 为 true 时发送解析后的 ConnectionSnapshot，否则发送原始 JSON
+    pub is_typed: Option<bool>,
+}
 
 // Dart → Rust：停止监听连接数据
 #[derive(Deserialize, DartSignal)]
@@ -131,6 +195,157 @@ pub struct IpcConnectionData {
     pub connections_json: String,
 }
 
+// Dart → Rust：关闭指定连接
+#[derive(Deserialize, DartSignal)]
+pub struct CloseConnection {
+    pub id: String,
+}
+
+// Dart → Rust：关闭所有连接
+#[derive(Deserialize, DartSignal)]
+pub struct CloseAllConnections;
+
+// Rust → Dart：关闭连接结果（id 为空表示关闭所有连接）
+#[derive(Serialize, RustSignal)]
+pub struct CloseConnectionResult {
+    pub id: Option<String>,
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+// Dart → Rust：切换策略组中选中的节点
+#[derive(Deserialize, DartSignal)]
+pub struct SelectProxy {
+    pub group: String,
+    pub node: String,
+}
+
+// Rust → Dart：切换节点结果
+#[derive(Serialize, RustSignal)]
+pub struct SelectProxyResult {
+    pub group: String,
+    pub selected: Option<String>,
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+// Dart → Rust：获取完整代理树
+#[derive(Deserialize, DartSignal)]
+pub struct GetProxiesSnapshot {
+    // 为 true 时跳过短期缓存
+    pub should_refresh: bool,
+}
+
+// Rust → Dart：代理树快照
+#[derive(Serialize, RustSignal)]
+pub struct ProxiesSnapshot {
+    pub groups: Vec<ProxyGroupInfo>,
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+// Dart → Rust：让核心重新加载配置文件
+#[derive(Deserialize, DartSignal)]
+pub struct ReloadConfig {
+    // 配置文件绝对路径
+    pub path: String,
+    pub force: bool,
+}
+
+// Rust → Dart：重新加载配置结果
+#[derive(Serialize, RustSignal)]
+pub struct ReloadConfigResult {
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+// Dart → Rust：修改核心运行时配置（无需重新加载）
+#[derive(Deserialize, DartSignal)]
+pub struct PatchRuntimeConfig {
+    pub mode: Option<CoreMode>,
+    pub log_level: Option<String>,
+    pub is_tun_enabled: Option<bool>,
+    pub allow_lan: Option<bool>,
+    pub ipv6: Option<bool>,
+    // 其他字段（JSON 对象字符串），与上面的字段合并后发送
+    pub extra_json: Option<String>,
+}
+
+// Rust → Dart：修改运行时配置结果
+#[derive(Serialize, RustSignal)]
+pub struct PatchRuntimeConfigResult {
+    pub status_code: u16,
+    pub body: String,
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+// Dart → Rust：获取核心版本与功能支持情况
+#[derive(Deserialize, DartSignal)]
+pub struct GetCoreVersion;
+
+// Rust → Dart：核心版本信息
+#[derive(Serialize, RustSignal)]
+pub struct CoreVersionResult {
+    pub info: Option<CoreVersionInfo>,
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+// Dart → Rust：检查核心是否在线
+#[derive(Deserialize, DartSignal)]
+pub struct CheckCoreAlive {
+    pub timeout_ms: Option<u64>,
+}
+
+// Rust → Dart：核心在线检查结果
+#[derive(Serialize, RustSignal)]
+pub struct CoreAliveResult {
+    pub is_alive: bool,
+    pub round_trip_ms: u64,
+    pub error_message: Option<String>,
+}
+
+// Dart → Rust：开启或关闭 IPC 重定向跟随（核心前置反向代理时使用）
+#[derive(Deserialize, DartSignal)]
+pub struct SetIpcRedirectFollowing {
+    pub is_enabled: bool,
+}
+
+// Dart → Rust：开启或关闭 IPC 连接复用。
+// 关闭后每个请求使用新连接，吞吐下降但不受失效连接影响，用于排查连接复用问题
+#[derive(Deserialize, DartSignal)]
+pub struct SetIpcPoolingEnabled {
+    pub is_enabled: bool,
+}
+
+// Dart → Rust：预热 IPC 连接池（核心确认在线后发送）
+#[derive(Deserialize, DartSignal)]
+pub struct WarmupIpcPool {
+    pub connection_count: u32,
+}
+
+// Dart → Rust：设置 IPC 连接池容量。
+// 批量延迟测试的并发数会被限制在该容量内，避免超出部分的连接无法回池而反复新建
+#[derive(Deserialize, DartSignal)]
+pub struct SetIpcPoolSize {
+    pub max_connections: u32,
+}
+
+// Dart → Rust：设置 WebSocket 断线重连策略（指数退避，单轮失败上限后等待核心重新上线）
+#[derive(Deserialize, DartSignal)]
+pub struct SetWsReconnectPolicy {
+    pub initial_delay_ms: u32,
+    pub max_delay_ms: u32,
+    pub max_attempts_per_burst: u32,
+}
+
+// Dart → Rust：开启或关闭 IPC 报文跟踪（trace 级别日志）
+#[derive(Deserialize, DartSignal)]
+pub struct SetIpcTraceLogging {
+    pub is_enabled: bool,
+}
+
 // Rust → Dart：流操作结果
 #[derive(Serialize, RustSignal)]
 pub struct StreamResult {
@@ -198,8 +413,10 @@ async fn handle_ipc_request_with_retry(
         // 使用连接发送请求
         match IpcClient::request_with_connection(method, path, body, ipc_conn).await {
             Ok((response, ipc_conn)) => {
-                // 归还连接
-                release_connection(ipc_conn).await;
+                // 归还连接（服务端声明关闭时丢弃）
+                if response.is_keep_alive {
+                    release_connection(ipc_conn).await;
+                }
 
                 // 特殊日志处理（仅 GET 请求）
                 if should_log_response {
@@ -358,8 +575,8 @@ pub fn start_connection_pool_health_check() {
 // 连接获取通用逻辑宏（消除 Windows 和 Unix 平台的重复代码）
 macro_rules! acquire_connection_with_retry {
     ($connect_fn:expr, $conn_type:literal) => {{
-        // 1. 尝试从池中获取（FIFO + 有效性检查）
-        loop {
+        // 1. 尝试从池中获取（FIFO + 有效性检查），关闭连接复用时跳过
+        while PooledIpcClient::is_pooling_enabled() {
             let mut pool = IPC_CONNECTION_POOL.write().await;
 
             if let Some(pooled) = pool.pop_front() {
@@ -444,6 +661,11 @@ async fn acquire_connection() -> Result<UnixStream, String> {
 // 归还连接到池中通用逻辑宏
 macro_rules! release_connection_impl {
     ($conn:expr) => {{
+        if !PooledIpcClient::is_pooling_enabled() {
+            log::trace!("连接复用已关闭，丢弃连接");
+            return;
+        }
+
         let mut pool = IPC_CONNECTION_POOL.write().await;
 
         if pool.len() < MAX_POOL_SIZE {
@@ -481,10 +703,90 @@ static TRAFFIC_CONNECTION_ID: Lazy<Arc<RwLock<Option<u32>>>> =
 static LOG_CONNECTION_ID: Lazy<Arc<RwLock<Option<u32>>>> =
     Lazy::new(|| Arc::new(RwLock::new(None)));
 
+// 流量数据默认合并窗口
+const DEFAULT_TRAFFIC_COALESCE_MS: u64 = 500;
+
+// 当前窗口内待发送的最新流量样本（上传，下载）
+static PENDING_TRAFFIC_SAMPLE: std::sync::Mutex<Option<(u64, u64)>> = std::sync::Mutex::new(None);
+
+// 流量样本定时发送任务
+static TRAFFIC_FLUSH_TASK: Lazy<RwLock<Option<tokio::task::JoinHandle<()>>>> =
+    Lazy::new(|| RwLock::new(None));
+
+// 发送窗口内的最新流量样本（如有）
+fn flush_pending_traffic_sample() {
+    let sample = match PENDING_TRAFFIC_SAMPLE.lock() {
+        Ok(mut pending) => pending.take(),
+        Err(_) => None,
+    };
+
+    if let Some((upload, download)) = sample {
+        IpcTrafficData { upload, download }.send_signal_to_dart();
+    }
+}
+
+// 停止流量定时发送任务，并补发最后一个样本
+async fn stop_traffic_flush_task() {
+    if let Some(task) = TRAFFIC_FLUSH_TASK.write().await.take() {
+        task.abort();
+    }
+    flush_pending_traffic_sample();
+}
+
+// mihomo 日志级别（按严重程度递增，下标即级别序号）
+const LOG_LEVELS: [&str; 5] = ["debug", "info", "warning", "error", "silent"];
+
+// 日志流转发到 Dart 的最低级别序号
+static LOG_MIN_LEVEL: AtomicU8 = AtomicU8::new(1);
+
+// 当前日志 WebSocket 向核心订阅的级别序号
+static LOG_SUBSCRIBED_LEVEL: AtomicU8 = AtomicU8::new(1);
+
+// 解析日志级别序号，未知级别按 info 处理
+fn log_level_rank(level: &str) -> u8 {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "debug" => 0,
+        "warning" | "warn" => 2,
+        "error" => 3,
+        "silent" => 4,
+        _ => 1,
+    }
+}
+
 // 存储当前的内存监控连接 ID
 static MEMORY_CONNECTION_ID: Lazy<Arc<RwLock<Option<u32>>>> =
     Lazy::new(|| Arc::new(RwLock::new(None)));
 
+// 内存数据默认合并窗口
+const DEFAULT_MEMORY_COALESCE_MS: u64 = 1000;
+
+// 当前窗口内待发送的最新内存样本
+static PENDING_MEMORY_SAMPLE: std::sync::Mutex<Option<CoreMemory>> = std::sync::Mutex::new(None);
+
+// 内存样本定时发送任务
+static MEMORY_FLUSH_TASK: Lazy<RwLock<Option<tokio::task::JoinHandle<()>>>> =
+    Lazy::new(|| RwLock::new(None));
+
+// 发送窗口内的最新内存样本（如有）
+fn flush_pending_memory_sample() {
+    let sample = match PENDING_MEMORY_SAMPLE.lock() {
+        Ok(mut pending) => pending.take(),
+        Err(_) => None,
+    };
+
+    if let Some(memory) = sample {
+        memory.emit_sample();
+    }
+}
+
+// 停止内存定时发送任务，并补发最后一个样本
+async fn stop_memory_flush_task() {
+    if let Some(task) = MEMORY_FLUSH_TASK.write().await.take() {
+        task.abort();
+    }
+    flush_pending_memory_sample();
+}
+
 // 连接流 WebSocket 连接 ID
 static CONNECTION_STREAM_ID: Lazy<Arc<RwLock<Option<u32>>>> =
     Lazy::new(|| Arc::new(RwLock::new(None)));
@@ -501,12 +803,25 @@ async fn ensure_ws_client_initialized() {
 
 // 清理 IPC 连接池（在 Clash 停止时调用）
 pub async fn cleanup_ipc_connection_pool() -> usize {
-    let mut pool = IPC_CONNECTION_POOL.write().await;
-    let count = pool.len();
-    pool.clear();
+    let connections: Vec<PooledConnection> = {
+        let mut pool = IPC_CONNECTION_POOL.write().await;
+        pool.drain(..).collect()
+    };
+
+    let count = connections.len();
+    for mut pooled in connections {
+        // 对端可能已关闭，关闭失败不影响清理
+        let _ = tokio::time::timeout(Duration::from_millis(100), pooled.conn.shutdown()).await;
+    }
     count
 }
 
+// 清空全部 IPC 连接池（REST 请求池与内部请求池），返回清理总数。
+// 在核心启动、停止或重启时调用
+pub async fn clear_ipc_connection_pools() -> usize {
+    cleanup_ipc_connection_pool().await + PooledIpcClient::clear_pool().await
+}
+
 // 清理 WebSocket 客户端（在 Clash 停止时调用）
 pub async fn cleanup_ws_client() -> bool {
     let mut client_guard = WS_CLIENT.write().await;
@@ -522,9 +837,15 @@ pub async fn cleanup_ws_client() -> bool {
 pub async fn cleanup_all_network_resources() {
     // 1. 清理 WebSocket 连接
     let ws_cleaned = cleanup_ws_client().await;
+    stop_traffic_flush_task().await;
+    stop_memory_flush_task().await;
+
+    // 核心已停止，清除与核心进程绑定的缓存
+    clear_core_version_cache().await;
+    invalidate_proxies_snapshot().await;
 
     // 2. 清理 IPC 连接池
-    let ipc_count = cleanup_ipc_connection_pool().await;
+    let ipc_count = clear_ipc_connection_pools().await;
 
     log::info!(
         "网络资源已清理（WebSocket={}, IPC 连接池={}个）",
@@ -616,6 +937,496 @@ impl IpcDeleteRequest {
     }
 }
 
+// 提取核心错误响应的原始内容，响应体为空时退回状态码
+fn core_error_message(response: &IpcHttpResponse) -> String {
+    let body = response.body.trim();
+    if body.is_empty() {
+        format!("HTTP {}", response.status_code)
+    } else {
+        body.to_string()
+    }
+}
+
+// 关闭连接请求：连接不存在视为已关闭，核心错误原样返回
+async fn close_connections(path: &str) -> Result<(), String> {
+    let response = PooledIpcClient::delete(path).await?;
+
+    match response.status_code {
+        200..=299 => Ok(()),
+        404 => {
+            log::debug!("连接已不存在，视为关闭成功：{}", path);
+            Ok(())
+        }
+        _ => Err(core_error_message(&response)),
+    }
+}
+
+// 关闭指定连接处理器
+impl CloseConnection {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let path = format!("/connections/{}", urlencoding::encode(&self.id));
+            let result = close_connections(&path).await;
+
+            if let Err(ref e) = result {
+                log::error!("关闭连接失败：{}，error：{}", self.id, e);
+            }
+
+            CloseConnectionResult {
+                id: Some(self.id),
+                is_successful: result.is_ok(),
+                error_message: result.err(),
+            }
+            .send_signal_to_dart();
+        });
+    }
+}
+
+// 关闭所有连接处理器
+impl CloseAllConnections {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let result = close_connections("/connections").await;
+
+            match result {
+                Ok(()) => log::info!("已关闭所有连接"),
+                Err(ref e) => log::error!("关闭所有连接失败：{}", e),
+            }
+
+            CloseConnectionResult {
+                id: None,
+                is_successful: result.is_ok(),
+                error_message: result.err(),
+            }
+            .send_signal_to_dart();
+        });
+    }
+}
+
+// 切换节点处理器
+impl SelectProxy {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let result = select_proxy(&self.group, &self.node).await;
+
+            match result {
+                Ok(()) => log::info!("策略组 {} 已切换到：{}", self.group, self.node),
+                Err(ref e) => log::error!("策略组 {} 切换节点失败：{}", self.group, e),
+            }
+
+            SelectProxyResult {
+                selected: result.is_ok().then(|| self.node.clone()),
+                group: self.group,
+                is_successful: result.is_ok(),
+                error_message: result.err(),
+            }
+            .send_signal_to_dart();
+        });
+    }
+}
+
+// 获取策略组的成员节点名称
+pub async fn get_group_members(group: &str) -> Result<Vec<String>, String> {
+    let path = format!("/proxies/{}", encode_name_segment(group)?);
+
+    let body = PooledIpcClient::get_with_pool(&path)
+        .await
+        .map_err(|e| format!("获取策略组 {} 失败：{}", group, e))?;
+    let group_info: serde_json::Value =
+        serde_json::from_str(&body).map_err(|e| format!("解析策略组信息失败：{}", e))?;
+
+    let members = group_info
+        .get("all")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| format!("{} 不是策略组", group))?;
+    Ok(members
+        .iter()
+        .filter_map(|member| member.as_str().map(str::to_string))
+        .collect())
+}
+
+// 先校验节点属于该策略组，再提交切换请求
+pub async fn select_proxy(group: &str, node: &str) -> Result<(), String> {
+    let members = get_group_members(group).await?;
+    if !members.iter().any(|member| member == node) {
+        return Err(format!("节点 {} 不属于策略组 {}", node, group));
+    }
+
+    let path = format!("/proxies/{}", encode_name_segment(group)?);
+    let payload = serde_json::json!({ "name": node }).to_string();
+    let response = PooledIpcClient::put(&path, &payload).await?;
+    if (200..300).contains(&response.status_code) {
+        invalidate_proxies_snapshot().await;
+        Ok(())
+    } else {
+        Err(core_error_message(&response))
+    }
+}
+
+// 代理树缓存有效期（合并 UI 短时间内的重复请求）
+const PROXIES_SNAPSHOT_TTL: Duration = Duration::from_secs(1);
+
+// 代理树缓存（获取时间，策略组列表）
+type ProxiesSnapshotCache = Option<(Instant, Vec<ProxyGroupInfo>)>;
+static PROXIES_SNAPSHOT_CACHE: Lazy<RwLock<ProxiesSnapshotCache>> = Lazy::new(|| RwLock::new(None));
+
+// 获取代理树处理器
+impl GetProxiesSnapshot {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let response = match get_proxy_groups(self.should_refresh).await {
+                Ok(groups) => ProxiesSnapshot {
+                    groups,
+                    is_successful: true,
+                    error_message: None,
+                },
+                Err(e) => {
+                    log::error!("获取代理树失败：{}", e);
+                    ProxiesSnapshot {
+                        groups: Vec::new(),
+                        is_successful: false,
+                        error_message: Some(e),
+                    }
+                }
+            };
+            response.send_signal_to_dart();
+        });
+    }
+}
+
+// 一次 GET /proxies 获取完整代理树，短期内的重复请求直接使用缓存
+async fn get_proxy_groups(should_refresh: bool) -> Result<Vec<ProxyGroupInfo>, String> {
+    if !should_refresh
+        && let Some((fetched_at, groups)) = PROXIES_SNAPSHOT_CACHE.read().await.as_ref()
+        && fetched_at.elapsed() < PROXIES_SNAPSHOT_TTL
+    {
+        log::trace!("使用缓存的代理树（{}个策略组）", groups.len());
+        return Ok(groups.clone());
+    }
+
+    let body = PooledIpcClient::get_with_pool("/proxies").await?;
+    let value: serde_json::Value =
+        serde_json::from_str(&body).map_err(|e| format!("解析代理列表失败：{}", e))?;
+    let mut groups = parse_proxy_groups(&value)?;
+    assign_node_ids(&mut groups);
+
+    *PROXIES_SNAPSHOT_CACHE.write().await = Some((Instant::now(), groups.clone()));
+    Ok(groups)
+}
+
+// 获取代理树，允许使用短期缓存
+pub async fn get_proxies_snapshot() -> Result<Vec<ProxyGroupInfo>, String> {
+    get_proxy_groups(false).await
+}
+
+// 使代理树缓存失效（切换节点等操作后调用）
+pub(super) async fn invalidate_proxies_snapshot() {
+    PROXIES_SNAPSHOT_CACHE.write().await.take();
+}
+
+// 重新加载配置处理器
+impl ReloadConfig {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let result = reload_config(&self.path, self.force).await;
+
+            match result {
+                Ok(()) => log::info!("核心已重新加载配置：{}", self.path),
+                Err(ref e) => log::error!("核心重新加载配置失败：{}", e),
+            }
+
+            ReloadConfigResult {
+                is_successful: result.is_ok(),
+                error_message: result.err(),
+            }
+            .send_signal_to_dart();
+        });
+    }
+}
+
+// 校验路径后通过 PUT /configs 通知核心重新加载（核心对无效路径的报错不直观）
+async fn reload_config(path: &str, force: bool) -> Result<(), String> {
+    let config_path = std::path::Path::new(path);
+    if !config_path.is_absolute() {
+        return Err(format!("配置文件路径必须为绝对路径：{}", path));
+    }
+    if !config_path.is_file() {
+        return Err(format!("配置文件不存在：{}", path));
+    }
+
+    // 获取配置更新信号量，防止并发配置修改
+    let _permit = CONFIG_UPDATE_SEMAPHORE
+        .acquire()
+        .await
+        .map_err(|e| format!("获取配置更新信号量失败：{}", e))?;
+
+    let endpoint = format!("/configs?force={}", force);
+    let payload = serde_json::json!({ "path": path }).to_string();
+    // 一次性操作，且重载期间核心可能重置连接，使用独立连接避免污染连接池
+    let response =
+        PooledIpcClient::send("PUT", &endpoint, Some(&payload), ConnectionStrategy::Fresh)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    if (200..300).contains(&response.status_code) {
+        invalidate_proxies_snapshot().await;
+        // 新配置可能修改了 secret 与节点，之后的请求需使用新密钥
+        refresh_core_config_state(config_path.to_path_buf()).await;
+        record_config_reload();
+        Ok(())
+    } else {
+        Err(core_error_message(&response))
+    }
+}
+
+// 从核心配置文件刷新控制器凭据与节点稳定标识，失败时保留原有状态。
+// 读取与解析大型配置文件较慢，放到阻塞线程池执行
+pub async fn refresh_core_config_state(config_path: std::path::PathBuf) {
+    let refresh = tokio::task::spawn_blocking(move || {
+        if let Err(e) = load_controller_credentials(&config_path) {
+            log::warn!("读取控制器凭据失败：{}", e);
+        }
+        if let Err(e) = load_node_ids(&config_path) {
+            log::warn!("读取节点稳定标识失败：{}", e);
+        }
+    });
+    if let Err(e) = refresh.await {
+        log::error!("刷新核心配置状态的任务异常退出：{}", e);
+    }
+}
+
+// 运行时配置中必须为布尔值的字段
+const RUNTIME_BOOL_KEYS: [&str; 5] = [
+    "allow-lan",
+    "ipv6",
+    "unified-delay",
+    "tcp-concurrent",
+    "sniffing",
+];
+
+// 核心支持的日志级别
+const RUNTIME_LOG_LEVELS: [&str; 5] = ["debug", "info", "warning", "error", "silent"];
+
+// 修改运行时配置处理器
+impl PatchRuntimeConfig {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let result = match self.build_payload() {
+                Ok(payload) => patch_runtime_config(&payload).await,
+                Err(e) => Err(e),
+            };
+
+            let response = match result {
+                Ok(response) if (200..300).contains(&response.status_code) => {
+                    log::info!("核心运行时配置已更新");
+                    PatchRuntimeConfigResult {
+                        status_code: response.status_code,
+                        body: response.body,
+                        is_successful: true,
+                        error_message: None,
+                    }
+                }
+                Ok(response) => {
+                    let error_message = core_error_message(&response);
+                    log::error!("核心运行时配置更新失败：{}", error_message);
+                    PatchRuntimeConfigResult {
+                        status_code: response.status_code,
+                        body: response.body,
+                        is_successful: false,
+                        error_message: Some(error_message),
+                    }
+                }
+                Err(e) => {
+                    log::error!("核心运行时配置更新失败：{}", e);
+                    PatchRuntimeConfigResult {
+                        status_code: 0,
+                        body: String::new(),
+                        is_successful: false,
+                        error_message: Some(e),
+                    }
+                }
+            };
+            response.send_signal_to_dart();
+        });
+    }
+
+    // 合并类型化字段与额外 JSON，并在发送前校验取值
+    fn build_payload(&self) -> Result<serde_json::Value, String> {
+        let mut payload = match self.extra_json.as_deref() {
+            Some(extra) if !extra.trim().is_empty() => {
+                serde_json::from_str::<serde_json::Value>(extra)
+                    .map_err(|e| format!("额外配置不是有效的 JSON：{}", e))?
+            }
+            _ => serde_json::json!({}),
+        };
+        let map = payload.as_object_mut().ok_or("额外配置必须是 JSON 对象")?;
+
+        if let Some(mode) = self.mode {
+            map.insert("mode".to_string(), serde_json::json!(mode.as_str()));
+        }
+        if let Some(ref log_level) = self.log_level {
+            map.insert("log-level".to_string(), serde_json::json!(log_level));
+        }
+        if let Some(is_tun_enabled) = self.is_tun_enabled {
+            let tun = map.entry("tun").or_insert_with(|| serde_json::json!({}));
+            let tun = tun.as_object_mut().ok_or("tun 必须是 JSON 对象")?;
+            tun.insert("enable".to_string(), serde_json::json!(is_tun_enabled));
+        }
+        if let Some(allow_lan) = self.allow_lan {
+            map.insert("allow-lan".to_string(), serde_json::json!(allow_lan));
+        }
+        if let Some(ipv6) = self.ipv6 {
+            map.insert("ipv6".to_string(), serde_json::json!(ipv6));
+        }
+
+        if map.is_empty() {
+            return Err("没有需要修改的配置".to_string());
+        }
+
+        // 校验取值，避免核心静默忽略错误类型的字段
+        if let Some(mode) = map.get("mode") {
+            let is_valid = mode.as_str().and_then(CoreMode::parse).is_some();
+            if !is_valid {
+                return Err(format!("无效的 mode：{}（可选 rule/global/direct）", mode));
+            }
+        }
+        if let Some(log_level) = map.get("log-level") {
+            let is_valid = log_level
+                .as_str()
+                .is_some_and(|level| RUNTIME_LOG_LEVELS.contains(&level));
+            if !is_valid {
+                return Err(format!("无效的 log-level：{}", log_level));
+            }
+        }
+        for key in RUNTIME_BOOL_KEYS {
+            if let Some(value) = map.get(key)
+                && !value.is_boolean()
+            {
+                return Err(format!("{} 必须是布尔值：{}", key, value));
+            }
+        }
+        if let Some(tun_enable) = map.get("tun").and_then(|tun| tun.get("enable"))
+            && !tun_enable.is_boolean()
+        {
+            return Err(format!("tun.enable 必须是布尔值：{}", tun_enable));
+        }
+
+        Ok(payload)
+    }
+}
+
+// 通过 PATCH /configs 修改运行时配置
+pub(super) async fn patch_runtime_config(
+    payload: &serde_json::Value,
+) -> Result<IpcHttpResponse, String> {
+    // 获取配置更新信号量，防止并发配置修改
+    let _permit = CONFIG_UPDATE_SEMAPHORE
+        .acquire()
+        .await
+        .map_err(|e| format!("获取配置更新信号量失败：{}", e))?;
+
+    Ok(PooledIpcClient::patch("/configs", &payload.to_string()).await?)
+}
+
+// 获取核心版本处理器
+impl GetCoreVersion {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let response = match get_core_version().await {
+                Ok(info) => CoreVersionResult {
+                    info: Some(info),
+                    is_successful: true,
+                    error_message: None,
+                },
+                Err(e) => {
+                    log::error!("获取核心版本失败：{}", e);
+                    CoreVersionResult {
+                        info: None,
+                        is_successful: false,
+                        error_message: Some(e),
+                    }
+                }
+            };
+            response.send_signal_to_dart();
+        });
+    }
+}
+
+impl SetIpcRedirectFollowing {
+    pub fn handle(self) {
+        PooledIpcClient::set_redirect_following(self.is_enabled);
+    }
+}
+
+impl SetIpcPoolingEnabled {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            PooledIpcClient::set_pooling_enabled(self.is_enabled).await;
+            if !self.is_enabled {
+                cleanup_ipc_connection_pool().await;
+            }
+        });
+    }
+}
+
+impl WarmupIpcPool {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let opened = PooledIpcClient::warmup(self.connection_count as usize).await;
+            log::info!(
+                "IPC 连接池预热完成：新建 {} 个连接（目标 {}）",
+                opened,
+                self.connection_count
+            );
+        });
+    }
+}
+
+impl SetIpcPoolSize {
+    pub fn handle(self) {
+        PooledIpcClient::set_pool_capacity(self.max_connections as usize);
+    }
+}
+
+impl SetWsReconnectPolicy {
+    pub fn handle(self) {
+        set_reconnect_policy(ReconnectPolicy {
+            initial_delay: Duration::from_millis(u64::from(self.initial_delay_ms)),
+            max_delay: Duration::from_millis(u64::from(self.max_delay_ms)),
+            max_attempts_per_burst: self.max_attempts_per_burst,
+        });
+    }
+}
+
+impl SetIpcTraceLogging {
+    pub fn handle(self) {
+        crate::atoms::ipc_client::trace::set_trace_enabled(self.is_enabled);
+    }
+}
+
+// 核心在线检查的默认超时
+const CORE_ALIVE_TIMEOUT_MS: u64 = 1500;
+
+impl CheckCoreAlive {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let limit = Duration::from_millis(self.timeout_ms.unwrap_or(CORE_ALIVE_TIMEOUT_MS));
+            let ping = PooledIpcClient::ping(limit).await;
+
+            if let Some(ref e) = ping.error_message {
+                log::debug!("核心在线检查失败：{}", e);
+            }
+
+            CoreAliveResult {
+                is_alive: ping.is_alive,
+                round_trip_ms: ping.round_trip_ms,
+                error_message: ping.error_message,
+            }
+            .send_signal_to_dart();
+        });
+    }
+}
+
 // 初始化 IPC REST API 消息监听器
 pub fn init_rest_api_listeners() {
     log::info!("初始化 IPC REST API 监听器");
@@ -623,92 +1434,197 @@ pub fn init_rest_api_listeners() {
     // 启动连接池健康检查
     start_connection_pool_health_check();
 
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = IpcGetRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle();
         }
     });
 
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = IpcPostRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle();
         }
     });
 
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = IpcPutRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle();
         }
     });
 
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = IpcPatchRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle();
         }
     });
 
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = IpcDeleteRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle();
         }
     });
 
+    shutdown::spawn_listener(async {
+        let receiver = CloseConnection::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = CloseAllConnections::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = SelectProxy::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = GetProxiesSnapshot::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = ReloadConfig::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = PatchRuntimeConfig::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = GetCoreVersion::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = SetIpcTraceLogging::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = SetIpcRedirectFollowing::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = SetIpcPoolingEnabled::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = WarmupIpcPool::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = SetIpcPoolSize::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = SetWsReconnectPolicy::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = CheckCoreAlive::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
     // WebSocket 流式数据监听器
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = StartTrafficStream::get_dart_signal_receiver();
-        while let Some(_dart_signal) = receiver.recv().await {
-            StartTrafficStream::handle_start().await;
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle_start().await;
         }
     });
 
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = StopTrafficStream::get_dart_signal_receiver();
         while let Some(_dart_signal) = receiver.recv().await {
             StopTrafficStream::handle_stop().await;
         }
     });
 
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = StartLogStream::get_dart_signal_receiver();
-        while let Some(_dart_signal) = receiver.recv().await {
-            StartLogStream::handle_start().await;
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle_start().await;
         }
     });
 
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
+        let receiver = SetLogStreamLevel::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle().await;
+        }
+    });
+
+    shutdown::spawn_listener(async {
         let receiver = StopLogStream::get_dart_signal_receiver();
         while let Some(_dart_signal) = receiver.recv().await {
             StopLogStream::handle_stop().await;
         }
     });
 
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = StartMemoryStream::get_dart_signal_receiver();
-        while let Some(_dart_signal) = receiver.recv().await {
-            StartMemoryStream::handle_start().await;
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle_start().await;
         }
     });
 
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = StopMemoryStream::get_dart_signal_receiver();
         while let Some(_dart_signal) = receiver.recv().await {
             StopMemoryStream::handle_stop().await;
         }
     });
 
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = StartConnectionStream::get_dart_signal_receiver();
-        while let Some(_dart_signal) = receiver.recv().await {
-            StartConnectionStream::handle_start().await;
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle_start().await;
         }
     });
 
-    tokio::spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = StopConnectionStream::get_dart_signal_receiver();
         while let Some(_dart_signal) = receiver.recv().await {
             StopConnectionStream::handle_stop().await;
@@ -719,24 +1635,36 @@ pub fn init_rest_api_listeners() {
 // WebSocket 流式数据处理器
 
 impl StartTrafficStream {
-    async fn handle_start() {
-        log::info!("开始监听流量数据");
+    async fn handle_start(self) {
+        let interval_ms = self
+            .coalesce_interval_ms
+            .unwrap_or(DEFAULT_TRAFFIC_COALESCE_MS);
+
+        log::info!("开始监听流量数据（合并窗口：{}ms）", interval_ms);
 
         // 确保 WebSocket 客户端已初始化
         ensure_ws_client_initialized().await;
 
+        // 重新开始前清理上一次的定时发送任务
+        stop_traffic_flush_task().await;
+
         // 建立 WebSocket 连接
         let client = WS_CLIENT.read().await;
         if let Some(ws_client) = client.as_ref() {
             match ws_client
-                .connect("/traffic", |json_value| {
+                .connect("/traffic", move |json_value| {
                     // 解析流量数据
                     if let Some(obj) = json_value.as_object() {
                         let upload = obj.get("up").and_then(|v| v.as_u64()).unwrap_or(0);
                         let download = obj.get("down").and_then(|v| v.as_u64()).unwrap_or(0);
 
-                        // 发送到 Dart 层
-                        IpcTrafficData { upload, download }.send_signal_to_dart();
+                        if interval_ms == 0 {
+                            // 不合并，直接发送到 Dart 层
+                            IpcTrafficData { upload, download }.send_signal_to_dart();
+                        } else if let Ok(mut pending) = PENDING_TRAFFIC_SAMPLE.lock() {
+                            // 窗口内后到的样本覆盖先到的样本
+                            *pending = Some((upload, download));
+                        }
                     }
                 })
                 .await
@@ -748,6 +1676,20 @@ impl StartTrafficStream {
                     let mut id_guard = TRAFFIC_CONNECTION_ID.write().await;
                     *id_guard = Some(connection_id);
 
+                    // 按窗口周期发送最新样本
+                    if interval_ms > 0 {
+                        let task = tokio::spawn(async move {
+                            let mut interval =
+                                tokio::time::interval(Duration::from_millis(interval_ms));
+                            interval.tick().await; // 跳过首次立即触发
+                            loop {
+                                interval.tick().await;
+                                flush_pending_traffic_sample();
+                            }
+                        });
+                        *TRAFFIC_FLUSH_TASK.write().await = Some(task);
+                    }
+
                     StreamResult {
                         is_successful: true,
                         error_message: None,
@@ -784,6 +1726,9 @@ impl StopTrafficStream {
             }
         }
 
+        // 连接断开后再补发窗口内的最后一个样本
+        stop_traffic_flush_task().await;
+
         StreamResult {
             is_successful: true,
             error_message: None,
@@ -793,62 +1738,126 @@ impl StopTrafficStream {
 }
 
 impl StartLogStream {
-    async fn handle_start() {
-        log::info!("开始监听日志数据");
+    async fn handle_start(self) {
+        let min_level = log_level_rank(self.level.as_deref().unwrap_or("info"));
+        LOG_MIN_LEVEL.store(min_level, Ordering::Relaxed);
 
-        // 确保 WebSocket 客户端已初始化
-        ensure_ws_client_initialized().await;
+        log::info!(
+            "开始监听日志数据（最低级别：{}）",
+            LOG_LEVELS[min_level as usize]
+        );
 
-        // 建立 WebSocket 连接
-        let client = WS_CLIENT.read().await;
-        if let Some(ws_client) = client.as_ref() {
-            match ws_client
-                .connect("/logs?level=info", |json_value| {
-                    // 解析日志数据
-                    if let Some(obj) = json_value.as_object() {
-                        let log_type = obj
-                            .get("type")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("info")
-                            .to_string();
-                        let payload = obj
-                            .get("payload")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-
-                        // 发送到 Dart 层
-                        IpcLogData { log_type, payload }.send_signal_to_dart();
-                    }
-                })
-                .await
-            {
-                Ok(connection_id) => {
-                    log::info!("日志监控 WebSocket 连接已建立：{}", connection_id);
+        match connect_log_stream(min_level).await {
+            Ok(connection_id) => {
+                log::info!("日志监控 WebSocket 连接已建立：{}", connection_id);
 
-                    // 保存连接 ID
-                    let mut id_guard = LOG_CONNECTION_ID.write().await;
-                    *id_guard = Some(connection_id);
+                // 保存连接 ID
+                let mut id_guard = LOG_CONNECTION_ID.write().await;
+                *id_guard = Some(connection_id);
 
-                    StreamResult {
-                        is_successful: true,
-                        error_message: None,
-                    }
-                    .send_signal_to_dart();
+                StreamResult {
+                    is_successful: true,
+                    error_message: None,
+                }
+                .send_signal_to_dart();
+            }
+            Err(e) => {
+                log::error!("日志监控 WebSocket 连接失败：{}", e);
+                StreamResult {
+                    is_successful: false,
+                    error_message: Some(e),
+                }
+                .send_signal_to_dart();
+            }
+        }
+    }
+}
+
+impl SetLogStreamLevel {
+    // 提高级别只需调整本地过滤；降低到订阅级别以下时需按新级别重新订阅
+    async fn handle(self) {
+        let min_level = log_level_rank(&self.level);
+        LOG_MIN_LEVEL.store(min_level, Ordering::Relaxed);
+
+        log::info!("日志流最低级别调整为：{}", LOG_LEVELS[min_level as usize]);
+
+        let mut id_guard = LOG_CONNECTION_ID.write().await;
+        let needs_resubscribe =
+            id_guard.is_some() && min_level < LOG_SUBSCRIBED_LEVEL.load(Ordering::Relaxed);
+
+        if needs_resubscribe {
+            if let Some(old_id) = id_guard.take() {
+                let client = WS_CLIENT.read().await;
+                if let Some(ws_client) = client.as_ref() {
+                    ws_client.disconnect(old_id).await;
+                }
+            }
+
+            match connect_log_stream(min_level).await {
+                Ok(connection_id) => {
+                    log::info!("日志监控 WebSocket 已按新级别重新订阅：{}", connection_id);
+                    *id_guard = Some(connection_id);
                 }
                 Err(e) => {
-                    log::error!("日志监控 WebSocket 连接失败：{}", e);
+                    log::error!("日志监控 WebSocket 重新订阅失败：{}", e);
                     StreamResult {
                         is_successful: false,
                         error_message: Some(e),
                     }
                     .send_signal_to_dart();
+                    return;
                 }
             }
         }
+
+        StreamResult {
+            is_successful: true,
+            error_message: None,
+        }
+        .send_signal_to_dart();
     }
 }
 
+// 建立日志 WebSocket 连接：核心按订阅级别过滤，转发前再按当前最低级别过滤
+async fn connect_log_stream(level: u8) -> Result<u32, String> {
+    // 确保 WebSocket 客户端已初始化
+    ensure_ws_client_initialized().await;
+
+    let client = WS_CLIENT.read().await;
+    let ws_client = client.as_ref().ok_or("WebSocket 客户端未初始化")?;
+
+    let endpoint = format!("/logs?level={}", LOG_LEVELS[level as usize]);
+    let connection_id = ws_client
+        .connect(&endpoint, |json_value| {
+            // 解析日志数据
+            if let Some(obj) = json_value.as_object() {
+                let log_type = obj
+                    .get("type")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("info")
+                    .to_string();
+
+                // 丢弃低于最低级别的日志，避免无效的跨语言传输
+                if log_level_rank(&log_type) < LOG_MIN_LEVEL.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                let payload = obj
+                    .get("payload")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+
+                // 发送到 Dart 层
+                IpcLogData { log_type, payload }.send_signal_to_dart();
+            }
+        })
+        .await?;
+
+    LOG_SUBSCRIBED_LEVEL.store(level, Ordering::Relaxed);
+    Ok(connection_id)
+}
+
 impl StopLogStream {
     async fn handle_stop() {
         log::info!("停止监听日志数据");
@@ -875,24 +1884,52 @@ impl StopLogStream {
 }
 
 impl StartMemoryStream {
-    async fn handle_start() {
-        log::info!("开始监听内存数据");
+    async fn handle_start(self) {
+        let interval_ms = self
+            .coalesce_interval_ms
+            .unwrap_or(DEFAULT_MEMORY_COALESCE_MS);
+
+        log::info!("开始监听内存数据（合并窗口：{}ms）", interval_ms);
+
+        // 核心不提供内存统计时告知 Dart 层，不视为错误；版本获取失败时仍尝试连接
+        match get_core_version().await {
+            Ok(version) if !version.supports_memory => {
+                let reason = format!("核心 {} 不提供内存统计", version.version);
+                log::info!("{}，跳过内存监控", reason);
+                CoreMemoryUnavailable { reason }.send_signal_to_dart();
+                StreamResult {
+                    is_successful: true,
+                    error_message: None,
+                }
+                .send_signal_to_dart();
+                return;
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("获取核心版本失败，仍尝试连接内存监控：{}", e),
+        }
 
         // 确保 WebSocket 客户端已初始化
         ensure_ws_client_initialized().await;
 
+        // 重新开始前清理上一次的定时发送任务
+        stop_memory_flush_task().await;
+
         // 建立 WebSocket 连接
         let client = WS_CLIENT.read().await;
         if let Some(ws_client) = client.as_ref() {
             match ws_client
-                .connect("/memory", |json_value| {
+                .connect("/memory", move |json_value| {
                     // 解析内存数据
-                    if let Some(obj) = json_value.as_object() {
-                        let inuse = obj.get("inuse").and_then(|v| v.as_u64()).unwrap_or(0);
-                        let oslimit = obj.get("oslimit").and_then(|v| v.as_u64()).unwrap_or(0);
-
-                        // 发送到 Dart 层
-                        IpcMemoryData { inuse, oslimit }.send_signal_to_dart();
+                    let Some(memory) = CoreMemory::from_json(&json_value) else {
+                        return;
+                    };
+
+                    if interval_ms == 0 {
+                        // 不合并，直接发送到 Dart 层
+                        memory.emit_sample();
+                    } else if let Ok(mut pending) = PENDING_MEMORY_SAMPLE.lock() {
+                        // 窗口内后到的样本覆盖先到的样本
+                        *pending = Some(memory);
                     }
                 })
                 .await
@@ -904,6 +1941,20 @@ impl StartMemoryStream {
                     let mut id_guard = MEMORY_CONNECTION_ID.write().await;
                     *id_guard = Some(connection_id);
 
+                    // 按窗口周期发送最新样本
+                    if interval_ms > 0 {
+                        let task = tokio::spawn(async move {
+                            let mut interval =
+                                tokio::time::interval(Duration::from_millis(interval_ms));
+                            interval.tick().await; // 跳过首次立即触发
+                            loop {
+                                interval.tick().await;
+                                flush_pending_memory_sample();
+                            }
+                        });
+                        *MEMORY_FLUSH_TASK.write().await = Some(task);
+                    }
+
                     StreamResult {
                         is_successful: true,
                         error_message: None,
@@ -940,6 +1991,9 @@ impl StopMemoryStream {
             }
         }
 
+        // 连接断开后再补发窗口内的最后一个样本
+        stop_memory_flush_task().await;
+
         StreamResult {
             is_successful: true,
             error_message: None,
@@ -949,8 +2003,9 @@ impl StopMemoryStream {
 }
 
 impl StartConnectionStream {
-    async fn handle_start() {
-        log::info!("开始监听连接数据");
+    async fn handle_start(self) {
+        let is_typed = self.is_typed.unwrap_or(false);
+        log::info!("开始监听连接数据（类型化：{}）", is_typed);
 
         // 确保 WebSocket 客户端已初始化
         ensure_ws_client_initialized().await;
@@ -959,10 +2014,14 @@ impl StartConnectionStream {
         let client = WS_CLIENT.read().await;
         if let Some(ws_client) = client.as_ref() {
             match ws_client
-                .connect("/connections", |json_value| {
-                    // 将整个 JSON 作为字符串发送到 Dart
-                    let connections_json = json_value.to_string();
-                    IpcConnectionData { connections_json }.send_signal_to_dart();
+                .connect("/connections", move |json_value| {
+                    if is_typed {
+                        ConnectionSnapshot::from_json(&json_value).send_signal_to_dart();
+                    } else {
+                        // 将整个 JSON 作为字符串发送到 Dart
+                        let connections_json = json_value.to_string();
+                        IpcConnectionData { connections_json }.send_signal_to_dart();
+                    }
                 })
                 .await
             {
@@ -1026,13 +2085,15 @@ pub async fn internal_ipc_get(path: &str) -> Result<String, String> {
     // 使用连接发送请求
     match IpcClient::request_with_connection("GET", path, None, ipc_conn).await {
         Ok((response, ipc_conn)) => {
-            // 归还连接
-            release_connection(ipc_conn).await;
+            // 归还连接（服务端声明关闭时丢弃）
+            if response.is_keep_alive {
+                release_connection(ipc_conn).await;
+            }
 
             if response.status_code >= 200 && response.status_code < 300 {
                 Ok(response.body)
             } else {
-                Err(format!("HTTP {}", response.status_code))
+                Err(describe_http_error(response.status_code, &response.body))
             }
         }
         Err(e) => Err(e),
@@ -1,6 +1,7 @@
 // Clash IPC 客户端：通过 Named Pipe（Windows）或 Unix Socket（Unix）通信。
 // 使用 Tokio 实现，并手动解析 HTTP 协议。
 
+use crate::atoms::ipc_client::{authorization_header, trace, validate_request_target};
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 
 #[cfg(unix)]
@@ -13,6 +14,8 @@ use tokio::net::windows::named_pipe::NamedPipeClient;
 pub struct HttpResponse {
     pub status_code: u16,
     pub body: String,
+    // 服务端未声明关闭连接，可归还连接池
+    pub is_keep_alive: bool,
 }
 
 // IPC 客户端
@@ -22,29 +25,7 @@ impl IpcClient {
     // 获取默认 IPC 路径
     // Debug/Profile 模式使用 _dev 后缀，避免与 Release 模式冲突
     pub fn default_ipc_path() -> String {
-        #[cfg(windows)]
-        {
-            #[cfg(debug_assertions)]
-            {
-                r"\\.\pipe\stelliberty_dev".to_string()
-            }
-            #[cfg(not(debug_assertions))]
-            {
-                r"\\.\pipe\stelliberty".to_string()
-            }
-        }
-
-        #[cfg(unix)]
-        {
-            #[cfg(debug_assertions)]
-            {
-                "/tmp/stelliberty_dev.sock".to_string()
-            }
-            #[cfg(not(debug_assertions))]
-            {
-                "/tmp/stelliberty.sock".to_string()
-            }
-        }
+        crate::atoms::IpcClient::default_ipc_path()
     }
 
     // 使用已有连接发送请求（连接池场景）
@@ -56,8 +37,9 @@ impl IpcClient {
         mut stream: NamedPipeClient,
     ) -> Result<(HttpResponse, NamedPipeClient), String> {
         // 1. 构建 HTTP 请求
+        validate_request_target(method, path)?;
         let request = Self::build_http_request_static(method, path, body);
-        log::trace!("发送 IPC 请求：\n{}", request);
+        trace::trace_request(method, path, &request);
 
         // 2. 发送请求
         stream
@@ -67,6 +49,7 @@ impl IpcClient {
 
         // 3. 读取响应
         let response = Self::read_http_response_static(&mut stream).await?;
+        trace::trace_response(method, path, response.status_code, &response.body);
 
         Ok((response, stream))
     }
@@ -78,8 +61,9 @@ impl IpcClient {
         body: Option<&str>,
         mut stream: UnixStream,
     ) -> Result<(HttpResponse, UnixStream), String> {
+        validate_request_target(method, path)?;
         let request = Self::build_http_request_static(method, path, body);
-        log::trace!("发送 IPC 请求：\n{}", request);
+        trace::trace_request(method, path, &request);
 
         stream
             .write_all(request.as_bytes())
@@ -87,6 +71,7 @@ impl IpcClient {
             .map_err(|e| format!("发送请求失败：{}", e))?;
 
         let response = Self::read_http_response_static(&mut stream).await?;
+        trace::trace_response(method, path, response.status_code, &response.body);
 
         Ok((response, stream))
     }
@@ -96,6 +81,10 @@ impl IpcClient {
         let mut request = format!("{} {} HTTP/1.1\r\n", method, path);
 
         request.push_str("Host: localhost\r\n");
+        if let Some(authorization) = authorization_header() {
+            request.push_str(&authorization);
+        }
+        request.push_str("Connection: keep-alive\r\n");
 
         if let Some(body_str) = body {
             request.push_str("Content-Type: application/json\r\n");
@@ -140,9 +129,10 @@ impl IpcClient {
         let status_line = header_lines.first().ok_or_else(|| "响应为空".to_string())?;
         let status_code = Self::parse_status_code_static(status_line)?;
 
-        // 3. 解析 headers
+        // 3. 解析 headers：HTTP/1.1 默认保持连接，HTTP/1.0 默认关闭
         let mut content_length: Option<usize> = None;
         let mut is_chunked = false;
+        let mut is_keep_alive = !status_line.starts_with("HTTP/1.0");
 
         for line in &header_lines[1..] {
             if let Some((key, value)) = line.split_once(':') {
@@ -155,6 +145,14 @@ impl IpcClient {
                 if key.eq_ignore_ascii_case("transfer-encoding") && value.contains("chunked") {
                     is_chunked = true;
                 }
+                if key.eq_ignore_ascii_case("connection") {
+                    let value = value.to_ascii_lowercase();
+                    if value.contains("close") {
+                        is_keep_alive = false;
+                    } else if value.contains("keep-alive") {
+                        is_keep_alive = true;
+                    }
+                }
             }
         }
 
@@ -172,7 +170,11 @@ impl IpcClient {
             String::new()
         };
 
-        Ok(HttpResponse { status_code, body })
+        Ok(HttpResponse {
+            status_code,
+            body,
+            is_keep_alive,
+        })
     }
 
     // 解析 HTTP 状态码（静态方法）
@@ -0,0 +1,70 @@
+// 节点稳定标识：从核心配置文件读取节点，建立名称到稳定标识的映射。
+// 代理树与延迟历史据此关联，订阅更新后节点改名也不丢失历史。
+// 代理集（proxy-providers）中的节点不在配置文件内，没有稳定标识，沿用名称。
+
+use super::proxies_model::ProxyGroupInfo;
+use crate::atoms::ProxyParser;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+static NODE_IDS: Lazy<RwLock<HashMap<String, String>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+fn set_node_ids(node_ids: HashMap<String, String>) {
+    match NODE_IDS.write() {
+        Ok(mut guard) => *guard = node_ids,
+        Err(e) => *e.into_inner() = node_ids,
+    }
+}
+
+// 节点的稳定标识，未知节点返回 None
+pub fn node_id(node_name: &str) -> Option<String> {
+    match NODE_IDS.read() {
+        Ok(guard) => guard.get(node_name).cloned(),
+        Err(e) => e.into_inner().get(node_name).cloned(),
+    }
+}
+
+// 从核心配置文件加载节点标识（核心启动与重载配置时调用），文件不存在时清空
+pub(super) fn load_node_ids(config_path: &Path) -> Result<(), String> {
+    let content = match std::fs::read_to_string(config_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            set_node_ids(HashMap::new());
+            return Ok(());
+        }
+        Err(e) => return Err(format!("读取核心配置文件失败：{}", e)),
+    };
+
+    let config: serde_json::Value =
+        serde_yaml_ng::from_str(&content).map_err(|e| format!("解析核心配置文件失败：{}", e))?;
+    let node_ids: HashMap<String, String> = config
+        .get("proxies")
+        .and_then(|v| v.as_array())
+        .map(|proxies| {
+            proxies
+                .iter()
+                .filter_map(|proxy| {
+                    let name = proxy.get("name")?.as_str()?.to_string();
+                    Some((name, ProxyParser::stable_node_id(proxy)?))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    log::debug!("已加载 {} 个节点的稳定标识", node_ids.len());
+    set_node_ids(node_ids);
+    Ok(())
+}
+
+// 为代理树中的成员填入稳定标识
+pub(super) fn assign_node_ids(groups: &mut [ProxyGroupInfo]) {
+    let node_ids = match NODE_IDS.read() {
+        Ok(guard) => guard,
+        Err(e) => e.into_inner(),
+    };
+    for member in groups.iter_mut().flat_map(|group| group.members.iter_mut()) {
+        member.id = node_ids.get(&member.name).cloned();
+    }
+}
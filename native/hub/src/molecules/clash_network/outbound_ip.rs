@@ -0,0 +1,199 @@
+// 出口 IP 查询：经核心的 HTTP 代理端口请求 IP 回显服务，确认当前节点的实际出口。
+
+use super::running_config::get_running_config;
+use crate::atoms::shutdown;
+use crate::atoms::tls_roots;
+use reqwest::{Client, Proxy};
+use rinf::{DartSignal, RustSignal, SignalPiece};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::time::Duration;
+
+// 默认 IP 回显地址（响应体为纯文本 IP）
+const DEFAULT_ECHO_URL: &str = "https://api.ip.sb/ip";
+// 地理位置查询地址（JSON，包含 ip、country、city 等字段）
+const GEO_LOOKUP_URL: &str = "https://api.ip.sb/geoip";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const PORT_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Dart → Rust：查询出口 IP
+#[derive(Deserialize, DartSignal)]
+pub struct GetOutboundIp {
+    pub request_id: i64,
+    // IP 回显地址，为 None 时使用默认地址
+    pub echo_url: Option<String>,
+    pub should_lookup_geo: bool,
+}
+
+// 出口 IP 的地理位置
+#[derive(Serialize, SignalPiece, Clone, Debug, Default)]
+pub struct OutboundIpGeo {
+    pub country: Option<String>,
+    pub country_code: Option<String>,
+    pub region: Option<String>,
+    pub city: Option<String>,
+    pub isp: Option<String>,
+}
+
+// Rust → Dart：出口 IP 查询结果
+#[derive(Serialize, RustSignal)]
+pub struct OutboundIpResult {
+    pub request_id: i64,
+    pub ip: Option<String>,
+    // 地理位置查询失败不影响 IP 结果，此时为 None
+    pub geo: Option<OutboundIpGeo>,
+    // 本次使用的核心代理端口
+    pub proxy_port: Option<u16>,
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+impl GetOutboundIp {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let echo_url = self
+                .echo_url
+                .filter(|url| !url.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_ECHO_URL.to_string());
+
+            let result = async {
+                let proxy_port = get_core_proxy_port().await?;
+                ensure_port_listening(proxy_port).await?;
+                let client = create_proxied_client(proxy_port)?;
+                let ip = fetch_outbound_ip(&client, &echo_url).await?;
+                Ok::<_, String>((proxy_port, client, ip))
+            }
+            .await;
+
+            let response = match result {
+                Ok((proxy_port, client, ip)) => {
+                    log::info!("出口 IP：{}（代理端口 {}）", ip, proxy_port);
+                    let geo = if self.should_lookup_geo {
+                        match fetch_geo(&client).await {
+                            Ok(geo) => Some(geo),
+                            Err(e) => {
+                                log::warn!("查询出口 IP 地理位置失败：{}", e);
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+                    OutboundIpResult {
+                        request_id: self.request_id,
+                        ip: Some(ip.to_string()),
+                        geo,
+                        proxy_port: Some(proxy_port),
+                        is_successful: true,
+                        error_message: None,
+                    }
+                }
+                Err(e) => {
+                    log::error!("查询出口 IP 失败：{}", e);
+                    OutboundIpResult {
+                        request_id: self.request_id,
+                        ip: None,
+                        geo: None,
+                        proxy_port: None,
+                        is_successful: false,
+                        error_message: Some(e),
+                    }
+                }
+            };
+            response.send_signal_to_dart();
+        });
+    }
+}
+
+// 从运行中配置读取核心的 HTTP 代理端口，优先使用混合端口
+async fn get_core_proxy_port() -> Result<u16, String> {
+    get_running_config()
+        .await?
+        .http_proxy_port()
+        .ok_or_else(|| "核心未开启 HTTP 代理端口（mixed-port / port）".to_string())
+}
+
+async fn ensure_port_listening(port: u16) -> Result<(), String> {
+    match tokio::time::timeout(
+        PORT_PROBE_TIMEOUT,
+        tokio::net::TcpStream::connect(("127.0.0.1", port)),
+    )
+    .await
+    {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(format!("代理端口 {} 未在监听：{}", port, e)),
+        Err(_) => Err(format!("连接代理端口 {} 超时", port)),
+    }
+}
+
+fn create_proxied_client(port: u16) -> Result<Client, String> {
+    let proxy = Proxy::all(format!("http://127.0.0.1:{}", port))
+        .map_err(|e| format!("创建代理配置失败：{}", e))?;
+    let builder = Client::builder().proxy(proxy).timeout(REQUEST_TIMEOUT);
+    tls_roots::apply(builder)
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败：{}", e))
+}
+
+async fn fetch_outbound_ip(client: &Client, echo_url: &str) -> Result<IpAddr, String> {
+    let response = client
+        .get(echo_url)
+        .send()
+        .await
+        .map_err(|e| format!("请求 IP 回显服务失败：{}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "IP 回显服务返回 HTTP {}",
+            response.status().as_u16()
+        ));
+    }
+
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("读取 IP 回显响应失败：{}", e))?;
+    body.trim()
+        .parse::<IpAddr>()
+        .map_err(|_| format!("IP 回显服务返回的不是有效 IP：{}", preview(&body)))
+}
+
+async fn fetch_geo(client: &Client) -> Result<OutboundIpGeo, String> {
+    let value: serde_json::Value = client
+        .get(GEO_LOOKUP_URL)
+        .send()
+        .await
+        .map_err(|e| format!("请求地理位置服务失败：{}", e))?
+        .error_for_status()
+        .map_err(|e| format!("地理位置服务返回错误：{}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("解析地理位置响应失败：{}", e))?;
+
+    let read_str = |key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    };
+    Ok(OutboundIpGeo {
+        country: read_str("country"),
+        country_code: read_str("country_code"),
+        region: read_str("region"),
+        city: read_str("city"),
+        isp: read_str("isp").or_else(|| read_str("organization")),
+    })
+}
+
+fn preview(text: &str) -> String {
+    text.trim().chars().take(50).collect()
+}
+
+pub fn init() {
+    shutdown::spawn_listener(async {
+        let receiver = GetOutboundIp::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+}
@@ -0,0 +1,269 @@
+// 代理集与规则集（proxy-providers / rule-providers）：
+// 列出 /providers 下的提供者，触发更新与代理集健康检查。
+
+use super::handlers::invalidate_proxies_snapshot;
+use crate::atoms::IpcClient;
+use crate::atoms::ipc_client::encode_name_segment;
+use crate::atoms::shutdown;
+use rinf::{DartSignal, RustSignal, SignalPiece};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+// 健康检查需等待核心测试完集内全部节点
+const HEALTH_CHECK_BUDGET: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize, Serialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProviderKind {
+    Proxy = 0,
+    Rule = 1,
+}
+
+impl ProviderKind {
+    fn api_path(self) -> &'static str {
+        match self {
+            ProviderKind::Proxy => "/providers/proxies",
+            ProviderKind::Rule => "/providers/rules",
+        }
+    }
+}
+
+// 提供者信息
+#[derive(Serialize, SignalPiece, Clone, Debug)]
+pub struct ProviderInfo {
+    pub name: String,
+    pub kind: ProviderKind,
+    pub vehicle_type: String,
+    // 规则集的匹配行为（domain / ipcidr / classical），代理集为 None
+    pub behavior: Option<String>,
+    // 最近一次更新时间（RFC 3339），从未更新时为 None
+    pub updated_at: Option<String>,
+    // 代理集为节点数，规则集为规则数
+    pub item_count: u32,
+}
+
+// Dart → Rust：获取代理集与规则集列表
+#[derive(Deserialize, DartSignal)]
+pub struct GetProviders;
+
+// Rust → Dart：提供者列表
+#[derive(Serialize, RustSignal)]
+pub struct ProvidersSnapshot {
+    pub proxy_providers: Vec<ProviderInfo>,
+    pub rule_providers: Vec<ProviderInfo>,
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+// Dart → Rust：更新提供者（重新拉取远程内容）
+#[derive(Deserialize, DartSignal)]
+pub struct UpdateProvider {
+    pub kind: ProviderKind,
+    pub name: String,
+}
+
+// Rust → Dart：更新提供者结果
+#[derive(Serialize, RustSignal)]
+pub struct UpdateProviderResult {
+    pub kind: ProviderKind,
+    pub name: String,
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+// Dart → Rust：对代理集执行健康检查
+#[derive(Deserialize, DartSignal)]
+pub struct HealthCheckProvider {
+    pub name: String,
+}
+
+// Rust → Dart：代理集健康检查结果，节点延迟通过代理树获取
+#[derive(Serialize, RustSignal)]
+pub struct HealthCheckProviderResult {
+    pub name: String,
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+impl GetProviders {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let result = async {
+                let proxy_providers = get_providers(ProviderKind::Proxy).await?;
+                let rule_providers = get_providers(ProviderKind::Rule).await?;
+                Ok::<_, String>((proxy_providers, rule_providers))
+            }
+            .await;
+
+            let response = match result {
+                Ok((proxy_providers, rule_providers)) => ProvidersSnapshot {
+                    proxy_providers,
+                    rule_providers,
+                    is_successful: true,
+                    error_message: None,
+                },
+                Err(e) => {
+                    log::error!("获取提供者列表失败：{}", e);
+                    ProvidersSnapshot {
+                        proxy_providers: Vec::new(),
+                        rule_providers: Vec::new(),
+                        is_successful: false,
+                        error_message: Some(e),
+                    }
+                }
+            };
+            response.send_signal_to_dart();
+        });
+    }
+}
+
+impl UpdateProvider {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let result = match encode_name_segment(&self.name) {
+                Ok(name) => {
+                    let path = format!("{}/{}", self.kind.api_path(), name);
+                    IpcClient::put(&path, "").await.map_err(|e| e.to_string())
+                }
+                Err(e) => Err(e),
+            };
+            let result = match result {
+                Ok(response) if (200..300).contains(&response.status_code) => Ok(()),
+                Ok(response) => Err(response.error_description()),
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(()) => {
+                    log::info!("提供者已更新：{:?} {}", self.kind, self.name);
+                    if self.kind == ProviderKind::Proxy {
+                        invalidate_proxies_snapshot().await;
+                    }
+                }
+                Err(ref e) => log::error!("更新提供者 {} 失败：{}", self.name, e),
+            }
+
+            UpdateProviderResult {
+                kind: self.kind,
+                name: self.name,
+                is_successful: result.is_ok(),
+                error_message: result.err(),
+            }
+            .send_signal_to_dart();
+        });
+    }
+}
+
+impl HealthCheckProvider {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let result = match encode_name_segment(&self.name) {
+                Ok(name) => {
+                    let path = format!("{}/{}/healthcheck", ProviderKind::Proxy.api_path(), name);
+                    IpcClient::get_with_pool_within(&path, HEALTH_CHECK_BUDGET)
+                        .await
+                        .map(|_| ())
+                        .map_err(|e| e.to_string())
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(()) => {
+                    log::info!("代理集健康检查完成：{}", self.name);
+                    invalidate_proxies_snapshot().await;
+                }
+                Err(ref e) => log::error!("代理集 {} 健康检查失败：{}", self.name, e),
+            }
+
+            HealthCheckProviderResult {
+                name: self.name,
+                is_successful: result.is_ok(),
+                error_message: result.err(),
+            }
+            .send_signal_to_dart();
+        });
+    }
+}
+
+async fn get_providers(kind: ProviderKind) -> Result<Vec<ProviderInfo>, String> {
+    let body = IpcClient::get_with_pool(kind.api_path()).await?;
+    let value: Value =
+        serde_json::from_str(&body).map_err(|e| format!("解析提供者列表失败：{}", e))?;
+    parse_providers(&value, kind)
+}
+
+// 解析 /providers/{proxies,rules} 返回的 JSON，按名称排序。
+// 核心为每个策略组自动生成的 Compatible 代理集不属于用户配置，予以跳过
+fn parse_providers(value: &Value, kind: ProviderKind) -> Result<Vec<ProviderInfo>, String> {
+    let providers = value
+        .get("providers")
+        .and_then(|v| v.as_object())
+        .ok_or("响应中缺少 providers 字段")?;
+
+    let mut infos: Vec<ProviderInfo> = providers
+        .iter()
+        .filter_map(|(name, provider)| {
+            let vehicle_type = read_str(provider, "vehicleType")?;
+            if vehicle_type == "Compatible" {
+                return None;
+            }
+
+            let item_count = match kind {
+                ProviderKind::Proxy => provider
+                    .get("proxies")
+                    .and_then(|v| v.as_array())
+                    .map_or(0, Vec::len),
+                ProviderKind::Rule => provider
+                    .get("ruleCount")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0) as usize,
+            };
+
+            Some(ProviderInfo {
+                name: name.clone(),
+                kind,
+                vehicle_type,
+                behavior: read_str(provider, "behavior"),
+                // 从未更新的提供者返回 Go 零值时间
+                updated_at: read_str(provider, "updatedAt")
+                    .filter(|time| !time.starts_with("0001-01-01")),
+                item_count: item_count as u32,
+            })
+        })
+        .collect();
+
+    infos.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    Ok(infos)
+}
+
+fn read_str(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+pub fn init() {
+    shutdown::spawn_listener(async {
+        let receiver = GetProviders::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = UpdateProvider::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = HealthCheckProvider::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+}
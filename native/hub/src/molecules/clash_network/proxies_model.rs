@@ -0,0 +1,212 @@
+// 代理树数据模型：将核心 /proxies 返回的 JSON 解析为策略组与成员的强类型结构。
+
+use rinf::SignalPiece;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// 单次延迟记录
+#[derive(Serialize, SignalPiece, Clone, Debug)]
+pub struct DelayHistoryEntry {
+    pub time: String,
+    // 0 表示超时或失败
+    pub delay: u32,
+}
+
+// 策略组成员（节点或嵌套策略组）
+#[derive(Serialize, SignalPiece, Clone, Debug)]
+pub struct ProxyMemberInfo {
+    // 显示名称
+    pub name: String,
+    // 由连接参数计算的稳定标识，改名后不变；不在配置文件中的节点为 None
+    pub id: Option<String>,
+    pub proxy_type: String,
+    pub is_alive: bool,
+    pub delay_history: Vec<DelayHistoryEntry>,
+}
+
+// 策略组
+#[derive(Serialize, SignalPiece, Clone, Debug)]
+pub struct ProxyGroupInfo {
+    pub name: String,
+    pub group_type: String,
+    // 当前选中的成员
+    pub now: Option<String>,
+    pub members: Vec<ProxyMemberInfo>,
+    // 配置中 hidden: true 的策略组，界面选择器中应隐藏
+    pub is_hidden: bool,
+    // 配置中的图标地址，未设置时为 None
+    pub icon: Option<String>,
+}
+
+// 解析 /proxies 返回的 JSON 为策略组列表。
+// 顺序与 GLOBAL 组的成员顺序一致（即配置文件中的顺序），GLOBAL 放在最后。
+pub fn parse_proxy_groups(value: &Value) -> Result<Vec<ProxyGroupInfo>, String> {
+    let proxies = value
+        .get("proxies")
+        .and_then(|v| v.as_object())
+        .ok_or("响应中缺少 proxies 字段")?;
+
+    let mut group_names: Vec<&str> = proxies
+        .get("GLOBAL")
+        .and_then(|global| global.get("all"))
+        .and_then(|all| all.as_array())
+        .map(|all| {
+            all.iter()
+                .filter_map(|name| name.as_str())
+                .filter(|name| is_group(proxies.get(*name)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // 未出现在 GLOBAL 中的策略组按名称追加
+    let mut remaining: Vec<&str> = proxies
+        .iter()
+        .filter(|(name, proxy)| {
+            name.as_str() != "GLOBAL"
+                && is_group(Some(proxy))
+                && !group_names.contains(&name.as_str())
+        })
+        .map(|(name, _)| name.as_str())
+        .collect();
+    remaining.sort_unstable();
+    group_names.extend(remaining);
+
+    if proxies.contains_key("GLOBAL") {
+        group_names.push("GLOBAL");
+    }
+
+    Ok(group_names
+        .into_iter()
+        .filter_map(|name| {
+            proxies
+                .get(name)
+                .map(|group| parse_group(name, group, proxies))
+        })
+        .collect())
+}
+
+fn is_group(proxy: Option<&Value>) -> bool {
+    proxy
+        .and_then(|p| p.get("all"))
+        .is_some_and(|all| all.is_array())
+}
+
+fn parse_group(name: &str, group: &Value, proxies: &Map<String, Value>) -> ProxyGroupInfo {
+    let members = group
+        .get("all")
+        .and_then(|all| all.as_array())
+        .map(|all| {
+            all.iter()
+                .filter_map(|member| member.as_str())
+                .map(|member| parse_member(member, proxies.get(member)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    ProxyGroupInfo {
+        name: name.to_string(),
+        group_type: read_str(group, "type"),
+        now: group
+            .get("now")
+            .and_then(|v| v.as_str())
+            .filter(|now| !now.is_empty())
+            .map(str::to_string),
+        members,
+        // 旧版本核心不返回 hidden 与 icon 字段
+        is_hidden: group
+            .get("hidden")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        icon: group
+            .get("icon")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|icon| !icon.is_empty())
+            .map(str::to_string),
+    }
+}
+
+fn parse_member(name: &str, proxy: Option<&Value>) -> ProxyMemberInfo {
+    let Some(proxy) = proxy else {
+        return ProxyMemberInfo {
+            name: name.to_string(),
+            id: None,
+            proxy_type: String::new(),
+            is_alive: false,
+            delay_history: Vec::new(),
+        };
+    };
+
+    ProxyMemberInfo {
+        name: name.to_string(),
+        id: None,
+        proxy_type: read_str(proxy, "type"),
+        // 旧版本核心没有 alive 字段，默认视为可用
+        is_alive: proxy.get("alive").and_then(|v| v.as_bool()).unwrap_or(true),
+        delay_history: parse_delay_history(proxy),
+    }
+}
+
+// 解析节点的延迟历史
+pub fn parse_delay_history(proxy: &Value) -> Vec<DelayHistoryEntry> {
+    proxy
+        .get("history")
+        .and_then(|v| v.as_array())
+        .map(|history| {
+            history
+                .iter()
+                .map(|entry| DelayHistoryEntry {
+                    time: read_str(entry, "time"),
+                    delay: entry.get("delay").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn read_str(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_proxy_groups;
+    use serde_json::json;
+
+    #[test]
+    fn parses_hidden_and_icon_fields() -> Result<(), String> {
+        let value = json!({
+            "proxies": {
+                "GLOBAL": { "type": "Selector", "all": ["Proxy", "Auto", "Legacy"] },
+                "Proxy": {
+                    "type": "Selector", "now": "HK-1", "all": ["HK-1", "Auto"],
+                    "hidden": true, "icon": " https://example.com/proxy.png ",
+                },
+                "Auto": { "type": "URLTest", "all": ["HK-1"], "hidden": false, "icon": "  " },
+                "Legacy": { "type": "Selector", "all": ["HK-1"] },
+                "HK-1": { "type": "Shadowsocks", "alive": true },
+            }
+        });
+
+        let groups = parse_proxy_groups(&value)?;
+        let fields: Vec<(&str, bool, Option<&str>)> = groups
+            .iter()
+            .map(|group| (group.name.as_str(), group.is_hidden, group.icon.as_deref()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("Proxy", true, Some("https://example.com/proxy.png")),
+                ("Auto", false, None),
+                // 旧版本核心不返回 hidden 与 icon
+                ("Legacy", false, None),
+                ("GLOBAL", false, None),
+            ]
+        );
+        Ok(())
+    }
+}
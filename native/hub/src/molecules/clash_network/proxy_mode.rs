@@ -0,0 +1,145 @@
+// 代理模式切换：核心出站模式与系统代理作为一个整体切换。
+// 先修改核心模式，再切换系统代理；系统代理失败时将核心模式回滚，避免两者不一致。
+
+use super::handlers::patch_runtime_config;
+use crate::atoms::IpcClient;
+use crate::atoms::shutdown;
+use crate::atoms::system_proxy::{
+    ProxyResult, SystemProxySettings, apply_system_proxy, clear_system_proxy, get_proxy_info,
+    last_applied_proxy,
+};
+use crate::molecules::{CoreMode, ProxyMode};
+use once_cell::sync::Lazy;
+use rinf::{DartSignal, RustSignal};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+// Dart → Rust：切换代理模式。
+// Direct：核心直连并关闭系统代理；System：开启系统代理；
+// Core：关闭系统代理，流量经 TUN 或显式代理端口进入核心（不改动 TUN）
+#[derive(Deserialize, DartSignal)]
+pub struct SetProxyMode {
+    pub mode: ProxyMode,
+    // System / Core 模式下的核心出站模式，默认 Rule
+    pub core_mode: Option<CoreMode>,
+    // System 模式的系统代理参数，为 None 时沿用最近一次应用的参数
+    pub system_proxy: Option<SystemProxySettings>,
+}
+
+// Rust → Dart：切换结果，携带切换后实际生效的状态
+#[derive(Serialize, RustSignal)]
+pub struct SetProxyModeResult {
+    // 最近一次切换成功的模式，从未成功切换时为 None
+    pub mode: Option<ProxyMode>,
+    pub core_mode: Option<CoreMode>,
+    pub is_system_proxy_enabled: bool,
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+// 当前生效的代理模式；锁同时保证切换过程串行执行
+static CURRENT_PROXY_MODE: Lazy<Mutex<Option<ProxyMode>>> = Lazy::new(|| Mutex::new(None));
+
+impl SetProxyMode {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let mut current_mode = CURRENT_PROXY_MODE.lock().await;
+            log::info!("切换代理模式：{:?} → {:?}", *current_mode, self.mode);
+
+            let result = switch_proxy_mode(self.mode, self.core_mode, self.system_proxy).await;
+            match result {
+                Ok(()) => {
+                    *current_mode = Some(self.mode);
+                    log::info!("代理模式已切换为 {:?}", self.mode);
+                }
+                Err(ref e) => log::error!("切换代理模式失败：{}", e),
+            }
+
+            SetProxyModeResult {
+                mode: *current_mode,
+                core_mode: read_core_mode().await.ok(),
+                is_system_proxy_enabled: get_proxy_info().await.is_enabled,
+                is_successful: result.is_ok(),
+                error_message: result.err(),
+            }
+            .send_signal_to_dart();
+        });
+    }
+}
+
+async fn switch_proxy_mode(
+    mode: ProxyMode,
+    core_mode: Option<CoreMode>,
+    system_proxy: Option<SystemProxySettings>,
+) -> Result<(), String> {
+    let target_core_mode = match mode {
+        ProxyMode::Direct => CoreMode::Direct,
+        ProxyMode::System | ProxyMode::Core => core_mode.unwrap_or(CoreMode::Rule),
+    };
+    let system_proxy = match mode {
+        ProxyMode::System => Some(
+            system_proxy
+                .or_else(last_applied_proxy)
+                .ok_or("缺少系统代理参数")?,
+        ),
+        ProxyMode::Direct | ProxyMode::Core => None,
+    };
+
+    let previous_core_mode = read_core_mode().await?;
+    set_core_mode(target_core_mode).await?;
+
+    let system_proxy_result = match system_proxy {
+        Some(settings) => apply_system_proxy(settings).await,
+        None => clear_system_proxy().await,
+    };
+    let ProxyResult::Error(system_proxy_error) = system_proxy_result else {
+        return Ok(());
+    };
+
+    // 系统代理切换失败：回滚核心模式
+    if previous_core_mode != target_core_mode
+        && let Err(e) = set_core_mode(previous_core_mode).await
+    {
+        return Err(format!(
+            "系统代理切换失败：{}；回滚核心模式失败：{}",
+            system_proxy_error, e
+        ));
+    }
+    Err(format!(
+        "系统代理切换失败：{}（核心模式已回滚）",
+        system_proxy_error
+    ))
+}
+
+pub(super) async fn read_core_mode() -> Result<CoreMode, String> {
+    let body = IpcClient::get_with_pool("/configs").await?;
+    let value: serde_json::Value =
+        serde_json::from_str(&body).map_err(|e| format!("解析核心配置失败：{}", e))?;
+    value
+        .get("mode")
+        .and_then(|mode| mode.as_str())
+        .and_then(CoreMode::parse)
+        .ok_or_else(|| "核心配置缺少有效的 mode 字段".to_string())
+}
+
+async fn set_core_mode(mode: CoreMode) -> Result<(), String> {
+    let payload = serde_json::json!({ "mode": mode.as_str() });
+    let response = patch_runtime_config(&payload).await?;
+    if (200..300).contains(&response.status_code) {
+        Ok(())
+    } else {
+        Err(format!(
+            "修改核心模式失败：{}",
+            response.error_description()
+        ))
+    }
+}
+
+pub fn init() {
+    shutdown::spawn_listener(async {
+        let receiver = SetProxyMode::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+}
@@ -0,0 +1,234 @@
+// 核心运行中配置：GET /configs 返回提供者与覆写生效后的实际配置。
+// 不同版本核心返回的字段不完全相同，缺失字段解析为 None。
+
+use crate::atoms::IpcClient;
+use crate::atoms::shutdown;
+use rinf::{DartSignal, RustSignal, SignalPiece};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Dart → Rust：获取核心运行中配置
+#[derive(Deserialize, DartSignal)]
+pub struct GetRunningConfig {
+    // 为 true 时解析常用字段，否则只返回原始 JSON
+    pub should_parse: bool,
+}
+
+// TUN 设置
+#[derive(Serialize, SignalPiece, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunningTunSettings {
+    pub is_enabled: bool,
+    pub stack: Option<String>,
+    pub device: Option<String>,
+    pub is_auto_route: Option<bool>,
+}
+
+// 入站监听协议
+#[derive(Serialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ListenerProtocol {
+    Http = 0,
+    Socks = 1,
+    Mixed = 2,
+    Redir = 3,
+    Tproxy = 4,
+}
+
+impl ListenerProtocol {
+    const ALL: [ListenerProtocol; 5] = [
+        ListenerProtocol::Mixed,
+        ListenerProtocol::Http,
+        ListenerProtocol::Socks,
+        ListenerProtocol::Redir,
+        ListenerProtocol::Tproxy,
+    ];
+
+    fn config_key(self) -> &'static str {
+        match self {
+            ListenerProtocol::Http => "port",
+            ListenerProtocol::Socks => "socks-port",
+            ListenerProtocol::Mixed => "mixed-port",
+            ListenerProtocol::Redir => "redir-port",
+            ListenerProtocol::Tproxy => "tproxy-port",
+        }
+    }
+}
+
+// 核心正在监听的入站端口
+#[derive(Serialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ListenPort {
+    pub protocol: ListenerProtocol,
+    pub port: u16,
+}
+
+// 运行中配置的常用字段，端口为 0（未开启）时为 None
+#[derive(Serialize, SignalPiece, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunningConfigFields {
+    pub mode: Option<String>,
+    pub port: Option<u16>,
+    pub socks_port: Option<u16>,
+    pub mixed_port: Option<u16>,
+    // 已开启的全部入站端口（混合、HTTP、SOCKS、透明代理），未开启任何入站时为空
+    pub listen_ports: Vec<ListenPort>,
+    pub allow_lan: Option<bool>,
+    pub log_level: Option<String>,
+    pub tun: Option<RunningTunSettings>,
+}
+
+impl RunningConfigFields {
+    // 可用于 HTTP 代理的端口，优先使用混合端口
+    pub fn http_proxy_port(&self) -> Option<u16> {
+        self.mixed_port.or(self.port)
+    }
+}
+
+// Rust → Dart：核心运行中配置
+#[derive(Serialize, RustSignal)]
+pub struct RunningConfigResult {
+    pub raw: Option<String>,
+    pub fields: Option<RunningConfigFields>,
+    pub is_successful: bool,
+    pub error_message: Option<String>,
+}
+
+impl GetRunningConfig {
+    pub fn handle(self) {
+        tokio::spawn(async move {
+            let result = async {
+                let raw = IpcClient::get_with_pool("/configs").await?;
+                let fields = if self.should_parse {
+                    Some(parse_running_config(&raw)?)
+                } else {
+                    None
+                };
+                Ok::<_, String>((raw, fields))
+            }
+            .await;
+
+            let response = match result {
+                Ok((raw, fields)) => RunningConfigResult {
+                    raw: Some(raw),
+                    fields,
+                    is_successful: true,
+                    error_message: None,
+                },
+                Err(e) => {
+                    log::error!("获取核心运行中配置失败：{}", e);
+                    RunningConfigResult {
+                        raw: None,
+                        fields: None,
+                        is_successful: false,
+                        error_message: Some(e),
+                    }
+                }
+            };
+            response.send_signal_to_dart();
+        });
+    }
+}
+
+// 获取并解析核心运行中配置
+pub async fn get_running_config() -> Result<RunningConfigFields, String> {
+    let body = IpcClient::get_with_pool("/configs").await?;
+    parse_running_config(&body)
+}
+
+// 获取核心已开启的入站端口（端口为 0 的入站视为未开启，不包含在内）
+pub async fn get_listen_ports() -> Result<Vec<ListenPort>, String> {
+    Ok(get_running_config().await?.listen_ports)
+}
+
+fn parse_running_config(body: &str) -> Result<RunningConfigFields, String> {
+    let value: Value =
+        serde_json::from_str(body).map_err(|e| format!("解析核心配置失败：{}", e))?;
+    if !value.is_object() {
+        return Err("核心配置不是 JSON 对象".to_string());
+    }
+
+    let read_str = |value: &Value, key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    };
+    let read_port = |key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_u64())
+            .filter(|port| *port > 0)
+            .and_then(|port| u16::try_from(port).ok())
+    };
+
+    let tun = value
+        .get("tun")
+        .filter(|tun| tun.is_object())
+        .map(|tun| RunningTunSettings {
+            is_enabled: tun.get("enable").and_then(|v| v.as_bool()).unwrap_or(false),
+            stack: read_str(tun, "stack"),
+            device: read_str(tun, "device"),
+            is_auto_route: tun.get("auto-route").and_then(|v| v.as_bool()),
+        });
+
+    let listen_ports = ListenerProtocol::ALL
+        .into_iter()
+        .filter_map(|protocol| {
+            read_port(protocol.config_key()).map(|port| ListenPort { protocol, port })
+        })
+        .collect();
+
+    Ok(RunningConfigFields {
+        mode: read_str(&value, "mode"),
+        port: read_port("port"),
+        socks_port: read_port("socks-port"),
+        mixed_port: read_port("mixed-port"),
+        listen_ports,
+        allow_lan: value.get("allow-lan").and_then(|v| v.as_bool()),
+        log_level: read_str(&value, "log-level"),
+        tun,
+    })
+}
+
+pub fn init() {
+    shutdown::spawn_listener(async {
+        let receiver = GetRunningConfig::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ListenPort, ListenerProtocol, RunningConfigFields, parse_running_config};
+
+    #[test]
+    fn tolerates_missing_fields() -> Result<(), String> {
+        let fields = parse_running_config(
+            r#"{"port":0,"socks-port":7891,"mixed-port":7890,"mode":"rule","log-level":"info",
+                "allow-lan":false,"tun":{"enable":true,"stack":"mixed","auto-route":true}}"#,
+        )?;
+        assert_eq!(fields.port, None);
+        assert_eq!(fields.http_proxy_port(), Some(7890));
+        assert_eq!(
+            fields.listen_ports,
+            [
+                ListenPort {
+                    protocol: ListenerProtocol::Mixed,
+                    port: 7890,
+                },
+                ListenPort {
+                    protocol: ListenerProtocol::Socks,
+                    port: 7891,
+                },
+            ]
+        );
+        assert_eq!(
+            fields.tun.map(|tun| (tun.is_enabled, tun.device)),
+            Some((true, None))
+        );
+
+        assert_eq!(parse_running_config("{}")?, RunningConfigFields::default());
+        assert!(parse_running_config("[]").is_err());
+        Ok(())
+    }
+}
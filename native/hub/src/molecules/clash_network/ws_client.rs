@@ -2,11 +2,16 @@
 // 通过 Named Pipe/Unix Socket 建立 WebSocket 连接
 
 use super::connection;
+use super::core_lifecycle::{CoreLifecycleState, subscribe_core_lifecycle};
+use crate::atoms::ipc_client::controller_credentials;
 use base64::Engine;
-use futures_util::stream::StreamExt;
+use futures_util::stream::{SplitStream, StreamExt};
+use rand::Rng;
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio_tungstenite::{client_async, tungstenite::protocol::Message};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::{WebSocketStream, client_async, tungstenite::protocol::Message};
 
 #[cfg(unix)]
 use tokio::net::UnixStream;
@@ -16,11 +21,84 @@ use tokio::net::windows::named_pipe::NamedPipeClient;
 
 // HTTP Request 构建器 (来自 http crate)
 use http::Request;
-use http::header::{CONNECTION, HOST, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_VERSION, UPGRADE};
+use http::header::{
+    AUTHORIZATION, CONNECTION, HOST, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_VERSION, UPGRADE,
+};
 
 // WebSocket 连接 ID
 pub type ConnectionId = u32;
 
+#[cfg(unix)]
+type WsReader = SplitStream<WebSocketStream<UnixStream>>;
+#[cfg(windows)]
+type WsReader = SplitStream<WebSocketStream<NamedPipeClient>>;
+
+// 断线重连策略：第 n 次重连前等待 min(initial_delay × 2ⁿ, max_delay)，
+// 并在其后一半区间内随机抖动，避免多个连接同时重连。
+// 连续失败 max_attempts_per_burst 次后暂停，核心重新上线时再开始新一轮
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub max_attempts_per_burst: u32,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            max_attempts_per_burst: 8,
+        }
+    }
+}
+
+static RECONNECT_POLICY: RwLock<Option<ReconnectPolicy>> = RwLock::new(None);
+
+pub fn reconnect_policy() -> ReconnectPolicy {
+    let policy = match RECONNECT_POLICY.read() {
+        Ok(guard) => *guard,
+        Err(e) => *e.into_inner(),
+    };
+    policy.unwrap_or_default()
+}
+
+// 设置重连策略，对之后的重连生效
+pub fn set_reconnect_policy(policy: ReconnectPolicy) {
+    let policy = ReconnectPolicy {
+        initial_delay: policy.initial_delay.max(Duration::from_millis(50)),
+        max_delay: policy.max_delay.max(policy.initial_delay),
+        max_attempts_per_burst: policy.max_attempts_per_burst.max(1),
+    };
+    log::info!("WebSocket 重连策略已更新：{:?}", policy);
+    match RECONNECT_POLICY.write() {
+        Ok(mut guard) => *guard = Some(policy),
+        Err(e) => *e.into_inner() = Some(policy),
+    }
+}
+
+// 计算第 attempt 次（从 0 开始）重连前的等待时间，jitter 取值 [0, 1)
+fn reconnect_delay(policy: &ReconnectPolicy, attempt: u32, jitter: f64) -> Duration {
+    let backoff = policy
+        .initial_delay
+        .saturating_mul(2u32.saturating_pow(attempt.min(16)))
+        .min(policy.max_delay);
+    let half = backoff / 2;
+    half + half.mul_f64(jitter.clamp(0.0, 1.0))
+}
+
+// 等待核心重新上线；事件通道关闭时返回 false
+async fn wait_for_core_up() -> bool {
+    let mut receiver = subscribe_core_lifecycle();
+    loop {
+        match receiver.recv().await {
+            Ok(CoreLifecycleState::Up | CoreLifecycleState::Restarted) => return true,
+            Ok(CoreLifecycleState::Down) | Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => return false,
+        }
+    }
+}
+
 // WebSocket 客户端
 pub struct WebSocketClient {
     ipc_path: String,
@@ -49,7 +127,8 @@ impl WebSocketClient {
     }
 
     // 连接到 WebSocket 端点并开始接收消息。
-    // 返回连接 ID，用于后续管理与断开连接。
+    // 返回连接 ID，用于后续管理与断开连接。连接意外断开后按重连策略自动重连，
+    // 连接 ID 保持不变
     pub async fn connect<F>(&self, endpoint: &str, on_message: F) -> Result<ConnectionId, String>
     where
         F: Fn(serde_json::Value) + Send + 'static,
@@ -64,107 +143,178 @@ impl WebSocketClient {
             id
         };
 
-        // 2. 连接到 IPC 端点
+        // 2. 首次连接失败直接返回错误，由调用方决定是否重试
+        let reader = Self::establish(&self.ipc_path, endpoint).await?;
+        log::info!("WebSocket 连接建立成功[{}]：{}", connection_id, endpoint);
+
+        // 3. 启动消息接收循环，断开后自动重连
+        let connections = self.connections.clone();
+        let ipc_path = self.ipc_path.clone();
+        let endpoint = endpoint.to_string();
+        let handle = tokio::spawn(async move {
+            let mut reader = reader;
+            let mut on_message = on_message;
+            loop {
+                Self::receive_messages(connection_id, &mut reader, &mut on_message).await;
+
+                match Self::reconnect(connection_id, &ipc_path, &endpoint).await {
+                    Some(new_reader) => reader = new_reader,
+                    None => break,
+                }
+            }
+
+            log::debug!("WebSocket 连接任务已结束[{}]", connection_id);
+
+            // 连接结束后，从连接表中移除
+            let mut conns = connections.lock().await;
+            conns.remove(&connection_id);
+        });
+
+        // 存储连接句柄
+        {
+            let mut conns = self.connections.lock().await;
+            conns.insert(connection_id, handle);
+        }
+
+        Ok(connection_id)
+    }
+
+    // 建立 IPC 连接并完成 WebSocket 握手，返回读取流
+    async fn establish(ipc_path: &str, endpoint: &str) -> Result<WsReader, String> {
         #[cfg(windows)]
-        let stream = self.connect_windows().await?;
+        let stream = connection::connect_named_pipe(ipc_path).await?;
 
         #[cfg(unix)]
-        let stream = self.connect_unix().await?;
+        let stream = connection::connect_unix_socket(ipc_path).await?;
 
-        // 3. 构造 WebSocket 握手请求（使用 http::Request）
+        // 构造 WebSocket 握手请求（使用 http::Request）
         // 关键：使用 ws:// scheme 以通过 tungstenite 的 URI 验证
         let uri = format!("ws://localhost{}", endpoint);
         log::trace!("构造 URI：{}", uri);
 
-        let request = Request::builder()
+        let mut builder = Request::builder()
             .uri(&uri)
             .header(HOST, "stelliberty")
             .header(SEC_WEBSOCKET_KEY, Self::generate_websocket_key())
             .header(CONNECTION, "Upgrade")
             .header(UPGRADE, "websocket")
-            .header(SEC_WEBSOCKET_VERSION, "13")
+            .header(SEC_WEBSOCKET_VERSION, "13");
+        if let Some(secret) = controller_credentials().secret {
+            builder = builder.header(AUTHORIZATION, format!("Bearer {}", secret));
+        }
+        let request = builder
             .body(())
             .map_err(|e| format!("构造 WebSocket 请求失败：{}", e))?;
 
-        log::trace!("WebSocket 请求构造成功，URI：{:?}", request.uri());
-
         log::trace!("发送 WebSocket 握手请求：{}", endpoint);
 
-        // 4. 使用 client_async 建立 WebSocket 连接
         let (ws_stream, _) = client_async(request, stream)
             .await
             .map_err(|e| format!("WebSocket 握手失败：{}", e))?;
 
-        log::info!("WebSocket 连接建立成功[{}]：{}", connection_id, endpoint);
-
-        // 5. 分离读写流
-        let (_writer, mut reader) = ws_stream.split();
+        // 分离读写流，只保留读取端
+        let (_writer, reader) = ws_stream.split();
+        Ok(reader)
+    }
 
-        // 6. 启动消息接收循环
-        let connections = self.connections.clone();
-        let handle = tokio::spawn(async move {
-            log::trace!("WebSocket 消息接收循环已启动 [{}]", connection_id);
-
-            while let Some(message) = reader.next().await {
-                match message {
-                    Ok(Message::Text(text)) => {
-                        // 解析 JSON 消息
-                        match serde_json::from_str::<serde_json::Value>(&text) {
-                            Ok(json_value) => {
-                                log::trace!(
-                                    "WebSocket 收到消息[{}]：{}bytes",
-                                    connection_id,
-                                    text.len()
-                                );
-                                on_message(json_value);
-                            }
-                            Err(e) => {
-                                log::error!(
-                                    "WebSocket 消息 JSON 解析失败[{}]：{}",
-                                    connection_id,
-                                    e
-                                );
-                            }
+    // 接收消息直到连接关闭或出错
+    async fn receive_messages<F>(
+        connection_id: ConnectionId,
+        reader: &mut WsReader,
+        on_message: &mut F,
+    ) where
+        F: Fn(serde_json::Value),
+    {
+        log::trace!("WebSocket 消息接收循环已启动 [{}]", connection_id);
+
+        while let Some(message) = reader.next().await {
+            match message {
+                Ok(Message::Text(text)) => {
+                    // 解析 JSON 消息
+                    match serde_json::from_str::<serde_json::Value>(&text) {
+                        Ok(json_value) => {
+                            log::trace!(
+                                "WebSocket 收到消息[{}]：{}bytes",
+                                connection_id,
+                                text.len()
+                            );
+                            on_message(json_value);
+                        }
+                        Err(e) => {
+                            log::error!("WebSocket 消息 JSON 解析失败[{}]：{}", connection_id, e);
                         }
                     }
-                    Ok(Message::Close(close_frame)) => {
-                        log::info!("WebSocket 连接关闭[{}]：{:?}", connection_id, close_frame);
-                        break;
-                    }
-                    Ok(Message::Ping(_)) | Ok(Message::Pong(_)) => {
-                        // Ping/Pong 由 tokio-tungstenite 自动处理
-                    }
-                    Ok(Message::Binary(data)) => {
-                        log::debug!(
-                            "WebSocket 收到二进制消息[{}]：{}bytes",
-                            connection_id,
-                            data.len()
-                        );
-                    }
-                    Ok(Message::Frame(_)) => {
-                        // 忽略原始帧
-                    }
-                    Err(e) => {
-                        log::error!("WebSocket 消息读取错误[{}]：{}", connection_id, e);
-                        break;
-                    }
+                }
+                Ok(Message::Close(close_frame)) => {
+                    log::info!("WebSocket 连接关闭[{}]：{:?}", connection_id, close_frame);
+                    break;
+                }
+                Ok(Message::Ping(_)) | Ok(Message::Pong(_)) => {
+                    // Ping/Pong 由 tokio-tungstenite 自动处理
+                }
+                Ok(Message::Binary(data)) => {
+                    log::debug!(
+                        "WebSocket 收到二进制消息[{}]：{}bytes",
+                        connection_id,
+                        data.len()
+                    );
+                }
+                Ok(Message::Frame(_)) => {
+                    // 忽略原始帧
+                }
+                Err(e) => {
+                    log::error!("WebSocket 消息读取错误[{}]：{}", connection_id, e);
+                    break;
                 }
             }
+        }
 
-            log::debug!("WebSocket 消息接收循环已结束[{}]", connection_id);
+        log::debug!("WebSocket 消息接收循环已结束[{}]", connection_id);
+    }
 
-            // 连接结束后，从连接表中移除
-            let mut conns = connections.lock().await;
-            conns.remove(&connection_id);
-        });
+    // 按指数退避（带抖动）重连。连续失败达到单轮上限后暂停，
+    // 等待生命周期监视报告核心重新上线再开始新一轮；事件通道关闭时返回 None
+    async fn reconnect(
+        connection_id: ConnectionId,
+        ipc_path: &str,
+        endpoint: &str,
+    ) -> Option<WsReader> {
+        let mut attempt = 0;
+        loop {
+            let policy = reconnect_policy();
+            if attempt >= policy.max_attempts_per_burst {
+                log::warn!(
+                    "WebSocket 连续重连 {} 次失败，暂停重连直到核心重新上线[{}]：{}",
+                    attempt,
+                    connection_id,
+                    endpoint
+                );
+                if !wait_for_core_up().await {
+                    return None;
+                }
+                attempt = 0;
+            }
 
-        // 存储连接句柄
-        {
-            let mut conns = self.connections.lock().await;
-            conns.insert(connection_id, handle);
+            let jitter = rand::rng().random::<f64>();
+            let delay = reconnect_delay(&policy, attempt, jitter);
+            log::debug!(
+                "WebSocket 将在 {}ms 后重连（第 {} 次）[{}]：{}",
+                delay.as_millis(),
+                attempt + 1,
+                connection_id,
+                endpoint
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+
+            match Self::establish(ipc_path, endpoint).await {
+                Ok(reader) => {
+                    log::info!("WebSocket 已重新连接[{}]：{}", connection_id, endpoint);
+                    return Some(reader);
+                }
+                Err(e) => log::debug!("WebSocket 重连失败[{}]：{}", connection_id, e),
+            }
         }
-
-        Ok(connection_id)
     }
 
     // 断开指定的 WebSocket 连接
@@ -197,18 +347,6 @@ impl WebSocketClient {
             log::info!("所有 WebSocket 连接已断开");
         }
     }
-
-    // Windows: 连接到 Named Pipe
-    #[cfg(windows)]
-    async fn connect_windows(&self) -> Result<NamedPipeClient, String> {
-        connection::connect_named_pipe(&self.ipc_path).await
-    }
-
-    // Unix: 连接到 Unix Socket
-    #[cfg(unix)]
-    async fn connect_unix(&self) -> Result<UnixStream, String> {
-        connection::connect_unix_socket(&self.ipc_path).await
-    }
 }
 
 #[cfg(test)]
@@ -231,6 +369,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_reconnect_delay_backoff() {
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(4),
+            max_attempts_per_burst: 8,
+        };
+
+        assert_eq!(reconnect_delay(&policy, 0, 0.0), Duration::from_millis(250));
+        assert_eq!(reconnect_delay(&policy, 2, 1.0), Duration::from_secs(2));
+        // 超过上限后不再增长，抖动只落在后一半区间
+        assert_eq!(reconnect_delay(&policy, 10, 0.0), Duration::from_secs(2));
+        assert_eq!(reconnect_delay(&policy, 30, 1.0), Duration::from_secs(4));
+    }
+
     #[test]
     fn test_connection_id_increment() {
         let client = WebSocketClient::new(String::from("test"));
@@ -1,6 +1,7 @@
 // Clash 进程管理：负责启动、停止与状态维护。
 // 适用于非服务模式的直接进程控制。
 
+use crate::atoms::shutdown;
 use crate::molecules::clash_network;
 use once_cell::sync::Lazy;
 use rinf::{DartSignal, RustSignal};
@@ -249,6 +250,14 @@ impl ClashProcess {
     }
 }
 
+// 从启动参数中取出 -f 指定的配置文件路径
+fn config_path_arg(args: &[String]) -> Option<&str> {
+    args.iter()
+        .position(|arg| arg == "-f")
+        .and_then(|index| args.get(index + 1))
+        .map(String::as_str)
+}
+
 // 处理启动 Clash 进程的请求
 impl StartClashProcess {
     pub fn handle(&self) {
@@ -276,6 +285,16 @@ impl StartClashProcess {
             Ok(process) => {
                 let pid = process.pid();
                 *manager = Some(process);
+                clash_network::record_core_started();
+
+                // 新核心进程不接受旧连接，清空残留的连接池，再从配置文件刷新凭据与节点标识
+                let config_path = config_path_arg(&self.args).map(std::path::PathBuf::from);
+                tokio::spawn(async move {
+                    clash_network::clear_ipc_connection_pools().await;
+                    if let Some(config_path) = config_path {
+                        clash_network::refresh_core_config_state(config_path).await;
+                    }
+                });
 
                 log::info!("Clash 进程启动成功，PID：{}", pid);
                 ClashProcessResult {
@@ -378,10 +397,8 @@ pub async fn cleanup_process_manager() {
 }
 
 pub fn init() {
-    use tokio::spawn;
-
     // 启动 Clash 进程
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = StartClashProcess::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -402,7 +419,7 @@ pub fn init() {
     });
 
     // 停止 Clash 进程
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = StopClashProcess::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle().await;
@@ -1,6 +1,8 @@
 // Clash 服务模式管理：通过 Windows Service/systemd 运行核心进程。
 // 需要提升权限以完成安装、启停与状态查询。
 
+use crate::atoms::shutdown;
+use crate::molecules::clash_network;
 use crate::molecules::clash_process::process_manager::ClashProcessResult;
 use anyhow::{Context, Result};
 use rinf::{DartSignal, RustSignal};
@@ -922,6 +924,15 @@ impl StartClash {
         {
             Ok(pid) => {
                 log::info!("通过服务启动 Clash 成功，PID：{:?}", pid);
+                clash_network::record_core_started();
+
+                // 新核心进程不接受旧连接，清空残留的连接池
+                clash_network::clear_ipc_connection_pools().await;
+                clash_network::refresh_core_config_state(std::path::PathBuf::from(
+                    &self.config_path,
+                ))
+                .await;
+
                 ClashProcessResult {
                     is_successful: true,
                     error_message: None,
@@ -958,6 +969,9 @@ impl StopClash {
             }
         };
 
+        // 与直接启动模式一致，停止前清理网络资源
+        clash_network::cleanup_all_network_resources().await;
+
         match service_manager.stop_clash().await {
             Ok(()) => {
                 log::info!("通过服务停止 Clash 成功");
@@ -1027,10 +1041,8 @@ impl GetServiceVersion {
 }
 
 pub fn init() {
-    use tokio::spawn;
-
     // 获取服务状态
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = GetServiceStatus::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -1041,7 +1053,7 @@ pub fn init() {
     });
 
     // 安装服务
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = InstallService::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -1052,7 +1064,7 @@ pub fn init() {
     });
 
     // 卸载服务
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = UninstallService::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -1063,7 +1075,7 @@ pub fn init() {
     });
 
     // 通过服务启动 Clash
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = StartClash::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -1074,7 +1086,7 @@ pub fn init() {
     });
 
     // 通过服务停止 Clash
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = StopClash::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -1085,7 +1097,7 @@ pub fn init() {
     });
 
     // 向服务发送心跳
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = SendServiceHeartbeat::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -1096,7 +1108,7 @@ pub fn init() {
     });
 
     // 获取服务版本号
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = GetServiceVersion::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -1,6 +1,8 @@
 // 核心更新服务：下载、解压并替换 Mihomo 核心。
 // 通过 rinf 信号对外提供查询与下载能力。
 
+use crate::atoms::shutdown;
+use crate::atoms::tls_roots;
 use flate2::read::GzDecoder;
 use reqwest::Client;
 use rinf::{DartSignal, RustSignal};
@@ -9,7 +11,6 @@ use serde_json::Value;
 use std::io::Read;
 use std::path::Path;
 use tokio::fs as async_fs;
-use tokio::spawn;
 use zip::ZipArchive;
 
 const GITHUB_REPO: &str = "MetaCubeX/mihomo";
@@ -148,8 +149,8 @@ async fn get_latest_release() -> Result<Value, Box<dyn std::error::Error + Send
 
     let client = Client::builder()
         .timeout(std::time::Duration::from_secs(10))
-        .user_agent("stelliberty")
-        .build()?;
+        .user_agent("stelliberty");
+    let client = tls_roots::apply(client).build()?;
 
     let response = client.get(&url).send().await?;
 
@@ -226,8 +227,8 @@ async fn download_file(url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error +
     let client = Client::builder()
         .timeout(std::time::Duration::from_secs(30)) // 总超时 30 秒
         .connect_timeout(std::time::Duration::from_secs(10)) // 连接超时 10 秒
-        .user_agent("stelliberty")
-        .build()?;
+        .user_agent("stelliberty");
+    let client = tls_roots::apply(client).build()?;
 
     let response = client.get(url).send().await?;
 
@@ -374,7 +375,7 @@ fn send_progress(progress: f64, message: &str, downloaded: u64, total: u64) {
 
 pub fn init() {
     // 监听获取最新版本信号
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = GetLatestCoreVersionRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -386,7 +387,7 @@ pub fn init() {
     });
 
     // 监听下载核心信号
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = DownloadCoreRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -398,7 +399,7 @@ pub fn init() {
     });
 
     // 监听替换核心信号
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = ReplaceCoreRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
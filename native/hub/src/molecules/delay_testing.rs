@@ -1,12 +1,25 @@
 // 延迟测试分子模块
 
+pub mod history;
+pub mod reachability;
+pub mod result_log;
 pub mod tester;
 
+pub use history::{
+    ClearDelayHistory, DelayHistoryResult, DelayRecord, GetDelayHistory, clear_delay_history,
+};
+pub use reachability::{ReachabilityError, ReachabilityResult, TestReachability};
+pub use result_log::{ResultLogFormat, ResultLogOptions};
 pub use tester::{
-    BatchDelayTestComplete, BatchDelayTestRequest, CancelDelayTestsRequest, DelayTestProgress,
-    SingleDelayTestRequest, SingleDelayTestResult,
+    BatchDelayTestComplete, BatchDelayTestRequest, CancelDelayTestsRequest, CaptivePortalDetected,
+    DelayStatus, DelayTestProgress, DelayTestProgressBatch, DelayTestStarted, GroupDelaySummary,
+    MultiGroupDelayTestComplete, MultiGroupDelayTestProgress, MultiGroupDelayTestRequest,
+    NestedGroupPolicy, QuickTest, QuickTestGroupResult, QuickTestResult, SingleDelayTestRequest,
+    SingleDelayTestResult, TestAndSelectFastest, TestAndSelectFastestResult,
 };
 
 pub fn init_listeners() {
     tester::init();
+    history::init();
+    reachability::init();
 }
@@ -0,0 +1,205 @@
+// 延迟测试历史：在内存中为每个节点保留最近的测试结果。
+// 仅用于绘制延迟趋势与计算滑动平均，不做磁盘持久化。
+
+use crate::atoms::shutdown;
+use crate::molecules::clash_network::node_id;
+use once_cell::sync::Lazy;
+use rinf::{DartSignal, RustSignal, SignalPiece};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+// 每个节点保留的最近记录数
+const MAX_RECORDS_PER_NODE: usize = 20;
+// 最多记录的节点数
+const MAX_TRACKED_NODES: usize = 2000;
+// 超过该时间未测试的节点会被淘汰
+const NODE_IDLE_EVICT_AFTER: Duration = Duration::from_secs(6 * 60 * 60);
+
+// Dart → Rust：获取节点延迟历史
+#[derive(Deserialize, DartSignal)]
+pub struct GetDelayHistory {
+    pub node_name: String,
+}
+
+// Dart → Rust：清除延迟历史，node_name 为 None 时清除全部节点。
+// 切换订阅后旧节点的历史不再有参考价值，且会影响最快节点的选择
+#[derive(Deserialize, DartSignal)]
+pub struct ClearDelayHistory {
+    pub node_name: Option<String>,
+}
+
+// Rust → Dart：节点延迟历史
+#[derive(Serialize, RustSignal)]
+pub struct DelayHistoryResult {
+    pub node_name: String,
+    // 按时间从旧到新排列
+    pub records: Vec<DelayRecord>,
+    // 成功记录的平均延迟，没有成功记录时为空
+    pub average_delay_ms: Option<i32>,
+}
+
+// 单次测试记录
+#[derive(Serialize, SignalPiece, Clone, Debug)]
+pub struct DelayRecord {
+    pub delay_ms: i32, // -1 表示失败
+    pub timestamp_ms: i64,
+}
+
+struct NodeDelayHistory {
+    records: VecDeque<DelayRecord>,
+    last_seen: Instant,
+}
+
+static DELAY_HISTORY: Lazy<Mutex<HashMap<String, NodeDelayHistory>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn lock_delay_history() -> MutexGuard<'static, HashMap<String, NodeDelayHistory>> {
+    match DELAY_HISTORY.lock() {
+        Ok(guard) => guard,
+        Err(e) => {
+            log::error!("延迟历史锁已中毒，继续使用恢复后的状态");
+            e.into_inner()
+        }
+    }
+}
+
+// 历史记录的键：优先使用节点稳定标识，节点改名后历史仍然保留
+fn history_key(node_name: &str) -> String {
+    node_id(node_name).unwrap_or_else(|| node_name.to_string())
+}
+
+// 记录一次测试结果
+pub fn record_delay(node_name: &str, delay_ms: i32) {
+    let key = history_key(node_name);
+    let mut history = lock_delay_history();
+
+    if !history.contains_key(&key) && history.len() >= MAX_TRACKED_NODES {
+        evict_nodes(&mut history);
+    }
+
+    let node_history = history.entry(key).or_insert_with(|| NodeDelayHistory {
+        records: VecDeque::with_capacity(MAX_RECORDS_PER_NODE),
+        last_seen: Instant::now(),
+    });
+
+    if node_history.records.len() >= MAX_RECORDS_PER_NODE {
+        node_history.records.pop_front();
+    }
+    node_history.records.push_back(DelayRecord {
+        delay_ms,
+        timestamp_ms: chrono::Local::now().timestamp_millis(),
+    });
+    node_history.last_seen = Instant::now();
+}
+
+// 获取节点的延迟历史（从旧到新）
+pub fn get_delay_history(node_name: &str) -> Vec<DelayRecord> {
+    let key = history_key(node_name);
+    lock_delay_history()
+        .get(&key)
+        .map(|node_history| node_history.records.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+// 清除指定节点或全部节点的历史，返回清除的节点数
+pub fn clear_delay_history(node_name: Option<&str>) -> usize {
+    let mut history = lock_delay_history();
+    match node_name {
+        Some(node_name) => usize::from(history.remove(&history_key(node_name)).is_some()),
+        None => {
+            let count = history.len();
+            history.clear();
+            count
+        }
+    }
+}
+
+// 计算成功记录的平均延迟
+pub fn average_delay(records: &[DelayRecord]) -> Option<i32> {
+    let successes: Vec<i64> = records
+        .iter()
+        .filter(|record| record.delay_ms > 0)
+        .map(|record| record.delay_ms as i64)
+        .collect();
+
+    if successes.is_empty() {
+        None
+    } else {
+        Some((successes.iter().sum::<i64>() / successes.len() as i64) as i32)
+    }
+}
+
+// 淘汰长时间未测试的节点；仍然超限时淘汰最久未测试的节点
+fn evict_nodes(history: &mut HashMap<String, NodeDelayHistory>) {
+    history.retain(|_, node_history| node_history.last_seen.elapsed() < NODE_IDLE_EVICT_AFTER);
+
+    if history.len() >= MAX_TRACKED_NODES
+        && let Some(oldest) = history
+            .iter()
+            .min_by_key(|(_, node_history)| node_history.last_seen)
+            .map(|(name, _)| name.clone())
+    {
+        history.remove(&oldest);
+    }
+}
+
+impl GetDelayHistory {
+    fn handle(self) {
+        let records = get_delay_history(&self.node_name);
+        let average_delay_ms = average_delay(&records);
+
+        DelayHistoryResult {
+            node_name: self.node_name,
+            records,
+            average_delay_ms,
+        }
+        .send_signal_to_dart();
+    }
+}
+
+impl ClearDelayHistory {
+    fn handle(self) {
+        let count = clear_delay_history(self.node_name.as_deref());
+        match self.node_name {
+            Some(node_name) => log::info!("已清除节点延迟历史：{}", node_name),
+            None => log::info!("已清除全部延迟历史：{} 个节点", count),
+        }
+    }
+}
+
+pub fn init() {
+    shutdown::spawn_listener(async {
+        let receiver = GetDelayHistory::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+        log::info!("延迟历史消息通道已关闭，退出监听器");
+    });
+
+    shutdown::spawn_listener(async {
+        let receiver = ClearDelayHistory::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clear_delay_history, get_delay_history, record_delay};
+
+    #[test]
+    fn clears_one_node_or_all() {
+        record_delay("history-test-a", 80);
+        record_delay("history-test-b", 90);
+
+        assert_eq!(clear_delay_history(Some("history-test-a")), 1);
+        assert!(get_delay_history("history-test-a").is_empty());
+        assert_eq!(get_delay_history("history-test-b").len(), 1);
+
+        assert!(clear_delay_history(None) >= 1);
+        assert!(get_delay_history("history-test-b").is_empty());
+    }
+}
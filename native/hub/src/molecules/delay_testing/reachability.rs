@@ -0,0 +1,360 @@
+// 连通性诊断：不经代理直接对任意 host:port 发起 TCP 连接，
+// 用于排查“本机能否访问服务器端口”。DNS 解析与 TCP 连接分开计时与归类。
+
+use crate::atoms::host_port::normalize_host;
+use crate::atoms::shutdown;
+use rinf::{DartSignal, RustSignal, SignalPiece};
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::spawn;
+
+const DEFAULT_TIMEOUT_MS: u32 = 5000;
+const MAX_TIMEOUT_MS: u32 = 30_000;
+
+// Dart → Rust：测试到 host:port 的直连连通性
+#[derive(Deserialize, DartSignal)]
+pub struct TestReachability {
+    pub host: String,
+    pub port: u16,
+    // 包含 DNS 解析在内的总超时，为 0 时使用默认值
+    pub timeout_ms: u32,
+}
+
+// 连通性失败原因
+#[derive(Serialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReachabilityError {
+    // 域名解析失败或没有可用地址
+    DnsFailure = 0,
+    // 目标主机拒绝连接（端口未监听或被防火墙拒绝）
+    Refused = 1,
+    Timeout = 2,
+    // 网络或主机不可达（无路由）
+    Unreachable = 3,
+    Other = 4,
+}
+
+// Rust → Dart：连通性测试结果
+#[derive(Serialize, RustSignal)]
+pub struct ReachabilityResult {
+    pub host: String,
+    pub port: u16,
+    // 实际连接成功的地址
+    pub connected_address: Option<String>,
+    // TCP 连接耗时（不含 DNS 解析）
+    pub connect_ms: Option<u32>,
+    pub dns_ms: Option<u32>,
+    pub error: Option<ReachabilityError>,
+    pub error_message: Option<String>,
+}
+
+struct ReachabilityOutcome {
+    connected_address: SocketAddr,
+    dns_ms: u32,
+    connect_ms: u32,
+    // 已建立的连接，调用方可在同一连接上继续测量
+    stream: TcpStream,
+}
+
+// 直连 HTTP 探测结果：各阶段耗时均来自同一条连接
+pub(super) struct DirectHttpProbe {
+    pub dns_ms: u32,
+    pub connect_ms: u32,
+    // 发出请求到收到响应首字节的耗时
+    pub first_byte_ms: u32,
+    pub status_code: u16,
+}
+
+impl TestReachability {
+    pub fn handle(self) {
+        spawn(async move {
+            let timeout_ms = match self.timeout_ms {
+                0 => DEFAULT_TIMEOUT_MS,
+                timeout_ms => timeout_ms.min(MAX_TIMEOUT_MS),
+            };
+            let host = normalize_host(&self.host);
+
+            let response = match test_reachability(&host, self.port, timeout_ms).await {
+                Ok(outcome) => {
+                    log::info!(
+                        "连通性测试成功：{}:{} → {}，连接耗时 {}ms",
+                        host,
+                        self.port,
+                        outcome.connected_address,
+                        outcome.connect_ms
+                    );
+                    ReachabilityResult {
+                        host: self.host,
+                        port: self.port,
+                        connected_address: Some(outcome.connected_address.to_string()),
+                        connect_ms: Some(outcome.connect_ms),
+                        dns_ms: Some(outcome.dns_ms),
+                        error: None,
+                        error_message: None,
+                    }
+                }
+                Err((error, message)) => {
+                    log::warn!(
+                        "连通性测试失败：{}:{}（{:?}）：{}",
+                        host,
+                        self.port,
+                        error,
+                        message
+                    );
+                    ReachabilityResult {
+                        host: self.host,
+                        port: self.port,
+                        connected_address: None,
+                        connect_ms: None,
+                        dns_ms: None,
+                        error: Some(error),
+                        error_message: Some(message),
+                    }
+                }
+            };
+            response.send_signal_to_dart();
+        });
+    }
+}
+
+// 直连发送 HEAD 请求，在同一条连接上依次测量 DNS 解析、TCP 连接与响应首字节耗时。
+// 仅支持明文 HTTP，总耗时不超过 timeout_ms
+pub(super) async fn probe_direct_http(
+    host: &str,
+    port: u16,
+    path: &str,
+    timeout_ms: u32,
+) -> Result<DirectHttpProbe, String> {
+    let deadline = Instant::now() + Duration::from_millis(u64::from(timeout_ms));
+    let host = normalize_host(host);
+    let mut outcome = test_reachability(&host, port, timeout_ms)
+        .await
+        .map_err(|(_, message)| message)?;
+
+    let request = format!(
+        "HEAD {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: Stelliberty\r\nConnection: close\r\n\r\n",
+        path,
+        host_header(&host, port)
+    );
+    let first_byte_start = Instant::now();
+    let mut head = [0u8; 16];
+    let read = async {
+        outcome.stream.write_all(request.as_bytes()).await?;
+        let mut size = outcome.stream.read(&mut head).await?;
+        // 状态行前 12 字节包含状态码，首段过短时继续读取
+        while size < head.len() {
+            match outcome.stream.read(&mut head[size..]).await? {
+                0 => break,
+                read => size += read,
+            }
+        }
+        Ok::<usize, std::io::Error>(size)
+    };
+    let size = match tokio::time::timeout_at(deadline.into(), read).await {
+        Ok(Ok(size)) if size > 0 => size,
+        Ok(Ok(_)) => return Err("连接在响应前关闭".to_string()),
+        Ok(Err(e)) => return Err(format!("HTTP 请求失败：{}", e)),
+        Err(_) => return Err(format!("等待响应超时（{}ms）", timeout_ms)),
+    };
+    let first_byte_ms = elapsed_ms(first_byte_start);
+
+    Ok(DirectHttpProbe {
+        dns_ms: outcome.dns_ms,
+        connect_ms: outcome.connect_ms,
+        first_byte_ms,
+        status_code: parse_status_code(&head[..size])?,
+    })
+}
+
+// Host 请求头：IPv6 地址加方括号，非默认端口附带端口号
+fn host_header(host: &str, port: u16) -> String {
+    let host = if host.contains(':') {
+        format!("[{}]", host)
+    } else {
+        host.to_string()
+    };
+    if port == 80 {
+        host
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+// 从响应开头解析状态码，如 "HTTP/1.1 204 No Content"
+fn parse_status_code(head: &[u8]) -> Result<u16, String> {
+    std::str::from_utf8(head)
+        .ok()
+        .filter(|text| text.starts_with("HTTP/"))
+        .and_then(|text| text.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| "响应不是有效的 HTTP 状态行".to_string())
+}
+
+// 先解析域名，再依次尝试各地址，直到成功或总超时耗尽
+async fn test_reachability(
+    host: &str,
+    port: u16,
+    timeout_ms: u32,
+) -> Result<ReachabilityOutcome, (ReachabilityError, String)> {
+    if host.is_empty() {
+        return Err((ReachabilityError::DnsFailure, "主机地址为空".to_string()));
+    }
+
+    let deadline = Instant::now() + Duration::from_millis(u64::from(timeout_ms));
+    let dns_start = Instant::now();
+    let addresses: Vec<SocketAddr> =
+        match tokio::time::timeout_at(deadline.into(), tokio::net::lookup_host((host, port))).await
+        {
+            Ok(Ok(addresses)) => addresses.collect(),
+            Ok(Err(e)) => {
+                return Err((
+                    ReachabilityError::DnsFailure,
+                    format!("域名解析失败：{}", e),
+                ));
+            }
+            Err(_) => {
+                return Err((
+                    ReachabilityError::DnsFailure,
+                    format!("域名解析超时（{}ms）", timeout_ms),
+                ));
+            }
+        };
+    let dns_ms = elapsed_ms(dns_start);
+    if addresses.is_empty() {
+        return Err((
+            ReachabilityError::DnsFailure,
+            "域名解析未返回任何地址".to_string(),
+        ));
+    }
+
+    let mut last_error = None;
+    for address in addresses {
+        let connect_start = Instant::now();
+        match tokio::time::timeout_at(deadline.into(), TcpStream::connect(address)).await {
+            Ok(Ok(stream)) => {
+                return Ok(ReachabilityOutcome {
+                    connected_address: address,
+                    dns_ms,
+                    connect_ms: elapsed_ms(connect_start),
+                    stream,
+                });
+            }
+            Ok(Err(e)) => {
+                last_error = Some((
+                    classify_connect_error(e.kind()),
+                    format!("{}：{}", address, e),
+                ));
+            }
+            Err(_) => {
+                last_error = Some((
+                    ReachabilityError::Timeout,
+                    format!("连接 {} 超时（{}ms）", address, timeout_ms),
+                ));
+                break;
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or((ReachabilityError::Other, "连接失败".to_string())))
+}
+
+fn classify_connect_error(kind: ErrorKind) -> ReachabilityError {
+    match kind {
+        ErrorKind::ConnectionRefused => ReachabilityError::Refused,
+        ErrorKind::TimedOut => ReachabilityError::Timeout,
+        ErrorKind::HostUnreachable
+        | ErrorKind::NetworkUnreachable
+        | ErrorKind::NetworkDown
+        | ErrorKind::AddrNotAvailable => ReachabilityError::Unreachable,
+        _ => ReachabilityError::Other,
+    }
+}
+
+fn elapsed_ms(start: Instant) -> u32 {
+    u32::try_from(start.elapsed().as_millis()).unwrap_or(u32::MAX)
+}
+
+pub fn init() {
+    shutdown::spawn_listener(async {
+        let receiver = TestReachability::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+        log::info!("连通性测试消息通道已关闭，退出监听器");
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ReachabilityError, host_header, parse_status_code, probe_direct_http, test_reachability,
+    };
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn distinguishes_refused_from_reachable_ports() -> Result<(), String> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| e.to_string())?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+        let outcome = test_reachability("127.0.0.1", port, 2000)
+            .await
+            .map_err(|(_, message)| message)?;
+        assert_eq!(outcome.connected_address.port(), port);
+
+        drop(listener);
+        let error = test_reachability("127.0.0.1", port, 2000).await.err();
+        assert_eq!(
+            error.map(|(kind, _)| kind),
+            Some(ReachabilityError::Refused)
+        );
+
+        let error = test_reachability("", port, 2000).await.err();
+        assert_eq!(
+            error.map(|(kind, _)| kind),
+            Some(ReachabilityError::DnsFailure)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn probes_http_status_on_one_connection() -> Result<(), String> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| e.to_string())?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await?;
+            let mut request = vec![0u8; 1024];
+            let size = stream.read(&mut request).await?;
+            stream.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").await?;
+            Ok::<String, std::io::Error>(String::from_utf8_lossy(&request[..size]).into_owned())
+        });
+
+        let probe = probe_direct_http("127.0.0.1", port, "/generate_204", 2000).await?;
+        assert_eq!(probe.status_code, 204);
+
+        let request = server
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+        assert!(request.starts_with("HEAD /generate_204 HTTP/1.1\r\n"));
+        assert!(request.contains(&format!("Host: 127.0.0.1:{}\r\n", port)));
+        Ok(())
+    }
+
+    #[test]
+    fn parses_status_line_and_host_header() {
+        assert_eq!(parse_status_code(b"HTTP/1.1 204 No Content\r\n"), Ok(204));
+        assert_eq!(parse_status_code(b"HTTP/1.0 301"), Ok(301));
+        assert!(parse_status_code(b"SSH-2.0-OpenSSH").is_err());
+
+        assert_eq!(host_header("example.com", 80), "example.com");
+        assert_eq!(host_header("example.com", 8080), "example.com:8080");
+        assert_eq!(host_header("::1", 8080), "[::1]:8080");
+    }
+}
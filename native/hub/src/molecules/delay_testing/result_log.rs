@@ -0,0 +1,184 @@
+// 批量测试结果文件记录：每个节点完成后追加一行，进程崩溃时已完成的结果仍保留在文件中。
+// 写入由独立任务串行完成，测试任务只投递记录，不等待磁盘 IO。
+
+use rinf::SignalPiece;
+use serde::Deserialize;
+use serde_json::json;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::tester::BatchTestResult;
+
+const CSV_HEADER: &str = "timestamp,node,delay_ms,test_url\n";
+
+// 结果文件格式
+#[derive(Deserialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResultLogFormat {
+    Csv = 0,
+    Jsonl = 1,
+}
+
+// 结果文件参数
+#[derive(Deserialize, SignalPiece, Clone, Debug)]
+pub struct ResultLogOptions {
+    pub path: String,
+    pub format: ResultLogFormat,
+    // 为 true 时追加到已有文件，否则每次测试前清空
+    pub should_append: bool,
+}
+
+// 单次批量测试的结果记录器
+pub struct ResultLogWriter {
+    sink: ResultLogSink,
+    task: JoinHandle<()>,
+}
+
+// 投递结果的句柄，可在进度回调间共享
+#[derive(Clone)]
+pub struct ResultLogSink {
+    format: ResultLogFormat,
+    sender: mpsc::UnboundedSender<String>,
+}
+
+impl ResultLogWriter {
+    pub async fn open(options: &ResultLogOptions) -> Result<Self, String> {
+        let path = options.path.trim();
+        if path.is_empty() {
+            return Err("结果文件路径不能为空".to_string());
+        }
+
+        if let Some(parent) = Path::new(path).parent()
+            && !parent.as_os_str().is_empty()
+        {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("创建结果文件目录失败：{}", e))?;
+        }
+
+        let mut open_options = tokio::fs::OpenOptions::new();
+        open_options.create(true);
+        if options.should_append {
+            open_options.append(true);
+        } else {
+            open_options.write(true).truncate(true);
+        }
+        let mut file = open_options
+            .open(path)
+            .await
+            .map_err(|e| format!("打开结果文件失败：{}", e))?;
+
+        // 新文件或清空后的 CSV 写入表头
+        let is_empty = file
+            .metadata()
+            .await
+            .map(|metadata| metadata.len() == 0)
+            .unwrap_or(false);
+        if options.format == ResultLogFormat::Csv && is_empty {
+            file.write_all(CSV_HEADER.as_bytes())
+                .await
+                .map_err(|e| format!("写入结果文件失败：{}", e))?;
+        }
+
+        let (sender, mut receiver) = mpsc::unbounded_channel::<String>();
+        let log_path = path.to_string();
+        let task = tokio::spawn(async move {
+            while let Some(line) = receiver.recv().await {
+                if let Err(e) = file.write_all(line.as_bytes()).await {
+                    log::warn!("写入结果文件失败，停止记录：{} - {}", log_path, e);
+                    return;
+                }
+            }
+            if let Err(e) = file.flush().await {
+                log::warn!("刷新结果文件失败：{} - {}", log_path, e);
+            }
+        });
+
+        log::info!("批量测试结果将记录到：{}（{:?}）", path, options.format);
+        Ok(Self {
+            sink: ResultLogSink {
+                format: options.format,
+                sender,
+            },
+            task,
+        })
+    }
+
+    pub fn sink(&self) -> ResultLogSink {
+        self.sink.clone()
+    }
+
+    // 等待已投递的结果全部写入；需先释放全部 sink，否则会一直等待
+    pub async fn finish(self) {
+        drop(self.sink);
+        let _ = self.task.await;
+    }
+}
+
+impl ResultLogSink {
+    // 投递一条结果，不等待写入完成
+    pub fn push(&self, result: &BatchTestResult) {
+        let line = format_line(self.format, result, &chrono::Local::now().to_rfc3339());
+        let _ = self.sender.send(line);
+    }
+}
+
+fn format_line(format: ResultLogFormat, result: &BatchTestResult, timestamp: &str) -> String {
+    match format {
+        ResultLogFormat::Csv => format!(
+            "{},{},{},{}\n",
+            timestamp,
+            csv_field(&result.node_name),
+            result.delay_ms,
+            csv_field(result.tested_url.as_deref().unwrap_or(""))
+        ),
+        ResultLogFormat::Jsonl => {
+            let record = json!({
+                "timestamp": timestamp,
+                "node": result.node_name,
+                "delay_ms": result.delay_ms,
+                "test_url": result.tested_url,
+            });
+            format!("{}\n", record)
+        }
+    }
+}
+
+// 含逗号、引号或换行的字段加引号，内部引号加倍
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ResultLogFormat, format_line};
+    use crate::molecules::delay_testing::tester::{BatchTestResult, DelayTiming};
+
+    #[test]
+    fn escapes_csv_fields_and_writes_jsonl() -> Result<(), String> {
+        let result = BatchTestResult {
+            node_name: "HK \"01\", IPLC".to_string(),
+            delay_ms: 42,
+            is_ipc_error: false,
+            timing: DelayTiming::default(),
+            tested_url: Some("https://example.com/204".to_string()),
+        };
+
+        assert_eq!(
+            format_line(ResultLogFormat::Csv, &result, "t"),
+            "t,\"HK \"\"01\"\", IPLC\",42,https://example.com/204\n"
+        );
+
+        let line = format_line(ResultLogFormat::Jsonl, &result, "t");
+        let value: serde_json::Value =
+            serde_json::from_str(line.trim_end()).map_err(|e| e.to_string())?;
+        assert_eq!(value["node"], "HK \"01\", IPLC");
+        assert_eq!(value["delay_ms"], 42);
+        Ok(())
+    }
+}
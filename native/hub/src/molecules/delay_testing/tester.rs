@@ -1,16 +1,24 @@
 // Clash 延迟测试模块
 
 use once_cell::sync::Lazy;
-use rinf::{DartSignal, RustSignal};
+use rinf::{DartSignal, RustSignal, SignalPiece};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::{Duration, Instant};
 use tokio::spawn;
 use tokio::sync::watch;
 use tokio::task::JoinSet;
 
+use super::history::record_delay;
+use super::reachability::probe_direct_http;
+use super::result_log::{ResultLogOptions, ResultLogWriter};
 use crate::atoms::IpcClient;
+use crate::atoms::ipc_client::{IpcError, encode_name_segment};
+use crate::atoms::shutdown;
+use crate::molecules::clash_network::{
+    ProxyGroupInfo, get_core_version, get_group_members, get_proxies_snapshot, select_proxy,
+};
 
 // Dart → Rust：取消测速请求
 #[derive(Deserialize, DartSignal)]
@@ -24,7 +32,13 @@ pub struct SingleDelayTestRequest {
     pub request_id: i64,
     pub node_name: String,
     pub test_url: String,
+    // test_url 失败时依次尝试的备用测试地址
+    pub fallback_test_urls: Vec<String>,
     pub timeout_ms: u32,
+    // 仅该状态码视为成功（如 204），None 时沿用核心默认判定
+    pub expected_status: Option<u16>,
+    // 延迟超过该值时状态为 Slow，None 时不区分
+    pub acceptable_ms: Option<u32>,
 }
 
 // Rust → Dart：单节点延迟测试结果
@@ -33,7 +47,34 @@ pub struct SingleDelayTestResult {
     pub request_id: i64,
     pub node_name: String,
     pub delay_ms: i32, // -1 表示失败
+    pub status: DelayStatus,
     pub is_cancelled: bool,
+    // 测试成功时所用的测试地址
+    pub tested_url: Option<String>,
+    // 分阶段耗时，无法测量时为 None
+    pub dns_ms: Option<u32>,
+    pub connect_ms: Option<u32>,
+    pub first_byte_ms: Option<u32>,
+}
+
+// 由延迟数值推导的节点状态，delay_ms 仍为权威结果
+#[derive(Serialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DelayStatus {
+    Good = 0,
+    Slow = 1,
+    Failed = 2,
+}
+
+impl DelayStatus {
+    fn from_delay(delay_ms: i32, acceptable_ms: Option<u32>) -> Self {
+        if delay_ms <= 0 {
+            return Self::Failed;
+        }
+        match acceptable_ms {
+            Some(acceptable_ms) if delay_ms as u32 > acceptable_ms => Self::Slow,
+            _ => Self::Good,
+        }
+    }
 }
 
 // Dart → Rust：批量延迟测试请求
@@ -42,8 +83,43 @@ pub struct BatchDelayTestRequest {
     pub request_id: i64,
     pub node_names: Vec<String>,
     pub test_url: String,
+    // test_url 失败时依次尝试的备用测试地址；非空时不使用策略组测速接口
+    pub fallback_test_urls: Vec<String>,
     pub timeout_ms: u32,
+    // 实际并发数不超过 IPC 连接池容量（默认 30，可通过 SetIpcPoolSize 调整）
     pub concurrency: u32,
+    // 仅该状态码视为成功（如 204），None 时沿用核心默认判定
+    pub expected_status: Option<u16>,
+    // 延迟超过该值时状态为 Slow，None 时不区分
+    pub acceptable_ms: Option<u32>,
+    // 节点所属策略组；核心支持 /group 接口时由核心一次性测试整个组
+    pub group_name: Option<String>,
+    // 进度合并发送参数，None 时每个节点完成后立即发送 DelayTestProgress
+    pub progress_batching: Option<ProgressBatching>,
+    // 成员中自动选择类策略组的处理方式，None 时与 Probe 相同
+    pub nested_group_policy: Option<NestedGroupPolicy>,
+    // 逐条记录结果到文件，None 时不记录
+    pub result_log: Option<ResultLogOptions>,
+    // 为 true 时每个节点开始测试前发送 DelayTestStarted
+    pub should_report_started: bool,
+}
+
+// 自动选择类策略组（url-test / fallback / load-balance）作为成员时的处理方式
+#[derive(Deserialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NestedGroupPolicy {
+    // 直接测试该策略组（结果为其当前选用节点的延迟）
+    Probe = 0,
+    // 跳过，在完成信号中单独列出
+    Skip = 1,
+    // 展开为组内的实际节点逐个测试
+    Expand = 2,
+}
+
+// 进度合并发送参数：每 interval_ms 毫秒或累计 max_results 个结果发送一次，先到为准
+#[derive(Deserialize, SignalPiece, Clone, Copy, Debug)]
+pub struct ProgressBatching {
+    pub interval_ms: u32,
+    pub max_results: u32,
 }
 
 // Rust → Dart：单个节点测试完成（流式进度更新）
@@ -52,6 +128,40 @@ pub struct DelayTestProgress {
     pub request_id: i64,
     pub node_name: String,
     pub delay_ms: i32, // -1 表示失败
+    pub status: DelayStatus,
+    // 测试成功时所用的测试地址
+    pub tested_url: Option<String>,
+    // 分阶段耗时，无法测量时为 None
+    pub dns_ms: Option<u32>,
+    pub connect_ms: Option<u32>,
+    pub first_byte_ms: Option<u32>,
+}
+
+// Rust → Dart：节点开始测试（请求 should_report_started 为 true 时发送），
+// 节点完成后仍发送 DelayTestProgress
+#[derive(Serialize, RustSignal)]
+pub struct DelayTestStarted {
+    pub request_id: i64,
+    pub node_name: String,
+}
+
+// 合并发送的单个节点进度
+#[derive(Serialize, SignalPiece, Clone, Debug)]
+pub struct DelayTestProgressEntry {
+    pub node_name: String,
+    pub delay_ms: i32, // -1 表示失败
+    pub status: DelayStatus,
+    pub tested_url: Option<String>,
+    pub dns_ms: Option<u32>,
+    pub connect_ms: Option<u32>,
+    pub first_byte_ms: Option<u32>,
+}
+
+// Rust → Dart：合并发送的一批节点进度（启用 progress_batching 时代替 DelayTestProgress）
+#[derive(Serialize, RustSignal)]
+pub struct DelayTestProgressBatch {
+    pub request_id: i64,
+    pub results: Vec<DelayTestProgressEntry>,
 }
 
 // Rust → Dart：批量测试完成
@@ -62,15 +172,159 @@ pub struct BatchDelayTestComplete {
     pub is_cancelled: bool,
     pub total_count: u32,
     pub success_count: u32,
+    // 按 NestedGroupPolicy::Skip 跳过、未参与测试的策略组
+    pub skipped_nodes: Vec<String>,
+    pub error_message: Option<String>,
+}
+
+// Rust → Dart：批量测试前检测到强制门户（如酒店、机场 WiFi 的登录页），
+// 此时节点延迟可能由门户代答，结果不可信
+#[derive(Serialize, RustSignal)]
+pub struct CaptivePortalDetected {
+    pub request_id: i64,
+    pub check_url: String,
+}
+
+// Dart → Rust：测试策略组全部节点并自动选中延迟最低的节点
+#[derive(Deserialize, DartSignal)]
+pub struct TestAndSelectFastest {
+    pub request_id: i64,
+    pub group: String,
+    pub test_url: String,
+    pub timeout_ms: u32,
+    // 优先的地区或标签（节点名称子串，不区分大小写，如 "JP"）。
+    // 非空时只在名称匹配任一标签的可用节点中选最快，都不可用时回退到全组最快
+    pub preferred_tags: Vec<String>,
+}
+
+// Rust → Dart：测试并选中结果，全部节点失败时保持原选择不变
+#[derive(Serialize, RustSignal)]
+pub struct TestAndSelectFastestResult {
+    pub request_id: i64,
+    pub group: String,
+    pub selected: Option<String>,
+    pub delay_ms: i32, // -1 表示未选中
+    // 选中的节点是否匹配优先标签；未指定标签或回退到全组最快时为 false
+    pub is_preferred_match: bool,
+    pub is_successful: bool,
+    pub is_cancelled: bool,
+    pub error_message: Option<String>,
+}
+
+// Dart → Rust：快速测试，只测试各 Selector 组当前选中的节点
+#[derive(Deserialize, DartSignal)]
+pub struct QuickTest {
+    pub request_id: i64,
+    pub test_url: String,
+    pub timeout_ms: u32,
+    // 延迟超过该值时状态为 Slow，None 时不区分
+    pub acceptable_ms: Option<u32>,
+}
+
+// 单个策略组的快速测试结果
+#[derive(Serialize, SignalPiece, Clone, Debug)]
+pub struct QuickTestGroupResult {
+    pub group: String,
+    // 当前选中的成员（可能是另一个策略组）
+    pub selected: String,
+    // 沿选中链解析到的实际节点，存在循环引用时为 None
+    pub effective_node: Option<String>,
+    pub delay_ms: i32, // -1 表示失败
+    pub status: DelayStatus,
+}
+
+// Rust → Dart：快速测试结果，按策略组列出
+#[derive(Serialize, RustSignal)]
+pub struct QuickTestResult {
+    pub request_id: i64,
+    pub groups: Vec<QuickTestGroupResult>,
+    pub is_successful: bool,
+    pub is_cancelled: bool,
+    pub error_message: Option<String>,
+}
+
+// Dart → Rust：一次测试多个策略组，组间共享的节点只测试一次
+#[derive(Deserialize, DartSignal)]
+pub struct MultiGroupDelayTestRequest {
+    pub request_id: i64,
+    pub groups: Vec<String>,
+    pub test_url: String,
+    pub timeout_ms: u32,
+    // 实际并发数不超过 IPC 连接池容量
+    pub concurrency: u32,
+    // 延迟超过该值时状态为 Slow，None 时不区分
+    pub acceptable_ms: Option<u32>,
+}
+
+// Rust → Dart：多策略组测试中单个节点完成，groups 为包含该节点的全部请求策略组
+#[derive(Serialize, RustSignal)]
+pub struct MultiGroupDelayTestProgress {
+    pub request_id: i64,
+    pub node_name: String,
+    pub groups: Vec<String>,
+    pub delay_ms: i32, // -1 表示失败
+    pub status: DelayStatus,
+    pub tested_url: Option<String>,
+}
+
+// 单个策略组的测试统计
+#[derive(Serialize, SignalPiece, Clone, Debug)]
+pub struct GroupDelaySummary {
+    pub group: String,
+    pub total_count: u32,
+    pub success_count: u32,
+}
+
+// Rust → Dart：多策略组测试完成
+#[derive(Serialize, RustSignal)]
+pub struct MultiGroupDelayTestComplete {
+    pub request_id: i64,
+    pub is_successful: bool,
+    pub is_cancelled: bool,
+    pub groups: Vec<GroupDelaySummary>,
+    // 代理树中不存在的策略组
+    pub missing_groups: Vec<String>,
+    // 去重后实际测试的节点数
+    pub tested_node_count: u32,
     pub error_message: Option<String>,
 }
 
+// 回退到逐节点测试时的并发数
+const SELECT_FASTEST_CONCURRENCY: usize = 8;
+// 快速测试只检查手动选择的策略组
+const QUICK_TEST_GROUP_TYPE: &str = "Selector";
+
+// 强制门户检测地址：明文 HTTP，正常网络下必定返回空的 204 响应
+const CAPTIVE_PORTAL_CHECK_URL: &str = "http://cp.cloudflare.com/generate_204";
+const CAPTIVE_PORTAL_CHECK_TIMEOUT_MS: u32 = 3000;
+
+// 进度合并发送的最短间隔，避免过小的间隔退化为逐条发送
+const MIN_PROGRESS_BATCH_INTERVAL: Duration = Duration::from_millis(50);
+// 测速请求路径（含编码后的节点名称与测试地址）的长度上限。
+// 部分核心构建或中间层对请求行长度限制较严，超出时请求可能被拒绝或截断，
+// 表现为难以定位的超时；8 KiB 与常见 HTTP 服务端的请求行上限一致
+const MAX_DELAY_PATH_LEN: usize = 8 * 1024;
+
 // 批量测试结果
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct BatchTestResult {
     pub node_name: String,
     pub delay_ms: i32,
+    // IPC 层失败（核心不可达），区别于节点本身超时
+    pub is_ipc_error: bool,
+    pub timing: DelayTiming,
+    pub tested_url: Option<String>,
+}
+
+// 延迟分阶段耗时：DNS 解析、TCP 连接、首字节。
+// 经代理的节点由核心测试，只返回总延迟，各阶段均为 None；
+// DIRECT 出站使用明文 HTTP 测试地址时由本机直连测量，各阶段与总延迟来自同一条连接
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DelayTiming {
+    pub dns_ms: Option<u32>,
+    pub connect_ms: Option<u32>,
+    pub first_byte_ms: Option<u32>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -118,6 +372,8 @@ impl DelayTestSessionState {
 struct DelayTestSessionHandle {
     request_id: i64,
     cancel_rx: watch::Receiver<bool>,
+    // 逐节点测试时是否在节点开始前发送 DelayTestStarted
+    should_report_started: bool,
 }
 
 impl DelayTestSessionHandle {
@@ -131,7 +387,9 @@ impl DelayTestSessionHandle {
 }
 
 enum NodeDelayTestOutcome {
-    Completed(i32),
+    // 延迟、分阶段耗时、测试成功时所用的测试地址
+    Completed(i32, DelayTiming, Option<String>),
+    IpcFailed,
     Cancelled,
 }
 
@@ -140,12 +398,84 @@ enum BatchNodeTestOutcome {
     Cancelled { node_name: String },
 }
 
+// 进度合并器：累计已完成的节点结果，达到数量上限或定时器触发时整批发送
+struct ProgressBatcher {
+    request_id: i64,
+    max_results: usize,
+    acceptable_ms: Option<u32>,
+    pending: Mutex<Vec<DelayTestProgressEntry>>,
+}
+
+impl ProgressBatcher {
+    fn new(request_id: i64, max_results: u32, acceptable_ms: Option<u32>) -> Self {
+        Self {
+            request_id,
+            max_results: max_results.max(1) as usize,
+            acceptable_ms,
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn lock_pending(&self) -> MutexGuard<'_, Vec<DelayTestProgressEntry>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn push(&self, result: &BatchTestResult) {
+        let full_batch = {
+            let mut pending = self.lock_pending();
+            pending.push(DelayTestProgressEntry {
+                node_name: result.node_name.clone(),
+                delay_ms: result.delay_ms,
+                status: DelayStatus::from_delay(result.delay_ms, self.acceptable_ms),
+                tested_url: result.tested_url.clone(),
+                dns_ms: result.timing.dns_ms,
+                connect_ms: result.timing.connect_ms,
+                first_byte_ms: result.timing.first_byte_ms,
+            });
+            (pending.len() >= self.max_results).then(|| std::mem::take(&mut *pending))
+        };
+
+        if let Some(results) = full_batch {
+            self.send(results);
+        }
+    }
+
+    fn flush(&self) {
+        let results = std::mem::take(&mut *self.lock_pending());
+        if !results.is_empty() {
+            self.send(results);
+        }
+    }
+
+    // 丢弃未发送的结果（测试已取消）
+    fn discard(&self) {
+        self.lock_pending().clear();
+    }
+
+    fn send(&self, results: Vec<DelayTestProgressEntry>) {
+        DelayTestProgressBatch {
+            request_id: self.request_id,
+            results,
+        }
+        .send_signal_to_dart();
+    }
+}
+
 static DELAY_TEST_SESSIONS: Lazy<Mutex<HashMap<i64, DelayTestSessionState>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
 pub fn init() {
-    // 取消测速请求监听器
+    // Dart 热重启后 Rust 入口会再次运行，上一次运行的测速会话已随旧 Dart 端失效
+    discard_stale_sessions();
+
+    // 应用关闭时停止进行中的测速；Dart 端已关闭，不再发送完成信号
     spawn(async {
+        shutdown::wait_for_shutdown().await;
+        cancel_all_sessions();
+    });
+
+    // 取消测速请求监听器
+    shutdown::spawn_listener(async {
         let receiver = CancelDelayTestsRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             spawn(async move {
@@ -156,7 +486,7 @@ pub fn init() {
     });
 
     // 单节点延迟测试请求监听器
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = SingleDelayTestRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             spawn(async move {
@@ -167,7 +497,7 @@ pub fn init() {
     });
 
     // 批量延迟测试请求监听器
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = BatchDelayTestRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             spawn(async move {
@@ -176,6 +506,39 @@ pub fn init() {
         }
         log::info!("批量延迟测试消息通道已关闭，退出监听器");
     });
+
+    // 测试并选中最快节点请求监听器
+    shutdown::spawn_listener(async {
+        let receiver = TestAndSelectFastest::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            spawn(async move {
+                handle_test_and_select_fastest(dart_signal.message).await;
+            });
+        }
+        log::info!("测试并选中最快节点消息通道已关闭，退出监听器");
+    });
+
+    // 多策略组延迟测试请求监听器
+    shutdown::spawn_listener(async {
+        let receiver = MultiGroupDelayTestRequest::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            spawn(async move {
+                handle_multi_group_delay_test_request(dart_signal.message).await;
+            });
+        }
+        log::info!("多策略组延迟测试消息通道已关闭，退出监听器");
+    });
+
+    // 快速测试请求监听器
+    shutdown::spawn_listener(async {
+        let receiver = QuickTest::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            spawn(async move {
+                handle_quick_test(dart_signal.message).await;
+            });
+        }
+        log::info!("快速测试消息通道已关闭，退出监听器");
+    });
 }
 
 fn lock_delay_test_sessions() -> MutexGuard<'static, HashMap<i64, DelayTestSessionState>> {
@@ -204,6 +567,7 @@ fn register_delay_test_session(
     DelayTestSessionHandle {
         request_id,
         cancel_rx,
+        should_report_started: false,
     }
 }
 
@@ -227,6 +591,35 @@ fn cancel_delay_test_session(request_id: i64) {
     }
 }
 
+fn cancel_all_sessions() {
+    let mut sessions = lock_delay_test_sessions();
+    let mut cancelled = 0;
+    for session in sessions.values_mut() {
+        if !session.is_cancelled {
+            session.cancel();
+            cancelled += 1;
+        }
+    }
+
+    if cancelled > 0 {
+        log::info!("应用关闭，已停止 {} 个测速会话", cancelled);
+    }
+}
+
+// 取消并清空上一次运行遗留的测速会话，新的 Dart 端不会再等待它们的结果
+fn discard_stale_sessions() {
+    let mut sessions = lock_delay_test_sessions();
+    if sessions.is_empty() {
+        return;
+    }
+
+    log::info!("清理上一次运行遗留的 {} 个测速会话", sessions.len());
+    for session in sessions.values_mut() {
+        session.cancel();
+    }
+    sessions.clear();
+}
+
 fn finish_delay_test_session(session: &DelayTestSessionHandle) -> bool {
     let mut sessions = lock_delay_test_sessions();
     let Some(active_session) = sessions.remove(&session.request_id) else {
@@ -251,8 +644,9 @@ async fn wait_for_delay_test_cancel(mut cancel_rx: watch::Receiver<bool>) {
 async fn test_single_node_with_cancel(
     request_id: i64,
     node_name: &str,
-    test_url: &str,
+    test_urls: &[String],
     timeout_ms: u32,
+    expected_status: Option<u16>,
     cancel_rx: watch::Receiver<bool>,
 ) -> NodeDelayTestOutcome {
     tokio::select! {
@@ -261,8 +655,13 @@ async fn test_single_node_with_cancel(
             log::info!("节点延迟测试已取消：request_id={}，{}", request_id, node_name);
             NodeDelayTestOutcome::Cancelled
         }
-        delay_ms = test_single_node(node_name, test_url, timeout_ms) => {
-            NodeDelayTestOutcome::Completed(delay_ms)
+        result = test_single_node(node_name, test_urls, timeout_ms, expected_status) => match result {
+            Ok((delay_ms, timing, tested_url)) => {
+                record_delay(node_name, delay_ms);
+                NodeDelayTestOutcome::Completed(delay_ms, timing, tested_url)
+            }
+            // IPC 失败不代表节点延迟，不计入历史
+            Err(_) => NodeDelayTestOutcome::IpcFailed,
         }
     }
 }
@@ -278,7 +677,10 @@ async fn handle_single_delay_test_request(request: SingleDelayTestRequest) {
         request_id,
         node_name,
         test_url,
+        fallback_test_urls,
         timeout_ms,
+        expected_status,
+        acceptable_ms,
     } = request;
 
     log::info!(
@@ -290,18 +692,24 @@ async fn handle_single_delay_test_request(request: SingleDelayTestRequest) {
     );
 
     let session = register_delay_test_session(request_id, DelayTestSessionKind::Single);
+    let test_urls = test_url_chain(test_url, fallback_test_urls);
 
     let outcome = test_single_node_with_cancel(
         request_id,
         &node_name,
-        &test_url,
+        &test_urls,
         timeout_ms,
+        expected_status,
         session.subscribe(),
     )
     .await;
-    let delay_ms = match outcome {
-        NodeDelayTestOutcome::Completed(delay_ms) => delay_ms,
-        NodeDelayTestOutcome::Cancelled => -1,
+    let (delay_ms, timing, tested_url) = match outcome {
+        NodeDelayTestOutcome::Completed(delay_ms, timing, ref tested_url) => {
+            (delay_ms, timing, tested_url.clone())
+        }
+        NodeDelayTestOutcome::IpcFailed | NodeDelayTestOutcome::Cancelled => {
+            (-1, DelayTiming::default(), None)
+        }
     };
     let is_cancelled =
         matches!(outcome, NodeDelayTestOutcome::Cancelled) || finish_delay_test_session(&session);
@@ -310,7 +718,12 @@ async fn handle_single_delay_test_request(request: SingleDelayTestRequest) {
         request_id,
         node_name,
         delay_ms,
+        status: DelayStatus::from_delay(delay_ms, acceptable_ms),
         is_cancelled,
+        tested_url,
+        dns_ms: timing.dns_ms,
+        connect_ms: timing.connect_ms,
+        first_byte_ms: timing.first_byte_ms,
     }
     .send_signal_to_dart();
 }
@@ -321,13 +734,53 @@ async fn handle_batch_delay_test_request(request: BatchDelayTestRequest) {
         request_id,
         node_names,
         test_url,
+        fallback_test_urls,
         timeout_ms,
         concurrency,
+        expected_status,
+        acceptable_ms,
+        group_name,
+        progress_batching,
+        nested_group_policy,
+        result_log,
+        should_report_started,
     } = request;
 
+    let (node_names, skipped_nodes, can_use_group_delay) =
+        apply_nested_group_policy(node_names, nested_group_policy).await;
+
     let total_count = node_names.len() as u32;
+
+    // 全部测试地址都过长时直接结束，避免每个节点都以超时告终
+    if let Some(e) = oversized_test_urls_error(&test_url, &fallback_test_urls, timeout_ms) {
+        log::error!("批量延迟测试无法进行：request_id={}，{}", request_id, e);
+        BatchDelayTestComplete {
+            request_id,
+            is_successful: false,
+            is_cancelled: false,
+            total_count,
+            success_count: 0,
+            skipped_nodes,
+            error_message: Some(e),
+        }
+        .send_signal_to_dart();
+        return;
+    }
+
     let requested_concurrency = concurrency.max(1) as usize;
-    let actual_concurrency = requested_concurrency.min(node_names.len().max(1));
+    // 并发数不超过批量请求可用的连接数：超出部分只会在 IPC 调度处排队，
+    // 剩余连接留给测试期间的界面操作
+    let bulk_capacity = IpcClient::bulk_capacity();
+    if requested_concurrency > bulk_capacity {
+        log::info!(
+            "请求并发数 {} 超过批量 IPC 请求可用的连接数 {}，按可用连接数执行",
+            requested_concurrency,
+            bulk_capacity
+        );
+    }
+    let actual_concurrency = requested_concurrency
+        .min(bulk_capacity)
+        .min(node_names.len().max(1));
 
     log::info!(
         "收到批量延迟测试请求：request_id={}，节点数：{}，并发数：{}（请求 {}），timeout {}ms，url={}",
@@ -339,51 +792,190 @@ async fn handle_batch_delay_test_request(request: BatchDelayTestRequest) {
         test_url
     );
 
-    let session = register_delay_test_session(request_id, DelayTestSessionKind::Batch);
+    let mut session = register_delay_test_session(request_id, DelayTestSessionKind::Batch);
+    session.should_report_started = should_report_started;
+    // 注册前已开始关闭时不会被统一停止，这里自行取消
+    if shutdown::is_shutting_down() {
+        cancel_delay_test_session(request_id);
+    }
+
+    // 每批只检测一次强制门户，与测试并行进行，不推迟第一批结果；
+    // 检测到时仍继续测试，由 UI 提示结果不可信
+    let portal_session = session.clone();
+    let captive_portal_check = spawn(async move {
+        if !detect_captive_portal().await || portal_session.is_cancelled() {
+            return;
+        }
+        log::warn!(
+            "检测到强制门户，延迟测试结果可能不可信：request_id={}",
+            request_id
+        );
+        CaptivePortalDetected {
+            request_id,
+            check_url: CAPTIVE_PORTAL_CHECK_URL.to_string(),
+        }
+        .send_signal_to_dart();
+    });
+
+    // 进度回调：每个节点测试完成后发送进度信号，启用合并时交给合并器定时整批发送
+    let progress_batcher = progress_batching.map(|batching| {
+        let batcher = Arc::new(ProgressBatcher::new(
+            request_id,
+            batching.max_results,
+            acceptable_ms,
+        ));
+        let interval =
+            Duration::from_millis(u64::from(batching.interval_ms)).max(MIN_PROGRESS_BATCH_INTERVAL);
+        let ticker_batcher = batcher.clone();
+        let ticker_session = session.clone();
+        let ticker = spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if !ticker_session.is_cancelled() {
+                    ticker_batcher.flush();
+                }
+            }
+        });
+        (batcher, ticker)
+    });
+
+    // 结果文件打开失败不影响测试本身
+    let result_log_writer = match result_log {
+        Some(ref options) => match ResultLogWriter::open(options).await {
+            Ok(writer) => Some(writer),
+            Err(e) => {
+                log::warn!("批量测试结果文件不可用，本次不记录：{}", e);
+                None
+            }
+        },
+        None => None,
+    };
+    let result_log_sink = result_log_writer.as_ref().map(ResultLogWriter::sink);
 
-    // 进度回调：每个节点测试完成后发送进度信号。
     let progress_session = session.clone();
-    let on_progress = Arc::new(move |node_name: String, delay_ms: i32| {
+    let batcher = progress_batcher
+        .as_ref()
+        .map(|(batcher, _)| batcher.clone());
+    let on_progress = Arc::new(move |result: &BatchTestResult| {
+        // 已完成的结果即使测试随后被取消也写入文件
+        if let Some(ref sink) = result_log_sink {
+            sink.push(result);
+        }
+
         if progress_session.is_cancelled() {
             log::debug!(
                 "批量延迟测试已取消，跳过进度信号：request_id={}，{}",
                 request_id,
-                node_name
+                result.node_name
             );
             return;
         }
 
+        if let Some(ref batcher) = batcher {
+            batcher.push(result);
+            return;
+        }
+
         DelayTestProgress {
             request_id,
-            node_name,
-            delay_ms,
+            node_name: result.node_name.clone(),
+            delay_ms: result.delay_ms,
+            status: DelayStatus::from_delay(result.delay_ms, acceptable_ms),
+            tested_url: result.tested_url.clone(),
+            dns_ms: result.timing.dns_ms,
+            connect_ms: result.timing.connect_ms,
+            first_byte_ms: result.timing.first_byte_ms,
         }
         .send_signal_to_dart();
     });
 
+    // 优先使用核心的策略组测速接口，不可用时回退到逐节点测试。
+    // 策略组接口只能使用单个测试地址，配置了备用地址时逐节点测试
+    let group_results = match group_name.as_deref() {
+        Some(group)
+            if !group.is_empty() && fallback_test_urls.is_empty() && can_use_group_delay =>
+        {
+            // 核心同时测试组内全部节点；回退到逐节点测试时会再次发送，Dart 端按节点名去重
+            if should_report_started && !session.is_cancelled() {
+                for node_name in &node_names {
+                    report_node_started(request_id, node_name);
+                }
+            }
+            test_group_delays(
+                &session,
+                group,
+                &node_names,
+                &test_url,
+                timeout_ms,
+                expected_status,
+                on_progress.as_ref(),
+            )
+            .await
+        }
+        _ => None,
+    };
+
     // 执行批量测试
-    let results = batch_test_delays(
-        session.clone(),
-        node_names,
-        test_url,
-        timeout_ms,
-        actual_concurrency,
-        on_progress,
-    )
-    .await;
+    let results = match group_results {
+        Some(results) => results,
+        None => {
+            batch_test_delays(
+                session.clone(),
+                node_names,
+                test_url_chain(test_url, fallback_test_urls),
+                timeout_ms,
+                expected_status,
+                actual_concurrency,
+                on_progress.clone(),
+            )
+            .await
+        }
+    };
+
+    // 释放进度回调持有的 sink 后等待结果文件写完
+    drop(on_progress);
+    if let Some(writer) = result_log_writer {
+        writer.finish().await;
+    }
 
     // 统计成功数量
     let success_count = results.iter().filter(|result| result.delay_ms > 0).count() as u32;
     let is_cancelled = session.is_cancelled() || finish_delay_test_session(&session);
+    let ipc_failure_message = summarize_ipc_failures(&results);
+
+    if let Some(ref message) = ipc_failure_message {
+        log::error!("批量延迟测试异常：request_id={}，{}", request_id, message);
+    }
+
+    // 强制门户提示须早于完成信号；已取消时不再等待
+    if is_cancelled {
+        captive_portal_check.abort();
+    }
+    let _ = captive_portal_check.await;
+
+    // 完成信号之前发送剩余的合并进度
+    if let Some((batcher, ticker)) = progress_batcher {
+        // 等待定时任务真正结束，保证不会有批次晚于完成信号发出
+        ticker.abort();
+        let _ = ticker.await;
+        if is_cancelled {
+            batcher.discard();
+        } else {
+            batcher.flush();
+        }
+    }
 
     // 发送完成信号
     BatchDelayTestComplete {
         request_id,
-        is_successful: !is_cancelled,
+        is_successful: !is_cancelled && ipc_failure_message.is_none(),
         is_cancelled,
         total_count,
         success_count,
-        error_message: None,
+        skipped_nodes,
+        error_message: ipc_failure_message,
     }
     .send_signal_to_dart();
 
@@ -396,52 +988,747 @@ async fn handle_batch_delay_test_request(request: BatchDelayTestRequest) {
     );
 }
 
-// 批量延迟测试：并发受限的滑动窗口。
-// 返回所有节点的测试结果列表。
-async fn batch_test_delays(
-    session: DelayTestSessionHandle,
-    node_names: Vec<String>,
-    test_url: String,
-    timeout_ms: u32,
-    concurrency: usize,
-    on_progress: Arc<dyn Fn(String, i32) + Send + Sync>,
-) -> Vec<BatchTestResult> {
-    if node_names.is_empty() {
-        log::warn!("批量延迟测试：节点列表为空");
-        return Vec::new();
+// 经 DIRECT 出站请求 generate_204：要求 204 时失败、不限状态码时成功，
+// 说明响应被改写为重定向或登录页，即处于强制门户之后
+async fn detect_captive_portal() -> bool {
+    match query_node_delay(
+        "DIRECT",
+        CAPTIVE_PORTAL_CHECK_URL,
+        CAPTIVE_PORTAL_CHECK_TIMEOUT_MS,
+        Some(204),
+    )
+    .await
+    {
+        // 正常网络；IPC 失败由后续测试报告
+        Ok(delay_ms) if delay_ms > 0 => return false,
+        Err(_) => return false,
+        Ok(_) => {}
     }
 
-    let total = node_names.len();
-    let test_url = Arc::new(test_url);
-    let mut pending_tasks = JoinSet::new();
-    let mut remaining_nodes: VecDeque<(usize, String)> =
-        node_names.into_iter().enumerate().collect();
-    let mut results = Vec::new();
-
-    loop {
-        while pending_tasks.len() < concurrency && !session.is_cancelled() {
-            let Some((index, node_name)) = remaining_nodes.pop_front() else {
-                break;
-            };
+    matches!(
+        query_node_delay(
+            "DIRECT",
+            CAPTIVE_PORTAL_CHECK_URL,
+            CAPTIVE_PORTAL_CHECK_TIMEOUT_MS,
+            None,
+        )
+        .await,
+        Ok(delay_ms) if delay_ms > 0
+    )
+}
 
-            let node_session = session.clone();
-            let test_url = Arc::clone(&test_url);
-            pending_tasks.spawn(async move {
-                log::debug!("开始测试节点 ({}/{}): {}", index + 1, total, node_name);
+// 按策略处理成员中的自动选择类策略组，返回（待测节点，跳过的策略组，能否使用策略组测速接口）。
+// 成员列表发生变化时核心的策略组接口结果不再对应，需逐节点测试
+async fn apply_nested_group_policy(
+    node_names: Vec<String>,
+    policy: Option<NestedGroupPolicy>,
+) -> (Vec<String>, Vec<String>, bool) {
+    let policy = match policy {
+        None | Some(NestedGroupPolicy::Probe) => return (node_names, Vec::new(), true),
+        Some(policy) => policy,
+    };
 
-                match test_single_node_with_cancel(
-                    node_session.request_id,
-                    &node_name,
-                    test_url.as_str(),
-                    timeout_ms,
-                    node_session.subscribe(),
+    match get_proxies_snapshot().await {
+        Ok(groups) => {
+            let expansion = expand_nested_groups(node_names, &groups, policy);
+            if expansion.is_changed {
+                log::info!(
+                    "嵌套策略组已按 {:?} 处理：待测节点 {} 个，跳过 {} 个",
+                    policy,
+                    expansion.node_names.len(),
+                    expansion.skipped_nodes.len()
+                );
+            }
+            (
+                expansion.node_names,
+                expansion.skipped_nodes,
+                !expansion.is_changed,
+            )
+        }
+        Err(e) => {
+            log::warn!("获取代理树失败，按原节点列表测试：{}", e);
+            (node_names, Vec::new(), true)
+        }
+    }
+}
+
+#[derive(Default)]
+struct NestedGroupExpansion {
+    node_names: Vec<String>,
+    skipped_nodes: Vec<String>,
+    is_changed: bool,
+    seen_nodes: HashSet<String>,
+    visited_groups: HashSet<String>,
+}
+
+fn is_auto_select_group(group_type: &str) -> bool {
+    matches!(
+        group_type.to_ascii_lowercase().as_str(),
+        "urltest" | "url-test" | "fallback" | "loadbalance" | "load-balance"
+    )
+}
+
+// 展开或跳过自动选择类策略组；展开时递归处理，节点去重并防止策略组循环引用
+fn expand_nested_groups(
+    node_names: Vec<String>,
+    groups: &[ProxyGroupInfo],
+    policy: NestedGroupPolicy,
+) -> NestedGroupExpansion {
+    let auto_groups: HashMap<&str, &ProxyGroupInfo> = groups
+        .iter()
+        .filter(|group| is_auto_select_group(&group.group_type))
+        .map(|group| (group.name.as_str(), group))
+        .collect();
+
+    let mut expansion = NestedGroupExpansion::default();
+    for node_name in &node_names {
+        expand_member(node_name, &auto_groups, policy, &mut expansion);
+    }
+    expansion
+}
+
+fn expand_member(
+    name: &str,
+    auto_groups: &HashMap<&str, &ProxyGroupInfo>,
+    policy: NestedGroupPolicy,
+    expansion: &mut NestedGroupExpansion,
+) {
+    match (auto_groups.get(name), policy) {
+        (Some(_), NestedGroupPolicy::Skip) => {
+            expansion.is_changed = true;
+            if !expansion
+                .skipped_nodes
+                .iter()
+                .any(|skipped| skipped == name)
+            {
+                expansion.skipped_nodes.push(name.to_string());
+            }
+        }
+        (Some(group), NestedGroupPolicy::Expand) => {
+            expansion.is_changed = true;
+            if expansion.visited_groups.insert(name.to_string()) {
+                for member in &group.members {
+                    expand_member(&member.name, auto_groups, policy, expansion);
+                }
+            }
+        }
+        _ => {
+            if expansion.seen_nodes.insert(name.to_string()) {
+                expansion.node_names.push(name.to_string());
+            }
+        }
+    }
+}
+
+// 处理测试并选中最快节点请求：复用策略组测速路径，再提交节点切换
+async fn handle_test_and_select_fastest(request: TestAndSelectFastest) {
+    let TestAndSelectFastest {
+        request_id,
+        group,
+        test_url,
+        timeout_ms,
+        preferred_tags,
+    } = request;
+
+    log::info!(
+        "收到测试并选中最快节点请求：request_id={}，{}（timeout {}ms，url={}，优先标签 {:?}）",
+        request_id,
+        group,
+        timeout_ms,
+        test_url,
+        preferred_tags
+    );
+
+    let session = register_delay_test_session(request_id, DelayTestSessionKind::Batch);
+    let result =
+        test_and_select_fastest(&session, &group, &test_url, timeout_ms, &preferred_tags).await;
+    let is_cancelled = session.is_cancelled() || finish_delay_test_session(&session);
+
+    let (selected, delay_ms, is_preferred_match, error_message) = match result {
+        _ if is_cancelled => (None, -1, false, Some("测试已取消".to_string())),
+        Ok(fastest) => {
+            log::info!(
+                "策略组 {} 已自动选中最快节点：{}（{}ms，匹配优先标签：{}）",
+                group,
+                fastest.node_name,
+                fastest.delay_ms,
+                fastest.is_preferred_match
+            );
+            (
+                Some(fastest.node_name),
+                fastest.delay_ms,
+                fastest.is_preferred_match,
+                None,
+            )
+        }
+        Err(e) => {
+            log::warn!("策略组 {} 自动选中最快节点失败：{}", group, e);
+            (None, -1, false, Some(e))
+        }
+    };
+
+    TestAndSelectFastestResult {
+        request_id,
+        group,
+        is_successful: selected.is_some(),
+        selected,
+        delay_ms,
+        is_preferred_match,
+        is_cancelled,
+        error_message,
+    }
+    .send_signal_to_dart();
+}
+
+// 选中的最快节点
+#[derive(Debug, PartialEq, Eq)]
+struct FastestNode {
+    node_name: String,
+    delay_ms: i32,
+    is_preferred_match: bool,
+}
+
+// 测试组内全部节点并选中延迟最低的可用节点（优先匹配标签的节点）
+async fn test_and_select_fastest(
+    session: &DelayTestSessionHandle,
+    group: &str,
+    test_url: &str,
+    timeout_ms: u32,
+    preferred_tags: &[String],
+) -> Result<FastestNode, String> {
+    let request_id = session.request_id;
+    let progress_session = session.clone();
+    let on_progress = Arc::new(move |result: &BatchTestResult| {
+        if progress_session.is_cancelled() {
+            return;
+        }
+
+        DelayTestProgress {
+            request_id,
+            node_name: result.node_name.clone(),
+            delay_ms: result.delay_ms,
+            status: DelayStatus::from_delay(result.delay_ms, None),
+            tested_url: result.tested_url.clone(),
+            dns_ms: result.timing.dns_ms,
+            connect_ms: result.timing.connect_ms,
+            first_byte_ms: result.timing.first_byte_ms,
+        }
+        .send_signal_to_dart();
+    });
+
+    let results = match test_group_delays(
+        session,
+        group,
+        &[],
+        test_url,
+        timeout_ms,
+        None,
+        on_progress.as_ref(),
+    )
+    .await
+    {
+        Some(results) => results,
+        None => {
+            let members = get_group_members(group).await?;
+            let concurrency = SELECT_FASTEST_CONCURRENCY.min(members.len().max(1));
+            batch_test_delays(
+                session.clone(),
+                members,
+                vec![test_url.to_string()],
+                timeout_ms,
+                None,
+                concurrency,
+                on_progress,
+            )
+            .await
+        }
+    };
+
+    if session.is_cancelled() {
+        return Err("测试已取消".to_string());
+    }
+    if let Some(message) = summarize_ipc_failures(&results) {
+        return Err(message);
+    }
+
+    let fastest = pick_fastest(&results, preferred_tags)
+        .ok_or_else(|| format!("策略组 {} 中没有可用节点，保持原选择", group))?;
+
+    select_proxy(group, &fastest.node_name).await?;
+    Ok(fastest)
+}
+
+// 先在名称匹配优先标签的可用节点中取最低延迟，没有匹配时回退到全部可用节点
+fn pick_fastest(results: &[BatchTestResult], preferred_tags: &[String]) -> Option<FastestNode> {
+    let tags: Vec<String> = preferred_tags
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    let is_preferred = |node_name: &str| {
+        let node_name = node_name.to_lowercase();
+        tags.iter().any(|tag| node_name.contains(tag.as_str()))
+    };
+    let fastest_where = |predicate: &dyn Fn(&BatchTestResult) -> bool| {
+        results
+            .iter()
+            .filter(|result| result.delay_ms > 0 && predicate(result))
+            .min_by_key(|result| result.delay_ms)
+    };
+
+    if !tags.is_empty() {
+        if let Some(result) = fastest_where(&|result| is_preferred(&result.node_name)) {
+            return Some(FastestNode {
+                node_name: result.node_name.clone(),
+                delay_ms: result.delay_ms,
+                is_preferred_match: true,
+            });
+        }
+        log::info!("没有匹配优先标签 {:?} 的可用节点，回退到全组最快", tags);
+    }
+
+    fastest_where(&|_| true).map(|result| FastestNode {
+        node_name: result.node_name.clone(),
+        delay_ms: result.delay_ms,
+        is_preferred_match: false,
+    })
+}
+
+// 处理多策略组延迟测试请求：一次获取代理树，节点去重后统一测试，再按策略组分发结果
+async fn handle_multi_group_delay_test_request(request: MultiGroupDelayTestRequest) {
+    let MultiGroupDelayTestRequest {
+        request_id,
+        groups,
+        test_url,
+        timeout_ms,
+        concurrency,
+        acceptable_ms,
+    } = request;
+
+    log::info!(
+        "收到多策略组延迟测试请求：request_id={}，策略组 {} 个，timeout {}ms，url={}",
+        request_id,
+        groups.len(),
+        timeout_ms,
+        test_url
+    );
+
+    let session = register_delay_test_session(request_id, DelayTestSessionKind::Batch);
+    if shutdown::is_shutting_down() {
+        cancel_delay_test_session(request_id);
+    }
+
+    let snapshot = get_proxies_snapshot().await;
+    let index = match snapshot {
+        Ok(ref snapshot) => GroupNodeIndex::build(&groups, snapshot),
+        Err(ref e) => {
+            log::error!("多策略组延迟测试获取代理树失败：{}", e);
+            GroupNodeIndex::default()
+        }
+    };
+    let tested_node_count = index.node_names.len() as u32;
+    if !index.missing_groups.is_empty() {
+        log::warn!("以下策略组不存在，已跳过：{:?}", index.missing_groups);
+    }
+
+    let node_groups = Arc::new(index.node_groups);
+    let progress_session = session.clone();
+    let progress_node_groups = node_groups.clone();
+    let on_progress = Arc::new(move |result: &BatchTestResult| {
+        if progress_session.is_cancelled() {
+            return;
+        }
+
+        MultiGroupDelayTestProgress {
+            request_id,
+            node_name: result.node_name.clone(),
+            groups: progress_node_groups
+                .get(&result.node_name)
+                .cloned()
+                .unwrap_or_default(),
+            delay_ms: result.delay_ms,
+            status: DelayStatus::from_delay(result.delay_ms, acceptable_ms),
+            tested_url: result.tested_url.clone(),
+        }
+        .send_signal_to_dart();
+    });
+
+    let actual_concurrency = (concurrency.max(1) as usize)
+        .min(IpcClient::bulk_capacity())
+        .min(index.node_names.len().max(1));
+    let results = batch_test_delays(
+        session.clone(),
+        index.node_names,
+        vec![test_url],
+        timeout_ms,
+        None,
+        actual_concurrency,
+        on_progress,
+    )
+    .await;
+
+    let is_cancelled = session.is_cancelled() || finish_delay_test_session(&session);
+    let error_message = match snapshot {
+        Err(e) => Some(e),
+        Ok(_) => summarize_ipc_failures(&results),
+    };
+
+    // 按策略组汇总：共享节点的结果计入每个包含它的策略组
+    let summaries: Vec<GroupDelaySummary> = index
+        .group_members
+        .into_iter()
+        .map(|(group, members)| {
+            let success_count = results
+                .iter()
+                .filter(|result| result.delay_ms > 0 && members.contains(&result.node_name))
+                .count() as u32;
+            GroupDelaySummary {
+                group,
+                total_count: members.len() as u32,
+                success_count,
+            }
+        })
+        .collect();
+
+    log::info!(
+        "多策略组延迟测试完成：request_id={}，策略组 {} 个，实际测试节点 {} 个，is_cancelled={}",
+        request_id,
+        summaries.len(),
+        tested_node_count,
+        is_cancelled
+    );
+
+    MultiGroupDelayTestComplete {
+        request_id,
+        is_successful: !is_cancelled && error_message.is_none(),
+        is_cancelled,
+        groups: summaries,
+        missing_groups: index.missing_groups,
+        tested_node_count,
+        error_message,
+    }
+    .send_signal_to_dart();
+}
+
+// 多策略组测试的节点索引
+#[derive(Default)]
+struct GroupNodeIndex {
+    // 去重后的待测节点，按首次出现顺序
+    node_names: Vec<String>,
+    // 节点 → 包含它的请求策略组
+    node_groups: HashMap<String, Vec<String>>,
+    // 策略组 → 成员，按请求顺序；不含嵌套策略组与内置出站
+    group_members: Vec<(String, HashSet<String>)>,
+    missing_groups: Vec<String>,
+}
+
+// 核心内置出站，不是可测速的节点
+fn is_builtin_outbound(name: &str) -> bool {
+    matches!(
+        name,
+        "DIRECT" | "REJECT" | "REJECT-DROP" | "PASS" | "COMPATIBLE"
+    )
+}
+
+impl GroupNodeIndex {
+    // 嵌套策略组与内置出站不计入待测节点，嵌套策略组的节点只在请求包含该组时测试
+    fn build(groups: &[String], snapshot: &[ProxyGroupInfo]) -> Self {
+        let group_names: HashSet<&str> = snapshot.iter().map(|group| group.name.as_str()).collect();
+
+        let mut index = Self::default();
+        for group_name in groups {
+            if index
+                .group_members
+                .iter()
+                .any(|(name, _)| name == group_name)
+            {
+                continue;
+            }
+            let Some(group) = snapshot.iter().find(|group| group.name == *group_name) else {
+                if !index.missing_groups.contains(group_name) {
+                    index.missing_groups.push(group_name.clone());
+                }
+                continue;
+            };
+
+            let mut members = HashSet::new();
+            for member in &group.members {
+                if group_names.contains(member.name.as_str())
+                    || is_builtin_outbound(&member.name)
+                    || !members.insert(member.name.clone())
+                {
+                    continue;
+                }
+                let owners = index.node_groups.entry(member.name.clone()).or_default();
+                if owners.is_empty() {
+                    index.node_names.push(member.name.clone());
+                }
+                owners.push(group_name.clone());
+            }
+            index.group_members.push((group_name.clone(), members));
+        }
+        index
+    }
+}
+
+// 处理快速测试请求：只测试各 Selector 组选中链末端的实际节点，复用批量测试路径
+async fn handle_quick_test(request: QuickTest) {
+    let QuickTest {
+        request_id,
+        test_url,
+        timeout_ms,
+        acceptable_ms,
+    } = request;
+
+    log::info!(
+        "收到快速测试请求：request_id={}，timeout {}ms，url={}",
+        request_id,
+        timeout_ms,
+        test_url
+    );
+
+    let session = register_delay_test_session(request_id, DelayTestSessionKind::Batch);
+    let result = quick_test(&session, test_url, timeout_ms, acceptable_ms).await;
+    let is_cancelled = session.is_cancelled() || finish_delay_test_session(&session);
+
+    let response = match result {
+        _ if is_cancelled => QuickTestResult {
+            request_id,
+            groups: Vec::new(),
+            is_successful: false,
+            is_cancelled,
+            error_message: Some("测试已取消".to_string()),
+        },
+        Ok(groups) => {
+            log::info!(
+                "快速测试完成：request_id={}，策略组 {} 个，可用 {} 个",
+                request_id,
+                groups.len(),
+                groups.iter().filter(|group| group.delay_ms > 0).count()
+            );
+            QuickTestResult {
+                request_id,
+                groups,
+                is_successful: true,
+                is_cancelled,
+                error_message: None,
+            }
+        }
+        Err(e) => {
+            log::warn!("快速测试失败：request_id={}，{}", request_id, e);
+            QuickTestResult {
+                request_id,
+                groups: Vec::new(),
+                is_successful: false,
+                is_cancelled,
+                error_message: Some(e),
+            }
+        }
+    };
+    response.send_signal_to_dart();
+}
+
+async fn quick_test(
+    session: &DelayTestSessionHandle,
+    test_url: String,
+    timeout_ms: u32,
+    acceptable_ms: Option<u32>,
+) -> Result<Vec<QuickTestGroupResult>, String> {
+    let groups = get_proxies_snapshot().await?;
+    let targets = quick_test_targets(&groups);
+
+    // 多个策略组可能最终指向同一节点，每个节点只测一次
+    let mut seen_nodes = HashSet::new();
+    let node_names: Vec<String> = targets
+        .iter()
+        .filter_map(|target| target.effective_node.clone())
+        .filter(|node| seen_nodes.insert(node.clone()))
+        .collect();
+
+    let request_id = session.request_id;
+    let progress_session = session.clone();
+    let on_progress = Arc::new(move |result: &BatchTestResult| {
+        if progress_session.is_cancelled() {
+            return;
+        }
+
+        DelayTestProgress {
+            request_id,
+            node_name: result.node_name.clone(),
+            delay_ms: result.delay_ms,
+            status: DelayStatus::from_delay(result.delay_ms, acceptable_ms),
+            tested_url: result.tested_url.clone(),
+            dns_ms: result.timing.dns_ms,
+            connect_ms: result.timing.connect_ms,
+            first_byte_ms: result.timing.first_byte_ms,
+        }
+        .send_signal_to_dart();
+    });
+
+    let concurrency = SELECT_FASTEST_CONCURRENCY.min(node_names.len().max(1));
+    let results = batch_test_delays(
+        session.clone(),
+        node_names,
+        vec![test_url],
+        timeout_ms,
+        None,
+        concurrency,
+        on_progress,
+    )
+    .await;
+
+    if let Some(message) = summarize_ipc_failures(&results) {
+        return Err(message);
+    }
+
+    let delays: HashMap<&str, i32> = results
+        .iter()
+        .map(|result| (result.node_name.as_str(), result.delay_ms))
+        .collect();
+    Ok(targets
+        .into_iter()
+        .map(|target| {
+            let delay_ms = target
+                .effective_node
+                .as_deref()
+                .and_then(|node| delays.get(node).copied())
+                .unwrap_or(-1);
+            QuickTestGroupResult {
+                group: target.group,
+                selected: target.selected,
+                effective_node: target.effective_node,
+                delay_ms,
+                status: DelayStatus::from_delay(delay_ms, acceptable_ms),
+            }
+        })
+        .collect())
+}
+
+// 快速测试的目标：策略组、其选中成员与选中链末端的实际节点
+#[derive(Debug, PartialEq, Eq)]
+struct QuickTestTarget {
+    group: String,
+    selected: String,
+    effective_node: Option<String>,
+}
+
+// 沿 now 逐层解析各 Selector 组的选中链，遇到循环引用时实际节点为 None
+fn quick_test_targets(groups: &[ProxyGroupInfo]) -> Vec<QuickTestTarget> {
+    let group_now: HashMap<&str, Option<&str>> = groups
+        .iter()
+        .map(|group| (group.name.as_str(), group.now.as_deref()))
+        .collect();
+
+    groups
+        .iter()
+        .filter(|group| group.group_type == QUICK_TEST_GROUP_TYPE)
+        .filter_map(|group| {
+            let selected = group.now.as_deref()?;
+            let mut visited = HashSet::from([group.name.as_str()]);
+            let mut current = selected;
+            let effective_node = loop {
+                match group_now.get(current) {
+                    None => break Some(current.to_string()),
+                    Some(_) if !visited.insert(current) => break None,
+                    Some(Some(next)) => current = next,
+                    Some(None) => break None,
+                }
+            };
+            Some(QuickTestTarget {
+                group: group.name.clone(),
+                selected: selected.to_string(),
+                effective_node,
+            })
+        })
+        .collect()
+}
+
+// IPC 失败占多数时视为核心不可达，返回描述信息；
+// 否则返回 None，失败节点按普通超时处理
+fn summarize_ipc_failures(results: &[BatchTestResult]) -> Option<String> {
+    let ipc_failure_count = results.iter().filter(|result| result.is_ipc_error).count();
+    if ipc_failure_count == 0 || ipc_failure_count * 2 <= results.len() {
+        return None;
+    }
+
+    // 结果按完成顺序排列，首个 IPC 失败之前的节点均正常完成
+    let completed_before = results
+        .iter()
+        .position(|result| result.is_ipc_error)
+        .unwrap_or(0);
+
+    Some(format!(
+        "核心不可达：第 {} 个节点之后 IPC 请求持续失败（{}/{} 个节点）",
+        completed_before,
+        ipc_failure_count,
+        results.len()
+    ))
+}
+
+// 批量延迟测试：并发受限的滑动窗口。
+// 返回所有节点的测试结果列表。
+async fn batch_test_delays(
+    session: DelayTestSessionHandle,
+    node_names: Vec<String>,
+    test_urls: Vec<String>,
+    timeout_ms: u32,
+    expected_status: Option<u16>,
+    concurrency: usize,
+    on_progress: Arc<dyn Fn(&BatchTestResult) + Send + Sync>,
+) -> Vec<BatchTestResult> {
+    if node_names.is_empty() {
+        log::warn!("批量延迟测试：节点列表为空");
+        return Vec::new();
+    }
+
+    let total = node_names.len();
+    let test_urls = Arc::new(test_urls);
+    let mut pending_tasks = JoinSet::new();
+    let mut remaining_nodes: VecDeque<(usize, String)> =
+        node_names.into_iter().enumerate().collect();
+    let mut results = Vec::new();
+
+    loop {
+        while pending_tasks.len() < concurrency && !session.is_cancelled() {
+            let Some((index, node_name)) = remaining_nodes.pop_front() else {
+                break;
+            };
+
+            if session.should_report_started {
+                report_node_started(session.request_id, &node_name);
+            }
+
+            let node_session = session.clone();
+            let test_urls = Arc::clone(&test_urls);
+            pending_tasks.spawn(async move {
+                log::debug!("开始测试节点 ({}/{}): {}", index + 1, total, node_name);
+
+                match test_single_node_with_cancel(
+                    node_session.request_id,
+                    &node_name,
+                    test_urls.as_slice(),
+                    timeout_ms,
+                    expected_status,
+                    node_session.subscribe(),
                 )
                 .await
                 {
-                    NodeDelayTestOutcome::Completed(delay_ms) => {
+                    NodeDelayTestOutcome::Completed(delay_ms, timing, tested_url) => {
                         BatchNodeTestOutcome::Completed(BatchTestResult {
                             node_name,
                             delay_ms,
+                            is_ipc_error: false,
+                            timing,
+                            tested_url,
+                        })
+                    }
+                    NodeDelayTestOutcome::IpcFailed => {
+                        BatchNodeTestOutcome::Completed(BatchTestResult {
+                            node_name,
+                            delay_ms: -1,
+                            is_ipc_error: true,
+                            timing: DelayTiming::default(),
+                            tested_url: None,
                         })
                     }
                     NodeDelayTestOutcome::Cancelled => {
@@ -457,7 +1744,7 @@ async fn batch_test_delays(
 
         match join_result {
             Ok(BatchNodeTestOutcome::Completed(result)) => {
-                on_progress(result.node_name.clone(), result.delay_ms);
+                on_progress(&result);
                 results.push(result);
             }
             Ok(BatchNodeTestOutcome::Cancelled { node_name }) => {
@@ -480,6 +1767,138 @@ async fn batch_test_delays(
     results
 }
 
+fn report_node_started(request_id: i64, node_name: &str) {
+    DelayTestStarted {
+        request_id,
+        node_name: node_name.to_string(),
+    }
+    .send_signal_to_dart();
+}
+
+// 策略组延迟测试：GET /group/{name}/delay 由核心并发测试组内全部节点。
+// 返回 None 表示核心不支持或请求失败，调用方应回退到逐节点测试。
+async fn test_group_delays(
+    session: &DelayTestSessionHandle,
+    group_name: &str,
+    node_names: &[String],
+    test_url: &str,
+    timeout_ms: u32,
+    expected_status: Option<u16>,
+    on_progress: &(dyn Fn(&BatchTestResult) + Send + Sync),
+) -> Option<Vec<BatchTestResult>> {
+    match get_core_version().await {
+        Ok(version) if version.supports_group_delay => {}
+        Ok(_) => {
+            log::debug!("核心不支持策略组测速接口，使用逐节点测试");
+            return None;
+        }
+        Err(e) => {
+            log::warn!("获取核心版本失败，使用逐节点测试：{}", e);
+            return None;
+        }
+    }
+
+    let encoded_group = match encode_name_segment(group_name) {
+        Ok(encoded_group) => encoded_group,
+        Err(e) => {
+            log::warn!("策略组名称无效，使用逐节点测试：{}", e);
+            return None;
+        }
+    };
+    let path = format!(
+        "/group/{}/delay?timeout={}&url={}{}",
+        encoded_group,
+        timeout_ms,
+        urlencoding::encode(test_url),
+        expected_query(expected_status)
+    );
+    if path.len() > MAX_DELAY_PATH_LEN {
+        log::warn!(
+            "策略组测速请求路径过长（{} 字节），使用逐节点测试：{}",
+            path.len(),
+            group_name
+        );
+        return None;
+    }
+
+    log::info!(
+        "使用策略组测速接口：request_id={}，{}",
+        session.request_id,
+        group_name
+    );
+
+    // 核心并发测试组内节点，整体耗时略高于单节点超时
+    let timeout = Duration::from_millis(timeout_ms as u64 + 2000);
+    let body = tokio::select! {
+        biased;
+        _ = wait_for_delay_test_cancel(session.subscribe()) => {
+            log::info!("策略组延迟测试已取消：request_id={}", session.request_id);
+            return Some(Vec::new());
+        }
+        response = tokio::time::timeout(timeout, IpcClient::get_with_pool(&path)) => match response {
+            Ok(Ok(body)) => body,
+            Ok(Err(e)) => {
+                log::warn!("策略组测速接口请求失败，回退到逐节点测试：{} - {}", group_name, e);
+                return None;
+            }
+            Err(_) => {
+                log::warn!("策略组测速接口请求超时，回退到逐节点测试：{}", group_name);
+                return None;
+            }
+        },
+    };
+
+    let delays: HashMap<String, i64> =
+        match serde_json::from_str::<HashMap<String, serde_json::Value>>(&body) {
+            Ok(delays) => delays
+                .into_iter()
+                .map(|(node_name, value)| {
+                    let delay = parse_delay_value(&value, 0).unwrap_or(-1);
+                    (node_name, delay)
+                })
+                .collect(),
+            Err(e) => {
+                log::warn!(
+                    "策略组测速结果解析失败，回退到逐节点测试：{} - {}",
+                    group_name,
+                    e
+                );
+                return None;
+            }
+        };
+
+    // 未出现在结果中的节点视为失败；未指定节点列表时使用核心返回的全部节点
+    let node_names: Vec<String> = if node_names.is_empty() {
+        delays.keys().cloned().collect()
+    } else {
+        node_names.to_vec()
+    };
+
+    let results = node_names
+        .into_iter()
+        .map(|node_name| {
+            let delay_ms = delays
+                .get(&node_name)
+                .copied()
+                .filter(|delay| *delay > 0)
+                .map(|delay| delay as i32)
+                .unwrap_or(-1);
+            record_delay(&node_name, delay_ms);
+            let result = BatchTestResult {
+                node_name,
+                delay_ms,
+                is_ipc_error: false,
+                timing: DelayTiming::default(),
+                tested_url: (delay_ms > 0).then(|| test_url.to_string()),
+            };
+            on_progress(&result);
+            result
+        })
+        .collect();
+
+    Some(results)
+}
+
 fn timeout_result(node_name: &str, timeout_ms: u32, elapsed_ms: u128, retry_count: u32) -> i32 {
     log::warn!(
         "节点延迟测试超时：{} - 超过 {}ms（耗时 {}ms，重试 {} 次）",
@@ -491,25 +1910,209 @@ fn timeout_result(node_name: &str, timeout_ms: u32, elapsed_ms: u128, retry_coun
     -1
 }
 
-// 测试单个节点延迟：通过 IPC 调用 Clash API。
-// GET /proxies/{proxyName}/delay?timeout={timeout}&url={testUrl}
-async fn test_single_node(node_name: &str, test_url: &str, timeout_ms: u32) -> i32 {
-    // 构建 Clash API 路径
-    let encoded_name = urlencoding::encode(node_name);
+// 主测试地址在前，去除空白与重复的备用地址
+fn test_url_chain(test_url: String, fallback_test_urls: Vec<String>) -> Vec<String> {
+    let mut test_urls = vec![test_url];
+    for url in fallback_test_urls {
+        let url = url.trim();
+        if !url.is_empty() && !test_urls.iter().any(|existing| existing == url) {
+            test_urls.push(url.to_string());
+        }
+    }
+    test_urls
+}
+
+// 单次尝试的超时：剩余预算平分给尚未尝试的地址，先失败的地址让出的时间留给后续地址
+fn attempt_timeout_ms(remaining_ms: u128, urls_left: usize) -> u32 {
+    let share = remaining_ms / urls_left.max(1) as u128;
+    u32::try_from(share).unwrap_or(u32::MAX)
+}
+
+// 测试单个节点延迟：按顺序尝试各测试地址，首个成功即返回，
+// 所有地址共享 timeout_ms 的总预算；同时返回可测量的分阶段耗时与成功时所用的测试地址
+async fn test_single_node(
+    node_name: &str,
+    test_urls: &[String],
+    timeout_ms: u32,
+    expected_status: Option<u16>,
+) -> Result<(i32, DelayTiming, Option<String>), String> {
+    let budget = Duration::from_millis(u64::from(timeout_ms));
+    let start_time = Instant::now();
+    for (index, test_url) in test_urls.iter().enumerate() {
+        let remaining_ms = budget.saturating_sub(start_time.elapsed()).as_millis();
+        let attempt_ms = attempt_timeout_ms(remaining_ms, test_urls.len() - index);
+        if attempt_ms == 0 {
+            break;
+        }
+        let direct_target = node_name
+            .eq_ignore_ascii_case("DIRECT")
+            .then(|| direct_http_target(test_url))
+            .flatten();
+        let (delay_ms, timing) = match direct_target {
+            Some((host, port, path)) => {
+                direct_http_delay(&host, port, &path, attempt_ms, expected_status).await
+            }
+            None => (
+                query_node_delay(node_name, test_url, attempt_ms, expected_status).await?,
+                DelayTiming::default(),
+            ),
+        };
+        if delay_ms > 0 {
+            if index > 0 {
+                log::debug!("节点 {} 使用备用测试地址成功：{}", node_name, test_url);
+            }
+            return Ok((delay_ms, timing, Some(test_url.clone())));
+        }
+    }
+    Ok((-1, DelayTiming::default(), None))
+}
+
+// DIRECT 可直连测量的明文 HTTP 测试地址：（主机，端口，路径与查询）。
+// HTTPS 需要 TLS 握手，仍交给核心测试
+fn direct_http_target(test_url: &str) -> Option<(String, u16, String)> {
+    let url = url::Url::parse(test_url).ok()?;
+    if url.scheme() != "http" {
+        return None;
+    }
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    Some((
+        url.host_str()?.to_string(),
+        url.port_or_known_default()?,
+        path,
+    ))
+}
+
+// DIRECT 出站不经代理，由本机在同一条连接上测量各阶段耗时，总延迟为各阶段之和
+async fn direct_http_delay(
+    host: &str,
+    port: u16,
+    path: &str,
+    timeout_ms: u32,
+    expected_status: Option<u16>,
+) -> (i32, DelayTiming) {
+    let probe = match probe_direct_http(host, port, path, timeout_ms).await {
+        Ok(probe) => probe,
+        Err(e) => {
+            log::warn!("DIRECT 直连测试失败：{}:{} - {}", host, port, e);
+            return (-1, DelayTiming::default());
+        }
+    };
+    if expected_status.is_some_and(|expected| expected != probe.status_code) {
+        log::warn!(
+            "DIRECT 直连测试状态码不符：{}:{} 返回 {}",
+            host,
+            port,
+            probe.status_code
+        );
+        return (-1, DelayTiming::default());
+    }
+
+    let delay_ms = probe
+        .dns_ms
+        .saturating_add(probe.connect_ms)
+        .saturating_add(probe.first_byte_ms)
+        .max(1);
+    (
+        i32::try_from(delay_ms).unwrap_or(i32::MAX),
+        DelayTiming {
+            dns_ms: Some(probe.dns_ms),
+            connect_ms: Some(probe.connect_ms),
+            first_byte_ms: Some(probe.first_byte_ms),
+        },
+    )
+}
+
+// 单节点测速请求路径，超出长度上限时返回错误而不是发出注定失败的请求
+fn node_delay_path(
+    node_name: &str,
+    test_url: &str,
+    timeout_ms: u32,
+    expected_status: Option<u16>,
+) -> Result<String, String> {
+    let encoded_name = encode_name_segment(node_name)?;
+    delay_path(&encoded_name, test_url, timeout_ms, expected_status)
+}
+
+fn delay_path(
+    encoded_name: &str,
+    test_url: &str,
+    timeout_ms: u32,
+    expected_status: Option<u16>,
+) -> Result<String, String> {
     let path = format!(
-        "/proxies/{}/delay?timeout={}&url={}",
-        encoded_name, timeout_ms, test_url
+        "/proxies/{}/delay?timeout={}&url={}{}",
+        encoded_name,
+        timeout_ms,
+        urlencoding::encode(test_url),
+        expected_query(expected_status)
     );
+    if path.len() > MAX_DELAY_PATH_LEN {
+        return Err(format!(
+            "测试地址过长：编码后请求路径 {} 字节，超过上限 {} 字节",
+            path.len(),
+            MAX_DELAY_PATH_LEN
+        ));
+    }
+    Ok(path)
+}
+
+// 不计节点名称时全部测试地址的请求路径都已超出上限，返回错误描述
+fn oversized_test_urls_error(
+    test_url: &str,
+    fallback_test_urls: &[String],
+    timeout_ms: u32,
+) -> Option<String> {
+    let mut errors = std::iter::once(test_url)
+        .chain(fallback_test_urls.iter().map(String::as_str))
+        .map(|url| delay_path("", url, timeout_ms, None).err());
+    let first_error = errors.next().flatten()?;
+    errors.all(|error| error.is_some()).then_some(first_error)
+}
+
+// 核心 /delay 接口的 expected 参数：状态码不符时核心按测试失败处理
+fn expected_query(expected_status: Option<u16>) -> String {
+    expected_status
+        .map(|status| format!("&expected={}", status))
+        .unwrap_or_default()
+}
+
+// 通过 IPC 调用 Clash API 查询节点延迟。
+// GET /proxies/{proxyName}/delay?timeout={timeout}&url={testUrl}[&expected={status}]
+// 返回 Err 表示 IPC 层失败（连接被拒、管道关闭等），而非节点超时。
+async fn query_node_delay(
+    node_name: &str,
+    test_url: &str,
+    timeout_ms: u32,
+    expected_status: Option<u16>,
+) -> Result<i32, String> {
+    // 构建 Clash API 路径；名称无效或路径过长属于请求参数问题，立即按测试失败处理
+    let path = match node_delay_path(node_name, test_url, timeout_ms, expected_status) {
+        Ok(path) => path,
+        Err(e) => {
+            log::warn!("跳过节点延迟测试：{} - {}", node_name, e);
+            return Ok(-1);
+        }
+    };
 
     let start_time = Instant::now();
-    let timeout = Duration::from_millis(timeout_ms as u64);
-    let response = tokio::time::timeout(timeout, IpcClient::get_with_pool(&path)).await;
+    // 单节点超时作为整个请求（含连接失效重试）的总时限
+    let budget = Duration::from_millis(timeout_ms as u64);
+    let response = IpcClient::get_bulk_within(&path, budget).await;
 
     match response {
-        Ok(result) => match result {
+        Err(IpcError::Timeout(_)) => Ok(timeout_result(
+            node_name,
+            timeout_ms,
+            start_time.elapsed().as_millis(),
+            0,
+        )),
+        result => match result {
             Ok(body) => match serde_json::from_str::<serde_json::Value>(&body) {
                 Ok(json) => {
-                    if let Some(delay) = json.get("delay").and_then(|value| value.as_i64()) {
+                    if let Some(delay) = response_delay(&json) {
                         let delay_i32 = delay as i32;
                         let elapsed_ms = start_time.elapsed().as_millis();
                         if delay_i32 > 0 {
@@ -526,30 +2129,300 @@ async fn test_single_node(node_name: &str, test_url: &str, timeout_ms: u32) -> i
                                 elapsed_ms
                             );
                         }
-                        return delay_i32;
+                        return Ok(delay_i32);
                     }
                     log::error!("节点延迟测试响应格式错误：{}", node_name);
-                    -1
+                    Ok(-1)
                 }
                 Err(e) => {
                     log::error!("节点延迟测试 JSON 解析失败：{} - {}", node_name, e);
-                    -1
+                    Ok(-1)
                 }
             },
+            // 核心以 503/504 表示节点测试超时
+            Err(IpcError::Http {
+                status: 503 | 504, ..
+            }) => Ok(timeout_result(
+                node_name,
+                timeout_ms,
+                start_time.elapsed().as_millis(),
+                0,
+            )),
+            // 其他 HTTP 状态码由核心返回，说明核心仍在线
+            Err(e @ (IpcError::Http { .. } | IpcError::Redirect { .. })) => {
+                log::warn!("节点延迟测试请求失败：{} - {}", node_name, e);
+                Ok(-1)
+            }
             Err(e) => {
-                if e.contains("HTTP 503") || e.contains("HTTP 504") {
-                    return timeout_result(
-                        node_name,
-                        timeout_ms,
-                        start_time.elapsed().as_millis(),
-                        0,
-                    );
-                }
-
                 log::warn!("节点延迟测试 IPC 请求失败：{} - {}", node_name, e);
-                -1
+                Err(e.to_string())
             }
         },
-        Err(_) => timeout_result(node_name, timeout_ms, start_time.elapsed().as_millis(), 0),
+    }
+}
+
+// 延迟字段嵌套对象的最大解析深度
+const MAX_DELAY_NESTING: usize = 2;
+
+// 读取 /delay 响应中的延迟：不同核心版本与分支使用 delay、meanDelay 或 mean 字段
+fn response_delay(json: &serde_json::Value) -> Option<i64> {
+    parse_delay_value(json, 0)
+}
+
+// 宽松解析延迟值：数字、数字字符串（可带 ms 后缀）或包含延迟字段的对象
+fn parse_delay_value(value: &serde_json::Value, depth: usize) -> Option<i64> {
+    use serde_json::Value;
+
+    match value {
+        Value::Number(number) => number
+            .as_i64()
+            .or_else(|| number.as_f64().map(|delay| delay.round() as i64)),
+        Value::String(text) => {
+            let text = text.trim();
+            let text = text.strip_suffix("ms").unwrap_or(text).trim_end();
+            text.parse::<i64>()
+                .ok()
+                .or_else(|| text.parse::<f64>().ok().map(|delay| delay.round() as i64))
+        }
+        Value::Object(object) if depth < MAX_DELAY_NESTING => ["delay", "meanDelay", "mean"]
+            .iter()
+            .filter_map(|key| object.get(*key))
+            .find_map(|value| parse_delay_value(value, depth + 1)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BatchTestResult, DelayTiming, GroupNodeIndex, MAX_DELAY_PATH_LEN, NestedGroupPolicy,
+        attempt_timeout_ms, direct_http_target, expand_nested_groups, node_delay_path,
+        oversized_test_urls_error, pick_fastest, quick_test_targets, response_delay,
+        test_url_chain,
+    };
+    use crate::molecules::clash_network::{ProxyGroupInfo, ProxyMemberInfo};
+
+    fn group(name: &str, group_type: &str, members: &[&str]) -> ProxyGroupInfo {
+        ProxyGroupInfo {
+            name: name.to_string(),
+            group_type: group_type.to_string(),
+            now: None,
+            members: members
+                .iter()
+                .map(|member| ProxyMemberInfo {
+                    name: member.to_string(),
+                    id: None,
+                    proxy_type: String::new(),
+                    is_alive: true,
+                    delay_history: Vec::new(),
+                })
+                .collect(),
+            is_hidden: false,
+            icon: None,
+        }
+    }
+
+    #[test]
+    fn skips_or_expands_auto_select_groups() {
+        let groups = vec![
+            group("Proxy", "Selector", &["Auto", "Backup", "HK-1"]),
+            group("Auto", "URLTest", &["HK-1", "JP-1", "Balance"]),
+            group("Balance", "LoadBalance", &["US-1", "Auto"]),
+            group("Backup", "Selector", &["JP-1"]),
+        ];
+        let members = || vec!["Auto".to_string(), "Backup".to_string(), "HK-1".to_string()];
+
+        let skipped = expand_nested_groups(members(), &groups, NestedGroupPolicy::Skip);
+        assert_eq!(skipped.node_names, ["Backup", "HK-1"]);
+        assert_eq!(skipped.skipped_nodes, ["Auto"]);
+        assert!(skipped.is_changed);
+
+        // 展开时递归进入嵌套组，节点去重且循环引用不会无限展开
+        let expanded = expand_nested_groups(members(), &groups, NestedGroupPolicy::Expand);
+        assert_eq!(expanded.node_names, ["HK-1", "JP-1", "US-1", "Backup"]);
+        assert!(expanded.skipped_nodes.is_empty());
+
+        let plain = expand_nested_groups(
+            vec!["Backup".to_string(), "HK-1".to_string()],
+            &groups,
+            NestedGroupPolicy::Skip,
+        );
+        assert!(!plain.is_changed);
+    }
+
+    #[test]
+    fn prefers_tagged_nodes_before_global_fastest() {
+        let results: Vec<BatchTestResult> =
+            [("US 01", 40), ("JP 01", 90), ("jp 02", 70), ("JP 03", -1)]
+                .into_iter()
+                .map(|(node_name, delay_ms)| BatchTestResult {
+                    node_name: node_name.to_string(),
+                    delay_ms,
+                    is_ipc_error: false,
+                    timing: DelayTiming::default(),
+                    tested_url: None,
+                })
+                .collect();
+
+        let fastest = pick_fastest(&results, &["JP".to_string()]);
+        assert_eq!(
+            fastest.map(|node| (node.node_name, node.is_preferred_match)),
+            Some(("jp 02".to_string(), true))
+        );
+
+        let fastest = pick_fastest(&results, &["SG".to_string()]);
+        assert_eq!(
+            fastest.map(|node| (node.node_name, node.is_preferred_match)),
+            Some(("US 01".to_string(), false))
+        );
+    }
+
+    #[test]
+    fn resolves_selection_chains_to_effective_nodes() {
+        let with_now = |mut group: ProxyGroupInfo, now: &str| {
+            group.now = Some(now.to_string());
+            group
+        };
+        let groups = vec![
+            with_now(group("Proxy", "Selector", &["Auto", "HK-1"]), "Auto"),
+            with_now(group("Auto", "URLTest", &["HK-1", "JP-1"]), "JP-1"),
+            with_now(group("Media", "Selector", &["HK-1"]), "HK-1"),
+            with_now(group("Loop", "Selector", &["Back"]), "Back"),
+            with_now(group("Back", "Selector", &["Loop"]), "Loop"),
+            group("Empty", "Selector", &[]),
+        ];
+
+        let targets: Vec<(String, Option<String>)> = quick_test_targets(&groups)
+            .into_iter()
+            .map(|target| (target.group, target.effective_node))
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                ("Proxy".to_string(), Some("JP-1".to_string())),
+                ("Media".to_string(), Some("HK-1".to_string())),
+                ("Loop".to_string(), None),
+                ("Back".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_over_long_test_urls_before_sending() {
+        let long_url = format!("https://example.com/?q={}", "中".repeat(MAX_DELAY_PATH_LEN));
+
+        let error = node_delay_path("HK-1", &long_url, 5000, Some(204)).err();
+        assert!(error.is_some_and(|e| e.contains("测试地址过长")));
+        assert!(node_delay_path("HK-1", "https://example.com/204", 5000, None).is_ok());
+
+        // 备用地址可用时不提前结束
+        assert!(oversized_test_urls_error(&long_url, &[], 5000).is_some());
+        assert!(
+            oversized_test_urls_error(&long_url, &["https://example.com/204".to_string()], 5000)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn builds_test_url_chain_with_primary_first() {
+        let chain = test_url_chain(
+            "https://a.example/204".to_string(),
+            [
+                " https://b.example/204 ",
+                "",
+                "https://a.example/204",
+                "https://b.example/204",
+            ]
+            .map(String::from)
+            .to_vec(),
+        );
+        assert_eq!(chain, ["https://a.example/204", "https://b.example/204"]);
+        assert_eq!(
+            test_url_chain("https://a.example".to_string(), Vec::new()).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn measures_only_plain_http_urls_directly() {
+        assert_eq!(
+            direct_http_target("http://cp.cloudflare.com/generate_204?a=1"),
+            Some((
+                "cp.cloudflare.com".to_string(),
+                80,
+                "/generate_204?a=1".to_string()
+            ))
+        );
+        assert_eq!(
+            direct_http_target("http://[::1]:8080"),
+            Some(("[::1]".to_string(), 8080, "/".to_string()))
+        );
+        assert_eq!(
+            direct_http_target("https://www.gstatic.com/generate_204"),
+            None
+        );
+        assert_eq!(direct_http_target("not a url"), None);
+    }
+
+    #[test]
+    fn splits_timeout_budget_across_remaining_urls() {
+        assert_eq!(attempt_timeout_ms(5000, 1), 5000);
+        assert_eq!(attempt_timeout_ms(5000, 3), 1666);
+        // 前一个地址提前失败时，后续地址分到更多时间
+        assert_eq!(attempt_timeout_ms(4500, 2), 2250);
+        assert_eq!(attempt_timeout_ms(1, 2), 0);
+        assert_eq!(attempt_timeout_ms(5000, 0), 5000);
+    }
+
+    #[test]
+    fn tests_shared_nodes_once_across_groups() {
+        let snapshot = vec![
+            group("Streaming", "Selector", &["HK-1", "JP-1", "US-1", "Auto"]),
+            group(
+                "Gaming",
+                "Selector",
+                &["JP-1", "HK-1", "SG-1", "DIRECT", "REJECT"],
+            ),
+            group("Auto", "URLTest", &["HK-1", "KR-1"]),
+        ];
+        let requested = ["Streaming", "Gaming", "Gone", "Streaming", "Gone"].map(String::from);
+
+        let index = GroupNodeIndex::build(&requested, &snapshot);
+        assert_eq!(index.node_names, ["HK-1", "JP-1", "US-1", "SG-1"]);
+        assert!(
+            index
+                .group_members
+                .iter()
+                .all(|(_, members)| members.len() == 3)
+        );
+        assert_eq!(
+            index.node_groups.get("JP-1").map(Vec::as_slice),
+            Some(["Streaming".to_string(), "Gaming".to_string()].as_slice())
+        );
+        assert_eq!(index.group_members.len(), 2);
+        assert_eq!(index.missing_groups, ["Gone"]);
+    }
+
+    #[test]
+    fn parses_delay_in_every_known_shape() -> Result<(), String> {
+        let delay = |body: &str| -> Result<Option<i64>, String> {
+            let json = serde_json::from_str(body).map_err(|e| e.to_string())?;
+            Ok(response_delay(&json))
+        };
+
+        assert_eq!(delay(r#"{"delay":120}"#)?, Some(120));
+        assert_eq!(delay(r#"{"delay":120.6}"#)?, Some(121));
+        assert_eq!(delay(r#"{"delay":"85"}"#)?, Some(85));
+        assert_eq!(delay(r#"{"delay":" 85ms "}"#)?, Some(85));
+        assert_eq!(delay(r#"{"delay":{"delay":64}}"#)?, Some(64));
+        assert_eq!(delay(r#"{"delay":{"mean":"70"}}"#)?, Some(70));
+        assert_eq!(delay(r#"{"meanDelay":93}"#)?, Some(93));
+        assert_eq!(delay(r#"{"mean":42}"#)?, Some(42));
+        assert_eq!(delay(r#"{"delay":0}"#)?, Some(0));
+
+        assert_eq!(delay(r#"{"delay":"timeout"}"#)?, None);
+        assert_eq!(delay(r#"{"message":"An error occurred"}"#)?, None);
+        assert_eq!(delay(r#"{"delay":{"delay":{"delay":1}}}"#)?, None);
+        Ok(())
     }
 }
@@ -8,7 +8,7 @@ mod processor;
 pub use downloader::{DownloadOverrideRequest, DownloadOverrideResponse};
 pub use processor::{
     ApplyOverridesRequest, ApplyOverridesResponse, ParseSubscriptionRequest,
-    ParseSubscriptionResponse,
+    ParseSubscriptionResponse, PreviewOverrideRequest, PreviewOverrideResponse,
 };
 
 // 从分子层共享类型导入
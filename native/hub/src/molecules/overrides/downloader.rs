@@ -1,6 +1,8 @@
 // 覆写文件下载器
 // 处理覆写文件的 HTTP 下载，支持多种代理模式
 
+use crate::atoms::shutdown;
+use crate::atoms::tls_roots;
 use crate::molecules::ProxyMode;
 use reqwest::Client;
 use rinf::{DartSignal, RustSignal};
@@ -146,14 +148,15 @@ fn create_http_client(
         }
     }
 
-    Ok(builder.build()?)
+    // 追加用户配置的额外根证书
+    Ok(tls_roots::apply(builder).build()?)
 }
 
 pub fn init() {
     use tokio::spawn;
 
     // 覆写文件下载请求监听器
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = DownloadOverrideRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             spawn(async move {
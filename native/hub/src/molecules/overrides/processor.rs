@@ -2,17 +2,40 @@
 // 处理配置覆写（YAML 合并 + JavaScript 执行）
 
 use crate::atoms::ProxyParser;
-use crate::atoms::override_processor::OverrideProcessor;
-use crate::molecules::OverrideConfig;
-use rinf::{DartSignal, RustSignal};
+use crate::atoms::override_processor::{
+    ConfigChange, ConsoleMessage, OverrideProcessor, ScriptContext,
+};
+use crate::atoms::shutdown;
+use crate::molecules::{OverrideConfig, ProxyMode};
+use rinf::{DartSignal, RustSignal, SignalPiece};
 use serde::{Deserialize, Serialize};
 
+// 覆写所应用的订阅信息，供 JavaScript 覆写通过 context 读取
+#[derive(Deserialize, SignalPiece, Clone, Debug)]
+pub struct OverrideScriptContext {
+    pub profile_name: String,
+    // 本地订阅为 None
+    pub profile_url: Option<String>,
+    pub proxy_mode: Option<ProxyMode>,
+}
+
+impl From<OverrideScriptContext> for ScriptContext {
+    fn from(context: OverrideScriptContext) -> Self {
+        Self {
+            profile_name: Some(context.profile_name),
+            profile_url: context.profile_url,
+            proxy_mode: context.proxy_mode.map(|mode| format!("{:?}", mode)),
+        }
+    }
+}
+
 // Dart → Rust：应用覆写请求
 #[derive(Deserialize, DartSignal)]
 pub struct ApplyOverridesRequest {
     pub request_id: String,
     pub base_config_content: String,
     pub overrides: Vec<OverrideConfig>,
+    pub script_context: Option<OverrideScriptContext>,
 }
 
 // Rust → Dart：应用覆写响应
@@ -25,6 +48,27 @@ pub struct ApplyOverridesResponse {
     pub logs: Vec<String>,
 }
 
+// Dart → Rust：预览覆写请求
+#[derive(Deserialize, DartSignal)]
+pub struct PreviewOverrideRequest {
+    pub request_id: String,
+    pub base_config_content: String,
+    pub overrides: Vec<OverrideConfig>,
+    pub script_context: Option<OverrideScriptContext>,
+}
+
+// Rust → Dart：预览覆写响应
+#[derive(Serialize, RustSignal)]
+pub struct PreviewOverrideResponse {
+    pub request_id: String,
+    pub is_successful: bool,
+    pub changes: Vec<ConfigChange>,
+    pub is_truncated: bool,
+    pub error_message: String,
+    // JavaScript 覆写的 console 输出，预览失败时同样返回
+    pub console_messages: Vec<ConsoleMessage>,
+}
+
 // Dart → Rust：解析订阅请求
 #[derive(Deserialize, DartSignal)]
 pub struct ParseSubscriptionRequest {
@@ -88,6 +132,10 @@ impl ApplyOverridesRequest {
             parsed_config.len()
         );
 
+        if let Some(script_context) = self.script_context {
+            processor.set_script_context(script_context.into());
+        }
+
         match processor.apply_overrides(&parsed_config, self.overrides) {
             Ok(result) => {
                 log::info!("[{}] 覆写处理成功", self.request_id);
@@ -115,6 +163,64 @@ impl ApplyOverridesRequest {
     }
 }
 
+impl PreviewOverrideRequest {
+    // 处理覆写预览请求：解析订阅后应用覆写并计算差异
+    pub fn handle(self) {
+        log::info!(
+            "[{}] 收到覆写预览请求，覆写数量：{}",
+            self.request_id,
+            self.overrides.len()
+        );
+
+        let mut console_messages = Vec::new();
+        let result = ProxyParser::parse_subscription(&self.base_config_content)
+            .map_err(|e| format!("订阅解析失败：{}", e))
+            .and_then(|parsed_config| {
+                let mut processor =
+                    OverrideProcessor::new().map_err(|e| format!("初始化处理器失败：{}", e))?;
+                if let Some(script_context) = self.script_context {
+                    processor.set_script_context(script_context.into());
+                }
+                // 预览需要脚本实际执行才能拿到 console 输出，不使用缓存
+                processor.set_cache_enabled(false);
+                let result = processor.preview_overrides(&parsed_config, self.overrides);
+                console_messages = processor.take_console_messages();
+                result
+            });
+
+        let response = match result {
+            Ok(diff) => {
+                log::info!(
+                    "[{}] 覆写预览完成，变更 {} 项（截断：{}）",
+                    self.request_id,
+                    diff.changes.len(),
+                    diff.is_truncated
+                );
+                PreviewOverrideResponse {
+                    request_id: self.request_id,
+                    is_successful: true,
+                    changes: diff.changes,
+                    is_truncated: diff.is_truncated,
+                    error_message: String::new(),
+                    console_messages,
+                }
+            }
+            Err(e) => {
+                log::error!("[{}] 覆写预览失败：{}", self.request_id, e);
+                PreviewOverrideResponse {
+                    request_id: self.request_id,
+                    is_successful: false,
+                    changes: vec![],
+                    is_truncated: false,
+                    error_message: e,
+                    console_messages,
+                }
+            }
+        };
+        response.send_signal_to_dart();
+    }
+}
+
 impl ParseSubscriptionRequest {
     // 处理订阅解析请求
     pub fn handle(self) {
@@ -154,10 +260,8 @@ impl ParseSubscriptionRequest {
 }
 
 pub fn init() {
-    use tokio::spawn;
-
     // 应用覆写请求监听器
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = ApplyOverridesRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -179,8 +283,32 @@ pub fn init() {
         }
     });
 
+    // 覆写预览请求监听器
+    shutdown::spawn_listener(async {
+        let receiver = PreviewOverrideRequest::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            let message = dart_signal.message;
+            let request_id = message.request_id.clone();
+            tokio::spawn(async move {
+                // 与应用覆写相同，QuickJS 同步执行需放入 blocking pool
+                if let Err(e) = tokio::task::spawn_blocking(move || message.handle()).await {
+                    log::error!("[{}] 覆写预览任务失败：{}", request_id, e);
+                    PreviewOverrideResponse {
+                        request_id,
+                        is_successful: false,
+                        changes: vec![],
+                        is_truncated: false,
+                        error_message: format!("覆写预览任务失败：{}", e),
+                        console_messages: vec![],
+                    }
+                    .send_signal_to_dart();
+                }
+            });
+        }
+    });
+
     // 订阅解析请求监听器
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = ParseSubscriptionRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle();
@@ -14,3 +14,31 @@ pub enum ProxyMode {
     System = 1, // 系统代理
     Core = 2,   // Clash 核心代理
 }
+
+// 核心出站模式（对应 mihomo 的 mode 配置）
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, SignalPiece)]
+pub enum CoreMode {
+    Rule = 0,   // 规则
+    Global = 1, // 全局
+    Direct = 2, // 直连
+}
+
+impl CoreMode {
+    // mihomo 配置中使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CoreMode::Rule => "rule",
+            CoreMode::Global => "global",
+            CoreMode::Direct => "direct",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "rule" => Some(CoreMode::Rule),
+            "global" => Some(CoreMode::Global),
+            "direct" => Some(CoreMode::Direct),
+            _ => None,
+        }
+    }
+}
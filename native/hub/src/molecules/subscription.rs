@@ -4,7 +4,8 @@ pub mod downloader;
 pub mod parser;
 
 pub use downloader::{
-    DownloadSubscriptionRequest, DownloadSubscriptionResponse, SubscriptionInfoData,
+    DownloadSubscriptionRequest, DownloadSubscriptionResponse, SubscriptionHeader,
+    SubscriptionInfoData,
 };
 pub use parser::ProxyParser;
 
@@ -1,12 +1,52 @@
 // 订阅下载器
 // 处理订阅配置的 HTTP 下载，支持多种代理模式
 
+use super::parser::ProxyParser;
+use crate::atoms::shutdown;
+use crate::atoms::tls_roots;
 use crate::molecules::ProxyMode;
-use reqwest::{Client, Proxy};
-use rinf::{DartSignal, RustSignal};
+use flate2::read::GzDecoder;
+use reqwest::header::{
+    ACCEPT_ENCODING, CONTENT_ENCODING, HeaderMap, HeaderName, HeaderValue, LOCATION,
+};
+use reqwest::{Client, Proxy, redirect};
+use rinf::{DartSignal, RustSignal, SignalPiece};
 use serde::{Deserialize, Serialize};
+use std::io::Read;
 use std::time::Duration;
 
+// 未指定 User-Agent 时使用的默认值：多数机场仅对 Clash 系客户端返回 YAML 配置
+const DEFAULT_USER_AGENT: &str = "clash.meta";
+
+// 最多跟随的重定向次数，与 reqwest 默认策略一致；
+// 自定义重定向策略会替换默认策略，需要自行限制次数
+const MAX_REDIRECTS: usize = 10;
+
+// 解压后订阅内容的大小上限，防止异常压缩数据耗尽内存
+const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+// gzip 数据的魔数
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+// 由 HTTP 客户端维护、不允许自定义的请求头
+const RESERVED_HEADER_NAMES: &[&str] = &[
+    "host",
+    "content-length",
+    "transfer-encoding",
+    "connection",
+    "accept-encoding",
+];
+
+// 名称包含以下关键字（小写匹配）的请求头视为敏感，日志中隐藏其值
+const SENSITIVE_HEADER_KEYWORDS: &[&str] = &["auth", "cookie", "token", "key", "secret"];
+
+// 订阅请求的自定义请求头（如 API Key、Authorization）
+#[derive(Deserialize, SignalPiece, Clone, Debug)]
+pub struct SubscriptionHeader {
+    pub name: String,
+    pub value: String,
+}
+
 // Dart → Rust：下载订阅请求
 #[derive(Deserialize, DartSignal)]
 pub struct DownloadSubscriptionRequest {
@@ -14,6 +54,8 @@ pub struct DownloadSubscriptionRequest {
     pub url: String,
     pub proxy_mode: ProxyMode,
     pub user_agent: String,
+    // 附加的请求头，与 User-Agent 同名时覆盖 User-Agent
+    pub headers: Vec<SubscriptionHeader>,
     pub timeout_seconds: u64,
     pub mixed_port: u16, // Clash 混合端口
 }
@@ -24,6 +66,8 @@ pub struct DownloadSubscriptionResponse {
     pub request_id: String, // 请求标识符，用于请求匹配
     pub is_successful: bool,
     pub content: String,
+    // 经 ProxyParser 转换后的标准 Clash 配置；内容无法识别时为 None
+    pub parsed_config: Option<String>,
     pub subscription_info: Option<SubscriptionInfoData>,
     pub error_message: Option<String>,
 }
@@ -45,6 +89,7 @@ impl DownloadSubscriptionRequest {
             &self.url,
             self.proxy_mode,
             &self.user_agent,
+            &self.headers,
             self.timeout_seconds,
             self.mixed_port,
         )
@@ -57,10 +102,22 @@ impl DownloadSubscriptionRequest {
                     self.request_id,
                     content.len()
                 );
+                let parsed_config = match ProxyParser::parse_subscription(&content) {
+                    Ok(config) => Some(config),
+                    Err(e) => {
+                        log::warn!(
+                            "订阅内容无法识别 [{}]：{}（可尝试更换 User-Agent）",
+                            self.request_id,
+                            e
+                        );
+                        None
+                    }
+                };
                 DownloadSubscriptionResponse {
                     request_id: self.request_id,
                     is_successful: true,
                     content,
+                    parsed_config,
                     subscription_info: info,
                     error_message: None,
                 }
@@ -71,6 +128,7 @@ impl DownloadSubscriptionRequest {
                     request_id: self.request_id,
                     is_successful: false,
                     content: String::new(),
+                    parsed_config: None,
                     subscription_info: None,
                     error_message: Some(e.to_string()),
                 }
@@ -82,27 +140,64 @@ impl DownloadSubscriptionRequest {
 }
 
 // 下载订阅配置并返回内容与订阅信息。
-// 支持代理模式、超时与自定义 User-Agent。
+// 支持代理模式、超时、自定义 User-Agent 与附加请求头。
 pub async fn download_subscription(
     url: &str,
     proxy_mode: ProxyMode,
     user_agent: &str,
+    headers: &[SubscriptionHeader],
     timeout_seconds: u64,
     mixed_port: u16,
 ) -> Result<(String, Option<SubscriptionInfoData>), Box<dyn std::error::Error + Send + Sync>> {
     log::info!("开始下载订阅：{}", url);
     log::info!("代理模式：{:?}", proxy_mode);
 
-    // 创建 HTTP 客户端
-    let client = create_http_client(proxy_mode, timeout_seconds, mixed_port)?;
+    // 先校验请求头，非法时不发出请求
+    let custom_headers = build_custom_headers(headers)?;
+    if !headers.is_empty() {
+        log::debug!("订阅请求附加请求头：{}", describe_headers(headers));
+    }
+
+    // 创建 HTTP 客户端：带自定义请求头时只跟随同主机的重定向
+    let has_custom_headers = !custom_headers.is_empty();
+    let client = create_http_client(proxy_mode, timeout_seconds, mixed_port, has_custom_headers)?;
 
     // 发送 HTTP GET 请求
-    let response = client
+    let user_agent = match user_agent.trim() {
+        "" => DEFAULT_USER_AGENT,
+        user_agent => user_agent,
+    };
+    log::debug!("订阅请求 User-Agent：{}", user_agent);
+    let mut response = client
         .get(url)
         .header("User-Agent", user_agent)
+        .header(ACCEPT_ENCODING, "gzip")
+        .headers(custom_headers)
         .send()
         .await?;
 
+    // 自定义请求头可能包含凭据，跨主机重定向时不再附加，改为重新请求新地址
+    if has_custom_headers
+        && response.status().is_redirection()
+        && let Some(location) = redirect_location(&response)
+    {
+        log::info!(
+            "订阅地址重定向到其他主机，不再附加自定义请求头：{}",
+            location
+        );
+        let client = create_http_client(proxy_mode, timeout_seconds, mixed_port, false)?;
+        response = client
+            .get(location)
+            .header("User-Agent", user_agent)
+            .header(ACCEPT_ENCODING, "gzip")
+            .send()
+            .await?;
+    }
+
+    if response.url().as_str() != url {
+        log::info!("订阅地址已重定向：{}", response.url());
+    }
+
     // 检查 HTTP 状态码
     let status = response.status();
     if !status.is_success() {
@@ -117,8 +212,14 @@ pub async fn download_subscription(
     // 解析订阅信息头
     let subscription_info = parse_subscription_info(response.headers());
 
-    // 读取响应体
-    let content = response.text().await?;
+    // 读取响应体，按 Content-Encoding 解压
+    let content_encoding = response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = response.bytes().await?;
+    let content = decode_body(&body, content_encoding.as_deref())?;
 
     if content.is_empty() {
         return Err("订阅内容为空".into());
@@ -129,15 +230,120 @@ pub async fn download_subscription(
     Ok((content, subscription_info))
 }
 
+// 校验并转换自定义请求头：名称须为合法的 HTTP token，值不能包含换行等控制字符，
+// 避免拼接出额外的请求头。敏感请求头标记为 sensitive，不出现在调试输出中
+fn build_custom_headers(headers: &[SubscriptionHeader]) -> Result<HeaderMap, String> {
+    let mut header_map = HeaderMap::new();
+    for header in headers {
+        let name = header.name.trim();
+        if name.is_empty() {
+            return Err("请求头名称不能为空".to_string());
+        }
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("请求头名称不合法：{}", name))?;
+        if RESERVED_HEADER_NAMES.contains(&header_name.as_str()) {
+            return Err(format!("不允许自定义请求头：{}", name));
+        }
+
+        let mut header_value = HeaderValue::from_str(header.value.trim())
+            .map_err(|_| format!("请求头 {} 的值包含非法字符", name))?;
+        header_value.set_sensitive(is_sensitive_header(header_name.as_str()));
+        header_map.insert(header_name, header_value);
+    }
+    Ok(header_map)
+}
+
+fn is_sensitive_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SENSITIVE_HEADER_KEYWORDS
+        .iter()
+        .any(|keyword| name.contains(keyword))
+}
+
+// 生成用于日志的请求头描述，敏感请求头的值以 *** 代替
+fn describe_headers(headers: &[SubscriptionHeader]) -> String {
+    headers
+        .iter()
+        .map(|header| {
+            let name = header.name.trim();
+            if is_sensitive_header(name) {
+                format!("{}: ***", name)
+            } else {
+                format!("{}: {}", name, header.value.trim())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// 解码响应体：声明 gzip 时解压，未声明时使用原始内容。
+// 声明 gzip 但数据不是 gzip 格式（已被中间代理解压）时同样使用原始内容
+fn decode_body(body: &[u8], content_encoding: Option<&str>) -> Result<String, String> {
+    let is_gzip = content_encoding.is_some_and(|encoding| {
+        encoding
+            .split(',')
+            .any(|item| item.trim().eq_ignore_ascii_case("gzip"))
+    });
+    if !is_gzip || !body.starts_with(&GZIP_MAGIC) {
+        return Ok(String::from_utf8_lossy(body).into_owned());
+    }
+
+    let mut bytes = Vec::new();
+    GzDecoder::new(body)
+        .take(MAX_DECOMPRESSED_SIZE + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("解压订阅内容失败：{}", e))?;
+    if bytes.len() as u64 > MAX_DECOMPRESSED_SIZE {
+        return Err(format!(
+            "解压后的订阅内容超过 {} MB",
+            MAX_DECOMPRESSED_SIZE / 1024 / 1024
+        ));
+    }
+
+    log::debug!("订阅内容已解压：{} → {} 字节", body.len(), bytes.len());
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+// 重定向响应的目标地址，相对地址按当前地址解析
+fn redirect_location(response: &reqwest::Response) -> Option<reqwest::Url> {
+    let location = response.headers().get(LOCATION)?.to_str().ok()?;
+    response.url().join(location).ok()
+}
+
+// 主机或端口不同即视为跨主机
+fn is_cross_host(from: &reqwest::Url, to: &reqwest::Url) -> bool {
+    from.host_str() != to.host_str() || from.port_or_known_default() != to.port_or_known_default()
+}
+
+// 重定向策略：is_same_host_only 为 true 时遇到跨主机重定向即停止，交由调用方处理
+fn redirect_policy(is_same_host_only: bool) -> redirect::Policy {
+    redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() > MAX_REDIRECTS {
+            return attempt.error(format!("重定向次数超过 {} 次", MAX_REDIRECTS));
+        }
+        let is_cross = attempt
+            .previous()
+            .first()
+            .is_some_and(|origin| is_cross_host(origin, attempt.url()));
+        if is_same_host_only && is_cross {
+            attempt.stop()
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
 // 创建 HTTP 客户端
 fn create_http_client(
     proxy_mode: ProxyMode,
     timeout_seconds: u64,
     mixed_port: u16,
+    is_same_host_redirect_only: bool,
 ) -> Result<Client, Box<dyn std::error::Error + Send + Sync>> {
     let mut builder = Client::builder()
         .timeout(Duration::from_secs(timeout_seconds))
         .connect_timeout(Duration::from_secs(10)) // 连接超时
+        .redirect(redirect_policy(is_same_host_redirect_only))
         .danger_accept_invalid_certs(false); // 验证 SSL 证书
 
     // 根据代理模式配置客户端
@@ -159,57 +365,73 @@ fn create_http_client(
         }
     }
 
-    Ok(builder.build()?)
+    // 追加用户配置的额外根证书
+    Ok(tls_roots::apply(builder).build()?)
 }
 
-// 解析订阅信息头（subscription-userinfo）。
-// 示例：upload=0; download=123; total=1073741824; expire=1735689600
+// 读取响应中的订阅信息头（subscription-userinfo）
 fn parse_subscription_info(headers: &reqwest::header::HeaderMap) -> Option<SubscriptionInfoData> {
     let header_value = headers.get("subscription-userinfo")?.to_str().ok()?;
-
     log::debug!("解析订阅信息头：{}", header_value);
+    SubscriptionInfoData::from_header(header_value)
+}
+
+impl SubscriptionInfoData {
+    // 解析订阅信息头，流量单位为字节，expire 为 Unix 时间戳（秒）。
+    // 示例：upload=0; download=123; total=1073741824; expire=1735689600
+    // 兼容键名大小写、多余空白、逗号分隔、浮点数值与缺失字段
+    pub fn from_header(header_value: &str) -> Option<Self> {
+        let mut info = Self {
+            upload: None,
+            download: None,
+            total: None,
+            expire: None,
+        };
 
-    let mut upload = None;
-    let mut download = None;
-    let mut total = None;
-    let mut expire = None;
-
-    // 解析键值对
-    for pair in header_value.split(';') {
-        let pair = pair.trim();
-        if let Some((key, value)) = pair.split_once('=') {
-            let key = key.trim();
-            let value = value.trim();
-
-            match key {
-                "upload" => upload = value.parse::<u64>().ok(),
-                "download" => download = value.parse::<u64>().ok(),
-                "total" => total = value.parse::<u64>().ok(),
-                "expire" => expire = value.parse::<i64>().ok(),
+        for pair in header_value.split([';', ',']) {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+            let value = parse_header_number(value);
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "upload" => info.upload = value.map(|v| v.max(0) as u64),
+                "download" => info.download = value.map(|v| v.max(0) as u64),
+                "total" => info.total = value.map(|v| v.max(0) as u64),
+                "expire" => info.expire = value,
                 _ => {}
             }
         }
+
+        // 如果至少有一个字段有值，则返回订阅信息
+        let has_any_field = info.upload.is_some()
+            || info.download.is_some()
+            || info.total.is_some()
+            || info.expire.is_some();
+        has_any_field.then_some(info)
     }
+}
 
-    // 如果至少有一个字段有值，则返回订阅信息
-    if upload.is_some() || download.is_some() || total.is_some() || expire.is_some() {
-        Some(SubscriptionInfoData {
-            upload,
-            download,
-            total,
-            expire,
-        })
-    } else {
-        None
+// 解析数值：部分服务端返回浮点数（如 1.5E+10）或带引号的值
+fn parse_header_number(value: &str) -> Option<i64> {
+    let value = value.trim().trim_matches('"').trim();
+    if value.is_empty() {
+        return None;
     }
+
+    value.parse::<i64>().ok().or_else(|| {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|number| number.is_finite())
+            .map(|number| number as i64)
+    })
 }
 
 // 初始化 Dart 信号监听器
 pub fn init() {
-    use tokio::spawn;
-
     // 订阅下载请求监听器
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = DownloadSubscriptionRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             tokio::spawn(async move {
@@ -218,3 +440,126 @@ pub fn init() {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        SubscriptionHeader, SubscriptionInfoData, build_custom_headers, decode_body,
+        describe_headers, is_cross_host,
+    };
+    use crate::molecules::subscription::parser::ProxyParser;
+    use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+    use flate2::{Compression, write::GzEncoder};
+    use std::io::Write;
+
+    #[test]
+    fn decodes_gzipped_base64_subscription() -> Result<(), String> {
+        let links = "trojan://secret@hk.example.com:443?sni=hk.example.com#HK-01\n\
+                     trojan://secret@jp.example.com:443?sni=jp.example.com#JP-01\n";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(BASE64.encode(links).as_bytes())
+            .map_err(|e| e.to_string())?;
+        let compressed = encoder.finish().map_err(|e| e.to_string())?;
+
+        let content = decode_body(&compressed, Some("gzip"))?;
+        let config = ProxyParser::parse_subscription(&content)?;
+        assert!(config.contains("HK-01") && config.contains("JP-01"));
+
+        // 未声明压缩时按原始内容处理
+        assert_eq!(decode_body(b"plain", None)?, "plain");
+        assert_eq!(decode_body(b"plain", Some("gzip"))?, "plain");
+        Ok(())
+    }
+
+    #[test]
+    fn detects_cross_host_redirects() -> Result<(), String> {
+        let url = |text: &str| reqwest::Url::parse(text).map_err(|e| e.to_string());
+        let origin = url("https://sub.example.com/api?token=a")?;
+
+        assert!(!is_cross_host(&origin, &url("https://sub.example.com/v2")?));
+        assert!(!is_cross_host(
+            &origin,
+            &url("https://sub.example.com:443/v2")?
+        ));
+        assert!(is_cross_host(&origin, &url("https://cdn.example.net/v2")?));
+        assert!(is_cross_host(
+            &origin,
+            &url("https://sub.example.com:8443/v2")?
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_injected_headers_and_redacts_secrets() -> Result<(), String> {
+        let header = |name: &str, value: &str| SubscriptionHeader {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+
+        let headers = vec![
+            header("Authorization", "Bearer abc"),
+            header("X-Api-Key", "k123"),
+            header("Accept", "text/plain"),
+        ];
+        let header_map = build_custom_headers(&headers)?;
+        assert_eq!(header_map.len(), 3);
+        assert!(
+            header_map
+                .get("authorization")
+                .is_some_and(|v| v.is_sensitive())
+        );
+        assert_eq!(
+            describe_headers(&headers),
+            "Authorization: ***, X-Api-Key: ***, Accept: text/plain"
+        );
+
+        assert!(build_custom_headers(&[header("X-Token", "a\r\nHost: evil")]).is_err());
+        assert!(build_custom_headers(&[header("X-Bad\r\nName", "v")]).is_err());
+        assert!(build_custom_headers(&[header("Host", "example.com")]).is_err());
+        assert!(build_custom_headers(&[header(" ", "v")]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parses_complete_header() -> Result<(), String> {
+        let info = SubscriptionInfoData::from_header(
+            "upload=455727941; download=6174315083; total=1073741824000; expire=1735689600",
+        )
+        .ok_or("解析失败")?;
+        assert_eq!(info.upload, Some(455727941));
+        assert_eq!(info.download, Some(6174315083));
+        assert_eq!(info.total, Some(1073741824000));
+        assert_eq!(info.expire, Some(1735689600));
+        Ok(())
+    }
+
+    #[test]
+    fn parses_header_without_expire() -> Result<(), String> {
+        let info = SubscriptionInfoData::from_header("upload=0; download=2048; total=10737418240;")
+            .ok_or("解析失败")?;
+        assert_eq!(info.download, Some(2048));
+        assert_eq!(info.total, Some(10737418240));
+        assert_eq!(info.expire, None);
+        Ok(())
+    }
+
+    #[test]
+    fn tolerates_odd_formatting() -> Result<(), String> {
+        let info = SubscriptionInfoData::from_header(
+            "  Upload = 1 ;DOWNLOAD=2.5E+3,total=\"3\";expire=;unknown=9",
+        )
+        .ok_or("解析失败")?;
+        assert_eq!(info.upload, Some(1));
+        assert_eq!(info.download, Some(2500));
+        assert_eq!(info.total, Some(3));
+        assert_eq!(info.expire, None);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_header_without_known_fields() {
+        assert!(SubscriptionInfoData::from_header("").is_none());
+        assert!(SubscriptionInfoData::from_header("foo=bar; expire=").is_none());
+    }
+}
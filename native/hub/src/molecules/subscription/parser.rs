@@ -6,6 +6,8 @@ use serde_json::{Value as JsonValue, json};
 use std::collections::HashMap;
 use url::Url;
 
+use crate::atoms::host_port::{normalize_host, split_host_port};
+
 // 代理链接解析器
 pub struct ProxyParser;
 
@@ -175,7 +177,7 @@ impl ProxyParser {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
         let uuid = url.username();
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url.port().ok_or("缺少端口")? as i64;
 
         let params = Self::parse_query_params(url.query().unwrap_or(""));
@@ -247,7 +249,7 @@ impl ProxyParser {
         let mut proxy = json!({
             "name": data["ps"].as_str().unwrap_or("VMess"),
             "type": "vmess",
-            "server": data["add"].as_str().unwrap_or(""),
+            "server": normalize_host(data["add"].as_str().unwrap_or("")),
             "port": data["port"].as_str().unwrap_or("443").parse::<i64>().unwrap_or(443),
             "uuid": data["id"].as_str().unwrap_or(""),
             "alterId": data["aid"].as_str().unwrap_or("0").parse::<i64>().unwrap_or(0),
@@ -294,7 +296,7 @@ impl ProxyParser {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
         let password = url.username();
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url.port().unwrap_or(443) as i64;
 
         let params = Self::parse_query_params(url.query().unwrap_or(""));
@@ -327,7 +329,7 @@ impl ProxyParser {
     fn parse_hysteria(link: &str) -> Result<JsonValue, String> {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url.port().unwrap_or(443) as i64;
         let auth = url.username();
 
@@ -381,11 +383,8 @@ impl ProxyParser {
         // 解析服务器和端口
         let (server_port, name_part) = rest.split_once('#').unwrap_or((rest, "Shadowsocks"));
 
-        let (server, port_str) = server_port
-            .rsplit_once(':')
-            .ok_or("SS 链接格式错误：缺少端口")?;
-
-        let port = port_str.parse::<i64>().map_err(|_| "端口解析失败")?;
+        let (server, port) =
+            split_host_port(server_port).ok_or("SS 链接格式错误：服务器地址或端口无效")?;
 
         let name = Self::url_decode(name_part);
 
@@ -411,12 +410,14 @@ impl ProxyParser {
 
         let (main_part, params_part) = decoded_str.split_once("/?").unwrap_or((&decoded_str, ""));
 
-        let parts: Vec<&str> = main_part.split(':').collect();
+        // 服务器可能是 IPv6 地址，从右侧拆分出其余 5 个字段
+        let mut parts: Vec<&str> = main_part.rsplitn(6, ':').collect();
         if parts.len() < 6 {
             return Err("SSR 链接格式错误".to_string());
         }
+        parts.reverse();
 
-        let server = parts[0];
+        let server = normalize_host(parts[0]);
         let port = parts[1].parse::<i64>().map_err(|_| "端口解析失败")?;
         let protocol = parts[2];
         let method = parts[3];
@@ -480,7 +481,7 @@ impl ProxyParser {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
         let password = url.username();
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url.port().unwrap_or(443) as i64;
 
         let params = Self::parse_query_params(url.query().unwrap_or(""));
@@ -529,7 +530,7 @@ impl ProxyParser {
 
         let uuid = url.username();
         let password = url.password().unwrap_or("");
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url.port().unwrap_or(443) as i64;
 
         let params = Self::parse_query_params(url.query().unwrap_or(""));
@@ -564,7 +565,7 @@ impl ProxyParser {
     fn parse_http(link: &str) -> Result<JsonValue, String> {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url
             .port()
             .unwrap_or(if link.starts_with("https") { 443 } else { 80 }) as i64;
@@ -603,7 +604,7 @@ impl ProxyParser {
     fn parse_socks(link: &str) -> Result<JsonValue, String> {
         let url = Url::parse(link).map_err(|e| format!("URL 解析失败：{}", e))?;
 
-        let server = url.host_str().ok_or("缺少服务器地址")?.to_string();
+        let server = normalize_host(url.host_str().ok_or("缺少服务器地址")?);
         let port = url.port().unwrap_or(1080) as i64;
         let username = if url.username().is_empty() {
             None
@@ -1,6 +1,6 @@
 // 系统操作分子模块
 
-use crate::atoms::{network_interfaces, system_proxy};
+use crate::atoms::{network_interfaces, system_proxy, tls_roots};
 
 pub mod app_update;
 pub mod auto_start;
@@ -8,6 +8,7 @@ pub mod backup;
 #[cfg(windows)]
 pub mod loopback;
 pub mod power_event;
+pub mod tun_state;
 pub mod url_launcher;
 
 pub use app_update::{AppUpdateResult, CheckAppUpdateRequest};
@@ -20,13 +21,16 @@ pub use loopback::{
     SaveLoopbackConfigurationResult, SetLoopback, SetLoopbackResult,
 };
 pub use power_event::{
-    PowerEventType, SystemPowerEvent, start_power_event_listener, stop_power_event_listener,
+    PowerEventType, SetPowerEventListenerEnabled, SystemPowerEvent, shutdown_power_event_listener,
+    start_power_event_listener, stop_power_event_listener, subscribe_power_events,
 };
+pub use tun_state::{TunCheckTrigger, TunStateMismatch, start_tun_state_monitor};
 pub use url_launcher::{OpenUrl, OpenUrlResult};
 
 pub fn init_listeners() {
     system_proxy::init();
     network_interfaces::init();
+    tls_roots::init();
 
     app_update::init();
     auto_start::init();
@@ -34,6 +38,8 @@ pub fn init_listeners() {
     #[cfg(windows)]
     loopback::init();
     url_launcher::init();
+    power_event::init();
 
     power_event::start_power_event_listener();
+    tun_state::start_tun_state_monitor();
 }
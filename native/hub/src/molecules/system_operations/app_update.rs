@@ -1,10 +1,12 @@
 // 应用更新服务：GitHub Release 检查
 
-use once_cell::sync::Lazy;
+use crate::atoms::shutdown;
+use crate::atoms::tls_roots;
 use reqwest;
 use rinf::{DartSignal, RustSignal};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::sync::Mutex;
 
 // Dart → Rust：检查应用更新请求
 #[derive(Debug, Clone, Serialize, Deserialize, DartSignal)]
@@ -104,17 +106,27 @@ struct PlatformMatchRules {
 }
 
 // HTTP 客户端单例 - 避免重复创建连接导致的内存泄漏
-static HTTP_CLIENT: Lazy<Result<reqwest::Client, String>> = Lazy::new(|| {
-    reqwest::Client::builder()
+// 同时记录创建时的根证书版本，额外根证书变更后重建
+static HTTP_CLIENT: Mutex<Option<(u64, reqwest::Client)>> = Mutex::new(None);
+
+// 获取 HTTP 客户端（内部为引用计数，克隆开销很小）
+fn get_http_client() -> Result<reqwest::Client, String> {
+    let generation = tls_roots::roots_generation();
+    let mut cached = HTTP_CLIENT.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_generation, client)) = cached.as_ref()
+        && *cached_generation == generation
+    {
+        return Ok(client.clone());
+    }
+
+    let builder = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(10))
-        .user_agent("Stelliberty-App")
+        .user_agent("Stelliberty-App");
+    let client = tls_roots::apply(builder)
         .build()
-        .map_err(|e| format!("HTTP 客户端初始化失败: {}", e))
-});
-
-// 获取 HTTP 客户端引用
-fn get_http_client() -> Result<&'static reqwest::Client, String> {
-    HTTP_CLIENT.as_ref().map_err(|e| e.clone())
+        .map_err(|e| format!("HTTP 客户端初始化失败: {}", e))?;
+    *cached = Some((generation, client.clone()));
+    Ok(client)
 }
 
 // 检查 GitHub Release 更新
@@ -348,9 +360,7 @@ pub struct UpdateCheckResult {
 }
 
 pub fn init() {
-    use tokio::spawn;
-
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = CheckAppUpdateRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
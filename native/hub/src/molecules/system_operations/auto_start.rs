@@ -1,6 +1,7 @@
 // 开机自启动管理：提供跨平台自启动配置能力（Windows/macOS/Linux）。
 // Windows 使用任务计划程序；macOS/Linux 使用 auto-launch。
 
+use crate::atoms::shutdown;
 use rinf::{DartSignal, RustSignal};
 use serde::{Deserialize, Serialize};
 
@@ -516,9 +517,7 @@ pub fn init() {
         log::debug!("Auto-start module not available on mobile platforms");
     }
 
-    use tokio::spawn;
-
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = GetAutoStartStatus::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -526,7 +525,7 @@ pub fn init() {
         }
     });
 
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = SetAutoStartStatus::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
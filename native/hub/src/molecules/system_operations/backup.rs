@@ -1,6 +1,7 @@
 // 备份与还原服务：负责导出与导入应用数据。
 // 使用结构化元信息描述版本与路径。
 
+use crate::atoms::shutdown;
 use base64::{Engine as _, engine::general_purpose};
 use rinf::{DartSignal, RustSignal};
 use serde::{Deserialize, Serialize};
@@ -534,7 +535,7 @@ async fn restore_file_base64(
 pub fn init() {
     use tokio::spawn;
 
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = CreateBackupRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -544,7 +545,7 @@ pub fn init() {
         }
     });
 
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = RestoreBackupRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
@@ -1,6 +1,7 @@
 // Windows UWP 回环豁免管理：提供回环豁免的查询与配置能力。
 // 仅在 Windows 平台启用。
 
+use crate::atoms::shutdown;
 use rinf::{DartSignal, RustSignal};
 use serde::{Deserialize, Serialize};
 use tokio::spawn;
@@ -977,21 +978,21 @@ pub fn set_loopback_exemption(package_family_name: &str, enabled: bool) -> Resul
 
 // 初始化 UWP 回环豁免消息监听器
 pub fn init() {
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = GetAppContainers::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle();
         }
     });
 
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = SetLoopback::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle();
         }
     });
 
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = SaveLoopbackConfiguration::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             dart_signal.message.handle();
@@ -1,7 +1,9 @@
 // Windows 电源事件监听：监听休眠与唤醒事件并上报到 Flutter
 
 #[cfg(target_os = "windows")]
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+#[cfg(target_os = "windows")]
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 #[cfg(target_os = "windows")]
 use windows::Win32::Foundation::{
     GetLastError, HANDLE, HWND, LPARAM, LRESULT, WIN32_ERROR, WPARAM,
@@ -10,21 +12,31 @@ use windows::Win32::Foundation::{
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
 #[cfg(target_os = "windows")]
 use windows::Win32::System::Power::{
-    POWERBROADCAST_SETTING, RegisterPowerSettingNotification, UnregisterPowerSettingNotification,
+    POWERBROADCAST_SETTING, RegisterPowerSettingNotification, RegisterSuspendResumeNotification,
+    UnregisterPowerSettingNotification, UnregisterSuspendResumeNotification,
 };
 #[cfg(target_os = "windows")]
 use windows::Win32::System::Threading::GetCurrentThreadId;
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::WindowsAndMessaging::{
-    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW,
-    PostThreadMessageW, REGISTER_NOTIFICATION_FLAGS, RegisterClassW, TranslateMessage,
-    WINDOW_EX_STYLE, WM_POWERBROADCAST, WM_QUIT, WNDCLASSW, WS_OVERLAPPEDWINDOW,
+    CreateWindowExW, DEVICE_NOTIFY_WINDOW_HANDLE, DefWindowProcW, DestroyWindow, DispatchMessageW,
+    GetMessageW, HWND_MESSAGE, PostThreadMessageW, RegisterClassW, TranslateMessage,
+    WINDOW_EX_STYLE, WINDOW_STYLE, WM_POWERBROADCAST, WM_QUIT, WNDCLASSW,
 };
 #[cfg(target_os = "windows")]
 use windows::core::GUID;
 
-use rinf::{RustSignal, SignalPiece};
+use crate::atoms::shutdown;
+use once_cell::sync::Lazy;
+use rinf::{DartSignal, RustSignal, SignalPiece};
 use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+// Dart → Rust：启用或停用电源事件监听（随"自动恢复 TUN"设置切换）
+#[derive(Deserialize, DartSignal)]
+pub struct SetPowerEventListenerEnabled {
+    pub is_enabled: bool,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, SignalPiece)]
 pub enum PowerEventType {
@@ -36,6 +48,19 @@ pub enum PowerEventType {
 #[derive(Serialize, RustSignal)]
 pub struct SystemPowerEvent {
     pub event_type: PowerEventType,
+    // 单调递增的事件序号，Dart 端据此忽略已处理过的事件
+    pub sequence: u64,
+    // 事件发生时间（Unix 毫秒）
+    pub timestamp_ms: i64,
+}
+
+// Rust 内部订阅者使用的事件通道
+static POWER_EVENT_SENDER: Lazy<broadcast::Sender<PowerEventType>> =
+    Lazy::new(|| broadcast::channel(16).0);
+
+// 订阅电源事件（仅 Windows 会产生事件）
+pub fn subscribe_power_events() -> broadcast::Receiver<PowerEventType> {
+    POWER_EVENT_SENDER.subscribe()
 }
 
 // GUID_MONITOR_POWER_ON: 监视器电源状态
@@ -75,6 +100,25 @@ static RUNNING: AtomicBool = AtomicBool::new(false);
 #[cfg(target_os = "windows")]
 static LISTENER_THREAD_ID: AtomicU32 = AtomicU32::new(0);
 
+// 电源事件序号（进程内单调递增，跨监听器重启保持）
+#[cfg(target_os = "windows")]
+static EVENT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+// 监听线程句柄，停止时等待线程退出，避免反复启停时遗留线程
+#[cfg(target_os = "windows")]
+static LISTENER_THREAD: Mutex<Option<std::thread::JoinHandle<()>>> = Mutex::new(None);
+
+#[cfg(target_os = "windows")]
+fn send_power_event(event_type: PowerEventType) {
+    let _ = POWER_EVENT_SENDER.send(event_type);
+    SystemPowerEvent {
+        event_type,
+        sequence: EVENT_SEQUENCE.fetch_add(1, Ordering::SeqCst) + 1,
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+    }
+    .send_signal_to_dart();
+}
+
 #[cfg(target_os = "windows")]
 unsafe extern "system" fn window_proc(
     hwnd: HWND,
@@ -89,26 +133,17 @@ unsafe extern "system" fn window_proc(
             match event_type {
                 PBT_APMSUSPEND => {
                     log::info!("系统进入休眠");
-                    SystemPowerEvent {
-                        event_type: PowerEventType::Suspend,
-                    }
-                    .send_signal_to_dart();
+                    send_power_event(PowerEventType::Suspend);
                 }
 
                 PBT_APMRESUMEAUTOMATIC => {
                     log::info!("系统自动唤醒");
-                    SystemPowerEvent {
-                        event_type: PowerEventType::ResumeAutomatic,
-                    }
-                    .send_signal_to_dart();
+                    send_power_event(PowerEventType::ResumeAutomatic);
                 }
 
                 PBT_APMRESUMESUSPEND => {
                     log::info!("用户唤醒系统");
-                    SystemPowerEvent {
-                        event_type: PowerEventType::ResumeSuspend,
-                    }
-                    .send_signal_to_dart();
+                    send_power_event(PowerEventType::ResumeSuspend);
                 }
 
                 PBT_POWERSETTINGCHANGE => {
@@ -158,20 +193,23 @@ pub fn start_power_event_listener() {
 
     log::info!("启动电源监听器");
 
-    std::thread::spawn(|| {
+    let handle = std::thread::spawn(|| {
         if let Err(e) = run_event_loop() {
             log::error!("电源事件循环失败: {}", e);
             RUNNING.store(false, Ordering::SeqCst);
             LISTENER_THREAD_ID.store(0, Ordering::SeqCst);
         }
     });
+
+    match LISTENER_THREAD.lock() {
+        Ok(mut guard) => *guard = Some(handle),
+        Err(e) => *e.into_inner() = Some(handle),
+    }
 }
 
 #[cfg(target_os = "windows")]
 fn run_event_loop() -> Result<(), String> {
     unsafe {
-        LISTENER_THREAD_ID.store(GetCurrentThreadId(), Ordering::SeqCst);
-
         let instance = GetModuleHandleW(None).map_err(|e| format!("获取模块句柄失败: {}", e))?;
 
         let class_name = windows::core::w!("StellibertyPowerEventClass");
@@ -191,28 +229,49 @@ fn run_event_loop() -> Result<(), String> {
             }
         }
 
+        // 仅消息窗口（父窗口为 HWND_MESSAGE）：不可见、不参与窗口枚举，开销更小
         let hwnd = CreateWindowExW(
             WINDOW_EX_STYLE::default(),
             class_name,
             windows::core::w!("Stelliberty Power Event Window"),
-            WS_OVERLAPPEDWINDOW,
+            WINDOW_STYLE::default(),
             0,
             0,
             0,
             0,
-            None,
+            Some(HWND_MESSAGE),
             None,
             Some(instance.into()),
             None,
         )
         .map_err(|e| format!("创建窗口失败: {}", e))?;
 
-        let notify_handle = RegisterPowerSettingNotification(
+        // 创建窗口后线程已拥有消息队列，此时记录线程 ID，
+        // 保证 stop_power_event_listener 投递的 WM_QUIT 不会因队列不存在而丢失
+        LISTENER_THREAD_ID.store(GetCurrentThreadId(), Ordering::SeqCst);
+
+        let notify_handle = match RegisterPowerSettingNotification(
             HANDLE(hwnd.0),
             &GUID_CONSOLE_DISPLAY_STATE,
-            REGISTER_NOTIFICATION_FLAGS(0x00000000),
-        )
-        .map_err(|e| format!("注册电源通知失败: {}", e))?;
+            DEVICE_NOTIFY_WINDOW_HANDLE,
+        ) {
+            Ok(handle) => handle,
+            Err(e) => {
+                let _ = DestroyWindow(hwnd);
+                return Err(format!("注册电源通知失败: {}", e));
+            }
+        };
+
+        // 仅消息窗口收不到广播，PBT_APMSUSPEND 与两种唤醒事件必须显式注册才会送达。
+        // 注册失败时仍保留显示状态通知，监听器继续运行
+        let suspend_notify_handle =
+            match RegisterSuspendResumeNotification(HANDLE(hwnd.0), DEVICE_NOTIFY_WINDOW_HANDLE) {
+                Ok(handle) => Some(handle),
+                Err(e) => {
+                    log::warn!("注册休眠唤醒通知失败，将收不到休眠与唤醒事件: {}", e);
+                    None
+                }
+            };
 
         log::info!("电源监听器就绪");
 
@@ -228,6 +287,12 @@ fn run_event_loop() -> Result<(), String> {
             log::warn!("注销电源通知失败: {}", e);
         }
 
+        if let Some(handle) = suspend_notify_handle
+            && let Err(e) = UnregisterSuspendResumeNotification(handle)
+        {
+            log::warn!("注销休眠唤醒通知失败: {}", e);
+        }
+
         let _ = DestroyWindow(hwnd);
 
         RUNNING.store(false, Ordering::SeqCst);
@@ -238,13 +303,21 @@ fn run_event_loop() -> Result<(), String> {
 }
 
 #[cfg(target_os = "windows")]
-#[allow(dead_code)]
 pub fn stop_power_event_listener() {
     if !RUNNING.load(Ordering::SeqCst) {
         return;
     }
 
-    let thread_id = LISTENER_THREAD_ID.load(Ordering::SeqCst);
+    // 监听线程可能刚启动、尚未创建消息队列，短暂等待线程 ID 就绪
+    let mut thread_id = LISTENER_THREAD_ID.load(Ordering::SeqCst);
+    for _ in 0..50 {
+        if thread_id != 0 || !RUNNING.load(Ordering::SeqCst) {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        thread_id = LISTENER_THREAD_ID.load(Ordering::SeqCst);
+    }
+
     if thread_id == 0 {
         log::warn!("电源监听器线程未就绪，无法停止");
         return;
@@ -252,6 +325,7 @@ pub fn stop_power_event_listener() {
 
     log::info!("停止电源监听器");
 
+    // WM_QUIT 必须投递到监听线程本身，GetMessageW 收到后退出消息循环
     unsafe {
         if let Err(e) = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0)) {
             log::warn!("发送退出消息失败: {}", e);
@@ -259,7 +333,18 @@ pub fn stop_power_event_listener() {
         }
     }
 
-    RUNNING.store(false, Ordering::SeqCst);
+    // 等待监听线程完成清理，RUNNING 由监听线程退出前重置
+    let handle = match LISTENER_THREAD.lock() {
+        Ok(mut guard) => guard.take(),
+        Err(e) => e.into_inner().take(),
+    };
+    if let Some(handle) = handle
+        && handle.join().is_err()
+    {
+        log::warn!("电源监听器线程异常退出");
+        RUNNING.store(false, Ordering::SeqCst);
+        LISTENER_THREAD_ID.store(0, Ordering::SeqCst);
+    }
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -267,3 +352,41 @@ pub fn start_power_event_listener() {}
 
 #[cfg(not(target_os = "windows"))]
 pub fn stop_power_event_listener() {}
+
+// 应用退出时停止监听器，并在时限内等待监听线程退出。
+// 停止操作放到独立线程执行，超时后不再等待，避免阻塞进程退出
+pub async fn shutdown_power_event_listener(limit: std::time::Duration) {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        stop_power_event_listener();
+        let _ = sender.send(());
+    });
+
+    if tokio::time::timeout(limit, receiver).await.is_err() {
+        log::warn!("等待电源监听器线程退出超时（{}ms）", limit.as_millis());
+    }
+}
+
+pub fn init() {
+    shutdown::spawn_listener(async {
+        let receiver = SetPowerEventListenerEnabled::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            let is_enabled = dart_signal.message.is_enabled;
+
+            // 停止时需要等待监听线程退出，放到阻塞线程池执行
+            let result = tokio::task::spawn_blocking(move || {
+                if is_enabled {
+                    start_power_event_listener();
+                } else {
+                    stop_power_event_listener();
+                }
+            })
+            .await;
+
+            if let Err(e) = result {
+                log::error!("切换电源监听器状态失败: {}", e);
+            }
+        }
+        log::info!("电源监听器开关消息通道已关闭，退出监听器");
+    });
+}
@@ -0,0 +1,239 @@
+// TUN 状态检查：核心配置已开启 TUN，系统中却没有对应的虚拟网卡时（常见于休眠唤醒后），
+// 发出 TunStateMismatch 作为恢复逻辑的触发条件。唤醒后与定时各检查一次，
+// 同一次异常只上报一次，确认网卡重新出现后才重新计数。
+
+use rinf::{RustSignal, SignalPiece};
+use serde::Serialize;
+
+#[cfg(not(target_os = "android"))]
+use super::power_event::{PowerEventType, subscribe_power_events};
+#[cfg(not(target_os = "android"))]
+use crate::atoms::network_interfaces::{NetworkInterfaceDetail, get_interface_details};
+#[cfg(not(target_os = "android"))]
+use crate::atoms::shutdown;
+#[cfg(not(target_os = "android"))]
+use crate::molecules::clash_network::get_running_config;
+#[cfg(not(target_os = "android"))]
+use std::net::Ipv4Addr;
+#[cfg(not(target_os = "android"))]
+use std::time::Duration;
+#[cfg(not(target_os = "android"))]
+use tokio::sync::broadcast::error::RecvError;
+
+// mihomo 在 macOS 以外平台的默认 TUN 设备名
+#[cfg(not(target_os = "android"))]
+const DEFAULT_TUN_DEVICE: &str = "Meta";
+// 定时检查周期
+#[cfg(not(target_os = "android"))]
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+// 唤醒后系统重建网卡需要时间，延迟检查避免误报
+#[cfg(not(target_os = "android"))]
+const RESUME_SETTLE_DELAY: Duration = Duration::from_secs(5);
+
+// 触发检查的原因
+#[derive(Serialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TunCheckTrigger {
+    Resume = 0,
+    Periodic = 1,
+}
+
+// Rust → Dart：TUN 已开启但不存在对应的虚拟网卡
+#[derive(Serialize, RustSignal)]
+pub struct TunStateMismatch {
+    pub trigger: TunCheckTrigger,
+    // 配置中指定的 TUN 设备名，未指定时为 None
+    pub configured_device: Option<String>,
+    // 检测时间（Unix 毫秒）
+    pub detected_at_ms: i64,
+}
+
+// 单次检查的结果
+#[cfg(not(target_os = "android"))]
+#[derive(Debug, PartialEq, Eq)]
+enum TunCheckOutcome {
+    // TUN 已开启且网卡存在
+    Present,
+    // TUN 已开启但网卡不存在，携带配置的设备名
+    Missing(Option<String>),
+    // 核心未运行、TUN 未开启或信息获取失败，无法判断
+    Skipped,
+}
+
+// mihomo 未指定设备名时的默认名称：macOS 为自动编号的 utunN，其他平台为 Meta
+#[cfg(not(target_os = "android"))]
+fn is_default_tun_device(iface: &NetworkInterfaceDetail) -> bool {
+    if cfg!(target_os = "macos") {
+        // 系统自带的 utun 网卡只有 IPv6 链路本地地址，mihomo 的网卡带有 198.18.0.0/15 内的 IPv4 地址
+        let is_utun = iface
+            .name
+            .strip_prefix("utun")
+            .is_some_and(|index| !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()));
+        is_utun
+            && iface.addresses.iter().any(|addr| {
+                addr.address
+                    .parse::<Ipv4Addr>()
+                    .is_ok_and(|ip| matches!(ip.octets(), [198, 18 | 19, _, _]))
+            })
+    } else {
+        iface.name.eq_ignore_ascii_case(DEFAULT_TUN_DEVICE)
+    }
+}
+
+// 判断 TUN 网卡是否存在：配置指定了设备名时按名称匹配，否则按 mihomo 的默认设备名匹配
+#[cfg(not(target_os = "android"))]
+fn is_tun_adapter_present(interfaces: &[NetworkInterfaceDetail], device: Option<&str>) -> bool {
+    match device {
+        Some(device) => interfaces
+            .iter()
+            .any(|iface| iface.name.eq_ignore_ascii_case(device)),
+        None => interfaces.iter().any(is_default_tun_device),
+    }
+}
+
+// 检查一次 TUN 状态
+#[cfg(not(target_os = "android"))]
+async fn check_tun_state() -> TunCheckOutcome {
+    let config = match get_running_config().await {
+        Ok(config) => config,
+        Err(e) => {
+            log::trace!("跳过 TUN 状态检查：{}", e);
+            return TunCheckOutcome::Skipped;
+        }
+    };
+    let Some(tun) = config.tun.filter(|tun| tun.is_enabled) else {
+        return TunCheckOutcome::Skipped;
+    };
+
+    // Windows 读取网卡描述需要调用 PowerShell，放入 blocking pool
+    let interfaces = match tokio::task::spawn_blocking(get_interface_details).await {
+        Ok(Ok(interfaces)) => interfaces,
+        Ok(Err(e)) => {
+            log::warn!("TUN 状态检查获取网络接口失败：{}", e);
+            return TunCheckOutcome::Skipped;
+        }
+        Err(e) => {
+            log::warn!("TUN 状态检查任务失败：{}", e);
+            return TunCheckOutcome::Skipped;
+        }
+    };
+
+    if is_tun_adapter_present(&interfaces, tun.device.as_deref()) {
+        TunCheckOutcome::Present
+    } else {
+        TunCheckOutcome::Missing(tun.device)
+    }
+}
+
+// 启动 TUN 状态检查（进程内只需启动一次）。Android 由系统 VpnService 管理，不检查
+#[cfg(not(target_os = "android"))]
+pub fn start_tun_state_monitor() {
+    shutdown::spawn_listener(async {
+        let mut power_events = subscribe_power_events();
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        interval.tick().await; // 跳过首次立即触发
+
+        let mut is_reported = false;
+        loop {
+            let trigger = tokio::select! {
+                _ = interval.tick() => TunCheckTrigger::Periodic,
+                event = power_events.recv() => match event {
+                    Ok(PowerEventType::ResumeAutomatic | PowerEventType::ResumeSuspend) => {
+                        tokio::time::sleep(RESUME_SETTLE_DELAY).await;
+                        // 每次唤醒都重新上报仍未恢复的异常
+                        is_reported = false;
+                        TunCheckTrigger::Resume
+                    }
+                    Ok(PowerEventType::Suspend) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                },
+            };
+
+            let configured_device = match check_tun_state().await {
+                TunCheckOutcome::Missing(device) => device,
+                TunCheckOutcome::Present => {
+                    is_reported = false;
+                    continue;
+                }
+                TunCheckOutcome::Skipped => continue,
+            };
+            if is_reported {
+                continue;
+            }
+
+            log::warn!(
+                "TUN 已开启但未找到虚拟网卡（设备：{}，触发：{:?}）",
+                configured_device.as_deref().unwrap_or("未指定"),
+                trigger
+            );
+            TunStateMismatch {
+                trigger,
+                configured_device,
+                detected_at_ms: chrono::Utc::now().timestamp_millis(),
+            }
+            .send_signal_to_dart();
+            is_reported = true;
+        }
+    });
+}
+
+#[cfg(target_os = "android")]
+pub fn start_tun_state_monitor() {}
+
+#[cfg(all(test, not(target_os = "android")))]
+mod tests {
+    use super::is_tun_adapter_present;
+    use crate::atoms::network_interfaces::{
+        InterfaceAddress, InterfaceKind, NetworkInterfaceDetail,
+    };
+
+    fn interface(name: &str, kind: InterfaceKind, address: Option<&str>) -> NetworkInterfaceDetail {
+        NetworkInterfaceDetail {
+            name: name.to_string(),
+            index: 1,
+            kind,
+            description: None,
+            addresses: address
+                .map(|address| InterfaceAddress {
+                    address: address.to_string(),
+                    is_ipv6: false,
+                    prefix_length: Some(30),
+                    scope_id: None,
+                    is_link_local: false,
+                    is_temporary: None,
+                })
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn matches_configured_device_by_name() {
+        let interfaces = vec![
+            interface("lo", InterfaceKind::Loopback, None),
+            interface("eth0", InterfaceKind::Physical, None),
+            interface("wg0", InterfaceKind::VirtualTunnel, None),
+        ];
+        assert!(!is_tun_adapter_present(&interfaces, Some("Mihomo")));
+
+        let interfaces = vec![interface("mihomo", InterfaceKind::VirtualTunnel, None)];
+        assert!(is_tun_adapter_present(&interfaces, Some("Mihomo")));
+    }
+
+    // 未指定设备名时只认 mihomo 的默认网卡，其他隧道网卡（如 WireGuard、系统 utun）不算
+    #[test]
+    fn unconfigured_device_matches_only_mihomo_default() {
+        let others = vec![
+            interface("wg0", InterfaceKind::VirtualTunnel, Some("10.0.0.2")),
+            interface("utun0", InterfaceKind::VirtualTunnel, None),
+        ];
+        assert!(!is_tun_adapter_present(&others, None));
+
+        let default_device = if cfg!(target_os = "macos") {
+            interface("utun5", InterfaceKind::VirtualTunnel, Some("198.18.0.1"))
+        } else {
+            interface("Meta", InterfaceKind::VirtualTunnel, Some("198.18.0.1"))
+        };
+        assert!(is_tun_adapter_present(&[default_device], None));
+    }
+}
@@ -1,5 +1,6 @@
 // URL 启动器：使用系统默认浏览器打开 URL
 
+use crate::atoms::shutdown;
 use rinf::{DartSignal, RustSignal};
 use serde::{Deserialize, Serialize};
 
@@ -53,9 +54,7 @@ pub fn open_url(url: &str) -> Result<(), String> {
 }
 
 pub fn init() {
-    use tokio::spawn;
-
-    spawn(async {
+    shutdown::spawn_listener(async {
         let receiver = OpenUrl::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;